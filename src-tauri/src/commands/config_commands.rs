@@ -1,9 +1,12 @@
 //! 配置管理相关的 Tauri 命令
 
-use pomoflow_rs::{PomodoroAppManager, PomodoroConfig};
+use pomoflow_rs::{
+    InitialPayload, MaintenanceSummary, MessageRecord, PomodoroAppManager, PomodoroConfig,
+    TaskMetadataView,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use tauri::State;
 use tracing::error;
 
@@ -20,10 +23,10 @@ pub struct GithubSyncConfig {
 /// 获取用户配置
 #[tauri::command]
 pub async fn get_user_config(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<Option<pomoflow_rs::core::state::app_state::UserConfig>>, CommandError> {
     let config = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_user_config().await
     };
 
@@ -35,13 +38,155 @@ pub async fn get_user_config(
     }
 }
 
+/// 一次性获取前端启动所需的数据（任务、统计、会话、配置、标签）
+#[tauri::command]
+pub async fn get_initial_payload(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<InitialPayload>, CommandError> {
+    let payload = {
+        let guard = app_manager.read().await;
+        guard.get_initial_payload().await
+    };
+
+    match payload {
+        Ok(payload) => Ok(CommandResult::success(payload)),
+        Err(err) => {
+            error!("Failed to load initial payload: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取最近的提示/错误消息
+#[tauri::command]
+pub async fn get_recent_messages(
+    limit: Option<usize>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Vec<MessageRecord>>, CommandError> {
+    let messages = {
+        let guard = app_manager.read().await;
+        guard.get_recent_messages(limit.unwrap_or(20)).await
+    };
+
+    match messages {
+        Ok(messages) => Ok(CommandResult::success(messages)),
+        Err(err) => Ok(command_error_result(err)),
+    }
+}
+
+/// 将所有消息标记为已读
+#[tauri::command]
+pub async fn mark_messages_read(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<usize>, CommandError> {
+    let marked = {
+        let guard = app_manager.read().await;
+        guard.mark_messages_read().await
+    };
+
+    match marked {
+        Ok(marked) => Ok(CommandResult::success(marked)),
+        Err(err) => Ok(command_error_result(err)),
+    }
+}
+
+/// 获取未读消息数
+#[tauri::command]
+pub async fn get_unread_message_count(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<usize>, CommandError> {
+    let count = {
+        let guard = app_manager.read().await;
+        guard.unread_message_count().await
+    };
+
+    match count {
+        Ok(count) => Ok(CommandResult::success(count)),
+        Err(err) => Ok(command_error_result(err)),
+    }
+}
+
+/// 获取运行超过指定秒数仍未结束的后台任务，供调试面板提示
+#[tauri::command]
+pub async fn get_stuck_tasks(
+    threshold_seconds: u64,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Vec<TaskMetadataView>>, CommandError> {
+    let tasks = {
+        let guard = app_manager.read().await;
+        guard
+            .get_stuck_tasks(std::time::Duration::from_secs(threshold_seconds))
+            .await
+    };
+
+    match tasks {
+        Ok(tasks) => Ok(CommandResult::success(tasks)),
+        Err(err) => Ok(command_error_result(err)),
+    }
+}
+
+/// 清空消息历史
+#[tauri::command]
+pub async fn clear_message_history(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.clear_message_history().await
+    };
+
+    match result {
+        Ok(()) => Ok(CommandResult::success(())),
+        Err(err) => Ok(command_error_result(err)),
+    }
+}
+
+/// 恢复出厂设置，`confirmation` 必须与约定的确认口令完全一致
+#[tauri::command]
+pub async fn factory_reset(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    confirmation: String,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let mut guard = app_manager.write().await;
+        guard.factory_reset(&confirmation).await
+    };
+
+    match result {
+        Ok(()) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to factory reset: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 立即执行一次数据库维护（清理同步队列、VACUUM、PRAGMA optimize）
+#[tauri::command]
+pub async fn run_database_maintenance(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<MaintenanceSummary>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.run_maintenance().await
+    };
+
+    match result {
+        Ok(summary) => Ok(CommandResult::success(summary)),
+        Err(err) => {
+            error!("Failed to run database maintenance: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
 /// 获取 GitHub 同步配置（仅当配置完整时返回）
 #[tauri::command]
 pub async fn get_github_sync_config(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<Option<GithubSyncConfig>>, CommandError> {
     let config = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_user_config().await
     };
 
@@ -82,6 +227,13 @@ fn validate_user_config(config: &pomoflow_rs::core::state::app_state::UserConfig
         short_break_duration: config.pomodoro_short_break_duration,
         long_break_duration: config.pomodoro_long_break_duration,
         cycles_until_long_break: config.pomodoro_cycles_until_long_break,
+        deep_work_duration: config.pomodoro_deep_work_duration,
+        auto_start_next: config.pomodoro_auto_start_next,
+        warn_before_seconds: config.pomodoro_warn_before_seconds,
+        phase_sequence: config.pomodoro_phase_sequence.clone(),
+        require_phase_ack: config.pomodoro_require_phase_ack,
+        break_reminders: config.pomodoro_break_reminders,
+        reminder_grace_secs: config.pomodoro_reminder_grace_secs,
     };
     pomodoro_config
         .validate()
@@ -134,7 +286,7 @@ fn validate_user_config(config: &pomoflow_rs::core::state::app_state::UserConfig
 /// 保存用户配置
 #[tauri::command]
 pub async fn save_user_config(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     config: pomoflow_rs::core::state::app_state::UserConfig,
 ) -> Result<CommandResult<()>, CommandError> {
     // 验证配置
@@ -143,7 +295,7 @@ pub async fn save_user_config(
     }
 
     let result = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard.save_user_config(config).await
     };
 
@@ -177,6 +329,7 @@ mod tests {
             notifications_enabled: true,
             sound_enabled: true,
             theme: "light".to_string(),
+            ..UserConfig::default()
         };
 
         assert!(validate_user_config(&config).is_err());
@@ -198,6 +351,7 @@ mod tests {
             notifications_enabled: true,
             sound_enabled: true,
             theme: "light".to_string(),
+            ..UserConfig::default()
         };
 
         assert!(validate_user_config(&config).is_err());
@@ -219,6 +373,7 @@ mod tests {
             notifications_enabled: true,
             sound_enabled: true,
             theme: "light".to_string(),
+            ..UserConfig::default()
         };
 
         assert!(validate_user_config(&config).is_ok());
@@ -240,6 +395,7 @@ mod tests {
             notifications_enabled: true,
             sound_enabled: true,
             theme: "light".to_string(),
+            ..UserConfig::default()
         };
 
         assert!(validate_user_config(&config).is_err());
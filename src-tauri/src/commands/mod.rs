@@ -70,6 +70,7 @@ impl From<pomoflow_rs::core::error::AppError> for CommandError {
             AppError::NotFound(e) => CommandError::NotFound(e),
             AppError::InvalidState(e) => CommandError::InvalidState(e),
             AppError::Validation(e) => CommandError::Validation(e),
+            AppError::ValidationDetailed { message, .. } => CommandError::Validation(message),
             AppError::Timeout => CommandError::Network("Request timeout".to_string()),
             AppError::Serialization(e) => CommandError::Internal(e.to_string()),
             AppError::Io(e) => CommandError::Internal(e.to_string()),
@@ -188,6 +189,15 @@ mod tests {
         assert_eq!(timeout.code(), "NETWORK");
     }
 
+    #[test]
+    fn detailed_validation_maps_to_validation_code() {
+        use pomoflow_rs::core::error::ValidationKind;
+
+        let err = CommandError::from(AppError::validation(ValidationKind::TitleEmpty, "empty"));
+        assert!(matches!(err, CommandError::Validation(ref msg) if msg == "empty"));
+        assert_eq!(err.code(), "VALIDATION");
+    }
+
     #[test]
     fn command_error_code_covers_all_variants() {
         let cases = vec![
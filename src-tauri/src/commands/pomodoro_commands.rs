@@ -1,21 +1,26 @@
 //! 番茄钟相关的 Tauri 命令
 
-use pomoflow_rs::{PomodoroAppManager, PomodoroConfig};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use pomoflow_rs::{
+    GoalProgress, LifetimeFocus, PauseReason, PomodoroAppManager, PomodoroConfig, PomodoroConfigView,
+    PomodoroPhase, PomodoroStats, SessionPage, StatsRange, Todo, WidgetPayload,
+};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use tauri::State;
 use tracing::error;
 
+use super::validators::validate_id;
 use super::{command_error_result, CommandError, CommandResult};
 
 /// 获取当前番茄钟会话状态
 #[tauri::command]
 pub async fn get_pomodoro_session(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<Option<pomoflow_rs::core::pomodoro::PomodoroSession>>, CommandError> {
     // 使用异步锁
     let session = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_pomodoro_session().await
     };
 
@@ -28,14 +33,358 @@ pub async fn get_pomodoro_session(
     }
 }
 
+/// 获取剩余时间（MM:SS），供托盘等精简显示使用
+#[tauri::command]
+pub async fn get_formatted_remaining(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<String>, CommandError> {
+    let formatted = {
+        let guard = app_manager.read().await;
+        guard.get_formatted_remaining().await
+    };
+
+    match formatted {
+        Ok(formatted) => Ok(CommandResult::success(formatted)),
+        Err(err) => {
+            error!("Failed to get formatted remaining time: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 按今日节奏预测达成每日专注目标的时间
+#[tauri::command]
+pub async fn project_goal_completion(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Option<DateTime<Utc>>>, CommandError> {
+    let projected = {
+        let guard = app_manager.read().await;
+        guard.project_goal_completion().await
+    };
+
+    match projected {
+        Ok(projected) => Ok(CommandResult::success(projected)),
+        Err(err) => {
+            error!("Failed to project goal completion: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取今日专注目标进度
+#[tauri::command]
+pub async fn get_goal_progress(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<GoalProgress>, CommandError> {
+    let progress = {
+        let guard = app_manager.read().await;
+        guard.get_goal_progress().await
+    };
+
+    match progress {
+        Ok(progress) => Ok(CommandResult::success(progress)),
+        Err(err) => {
+            error!("Failed to get goal progress: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取每日番茄数目标进度：(今日完成的工作番茄数, 目标)
+#[tauri::command]
+pub async fn get_daily_goal_progress(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<(u32, Option<u32>)>, CommandError> {
+    let progress = {
+        let guard = app_manager.read().await;
+        guard.get_daily_goal_progress().await
+    };
+
+    match progress {
+        Ok(progress) => Ok(CommandResult::success(progress)),
+        Err(err) => {
+            error!("Failed to get daily pomodoro goal progress: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 今日专注是否已达到每日目标
+#[tauri::command]
+pub async fn is_daily_goal_met(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<bool>, CommandError> {
+    let met = {
+        let guard = app_manager.read().await;
+        guard.is_daily_goal_met().await
+    };
+
+    match met {
+        Ok(met) => Ok(CommandResult::success(met)),
+        Err(err) => {
+            error!("Failed to check daily goal: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取番茄钟统计（今天或全部）
+#[tauri::command]
+pub async fn get_pomodoro_stats(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    range: StatsRange,
+) -> Result<CommandResult<PomodoroStats>, CommandError> {
+    let stats = {
+        let guard = app_manager.read().await;
+        guard.get_pomodoro_stats(range).await
+    };
+
+    match stats {
+        Ok(stats) => Ok(CommandResult::success(stats)),
+        Err(err) => {
+            error!("Failed to get pomodoro stats: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 分页获取某天（默认今天，本地日期）的会话记录
+#[tauri::command]
+pub async fn get_session_history_page(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    date: Option<NaiveDate>,
+    offset: u32,
+    limit: u32,
+) -> Result<CommandResult<SessionPage>, CommandError> {
+    let date = date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let page = {
+        let guard = app_manager.read().await;
+        guard.get_session_history_page(date, offset, limit).await
+    };
+
+    match page {
+        Ok(page) => Ok(CommandResult::success(page)),
+        Err(err) => {
+            error!("Failed to get session history: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取区间内专注最多的星期几及其专注分钟数
+#[tauri::command]
+pub async fn get_best_weekday(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<CommandResult<Option<(Weekday, u64)>>, CommandError> {
+    let best = {
+        let guard = app_manager.read().await;
+        guard.get_best_weekday(from, to).await
+    };
+
+    match best {
+        Ok(best) => Ok(CommandResult::success(best)),
+        Err(err) => {
+            error!("Failed to get best weekday: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 根据最近的专注历史建议每日目标（分钟），`lookback_days` 默认 30 天
+#[tauri::command]
+pub async fn suggest_daily_goal(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    lookback_days: Option<u32>,
+) -> Result<CommandResult<u32>, CommandError> {
+    let goal = {
+        let guard = app_manager.read().await;
+        guard.suggest_daily_goal(lookback_days.unwrap_or(30)).await
+    };
+
+    match goal {
+        Ok(goal) => Ok(CommandResult::success(goal)),
+        Err(err) => {
+            error!("Failed to suggest daily goal: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取当前生效的番茄钟配置，各时长同时给出秒数与分钟数
+#[tauri::command]
+pub async fn get_pomodoro_config_view(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<PomodoroConfigView>, CommandError> {
+    let view = {
+        let guard = app_manager.read().await;
+        guard.get_pomodoro_config_view().await
+    };
+
+    match view {
+        Ok(view) => Ok(CommandResult::success(view)),
+        Err(err) => {
+            error!("Failed to get pomodoro config view: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取累计专注时长
+#[tauri::command]
+pub async fn get_lifetime_focus(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<LifetimeFocus>, CommandError> {
+    let focus = {
+        let guard = app_manager.read().await;
+        guard.get_lifetime_focus().await
+    };
+
+    match focus {
+        Ok(focus) => Ok(CommandResult::success(focus)),
+        Err(err) => {
+            error!("Failed to get lifetime focus: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取专注时长最多的任务排行（分钟），`limit` 默认 10
+#[tauri::command]
+pub async fn get_top_focused_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    limit: Option<u32>,
+) -> Result<CommandResult<Vec<(Todo, u64)>>, CommandError> {
+    let leaderboard = {
+        let guard = app_manager.read().await;
+        guard.get_top_focused_todos(limit.unwrap_or(10)).await
+    };
+
+    match leaderboard {
+        Ok(leaderboard) => Ok(CommandResult::success(leaderboard)),
+        Err(err) => {
+            error!("Failed to get top focused todos: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取小组件数据（连续天数、今日专注、目标与计时状态）
+#[tauri::command]
+pub async fn get_widget_payload(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<WidgetPayload>, CommandError> {
+    let payload = {
+        let guard = app_manager.read().await;
+        guard.get_widget_payload().await
+    };
+
+    match payload {
+        Ok(payload) => Ok(CommandResult::success(payload)),
+        Err(err) => {
+            error!("Failed to get widget payload: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 上报系统空闲秒数（由前端空闲检测定期调用）
+#[tauri::command]
+pub async fn report_idle_time(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    seconds: u64,
+) -> Result<CommandResult<()>, CommandError> {
+    let guard = app_manager.read().await;
+    guard.report_idle_seconds(seconds).await;
+    Ok(CommandResult::success(()))
+}
+
+/// 开始专注块（连续多个番茄）
+#[tauri::command]
+pub async fn start_focus_block(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    total: u32,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.start_focus_block(total).await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to start focus block: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取当前专注块进度（已完成, 总数）
+#[tauri::command]
+pub async fn get_focus_block_progress(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Option<(u32, u32)>>, CommandError> {
+    let progress = {
+        let guard = app_manager.read().await;
+        guard.get_focus_block_progress().await
+    };
+
+    match progress {
+        Ok(progress) => Ok(CommandResult::success(progress)),
+        Err(err) => {
+            error!("Failed to get focus block progress: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 提前结束休息并开始工作
+#[tauri::command]
+pub async fn end_break_now(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.end_break_now().await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to end break: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 获取当前阶段的生效颜色
+#[tauri::command]
+pub async fn get_current_phase_color(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<String>, CommandError> {
+    let color = {
+        let guard = app_manager.read().await;
+        guard.get_current_phase_color().await
+    };
+
+    match color {
+        Ok(color) => Ok(CommandResult::success(color)),
+        Err(err) => {
+            error!("Failed to get current phase color: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
 /// 启动番茄钟
 #[tauri::command]
 pub async fn start_pomodoro(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<()>, CommandError> {
     // 使用异步锁
     let result = {
-        let mut guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.start_pomodoro().await
     };
 
@@ -48,15 +397,60 @@ pub async fn start_pomodoro(
     }
 }
 
-/// 暂停番茄钟
+/// 开始深度工作
+#[tauri::command]
+pub async fn start_deep_work(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.start_deep_work().await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to start deep work: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 设置计时刷新间隔（毫秒），返回实际生效的间隔
+#[tauri::command]
+pub async fn set_tick_interval(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    interval_ms: u64,
+) -> Result<CommandResult<u64>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.set_tick_interval_ms(interval_ms).await
+    };
+
+    match result {
+        Ok(effective) => Ok(CommandResult::success(effective)),
+        Err(err) => {
+            error!("Failed to set tick interval: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 暂停番茄钟，`reason` 缺省为手动暂停（manual / idle_auto / break_enforced）
 #[tauri::command]
 pub async fn pause_pomodoro(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    reason: Option<String>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<()>, CommandError> {
+    let reason = match reason.as_deref().map(PauseReason::parse).transpose() {
+        Ok(reason) => reason.unwrap_or(PauseReason::Manual),
+        Err(err) => return Ok(command_error_result(err)),
+    };
+
     // 使用异步锁
     let result = {
-        let mut guard = app_manager.lock().await;
-        guard.pause_pomodoro().await
+        let guard = app_manager.read().await;
+        guard.pause_pomodoro_with_reason(reason).await
     };
 
     match result {
@@ -68,14 +462,83 @@ pub async fn pause_pomodoro(
     }
 }
 
+/// 放弃当前番茄钟会话
+#[tauri::command]
+pub async fn abandon_pomodoro(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.abandon_pomodoro().await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to abandon pomodoro: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 设置当前会话专注的任务（传入空值取消专注）
+#[tauri::command]
+pub async fn set_focus_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    todo_id: Option<String>,
+) -> Result<CommandResult<()>, CommandError> {
+    if let Some(id) = &todo_id {
+        if let Err(err) = validate_id(id) {
+            return Ok(command_error_result(err));
+        }
+    }
+
+    let result = {
+        let guard = app_manager.read().await;
+        guard.set_focus_todo(todo_id).await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to set focus todo: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 专注指定任务并开始番茄钟
+#[tauri::command]
+pub async fn start_pomodoro_for_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    todo_id: String,
+) -> Result<CommandResult<()>, CommandError> {
+    if let Err(err) = validate_id(&todo_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let result = {
+        let guard = app_manager.read().await;
+        guard.start_pomodoro_for_todo(todo_id).await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to start pomodoro for todo: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
 /// 重置番茄钟
 #[tauri::command]
 pub async fn reset_pomodoro(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<()>, CommandError> {
     // 使用异步锁
     let result = {
-        let mut guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.reset_pomodoro().await
     };
 
@@ -88,14 +551,71 @@ pub async fn reset_pomodoro(
     }
 }
 
+/// 清零番茄钟周期计数（保持当前阶段）
+#[tauri::command]
+pub async fn reset_cycle_count(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.reset_cycle_count().await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to reset cycle count: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 记录当前阶段的一次打断，返回本阶段累计的打断次数
+#[tauri::command]
+pub async fn record_pomodoro_interruption(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<u32>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.record_pomodoro_interruption().await
+    };
+
+    match result {
+        Ok(interruptions) => Ok(CommandResult::success(interruptions)),
+        Err(err) => {
+            error!("Failed to record pomodoro interruption: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 确认已到时的工作阶段并进入休息
+#[tauri::command]
+pub async fn acknowledge_pomodoro_phase(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<()>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.acknowledge_pomodoro_phase().await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to acknowledge pomodoro phase: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
 /// 跳过当前阶段
 #[tauri::command]
 pub async fn skip_pomodoro_phase(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<()>, CommandError> {
     // 使用异步锁
     let result = {
-        let mut guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.skip_pomodoro_phase().await
     };
 
@@ -109,25 +629,54 @@ pub async fn skip_pomodoro_phase(
 }
 
 /// 更新番茄钟配置
+///
+/// 可选参数为空时沿用当前配置；`warn_before_seconds` 为 0 表示关闭阶段结束前提醒，
+/// `clear_phase_sequence` 为真时清除自定义阶段序列（优先于 `phase_sequence`）
 #[tauri::command]
 pub async fn update_pomodoro_config(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     work_duration: u64,
     short_break: u64,
     long_break: u64,
     cycles: u32,
+    deep_work: Option<u64>,
+    auto_start_next: Option<bool>,
+    warn_before_seconds: Option<u64>,
+    phase_sequence: Option<Vec<PomodoroPhase>>,
+    clear_phase_sequence: Option<bool>,
+    require_phase_ack: Option<bool>,
+    break_reminders: Option<bool>,
+    reminder_grace_secs: Option<u64>,
 ) -> Result<CommandResult<()>, CommandError> {
-    // 仅更新运行时番茄钟配置，不覆盖用户主题和通知偏好
-    let config = PomodoroConfig {
-        work_duration,
-        short_break_duration: short_break,
-        long_break_duration: long_break,
-        cycles_until_long_break: cycles,
-    };
-
-    // 使用异步锁，调用新的 update_pomodoro_config 方法
+    // 仅更新运行时番茄钟配置，不覆盖用户主题和通知偏好；未传的可选参数沿用当前配置
     let result = {
-        let mut guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
+        let current = match guard.get_pomodoro_session().await {
+            Ok(session) => session.map(|session| session.config).unwrap_or_default(),
+            Err(err) => {
+                error!("Failed to read current pomodoro config: {}", err);
+                return Ok(command_error_result(err));
+            }
+        };
+        let config = PomodoroConfig {
+            work_duration,
+            short_break_duration: short_break,
+            long_break_duration: long_break,
+            cycles_until_long_break: cycles,
+            deep_work_duration: deep_work.unwrap_or(current.deep_work_duration),
+            auto_start_next: auto_start_next.unwrap_or(current.auto_start_next),
+            // 传 0 关闭阶段结束前提醒
+            warn_before_seconds: warn_before_seconds
+                .map_or(current.warn_before_seconds, |seconds| (seconds > 0).then_some(seconds)),
+            phase_sequence: if clear_phase_sequence.unwrap_or(false) {
+                None
+            } else {
+                phase_sequence.or(current.phase_sequence)
+            },
+            require_phase_ack: require_phase_ack.unwrap_or(current.require_phase_ack),
+            break_reminders: break_reminders.unwrap_or(current.break_reminders),
+            reminder_grace_secs: reminder_grace_secs.unwrap_or(current.reminder_grace_secs),
+        };
         guard.update_pomodoro_config(config).await
     };
 
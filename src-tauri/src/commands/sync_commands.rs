@@ -3,20 +3,21 @@
 use pomoflow_rs::{GithubSyncReport, PomodoroAppManager};
 use std::sync::Arc;
 use tauri::State;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use tracing::error;
 
+use super::validators::validate_id;
 use super::{command_error_result, CommandError, CommandResult};
 
 /// 运行 GitHub 同步（当前为 dry-run 阶段）
 #[tauri::command]
 pub async fn run_github_sync(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     dry_run: Option<bool>,
 ) -> Result<CommandResult<GithubSyncReport>, CommandError> {
     let dry_run = dry_run.unwrap_or(true);
     let result = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard.run_github_sync(dry_run).await
     };
 
@@ -28,3 +29,46 @@ pub async fn run_github_sync(
         }
     }
 }
+
+/// 将指定任务重新放回同步队列
+#[tauri::command]
+pub async fn requeue_todo_sync(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+) -> Result<CommandResult<()>, CommandError> {
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+
+    let result = {
+        let mut guard = app_manager.write().await;
+        guard.requeue_todo_sync(&id).await
+    };
+
+    match result {
+        Ok(()) => Ok(CommandResult::success(())),
+        Err(err) => {
+            error!("Failed to requeue todo sync: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
+
+/// 将所有任务重新放回同步队列
+#[tauri::command]
+pub async fn requeue_all_sync(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<usize>, CommandError> {
+    let result = {
+        let mut guard = app_manager.write().await;
+        guard.requeue_all_sync().await
+    };
+
+    match result {
+        Ok(count) => Ok(CommandResult::success(count)),
+        Err(err) => {
+            error!("Failed to requeue all sync: {}", err);
+            Ok(command_error_result(err))
+        }
+    }
+}
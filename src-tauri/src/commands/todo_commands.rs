@@ -1,10 +1,19 @@
 //! 待办事项相关的 Tauri 命令
 
-use super::validators::{validate_id, validate_todo_description, validate_todo_title};
+use super::validators::{
+    validate_id, validate_tag_color, validate_tag_name, validate_todo_description,
+    validate_todo_title,
+};
 use super::{command_error_result, CommandError, CommandResult};
-use pomoflow_rs::{PomodoroAppManager, Todo, TodoStatus, TodoUpdate};
+use chrono::{DateTime, NaiveDate, Utc};
+use pomoflow_rs::core::todo::{
+    EstimatedBacklog, ImportPlan, PlanScope, Tag, TagSort, TodoExport, TodoNote, TodoPage,
+    TodoSearchResult, TodoStats, TodoWithProgress, ToggleResult,
+};
+use pomoflow_rs::{MoveDirection, PomodoroAppManager, SortKey, Todo, TodoFilter, TodoStatus, TodoUpdate};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use tauri::State;
 
 fn validate_positive_i64(value: i64, field_name: &str) -> Result<(), CommandError> {
@@ -20,10 +29,10 @@ fn validate_positive_i64(value: i64, field_name: &str) -> Result<(), CommandErro
 /// 获取所有待办事项
 #[tauri::command]
 pub async fn get_todos(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<Vec<Todo>>, CommandError> {
     let todos = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_todos().await
     };
 
@@ -33,10 +42,215 @@ pub async fn get_todos(
     }
 }
 
+/// 获取今天完成的任务数
+#[tauri::command]
+pub async fn get_completed_today_count(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<u64>, CommandError> {
+    let count = {
+        let guard = app_manager.read().await;
+        guard.get_completed_today_count().await
+    };
+
+    match count {
+        Ok(count) => Ok(CommandResult::success(count)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 从 Markdown 清单批量导入任务
+#[tauri::command]
+pub async fn import_markdown(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    markdown: String,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let created = {
+        let mut guard = app_manager.write().await;
+        guard.import_markdown(&markdown).await
+    };
+
+    match created {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 分页获取任务，`total` 为未删除任务总数
+#[tauri::command]
+pub async fn get_todos_page(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    offset: usize,
+    limit: usize,
+) -> Result<CommandResult<TodoPage>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.get_todos_page(offset, limit).await
+    };
+
+    match result {
+        Ok(page) => Ok(CommandResult::success(page)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 按筛选器搜索任务，`truncated` 为 true 时表示只返回了前若干条
+#[tauri::command]
+pub async fn search_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    filter: TodoFilter,
+) -> Result<CommandResult<TodoSearchResult>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.search_todos(&filter).await
+    };
+
+    match result {
+        Ok(result) => Ok(CommandResult::success(result)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 全文搜索任务标题和描述
+#[tauri::command]
+pub async fn full_text_search_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    query: String,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let result = {
+        let guard = app_manager.read().await;
+        guard.full_text_search_todos(&query).await
+    };
+
+    match result {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 按筛选器获取任务并按指定字段排序，`ascending` 为 false 时倒序
+#[tauri::command]
+pub async fn get_todos_sorted(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    filter: TodoFilter,
+    sort_key: SortKey,
+    ascending: bool,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let todos = {
+        let guard = app_manager.read().await;
+        guard.get_todos_sorted(&filter, sort_key, ascending).await
+    };
+
+    match todos {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取时间范围内创建的任务，用于回顾
+#[tauri::command]
+pub async fn get_todos_for_review(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let todos = {
+        let guard = app_manager.read().await;
+        guard.get_todos_for_review(from, to).await
+    };
+
+    match todos {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取时间范围内完成的任务，用于成果报告
+#[tauri::command]
+pub async fn get_todos_completed_between(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let todos = {
+        let guard = app_manager.read().await;
+        guard.get_todos_completed_between(from, to).await
+    };
+
+    match todos {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 估算未完成任务的总耗时
+#[tauri::command]
+pub async fn estimate_backlog_time(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<EstimatedBacklog>, CommandError> {
+    let backlog = {
+        let guard = app_manager.read().await;
+        guard.estimate_backlog_time().await
+    };
+
+    match backlog {
+        Ok(backlog) => Ok(CommandResult::success(backlog)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 生成今日/本周的可打印计划（Markdown）
+#[tauri::command]
+pub async fn generate_plan(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    scope: PlanScope,
+) -> Result<CommandResult<String>, CommandError> {
+    let plan = {
+        let guard = app_manager.read().await;
+        guard.generate_plan(scope).await
+    };
+
+    match plan {
+        Ok(plan) => Ok(CommandResult::success(plan)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 导出任务为 iCalendar（.ics）文本
+#[tauri::command]
+pub async fn export_todos_ics(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<String>, CommandError> {
+    let ics = {
+        let guard = app_manager.read().await;
+        guard.export_todos_ics().await
+    };
+
+    match ics {
+        Ok(ics) => Ok(CommandResult::success(ics)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取建议下一个开始的任务
+#[tauri::command]
+pub async fn suggest_next_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Option<Todo>>, CommandError> {
+    let todo = {
+        let guard = app_manager.read().await;
+        guard.suggest_next_todo().await
+    };
+
+    match todo {
+        Ok(todo) => Ok(CommandResult::success(todo)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
 /// 创建新任务
 #[tauri::command]
 pub async fn create_todo(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     title: String,
     description: Option<String>,
     status: Option<String>,
@@ -60,7 +274,7 @@ pub async fn create_todo(
     };
 
     let todo = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard
             .create_todo_with_status(title, description, todo_status)
             .await
@@ -73,13 +287,20 @@ pub async fn create_todo(
 }
 
 /// 更新任务
+///
+/// 参数为空表示不修改；`clear_estimate_minutes` 为真时清除预估耗时（优先于 `estimate_minutes`），
+/// `clear_estimated_pomodoros` 为真时清除预估番茄数（优先于 `estimated_pomodoros`）
 #[tauri::command]
 pub async fn update_todo(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
     title: Option<String>,
     description: Option<String>,
     status: Option<String>,
+    estimate_minutes: Option<u32>,
+    clear_estimate_minutes: Option<bool>,
+    estimated_pomodoros: Option<u32>,
+    clear_estimated_pomodoros: Option<bool>,
 ) -> Result<CommandResult<Todo>, CommandError> {
     // 验证ID
     if let Err(err) = validate_id(&id) {
@@ -118,8 +339,20 @@ pub async fn update_todo(
         updates = updates.with_status(parsed_status);
     }
 
+    if clear_estimate_minutes.unwrap_or(false) {
+        updates = updates.with_estimate_minutes(None);
+    } else if let Some(estimate_minutes) = estimate_minutes {
+        updates = updates.with_estimate_minutes(Some(estimate_minutes));
+    }
+
+    if clear_estimated_pomodoros.unwrap_or(false) {
+        updates = updates.with_estimated_pomodoros(None);
+    } else if let Some(estimated_pomodoros) = estimated_pomodoros {
+        updates = updates.with_estimated_pomodoros(Some(estimated_pomodoros));
+    }
+
     let todo = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard.update_todo(&id, updates).await
     };
 
@@ -132,7 +365,7 @@ pub async fn update_todo(
 /// 删除任务
 #[tauri::command]
 pub async fn delete_todo(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
 ) -> Result<CommandResult<bool>, CommandError> {
     // 验证ID
@@ -141,7 +374,7 @@ pub async fn delete_todo(
     }
 
     match {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard.delete_todo(&id).await
     } {
         Ok(_) => Ok(CommandResult::success(true)),
@@ -149,21 +382,203 @@ pub async fn delete_todo(
     }
 }
 
+/// 将任务在手动顺序中上移或下移一位，返回是否发生了移动
+#[tauri::command]
+pub async fn move_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+    direction: MoveDirection,
+) -> Result<CommandResult<bool>, CommandError> {
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+
+    match {
+        let mut guard = app_manager.write().await;
+        guard.move_todo(&id, direction).await
+    } {
+        Ok(moved) => Ok(CommandResult::success(moved)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 按拖拽后的顺序重排任务，`ordered_ids` 中的每个任务都必须存在
+#[tauri::command]
+pub async fn reorder_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    ordered_ids: Vec<String>,
+) -> Result<CommandResult<()>, CommandError> {
+    for id in &ordered_ids {
+        if let Err(err) = validate_id(id) {
+            return Ok(command_error_result(err));
+        }
+    }
+
+    match {
+        let mut guard = app_manager.write().await;
+        guard.reorder_todos(&ordered_ids).await
+    } {
+        Ok(()) => Ok(CommandResult::success(())),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 从回收站恢复任务
+#[tauri::command]
+pub async fn restore_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+) -> Result<CommandResult<Todo>, CommandError> {
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+
+    match {
+        let mut guard = app_manager.write().await;
+        guard.restore_todo(&id).await
+    } {
+        Ok(todo) => Ok(CommandResult::success(todo)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取回收站中的任务
+#[tauri::command]
+pub async fn get_deleted_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let todos = {
+        let guard = app_manager.read().await;
+        guard.get_deleted_todos().await
+    };
+
+    match todos {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 永久删除回收站中的任务
+#[tauri::command]
+pub async fn purge_deleted_todo(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+) -> Result<CommandResult<bool>, CommandError> {
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+
+    match {
+        let mut guard = app_manager.write().await;
+        guard.purge_deleted_todo(&id).await
+    } {
+        Ok(_) => Ok(CommandResult::success(true)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 清除所有已完成任务，返回清除数量
+#[tauri::command]
+pub async fn clear_completed_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<usize>, CommandError> {
+    match {
+        let mut guard = app_manager.write().await;
+        guard.clear_completed_todos().await
+    } {
+        Ok(count) => Ok(CommandResult::success(count)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 撤销最近一次批量操作，返回是否执行了撤销
+#[tauri::command]
+pub async fn undo_last_bulk_operation(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<bool>, CommandError> {
+    match {
+        let mut guard = app_manager.write().await;
+        guard.undo_last_bulk_operation().await
+    } {
+        Ok(undone) => Ok(CommandResult::success(undone)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 预览导入备份会产生的变化（不修改数据）
+#[tauri::command]
+pub async fn import_all_dry_run(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    backup: TodoExport,
+) -> Result<CommandResult<ImportPlan>, CommandError> {
+    match {
+        let guard = app_manager.read().await;
+        guard.import_all_dry_run(&backup).await
+    } {
+        Ok(plan) => Ok(CommandResult::success(plan)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 导入备份中的任务，返回实际执行的导入计划
+#[tauri::command]
+pub async fn import_all(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    backup: TodoExport,
+) -> Result<CommandResult<ImportPlan>, CommandError> {
+    match {
+        let mut guard = app_manager.write().await;
+        guard.import_all(&backup).await
+    } {
+        Ok(plan) => Ok(CommandResult::success(plan)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 从备份 JSON 恢复任务，`merge` 为 false 时先清空现有任务，返回导入数量
+#[tauri::command]
+pub async fn import_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    json: String,
+    merge: bool,
+) -> Result<CommandResult<usize>, CommandError> {
+    let export = match TodoExport::import(&json) {
+        Ok(export) => export,
+        Err(e) => return Ok(command_error_result(e)),
+    };
+
+    match {
+        let mut guard = app_manager.write().await;
+        guard.import_todos(export, merge).await
+    } {
+        Ok(count) => Ok(CommandResult::success(count)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
 // ========================================================================
 // 标签相关命令
 // ========================================================================
 
 /// 获取所有标签
+///
+/// `sort` 可选 `created_at`（默认，最新在前）或 `name`（按名称升序）
 #[tauri::command]
 pub async fn get_tags(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
-) -> Result<CommandResult<Vec<(String, String, String)>>, CommandError> {
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    sort: Option<String>,
+) -> Result<CommandResult<Vec<Tag>>, CommandError> {
+    let sort = match sort.as_deref().map(TagSort::from_string).transpose() {
+        Ok(sort) => sort.unwrap_or_default(),
+        Err(err) => return Ok(command_error_result(err)),
+    };
+
     let db = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_database().clone()
     };
 
-    match db.get_all_tags().await {
+    match db.get_all_tags_sorted(sort).await {
         Ok(tags) => Ok(CommandResult::success(tags)),
         Err(e) => Ok(command_error_result(e)),
     }
@@ -172,32 +587,17 @@ pub async fn get_tags(
 /// 创建标签
 #[tauri::command]
 pub async fn create_tag(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     name: String,
     color: String,
-) -> Result<CommandResult<(String, String, String)>, CommandError> {
+) -> Result<CommandResult<Tag>, CommandError> {
     // 验证输入
-    if name.trim().is_empty() {
-        return Ok(command_error_result(CommandError::Validation(
-            "标签名称不能为空".to_string(),
-        )));
-    }
-
-    if name.len() > 50 {
-        return Ok(command_error_result(CommandError::Validation(
-            "标签名称不能超过50字符".to_string(),
-        )));
-    }
-
-    // 验证颜色格式（简单的 hex 颜色代码）
-    if !color.starts_with('#') || color.len() != 7 {
-        return Ok(command_error_result(CommandError::Validation(
-            "颜色格式无效，应为 #RRGGBB 格式".to_string(),
-        )));
+    if let Err(err) = validate_tag_name(&name).and_then(|_| validate_tag_color(&color)) {
+        return Ok(command_error_result(err));
     }
 
     let db = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_database().clone()
     };
 
@@ -207,10 +607,41 @@ pub async fn create_tag(
     }
 }
 
+/// 修改标签名称和/或颜色
+#[tauri::command]
+pub async fn update_tag(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<CommandResult<Tag>, CommandError> {
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+    if let Some(name) = &name {
+        if let Err(err) = validate_tag_name(name) {
+            return Ok(command_error_result(err));
+        }
+    }
+    if let Some(color) = &color {
+        if let Err(err) = validate_tag_color(color) {
+            return Ok(command_error_result(err));
+        }
+    }
+
+    match {
+        let guard = app_manager.read().await;
+        guard.update_tag(&id, name.as_deref(), color.as_deref()).await
+    } {
+        Ok(tag) => Ok(CommandResult::success(tag)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
 /// 删除标签
 #[tauri::command]
 pub async fn delete_tag(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
 ) -> Result<CommandResult<bool>, CommandError> {
     // 验证ID
@@ -218,12 +649,10 @@ pub async fn delete_tag(
         return Ok(command_error_result(err));
     }
 
-    let db = {
-        let guard = app_manager.lock().await;
-        guard.get_database().clone()
-    };
-
-    match db.delete_tag(&id).await {
+    match {
+        let guard = app_manager.read().await;
+        guard.delete_tag(&id).await
+    } {
         Ok(deleted) => Ok(CommandResult::success(deleted)),
         Err(e) => Ok(command_error_result(e)),
     }
@@ -232,7 +661,7 @@ pub async fn delete_tag(
 /// 为待办事项添加标签
 #[tauri::command]
 pub async fn assign_tag_to_todo(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     todo_id: String,
     tag_id: String,
 ) -> Result<CommandResult<()>, CommandError> {
@@ -244,12 +673,10 @@ pub async fn assign_tag_to_todo(
         return Ok(command_error_result(err));
     }
 
-    let db = {
-        let guard = app_manager.lock().await;
-        guard.get_database().clone()
-    };
-
-    match db.add_tag_to_todo(&todo_id, &tag_id).await {
+    match {
+        let guard = app_manager.read().await;
+        guard.add_tag_to_todo(&todo_id, &tag_id).await
+    } {
         Ok(_) => Ok(CommandResult::success(())),
         Err(e) => Ok(command_error_result(e)),
     }
@@ -258,7 +685,7 @@ pub async fn assign_tag_to_todo(
 /// 从待办事项移除标签
 #[tauri::command]
 pub async fn remove_tag_from_todo(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     todo_id: String,
     tag_id: String,
 ) -> Result<CommandResult<()>, CommandError> {
@@ -270,12 +697,10 @@ pub async fn remove_tag_from_todo(
         return Ok(command_error_result(err));
     }
 
-    let db = {
-        let guard = app_manager.lock().await;
-        guard.get_database().clone()
-    };
-
-    match db.remove_tag_from_todo(&todo_id, &tag_id).await {
+    match {
+        let guard = app_manager.read().await;
+        guard.remove_tag_from_todo(&todo_id, &tag_id).await
+    } {
         Ok(_) => Ok(CommandResult::success(())),
         Err(e) => Ok(command_error_result(e)),
     }
@@ -284,16 +709,16 @@ pub async fn remove_tag_from_todo(
 /// 获取待办事项的所有标签
 #[tauri::command]
 pub async fn get_todo_tags(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     todo_id: String,
-) -> Result<CommandResult<Vec<(String, String, String)>>, CommandError> {
+) -> Result<CommandResult<Vec<Tag>>, CommandError> {
     // 验证ID
     if let Err(err) = validate_id(&todo_id) {
         return Ok(command_error_result(err));
     }
 
     let db = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_database().clone()
     };
 
@@ -303,20 +728,121 @@ pub async fn get_todo_tags(
     }
 }
 
-/// 切换任务状态
+/// 获取带有指定标签的任务累计专注时长（分钟）
 #[tauri::command]
-pub async fn toggle_todo_status(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+pub async fn get_tag_focus_time(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    tag_id: String,
+) -> Result<CommandResult<u64>, CommandError> {
+    // 验证ID
+    if let Err(err) = validate_id(&tag_id) {
+        return Ok(command_error_result(err));
+    }
+
+    match {
+        let guard = app_manager.read().await;
+        guard.get_tag_focus_time(&tag_id).await
+    } {
+        Ok(minutes) => Ok(CommandResult::success(minutes)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 为任务追加一条备注
+#[tauri::command]
+pub async fn add_todo_note(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    todo_id: String,
+    body: String,
+) -> Result<CommandResult<TodoNote>, CommandError> {
+    if let Err(err) = validate_id(&todo_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let note = {
+        let guard = app_manager.read().await;
+        guard.add_todo_note(&todo_id, &body).await
+    };
+
+    match note {
+        Ok(note) => Ok(CommandResult::success(note)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取任务的预估番茄数与实际完成的工作番茄数
+#[tauri::command]
+pub async fn get_todo_progress(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    todo_id: String,
+) -> Result<CommandResult<TodoWithProgress>, CommandError> {
+    if let Err(err) = validate_id(&todo_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let progress = {
+        let guard = app_manager.read().await;
+        guard.get_todo_progress(&todo_id).await
+    };
+
+    match progress {
+        Ok(progress) => Ok(CommandResult::success(progress)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 在指定任务下创建子任务
+#[tauri::command]
+pub async fn create_subtask(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    parent_id: String,
+    title: String,
+    description: Option<String>,
+) -> Result<CommandResult<Todo>, CommandError> {
+    if let Err(err) = validate_id(&parent_id) {
+        return Ok(command_error_result(err));
+    }
+    if let Err(err) = validate_todo_title(&title) {
+        return Ok(command_error_result(err));
+    }
+    if let Some(ref desc) = description {
+        if let Err(err) = validate_todo_description(desc) {
+            return Ok(command_error_result(err));
+        }
+    }
+
+    let todo = {
+        let mut guard = app_manager.write().await;
+        guard.create_subtask(&parent_id, title, description).await
+    };
+
+    match todo {
+        Ok(todo) => Ok(CommandResult::success(todo)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 设置任务的父任务，`parent_id` 为空时改为顶层任务
+#[tauri::command]
+pub async fn set_todo_parent(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
+    parent_id: Option<String>,
 ) -> Result<CommandResult<Todo>, CommandError> {
-    // 验证ID
     if let Err(err) = validate_id(&id) {
         return Ok(command_error_result(err));
     }
+    if let Some(ref parent_id) = parent_id {
+        if let Err(err) = validate_id(parent_id) {
+            return Ok(command_error_result(err));
+        }
+    }
 
     let todo = {
-        let mut guard = app_manager.lock().await;
-        guard.toggle_todo_status(&id).await
+        let mut guard = app_manager.write().await;
+        guard
+            .update_todo(&id, TodoUpdate::new().with_parent(parent_id))
+            .await
     };
 
     match todo {
@@ -325,10 +851,163 @@ pub async fn toggle_todo_status(
     }
 }
 
+/// 获取任务的直接子任务
+#[tauri::command]
+pub async fn get_subtasks(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    parent_id: String,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    if let Err(err) = validate_id(&parent_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let subtasks = {
+        let guard = app_manager.read().await;
+        guard.get_subtasks(&parent_id).await
+    };
+
+    match subtasks {
+        Ok(subtasks) => Ok(CommandResult::success(subtasks)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取任务的全部备注
+#[tauri::command]
+pub async fn get_todo_notes(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    todo_id: String,
+) -> Result<CommandResult<Vec<TodoNote>>, CommandError> {
+    if let Err(err) = validate_id(&todo_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let notes = {
+        let guard = app_manager.read().await;
+        guard.get_todo_notes(&todo_id).await
+    };
+
+    match notes {
+        Ok(notes) => Ok(CommandResult::success(notes)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 删除一条备注
+#[tauri::command]
+pub async fn delete_todo_note(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    note_id: String,
+) -> Result<CommandResult<()>, CommandError> {
+    if let Err(err) = validate_id(&note_id) {
+        return Ok(command_error_result(err));
+    }
+
+    let result = {
+        let guard = app_manager.read().await;
+        guard.delete_todo_note(&note_id).await
+    };
+
+    match result {
+        Ok(_) => Ok(CommandResult::success(())),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取没有任何标签的未完成任务
+#[tauri::command]
+pub async fn get_untagged_todos(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+) -> Result<CommandResult<Vec<Todo>>, CommandError> {
+    let todos = {
+        let guard = app_manager.read().await;
+        guard.get_untagged_todos().await
+    };
+
+    match todos {
+        Ok(todos) => Ok(CommandResult::success(todos)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 按天获取任务状态分布（本地日期，含首尾）
+#[tauri::command]
+pub async fn get_status_distribution_by_day(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<CommandResult<Vec<(NaiveDate, TodoStats)>>, CommandError> {
+    let distribution = {
+        let guard = app_manager.read().await;
+        guard.get_status_distribution_by_day(from, to).await
+    };
+
+    match distribution {
+        Ok(distribution) => Ok(CommandResult::success(distribution)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 批量获取任务的工作番茄数（看板显示用），没有记录的任务为 0
+#[tauri::command]
+pub async fn get_pomodoro_counts(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    ids: Vec<String>,
+) -> Result<CommandResult<HashMap<String, u32>>, CommandError> {
+    let counts = {
+        let guard = app_manager.read().await;
+        guard.get_pomodoro_counts(&ids).await
+    };
+
+    match counts {
+        Ok(counts) => Ok(CommandResult::success(counts)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 获取最近若干天平均每天完成的任务数
+#[tauri::command]
+pub async fn get_completion_velocity(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    days: u32,
+) -> Result<CommandResult<f32>, CommandError> {
+    let velocity = {
+        let guard = app_manager.read().await;
+        guard.get_completion_velocity(days).await
+    };
+
+    match velocity {
+        Ok(velocity) => Ok(CommandResult::success(velocity)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
+/// 切换任务状态
+#[tauri::command]
+pub async fn toggle_todo_status(
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
+    id: String,
+) -> Result<CommandResult<ToggleResult>, CommandError> {
+    // 验证ID
+    if let Err(err) = validate_id(&id) {
+        return Ok(command_error_result(err));
+    }
+
+    let result = {
+        let mut guard = app_manager.write().await;
+        guard.toggle_todo_status(&id).await
+    };
+
+    match result {
+        Ok(result) => Ok(CommandResult::success(result)),
+        Err(e) => Ok(command_error_result(e)),
+    }
+}
+
 /// 显式设置任务状态
 #[tauri::command]
 pub async fn set_todo_status(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
     status: String,
 ) -> Result<CommandResult<Todo>, CommandError> {
@@ -341,7 +1020,7 @@ pub async fn set_todo_status(
     };
 
     let todo = {
-        let mut guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.set_todo_status(&id, parsed_status).await
     };
 
@@ -354,7 +1033,7 @@ pub async fn set_todo_status(
 /// 关联待办事项与 GitHub Issue / Project
 #[tauri::command]
 pub async fn link_todo_github(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
     issue_id: i64,
     issue_number: i64,
@@ -374,7 +1053,7 @@ pub async fn link_todo_github(
     }
 
     let todo = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard
             .link_todo_github(&id, issue_id, issue_number, project_id)
             .await
@@ -389,7 +1068,7 @@ pub async fn link_todo_github(
 /// 清除待办事项的 GitHub 关联
 #[tauri::command]
 pub async fn clear_todo_github_link(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
     id: String,
 ) -> Result<CommandResult<Todo>, CommandError> {
     if let Err(err) = validate_id(&id) {
@@ -397,7 +1076,7 @@ pub async fn clear_todo_github_link(
     }
 
     let todo = {
-        let mut guard = app_manager.lock().await;
+        let mut guard = app_manager.write().await;
         guard.clear_todo_github_link(&id).await
     };
 
@@ -410,10 +1089,10 @@ pub async fn clear_todo_github_link(
 /// 获取任务统计
 #[tauri::command]
 pub async fn get_todo_stats(
-    app_manager: State<'_, Arc<Mutex<PomodoroAppManager>>>,
+    app_manager: State<'_, Arc<RwLock<PomodoroAppManager>>>,
 ) -> Result<CommandResult<serde_json::Value>, CommandError> {
     let todos = {
-        let guard = app_manager.lock().await;
+        let guard = app_manager.read().await;
         guard.get_todos().await
     };
 
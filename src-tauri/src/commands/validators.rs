@@ -23,6 +23,27 @@ pub fn validate_todo_description(description: &str) -> Result<(), CommandError>
     Ok(())
 }
 
+/// 验证标签名称
+pub fn validate_tag_name(name: &str) -> Result<(), CommandError> {
+    if name.trim().is_empty() {
+        return Err(CommandError::Validation("标签名称不能为空".to_string()));
+    }
+    if name.len() > 50 {
+        return Err(CommandError::Validation("标签名称不能超过50字符".to_string()));
+    }
+    Ok(())
+}
+
+/// 验证标签颜色（简单的 hex 颜色代码）
+pub fn validate_tag_color(color: &str) -> Result<(), CommandError> {
+    if !color.starts_with('#') || color.len() != 7 {
+        return Err(CommandError::Validation(
+            "颜色格式无效，应为 #RRGGBB 格式".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// 验证ID格式
 pub fn validate_id(id: &str) -> Result<(), CommandError> {
     if id.trim().is_empty() {
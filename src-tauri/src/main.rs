@@ -1,11 +1,11 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use pomoflow_rs::PomodoroAppManager;
+use pomoflow_rs::{AppEvent, EventReceiver, PomodoroAppManager, PomodoroEvent, SoundPlayer};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use std::time::Duration;
 use tauri::Manager;
 
@@ -14,6 +14,9 @@ mod commands;
 use commands::*;
 
 fn main() {
+    // 将日志输出到终端，初始化进度改由 tracing 记录
+    tracing_subscriber::fmt::init();
+
     // 设置 panic hook
     std::panic::set_hook(Box::new(|panic_info| {
         eprintln!("=== PANIC OCCURRED ===");
@@ -38,17 +41,14 @@ fn main() {
         // 初始化全局应用管理器
         .setup(|app| {
             let app_handle = app.handle().clone();
-            println!("🔧 Tauri Setup 开始 - 同步初始化模式");
+            tracing::info!("Tauri setup 开始（同步初始化）");
 
             // 使用 block_on 同步等待初始化完成，确保状态在命令调用前已设置
             let result = tauri::async_runtime::block_on(async move {
-                println!("🔄 开始初始化应用管理器...");
                 let app_handle_for_events = app_handle.clone();
 
                 match initialize_app_manager_sync(app_handle).await {
                     Ok(app_manager_arc) => {
-                        println!("✅ 应用管理器初始化完成");
-
                         // 启动事件系统（异步，不阻塞）
                         let app_manager_for_events = app_manager_arc.clone();
                         tauri::async_runtime::spawn(async move {
@@ -60,7 +60,7 @@ fn main() {
                         Ok(app_manager_arc)
                     }
                     Err(e) => {
-                        eprintln!("❌ 应用管理器初始化失败: {}", e);
+                        tracing::error!("应用管理器初始化失败: {}", e);
                         Err(e)
                     }
                 }
@@ -68,14 +68,14 @@ fn main() {
 
             match result {
                 Ok(_) => {
-                    println!("✅ Tauri Setup 完成，状态已就绪");
+                    tracing::info!("Tauri setup 完成，状态已就绪");
                     if let Err(e) = app.emit_all("initialization-complete", "ok") {
                         eprintln!("Failed to emit initialization-complete: {}", e);
                     }
                     Ok(())
                 }
                 Err(e) => {
-                    eprintln!("❌ Tauri Setup 失败: {}", e);
+                    tracing::error!("Tauri setup 失败: {}", e);
                     let err_msg = format!("Failed to initialize app: {}", e);
                     if let Err(emit_err) = app.emit_all("initialization-error", err_msg.clone()) {
                         eprintln!("Failed to emit initialization-error: {}", emit_err);
@@ -88,34 +88,105 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // 番茄钟命令
             start_pomodoro,
+            start_deep_work,
             pause_pomodoro,
+            set_tick_interval,
+            abandon_pomodoro,
+            set_focus_todo,
+            start_pomodoro_for_todo,
             reset_pomodoro,
+            reset_cycle_count,
             skip_pomodoro_phase,
+            acknowledge_pomodoro_phase,
+            record_pomodoro_interruption,
             get_pomodoro_session,
+            get_formatted_remaining,
+            project_goal_completion,
+            get_goal_progress,
+            get_daily_goal_progress,
+            is_daily_goal_met,
+            get_lifetime_focus,
+            get_top_focused_todos,
+            get_pomodoro_stats,
+            get_best_weekday,
+            suggest_daily_goal,
+            get_session_history_page,
+            get_current_phase_color,
+            end_break_now,
+            get_widget_payload,
+            report_idle_time,
+            start_focus_block,
+            get_focus_block_progress,
             update_pomodoro_config,
+            get_pomodoro_config_view,
             // 待办事项命令
             create_todo,
+            get_completed_today_count,
             update_todo,
             delete_todo,
+            move_todo,
+            reorder_todos,
+            restore_todo,
+            get_deleted_todos,
+            purge_deleted_todo,
+            clear_completed_todos,
+            undo_last_bulk_operation,
+            import_all_dry_run,
+            import_all,
+            import_todos,
+            import_markdown,
             toggle_todo_status,
             set_todo_status,
             link_todo_github,
             clear_todo_github_link,
             get_todos,
+            get_todos_page,
+            search_todos,
+            full_text_search_todos,
+            get_todos_sorted,
+            get_todos_for_review,
+            get_todos_completed_between,
+            generate_plan,
+            export_todos_ics,
             get_todo_stats,
+            suggest_next_todo,
+            estimate_backlog_time,
             // 标签命令
             get_tags,
             create_tag,
+            update_tag,
             delete_tag,
             assign_tag_to_todo,
             remove_tag_from_todo,
             get_todo_tags,
+            get_tag_focus_time,
+            get_untagged_todos,
+            get_completion_velocity,
+            get_pomodoro_counts,
+            get_status_distribution_by_day,
+            add_todo_note,
+            get_todo_notes,
+            create_subtask,
+            set_todo_parent,
+            get_subtasks,
+            get_todo_progress,
+            delete_todo_note,
             // 配置管理命令
             get_user_config,
+            get_initial_payload,
+            get_recent_messages,
+            mark_messages_read,
+            get_unread_message_count,
+            get_stuck_tasks,
+            clear_message_history,
+            factory_reset,
+            run_database_maintenance,
             get_github_sync_config,
             save_user_config,
             // 同步命令
-            run_github_sync
+            run_github_sync,
+            requeue_todo_sync,
+            requeue_all_sync
         ])
         .run(tauri::generate_context!())
         .map_err(|e| {
@@ -124,58 +195,108 @@ fn main() {
         });
 }
 
+/// 消费应用事件：任务完成与番茄钟完成分别播放各自的音效，并按会话状态启停专注背景音；
+/// 阶段即将结束的提醒转发给前端
+async fn handle_app_events(
+    app_handle: tauri::AppHandle,
+    mut events: EventReceiver,
+    mut sound_player: SoundPlayer,
+    app_manager: Arc<RwLock<PomodoroAppManager>>,
+) {
+    while let Some(event) = events.recv().await {
+        if let AppEvent::UserConfigUpdated(config) = &event {
+            sound_player.update_from_config(config);
+        }
+
+        if let AppEvent::PomodoroEvent(PomodoroEvent::PhaseEndingSoon { remaining }) = &event {
+            let payload = serde_json::json!({ "remaining": remaining });
+            if let Err(e) = app_handle.emit_all("pomodoro-ending-soon", payload) {
+                eprintln!("Failed to emit pomodoro-ending-soon event: {}", e);
+            }
+        }
+
+        // 会话每次更新（开始、暂停、重置、放弃、计时与阶段切换）都会发出 PomodoroStarted
+        if matches!(event, AppEvent::PomodoroStarted | AppEvent::UserConfigUpdated(_)) {
+            let session = {
+                let guard = app_manager.read().await;
+                guard.get_pomodoro_session().await
+            };
+            if let Err(e) = sound_player.sync_focus_loop(session.ok().flatten().as_ref()) {
+                eprintln!("Failed to update focus sound: {}", e);
+            }
+        }
+
+        if let Some(effect) = sound_player.effect_for_event(&event) {
+            let player = sound_player.clone();
+            // 播放会阻塞（多声提示音之间有停顿），放到阻塞线程执行
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = player.play(&effect) {
+                    eprintln!("Failed to play sound: {}", e);
+                }
+            });
+        }
+    }
+}
+
 /// 初始化全局应用管理器（同步版本，在 setup 中使用 block_on 调用）
 async fn initialize_app_manager_sync(
     app_handle: tauri::AppHandle,
-) -> Result<Arc<Mutex<PomodoroAppManager>>, Box<dyn std::error::Error>> {
+) -> Result<Arc<RwLock<PomodoroAppManager>>, Box<dyn std::error::Error>> {
     use std::time::Instant;
 
     let total_start = Instant::now();
-    println!("🚀 Initializing PomodoroFlow-Rs Tauri App (同步模式)...");
 
     // 创建应用管理器实例
     let manager_start = Instant::now();
-    let mut app_manager = PomodoroAppManager::new().await?;
+    // 初始化进度转发给前端启动画面
+    let progress_handle = app_handle.clone();
+    let mut app_manager = PomodoroAppManager::new_with_progress(move |event| {
+        if let AppEvent::InitProgress { step, elapsed_ms } = event {
+            let payload = serde_json::json!({
+                "step": step,
+                "label": step.to_string(),
+                "elapsed_ms": elapsed_ms,
+            });
+            if let Err(e) = progress_handle.emit_all("app-init-progress", payload) {
+                eprintln!("Failed to emit app-init-progress event: {}", e);
+            }
+        }
+    })
+    .await?;
     let manager_elapsed = manager_start.elapsed();
-    println!("   ├── 应用管理器创建: {:.2?}", manager_elapsed);
 
     // 启动应用服务
     let service_start = Instant::now();
-    println!("   ├── 开始调用 app_manager.start()...");
     let service_result = app_manager.start().await;
     let service_elapsed = service_start.elapsed();
 
-    match service_result {
-        Ok(_) => {
-            println!("   ├── 核心服务启动成功: {:.2?}", service_elapsed);
-        }
-        Err(e) => {
-            println!("   ├── 核心服务启动失败 (耗时: {:.2?}): {}", service_elapsed, e);
-            return Err(Box::new(e) as Box<dyn std::error::Error>);
-        }
+    if let Err(e) = service_result {
+        tracing::error!("核心服务启动失败 (耗时: {:.2?}): {}", service_elapsed, e);
+        return Err(Box::new(e) as Box<dyn std::error::Error>);
     }
 
+    // 应用事件处理：按事件播放音效
+    let events = EventReceiver::new(app_manager.subscribe());
+    let sound_player = SoundPlayer::from_config(&app_manager.get_user_config().await?.unwrap_or_default());
+
     // 将应用管理器存储到全局状态（关键：必须在 setup 返回前完成）
-    let app_manager_arc = Arc::new(Mutex::new(app_manager));
+    let app_manager_arc = Arc::new(RwLock::new(app_manager));
     app_handle.manage(app_manager_arc.clone());
+    tauri::async_runtime::spawn(handle_app_events(
+        app_handle.clone(),
+        events,
+        sound_player,
+        app_manager_arc.clone(),
+    ));
 
     let total_elapsed = total_start.elapsed();
 
-    // 打印启动报告
-    println!("\n📊 性能统计:");
-    println!("   ├── 应用管理器创建: {:.2?}", manager_elapsed);
-    println!("   ├── 核心服务启动: {:.2?}", service_elapsed);
-    println!("   └── 总初始化时间: {:.2?}", total_elapsed);
-
-    if total_elapsed.as_millis() > 3000 {
-        println!("⚠️  注意：初始化时间超过3秒，建议进一步优化");
-    } else if total_elapsed.as_millis() > 1000 {
-        println!("📈 初始化时间在可接受范围内");
-    } else {
-        println!("🎉 初始化性能优秀！");
-    }
-
-    println!("✅ PomodoroFlow-Rs Tauri App initialized successfully!");
+    tracing::info!(
+        "初始化完成：应用管理器 {:.2?}，核心服务 {:.2?}，共 {:.2?}",
+        manager_elapsed,
+        service_elapsed,
+        total_elapsed
+    );
 
     Ok(app_manager_arc)
 }
@@ -183,7 +304,7 @@ async fn initialize_app_manager_sync(
 /// 启动事件系统后台任务
 async fn start_event_system(
     app_handle: tauri::AppHandle,
-    app_manager: Arc<Mutex<PomodoroAppManager>>,
+    app_manager: Arc<RwLock<PomodoroAppManager>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let app_handle_clone = app_handle.clone();
 
@@ -199,7 +320,7 @@ async fn start_event_system(
 
             // 使用异步锁
             let result = {
-                let guard = app_manager.lock().await;
+                let guard = app_manager.read().await;
                 guard.get_pomodoro_session().await
             };
 
@@ -230,23 +351,20 @@ async fn start_event_system(
                             }
                         }
 
-                        // 通过“上一拍运行中 + 阶段发生变化”的边沿检测阶段完成。
-                        // 兼容后端自动启动下一阶段（阶段切换时仍保持 is_running=true）。
-                        if let Some(last) = &last_session {
-                            let phase_completed =
-                                last.is_running &&
-                                (pomodoro_session.phase != last.phase || pomodoro_session.cycle_count != last.cycle_count);
-
-                            if phase_completed {
-                                if let Err(e) = app_handle_clone
-                                    .emit_all("pomodoro-phase-completed", &pomodoro_session) {
-                                    eprintln!("Failed to emit pomodoro-phase-completed event: {}", e);
-                                }
+                        // 轮询后端的一次性完成标记，每个完成的阶段只通知一次
+                        let completed = {
+                            let guard = app_manager.read().await;
+                            guard.take_completed_phase().await
+                        };
+                        if let Ok(Some(_)) = completed {
+                            if let Err(e) = app_handle_clone
+                                .emit_all("pomodoro-phase-completed", &pomodoro_session) {
+                                eprintln!("Failed to emit pomodoro-phase-completed event: {}", e);
+                            }
 
-                                let phase_str = format!("{:?}", pomodoro_session.phase);
-                                if let Err(e) = app_handle_clone.emit_all("show-pomodoro-notification", &phase_str) {
-                                    eprintln!("Failed to emit show-pomodoro-notification event: {}", e);
-                                }
+                            let phase_str = format!("{:?}", pomodoro_session.phase);
+                            if let Err(e) = app_handle_clone.emit_all("show-pomodoro-notification", &phase_str) {
+                                eprintln!("Failed to emit show-pomodoro-notification event: {}", e);
                             }
                         }
 
@@ -2,4 +2,4 @@
 
 pub mod task_manager;
 
-pub use task_manager::TaskManager;
+pub use task_manager::{TaskManager, TaskMetadataView};
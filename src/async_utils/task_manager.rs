@@ -3,14 +3,18 @@
 use std::collections::HashMap;
 use std::future::Future;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
 use crate::core::error::{AppError, Result};
 
 /// 任务状态
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
     Pending,
     Running,
@@ -24,8 +28,8 @@ pub enum TaskStatus {
 pub struct TaskMetadata {
     pub name: String,
     pub status: TaskStatus,
-    pub created_at: std::time::Instant,
-    pub started_at: Option<std::time::Instant>,
+    pub created_at: Instant,
+    pub started_at: Option<Instant>,
     pub error: Option<String>,
 }
 
@@ -34,17 +38,70 @@ impl TaskMetadata {
         Self {
             name,
             status: TaskStatus::Pending,
-            created_at: std::time::Instant::now(),
+            created_at: Instant::now(),
             started_at: None,
             error: None,
         }
     }
+
+    /// 自开始运行（未开始时自创建）起经过的时间
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.unwrap_or(self.created_at).elapsed()
+    }
+
+    /// 是否已经结束（完成、失败或取消）
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
+        )
+    }
+}
+
+/// 单个任务的元数据，由任务自身在开始和结束时更新
+type SharedMetadata = Arc<StdMutex<TaskMetadata>>;
+
+/// 读取元数据快照；任务句柄已结束但状态仍未收尾时（例如任务 panic），视为失败
+fn snapshot(handle: &JoinHandle<Result<()>>, metadata: &SharedMetadata) -> TaskMetadata {
+    let mut snapshot = metadata
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if handle.is_finished() && !snapshot.is_finished() {
+        snapshot.status = TaskStatus::Failed;
+        snapshot.error = Some("任务异常终止".to_string());
+    }
+    snapshot
+}
+
+/// 可序列化的任务概况，供调试面板展示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskMetadataView {
+    pub name: String,
+    pub status: TaskStatus,
+    /// 已运行的秒数
+    pub running_seconds: u64,
+    pub error: Option<String>,
+}
+
+impl From<&TaskMetadata> for TaskMetadataView {
+    fn from(metadata: &TaskMetadata) -> Self {
+        Self {
+            name: metadata.name.clone(),
+            status: metadata.status.clone(),
+            running_seconds: metadata.elapsed().as_secs(),
+            error: metadata.error.clone(),
+        }
+    }
 }
 
+/// 任务表：任务名 -> (句柄, 元数据)
+type TaskMap = HashMap<String, (JoinHandle<Result<()>>, SharedMetadata)>;
+
 /// 任务管理器
 #[derive(Debug)]
 pub struct TaskManager {
-    tasks: Arc<Mutex<HashMap<String, (JoinHandle<Result<()>>, TaskMetadata)>>>,
+    tasks: Arc<Mutex<TaskMap>>,
 }
 
 // 安全实现 Send，因为所有字段都是 Arc 包装的
@@ -59,6 +116,9 @@ impl TaskManager {
     }
 
     /// 启动后台任务
+    ///
+    /// 任务登记为 `Pending`，真正开始执行时转为 `Running` 并记录 `started_at`，
+    /// 结束后按返回值记为 `Completed` 或 `Failed`（附错误信息）。
     pub async fn spawn<F, Fut>(&self, name: String, future: F) -> Result<()>
     where
         F: FnOnce() -> Fut + Send + 'static,
@@ -70,9 +130,29 @@ impl TaskManager {
             return Err(AppError::InvalidState(format!("任务 '{}' 已存在", name)));
         }
 
-        let handle = tokio::spawn(future());
+        let metadata: SharedMetadata = Arc::new(StdMutex::new(TaskMetadata::new(name.clone())));
+        let tracked = Arc::clone(&metadata);
+        let task = future();
+        let handle = tokio::spawn(async move {
+            {
+                let mut metadata = tracked.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                metadata.status = TaskStatus::Running;
+                metadata.started_at = Some(Instant::now());
+            }
+
+            let result = task.await;
+
+            let mut metadata = tracked.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            match &result {
+                Ok(()) => metadata.status = TaskStatus::Completed,
+                Err(err) => {
+                    metadata.status = TaskStatus::Failed;
+                    metadata.error = Some(err.to_string());
+                }
+            }
+            result
+        });
 
-        let metadata = TaskMetadata::new(name.clone());
         tasks.insert(name, (handle, metadata));
 
         Ok(())
@@ -126,10 +206,27 @@ impl TaskManager {
         let tasks = self.tasks.lock().await;
         tasks
             .iter()
-            .map(|(name, (_, metadata))| (name.clone(), metadata.clone()))
+            .map(|(name, (handle, metadata))| (name.clone(), snapshot(handle, metadata)))
             .collect()
     }
 
+    /// 运行时间超过 `threshold` 且尚未结束的任务，按运行时间从长到短排列
+    pub async fn long_running_tasks(&self, threshold: Duration) -> Vec<TaskMetadataView> {
+        let tasks = self.tasks.lock().await;
+        let mut stuck: Vec<(Duration, TaskMetadataView)> = tasks
+            .values()
+            .filter(|(handle, _)| !handle.is_finished())
+            .map(|(handle, metadata)| snapshot(handle, metadata))
+            .map(|metadata| (metadata.elapsed(), metadata))
+            .filter(|(elapsed, _)| *elapsed > threshold)
+            .map(|(elapsed, metadata)| (elapsed, TaskMetadataView::from(&metadata)))
+            .collect();
+        stuck.sort_by(|(a, view_a), (b, view_b)| {
+            b.cmp(a).then_with(|| view_a.name.cmp(&view_b.name))
+        });
+        stuck.into_iter().map(|(_, view)| view).collect()
+    }
+
     /// 检查任务是否存在
     pub async fn exists(&self, name: &str) -> bool {
         let tasks = self.tasks.lock().await;
@@ -263,7 +360,9 @@ impl TaskNames {
     pub const BACKUP: &'static str = "backup";
     pub const CLEANUP: &'static str = "cleanup";
     pub const POMODORO_TICK: &'static str = "pomodoro-tick";
+    pub const SESSION_AUTOSAVE: &'static str = "session-autosave";
     pub const NOTIFICATION: &'static str = "notification";
+    pub const MAINTENANCE: &'static str = "maintenance";
 }
 
 /// 创建常用信号量
@@ -345,6 +444,76 @@ mod tests {
         assert!(!manager.exists(&name).await);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_long_running_tasks() {
+        let manager = TaskManager::new();
+        manager
+            .spawn("old".to_string(), || async {
+                sleep(Duration::from_secs(10)).await;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        manager
+            .spawn("finished".to_string(), || async { Ok(()) })
+            .await
+            .unwrap();
+        tokio::task::yield_now().await;
+        tokio::time::advance(Duration::from_millis(60)).await;
+        manager
+            .spawn("fresh".to_string(), || async {
+                sleep(Duration::from_secs(10)).await;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        tokio::task::yield_now().await;
+
+        let stuck = manager.long_running_tasks(Duration::from_millis(40)).await;
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].name, "old");
+        assert_eq!(stuck[0].status, TaskStatus::Running);
+        assert!(manager.long_running_tasks(Duration::from_secs(5)).await.is_empty());
+
+        manager.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_task_status_follows_execution() {
+        let manager = TaskManager::new();
+        manager
+            .spawn("ok".to_string(), || async { Ok(()) })
+            .await
+            .unwrap();
+        manager
+            .spawn("broken".to_string(), || async {
+                Err(AppError::Network("连接被拒绝".to_string()))
+            })
+            .await
+            .unwrap();
+        manager
+            .spawn("slow".to_string(), || async {
+                sleep(Duration::from_secs(10)).await;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(50)).await;
+
+        let tasks = manager.list_tasks().await;
+        assert_eq!(tasks["ok"].status, TaskStatus::Completed);
+        assert!(tasks["ok"].started_at.is_some());
+        assert_eq!(tasks["broken"].status, TaskStatus::Failed);
+        assert_eq!(
+            tasks["broken"].error.as_deref(),
+            Some(AppError::Network("连接被拒绝".to_string()).to_string().as_str())
+        );
+        assert_eq!(tasks["slow"].status, TaskStatus::Running);
+        assert!(tasks["slow"].error.is_none());
+
+        manager.shutdown().await;
+    }
+
     #[tokio::test]
     async fn test_spawn_with_retry() {
         let manager = TaskManager::new();
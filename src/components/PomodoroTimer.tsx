@@ -3,7 +3,7 @@ import { useAppStore } from "../stores/appStore";
 import "../styles/PomodoroTimer.css";
 import "../styles/PomodoroCard.css";
 
-type Phase = "work" | "short_break" | "long_break";
+type Phase = "work" | "short_break" | "long_break" | "deep_work";
 
 export function PomodoroTimer() {
   const store = useAppStore();
@@ -30,6 +30,8 @@ export function PomodoroTimer() {
         return userConfig.pomodoro_short_break_duration;
       case "long_break":
         return userConfig.pomodoro_long_break_duration;
+      case "deep_work":
+        return userConfig.pomodoro_deep_work_duration ?? 5400;
       default:
         return 1500;
     }
@@ -52,6 +54,8 @@ export function PomodoroTimer() {
         return "短暂休息";
       case "long_break":
         return "长休息";
+      case "deep_work":
+        return "深度工作";
       default:
         return "准备开始";
     }
@@ -60,6 +64,7 @@ export function PomodoroTimer() {
   const getPhaseClass = (currentPhase: Phase) => {
     switch (currentPhase) {
       case "work":
+      case "deep_work":
         return "phase-work";
       case "short_break":
         return "phase-short-break";
@@ -78,6 +83,8 @@ export function PomodoroTimer() {
         return "🍅";
       case "long_break":
         return "☕️";
+      case "deep_work":
+        return "🧠";
       default:
         return "⏱️";
     }
@@ -333,6 +333,14 @@ export function TodoList() {
               <div className="ios-todo-content">
                 <span className="ios-todo-title">{todo.title}</span>
 
+                {(todo.pomodoro_count ?? 0) > 0 && (
+                  <div className="ios-todo-meta">
+                    <span className="todo-pomodoro-count">
+                      🍅 {todo.pomodoro_count}
+                    </span>
+                  </div>
+                )}
+
                 {typeof todo.github_issue_number === "number" && (
                   <div className="ios-todo-meta">
                     <span className="todo-github-link">
@@ -2,6 +2,40 @@
 
 use thiserror::Error;
 
+/// 校验失败的具体原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationKind {
+    TitleEmpty,
+    TitleTooLong,
+    DescriptionTooLong,
+    WorkDurationOutOfRange,
+    ShortBreakDurationOutOfRange,
+    LongBreakDurationOutOfRange,
+    CyclesUntilLongBreakOutOfRange,
+    DeepWorkDurationOutOfRange,
+    PhaseSequenceInvalid,
+    NoteEmpty,
+    NoteTooLong,
+}
+
+impl ValidationKind {
+    /// 对应的输入字段名，便于前端高亮
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationKind::TitleEmpty | ValidationKind::TitleTooLong => "title",
+            ValidationKind::DescriptionTooLong => "description",
+            ValidationKind::WorkDurationOutOfRange => "work_duration",
+            ValidationKind::ShortBreakDurationOutOfRange => "short_break_duration",
+            ValidationKind::LongBreakDurationOutOfRange => "long_break_duration",
+            ValidationKind::CyclesUntilLongBreakOutOfRange => "cycles_until_long_break",
+            ValidationKind::DeepWorkDurationOutOfRange => "deep_work_duration",
+            ValidationKind::PhaseSequenceInvalid => "phase_sequence",
+            ValidationKind::NoteEmpty | ValidationKind::NoteTooLong => "note",
+        }
+    }
+}
+
 /// 应用统一错误类型
 #[derive(Debug, Error)]
 pub enum AppError {
@@ -26,6 +60,12 @@ pub enum AppError {
     #[error("Validation failed: {0}")]
     Validation(String),
 
+    #[error("Validation failed: {message}")]
+    ValidationDetailed {
+        kind: ValidationKind,
+        message: String,
+    },
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -46,6 +86,30 @@ pub enum AppError {
 }
 
 impl AppError {
+    /// 创建带具体原因的校验错误
+    pub fn validation(kind: ValidationKind, message: impl Into<String>) -> Self {
+        AppError::ValidationDetailed {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// 判断是否是校验错误（含带原因的校验错误）
+    pub fn is_validation(&self) -> bool {
+        matches!(
+            self,
+            AppError::Validation(_) | AppError::ValidationDetailed { .. }
+        )
+    }
+
+    /// 获取校验错误的具体原因
+    pub fn validation_kind(&self) -> Option<ValidationKind> {
+        match self {
+            AppError::ValidationDetailed { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
     /// 判断错误是否可重试
     pub fn is_retryable(&self) -> bool {
         matches!(self, AppError::Network(_) | AppError::Timeout)
@@ -63,7 +127,9 @@ impl AppError {
             AppError::Network(_) => "Network error, please check connection".to_string(),
             AppError::Authentication(_) => "Authentication failed".to_string(),
             AppError::Timeout => "Request timeout, please check connection".to_string(),
-            AppError::Validation(msg) => format!("Input validation failed: {}", msg),
+            AppError::Validation(msg) | AppError::ValidationDetailed { message: msg, .. } => {
+                format!("Input validation failed: {}", msg)
+            }
             AppError::NotFound(msg) => format!("Not found: {}", msg),
             AppError::InvalidState(msg) => format!("State error: {}", msg),
             AppError::Serialization(_) => "Data serialization error".to_string(),
@@ -78,7 +144,7 @@ impl AppError {
             AppError::Network(_) => "E003",
             AppError::Authentication(_) => "E004",
             AppError::Timeout => "E005",
-            AppError::Validation(_) => "E007",
+            AppError::Validation(_) | AppError::ValidationDetailed { .. } => "E007",
             AppError::NotFound(_) => "E008",
             AppError::InvalidState(_) => "E010",
             AppError::Serialization(_) => "E011",
@@ -96,3 +162,21 @@ impl From<anyhow::Error> for AppError {
         AppError::Other(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AppError, ValidationKind};
+
+    #[test]
+    fn detailed_validation_behaves_like_validation() {
+        let err = AppError::validation(ValidationKind::TitleTooLong, "too long");
+        assert!(err.is_validation());
+        assert_eq!(err.code(), "E007");
+        assert_eq!(err.validation_kind(), Some(ValidationKind::TitleTooLong));
+        assert_eq!(err.to_string(), "Validation failed: too long");
+
+        let plain = AppError::Validation("bad".to_string());
+        assert!(plain.is_validation());
+        assert_eq!(plain.validation_kind(), None);
+    }
+}
@@ -309,10 +309,9 @@ impl GithubSyncClient {
         if let Some(errors) = body.get("errors") {
             return Err(AppError::Network(format!("GitHub GraphQL 返回错误: {errors}")));
         }
-        Ok(body
-            .get("data")
+        body.get("data")
             .cloned()
-            .ok_or_else(|| AppError::Network("GraphQL 响应缺少 data 字段".to_string()))?)
+            .ok_or_else(|| AppError::Network("GraphQL 响应缺少 data 字段".to_string()))
     }
 
     async fn find_project_item_status_context(
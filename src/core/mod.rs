@@ -10,7 +10,7 @@ pub mod state_updater;
 pub mod todo;
 
 pub use error::{AppError, Result};
-pub use pomodoro::{PomodoroConfig, PomodoroPhase, PomodoroService, PomodoroSession};
+pub use pomodoro::{FocusBlock, PauseCounts, PauseReason, PomodoroConfig, PomodoroPhase, PomodoroService, PomodoroSession};
 pub use state::{AppStateManager, UserConfig};
 pub use state_updater::{StateUpdater, StateUpdaterConfig};
-pub use todo::{NewTodo, Tag, Todo, TodoFilter, TodoService, TodoStatus, TodoUpdate};
+pub use todo::{ImportPlan, InitialStatusPolicy, NewTodo, SortKey, Tag, TagSort, Todo, TodoFilter, TodoPriority, TodoService, TodoStatus, TodoUpdate};
@@ -3,8 +3,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::time::Instant;
+use uuid::Uuid;
 
-use crate::core::error::{AppError, Result};
+use crate::core::error::{AppError, Result, ValidationKind};
 
 /// 番茄钟阶段
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -13,6 +14,8 @@ pub enum PomodoroPhase {
     Work,
     ShortBreak,
     LongBreak,
+    /// 长时段深度工作，按工作阶段计入周期与专注统计
+    DeepWork,
 }
 
 impl PomodoroPhase {
@@ -22,6 +25,7 @@ impl PomodoroPhase {
             PomodoroPhase::Work => "Work",
             PomodoroPhase::ShortBreak => "Short Break",
             PomodoroPhase::LongBreak => "Long Break",
+            PomodoroPhase::DeepWork => "Deep Work",
         }
     }
 
@@ -31,6 +35,7 @@ impl PomodoroPhase {
             PomodoroPhase::Work => 1500,      // 25 minutes
             PomodoroPhase::ShortBreak => 300, // 5 minutes
             PomodoroPhase::LongBreak => 900,  // 15 minutes
+            PomodoroPhase::DeepWork => 5400,  // 90 minutes
         }
     }
 
@@ -40,15 +45,21 @@ impl PomodoroPhase {
             PomodoroPhase::Work => "#e74c3c",       // red
             PomodoroPhase::ShortBreak => "#3498db", // blue
             PomodoroPhase::LongBreak => "#9b59b6",  // purple
+            PomodoroPhase::DeepWork => "#c0392b",   // dark red
         }
     }
 
+    /// Whether this is a focus phase (work or deep work)
+    pub fn is_work(&self) -> bool {
+        matches!(self, PomodoroPhase::Work | PomodoroPhase::DeepWork)
+    }
+
     /// Get next phase
     pub fn next(&self, cycle_count: u32, cycles_until_long_break: u32) -> PomodoroPhase {
         match self {
-            PomodoroPhase::Work => {
+            PomodoroPhase::Work | PomodoroPhase::DeepWork => {
                 // After work ends, check if long break is needed
-                if (cycle_count + 1) % cycles_until_long_break == 0 {
+                if (cycle_count + 1).is_multiple_of(cycles_until_long_break) {
                     PomodoroPhase::LongBreak
                 } else {
                     PomodoroPhase::ShortBreak
@@ -69,6 +80,28 @@ pub struct PomodoroConfig {
     pub short_break_duration: u64,    // short break duration in seconds
     pub long_break_duration: u64,     // long break duration in seconds
     pub cycles_until_long_break: u32, // work cycles until long break
+    #[serde(default = "default_deep_work_duration")]
+    pub deep_work_duration: u64,      // deep work duration in seconds
+    #[serde(default)]
+    pub auto_start_next: bool,        // keep running after skipping a phase
+    #[serde(default)]
+    pub warn_before_seconds: Option<u64>, // emit PhaseEndingSoon this many seconds before a phase ends
+    #[serde(default)]
+    pub phase_sequence: Option<Vec<PomodoroPhase>>, // custom cyclic phase order replacing the classic cycle
+    #[serde(default)]
+    pub require_phase_ack: bool,      // work phases keep running at zero until acknowledged
+    #[serde(default)]
+    pub break_reminders: bool,        // remind once when an unacknowledged work phase over-runs
+    #[serde(default = "default_reminder_grace_secs")]
+    pub reminder_grace_secs: u64,     // over-run allowed before the break reminder
+}
+
+fn default_deep_work_duration() -> u64 {
+    PomodoroPhase::DeepWork.default_duration()
+}
+
+fn default_reminder_grace_secs() -> u64 {
+    60
 }
 
 impl Default for PomodoroConfig {
@@ -78,6 +111,13 @@ impl Default for PomodoroConfig {
             short_break_duration: 300,  // 5 minutes
             long_break_duration: 900,   // 15 minutes
             cycles_until_long_break: 4, // work cycles until long break
+            deep_work_duration: 5400,   // 90 minutes
+            auto_start_next: false,
+            warn_before_seconds: None,
+            phase_sequence: None,
+            require_phase_ack: false,
+            break_reminders: false,
+            reminder_grace_secs: default_reminder_grace_secs(),
         }
     }
 }
@@ -109,69 +149,203 @@ impl PomodoroConfig {
         self
     }
 
+    pub fn with_phase_sequence(mut self, sequence: Vec<PomodoroPhase>) -> Self {
+        self.phase_sequence = Some(sequence);
+        self
+    }
+
+    /// First phase of a session: the head of the custom sequence, or work
+    pub fn first_phase(&self) -> PomodoroPhase {
+        self.phase_sequence
+            .as_ref()
+            .and_then(|sequence| sequence.first().copied())
+            .unwrap_or(PomodoroPhase::Work)
+    }
+
     /// Get duration for a specific phase
     pub fn get_duration(&self, phase: PomodoroPhase) -> u64 {
         match phase {
             PomodoroPhase::Work => self.work_duration,
             PomodoroPhase::ShortBreak => self.short_break_duration,
             PomodoroPhase::LongBreak => self.long_break_duration,
+            PomodoroPhase::DeepWork => self.deep_work_duration,
         }
     }
 
     /// Validate configuration
     pub fn validate(&self) -> Result<()> {
         if self.work_duration < 60 {
-            return Err(AppError::Validation(
-                "Work duration cannot be less than 1 minute".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::WorkDurationOutOfRange,
+                "Work duration cannot be less than 1 minute",
             ));
         }
 
         if self.work_duration > 7200 {
-            return Err(AppError::Validation(
-                "Work duration cannot exceed 2 hours".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::WorkDurationOutOfRange,
+                "Work duration cannot exceed 2 hours",
             ));
         }
 
         if self.short_break_duration < 60 {
-            return Err(AppError::Validation(
-                "Short break duration cannot be less than 1 minute".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::ShortBreakDurationOutOfRange,
+                "Short break duration cannot be less than 1 minute",
             ));
         }
 
         if self.short_break_duration > 1800 {
-            return Err(AppError::Validation(
-                "Short break duration cannot exceed 30 minutes".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::ShortBreakDurationOutOfRange,
+                "Short break duration cannot exceed 30 minutes",
             ));
         }
 
         if self.long_break_duration < 300 {
-            return Err(AppError::Validation(
-                "Long break duration cannot be less than 5 minutes".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::LongBreakDurationOutOfRange,
+                "Long break duration cannot be less than 5 minutes",
             ));
         }
 
         if self.long_break_duration > 3600 {
-            return Err(AppError::Validation(
-                "Long break duration cannot exceed 1 hour".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::LongBreakDurationOutOfRange,
+                "Long break duration cannot exceed 1 hour",
+            ));
+        }
+
+        if self.deep_work_duration < 1800 {
+            return Err(AppError::validation(
+                ValidationKind::DeepWorkDurationOutOfRange,
+                "Deep work duration cannot be less than 30 minutes",
+            ));
+        }
+
+        if self.deep_work_duration > 14400 {
+            return Err(AppError::validation(
+                ValidationKind::DeepWorkDurationOutOfRange,
+                "Deep work duration cannot exceed 4 hours",
             ));
         }
 
         if self.cycles_until_long_break < 2 {
-            return Err(AppError::Validation(
-                "Long break interval cannot be less than 2 cycles".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::CyclesUntilLongBreakOutOfRange,
+                "Long break interval cannot be less than 2 cycles",
             ));
         }
 
         if self.cycles_until_long_break > 10 {
-            return Err(AppError::Validation(
-                "Long break interval cannot exceed 10 cycles".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::CyclesUntilLongBreakOutOfRange,
+                "Long break interval cannot exceed 10 cycles",
             ));
         }
 
+        if let Some(sequence) = &self.phase_sequence {
+            if sequence.is_empty() {
+                return Err(AppError::validation(
+                    ValidationKind::PhaseSequenceInvalid,
+                    "Phase sequence cannot be empty",
+                ));
+            }
+
+            if !sequence.iter().any(PomodoroPhase::is_work) {
+                return Err(AppError::validation(
+                    ValidationKind::PhaseSequenceInvalid,
+                    "Phase sequence must contain at least one work phase",
+                ));
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Focus block: a chain of work phases started together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusBlock {
+    /// Work phases completed so far in this block.
+    pub completed: u32,
+    /// Work phases planned for this block.
+    pub total: u32,
+}
+
+/// Why the timer was paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    /// Paused by the user.
+    Manual,
+    /// Paused automatically because the user went idle.
+    IdleAuto,
+    /// Paused so that a required break is taken.
+    BreakEnforced,
+}
+
+impl PauseReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PauseReason::Manual => "manual",
+            PauseReason::IdleAuto => "idle_auto",
+            PauseReason::BreakEnforced => "break_enforced",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "manual" => Ok(PauseReason::Manual),
+            "idle_auto" => Ok(PauseReason::IdleAuto),
+            "break_enforced" => Ok(PauseReason::BreakEnforced),
+            other => Err(AppError::Validation(format!("未知的暂停原因: {}", other))),
+        }
+    }
+}
+
+/// Pause counts per reason within one phase run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PauseCounts {
+    pub manual: u32,
+    pub idle_auto: u32,
+    pub break_enforced: u32,
+}
+
+impl PauseCounts {
+    /// Count one more pause for `reason`.
+    pub fn record(&mut self, reason: PauseReason) {
+        *self.slot(reason) += 1;
+    }
+
+    /// Pauses recorded for `reason`.
+    pub fn get(&self, reason: PauseReason) -> u32 {
+        match reason {
+            PauseReason::Manual => self.manual,
+            PauseReason::IdleAuto => self.idle_auto,
+            PauseReason::BreakEnforced => self.break_enforced,
+        }
+    }
+
+    /// Pauses recorded for any reason.
+    pub fn total(&self) -> u32 {
+        self.manual + self.idle_auto + self.break_enforced
+    }
+
+    fn slot(&mut self, reason: PauseReason) -> &mut u32 {
+        match reason {
+            PauseReason::Manual => &mut self.manual,
+            PauseReason::IdleAuto => &mut self.idle_auto,
+            PauseReason::BreakEnforced => &mut self.break_enforced,
+        }
+    }
+}
+
+/// 为一次阶段运行生成新的唯一标识
+fn new_session_uuid() -> String {
+    Uuid::new_v4().to_string()
+}
+
 /// 番茄钟会话
 #[derive(Debug, Clone, Serialize)]
 pub struct PomodoroSession {
@@ -183,6 +357,21 @@ pub struct PomodoroSession {
     #[serde(skip)]
     pub started_at: Option<Instant>, // 开始时间点（不序列化）
     pub config: PomodoroConfig, // 当前配置
+    pub focus_todo_id: Option<String>, // 当前专注的任务
+    pub session_uuid: String,          // 本次阶段运行的唯一标识，用于完成记录去重
+    pub focus_block: Option<FocusBlock>, // 进行中的专注块
+    pub last_pause_reason: Option<PauseReason>, // 本次阶段运行最近一次暂停的原因
+    pub pause_counts: PauseCounts,              // 本次阶段运行按原因统计的暂停次数
+    pub interruptions: u32,                     // 本次阶段运行记录的打断次数
+    pub sequence_index: u32,                    // 自定义阶段序列中当前阶段的位置
+    #[serde(skip)]
+    consumed_ms: u64, // 当前这一秒已经走过的毫秒数（0..1000），`remaining` 按整秒向上取整
+    #[serde(skip)]
+    last_tick_at: Option<Instant>, // 上次结算剩余时间的时刻，运行中才有值
+    #[serde(skip)]
+    completed_phase: Option<(PomodoroPhase, u32)>, // 刚完成的阶段及完成后的周期数（一次性标记）
+    #[serde(skip)]
+    overrun_ms: u64, // 等待确认的工作阶段超出设定时长的毫秒数
 }
 
 // 安全实现 Send + Sync，因为 Instant 在单线程环境中是安全的
@@ -202,6 +391,20 @@ impl<'de> serde::Deserialize<'de> for PomodoroSession {
             is_running: bool,
             cycle_count: u32,
             config: PomodoroConfig,
+            #[serde(default)]
+            focus_todo_id: Option<String>,
+            #[serde(default)]
+            session_uuid: Option<String>,
+            #[serde(default)]
+            focus_block: Option<FocusBlock>,
+            #[serde(default)]
+            last_pause_reason: Option<PauseReason>,
+            #[serde(default)]
+            pause_counts: PauseCounts,
+            #[serde(default)]
+            interruptions: u32,
+            #[serde(default)]
+            sequence_index: u32,
         }
 
         let data = PomodoroSessionData::deserialize(deserializer)?;
@@ -214,109 +417,384 @@ impl<'de> serde::Deserialize<'de> for PomodoroSession {
             cycle_count: data.cycle_count,
             started_at: None, // 重置为 None，因为 Instant 无法反序列化
             config: data.config,
+            focus_todo_id: data.focus_todo_id,
+            session_uuid: data.session_uuid.unwrap_or_else(new_session_uuid),
+            focus_block: data.focus_block,
+            last_pause_reason: data.last_pause_reason,
+            pause_counts: data.pause_counts,
+            interruptions: data.interruptions,
+            sequence_index: data.sequence_index,
+            consumed_ms: 0,
+            last_tick_at: None,
+            completed_phase: None,
+            overrun_ms: 0,
         })
     }
 }
 
 impl PomodoroSession {
-    /// 创建新的番茄钟会话（工作阶段）
+    /// 创建新的番茄钟会话（工作阶段，或自定义序列的第一个阶段）
     pub fn new(config: PomodoroConfig) -> Self {
-        let work_duration = config.work_duration;
+        let phase = config.first_phase();
+        let duration = config.get_duration(phase);
         Self {
-            phase: PomodoroPhase::Work,
-            duration: work_duration,
-            remaining: work_duration,
+            phase,
+            duration,
+            remaining: duration,
             is_running: false,
             cycle_count: 0,
             started_at: None,
             config,
+            focus_todo_id: None,
+            session_uuid: new_session_uuid(),
+            focus_block: None,
+            last_pause_reason: None,
+            pause_counts: PauseCounts::default(),
+            interruptions: 0,
+            sequence_index: 0,
+            consumed_ms: 0,
+            last_tick_at: None,
+            completed_phase: None,
+            overrun_ms: 0,
         }
     }
 
+    /// 开始新的阶段运行：换新标识并清空暂停与打断统计
+    fn begin_new_run(&mut self) {
+        self.session_uuid = new_session_uuid();
+        self.last_pause_reason = None;
+        self.pause_counts = PauseCounts::default();
+        self.interruptions = 0;
+        self.consumed_ms = 0;
+        self.last_tick_at = None;
+        self.overrun_ms = 0;
+    }
+
+    /// 记录一次打断（如被人打扰、查看消息），计入本次阶段运行
+    pub fn record_interruption(&mut self) {
+        self.interruptions = self.interruptions.saturating_add(1);
+    }
+
     /// 开始计时
     pub fn start(&mut self) -> Result<()> {
         if self.is_running {
             return Err(AppError::InvalidState("计时器已在运行中".to_string()));
         }
 
+        let now = Instant::now();
         self.is_running = true;
-        self.started_at = Some(Instant::now());
+        self.started_at = Some(now);
+        self.last_tick_at = Some(now);
         Ok(())
     }
 
-    /// 暂停计时
+    /// 暂停计时（手动暂停）
     pub fn pause(&mut self) -> Result<()> {
+        self.pause_with_reason(PauseReason::Manual)
+    }
+
+    /// 按指定原因暂停计时，并计入本次阶段运行的暂停统计
+    pub fn pause_with_reason(&mut self, reason: PauseReason) -> Result<()> {
         if !self.is_running {
             return Err(AppError::InvalidState("计时器未运行".to_string()));
         }
 
+        // 先把上次结算以来经过的时间记入剩余时间，恢复后从这里继续
+        self.settle_elapsed(Instant::now());
         self.is_running = false;
         self.started_at = None;
+        self.last_tick_at = None;
+        self.last_pause_reason = Some(reason);
+        self.pause_counts.record(reason);
         Ok(())
     }
 
     /// 重置当前阶段
     pub fn reset(&mut self) -> Result<()> {
         self.remaining = self.duration;
+        self.begin_new_run();
         self.is_running = false;
         self.started_at = None;
         Ok(())
     }
 
+    /// 清零工作周期计数，保持当前阶段和剩余时间不变
+    pub fn reset_cycle_count(&mut self) {
+        self.cycle_count = 0;
+    }
+
+    /// 放弃当前会话：回到工作阶段（或自定义序列）开头，清零周期并取消专注任务
+    pub fn abandon(&mut self) {
+        self.phase = self.config.first_phase();
+        self.duration = self.config.get_duration(self.phase);
+        self.remaining = self.duration;
+        self.is_running = false;
+        self.started_at = None;
+        self.cycle_count = 0;
+        self.sequence_index = 0;
+        self.focus_todo_id = None;
+        self.focus_block = None;
+        self.begin_new_run();
+    }
+
     /// 跳过当前阶段
+    ///
+    /// 计时中也可以跳过：当前阶段先停止，新阶段默认保持暂停，
+    /// 开启 `auto_start_next` 时立即开始。
     pub fn skip(&mut self) -> Result<PomodoroPhase> {
+        let next_phase = self.advance_phase();
+        self.switch_to_phase(next_phase)?;
+        if self.config.auto_start_next {
+            self.start()?;
+        }
+        Ok(next_phase)
+    }
+
+    /// 提前结束休息并立即开始新的工作阶段
+    ///
+    /// 不在休息阶段，或休息时长不足 `min_break_seconds` 时返回错误。
+    pub fn end_break(&mut self, min_break_seconds: u64) -> Result<()> {
+        if self.phase.is_work() {
+            return Err(AppError::InvalidState("当前不在休息阶段".to_string()));
+        }
+
+        let elapsed = self.duration.saturating_sub(self.remaining);
+        if elapsed < min_break_seconds {
+            return Err(AppError::InvalidState(format!(
+                "至少需要休息 {} 秒，还剩 {} 秒",
+                min_break_seconds,
+                min_break_seconds - elapsed
+            )));
+        }
+
+        self.switch_to_phase(PomodoroPhase::Work)?;
+        self.start()
+    }
+
+    /// 切换到深度工作阶段并开始计时
+    pub fn start_deep_work(&mut self) -> Result<()> {
         if self.is_running {
-            return Err(AppError::InvalidState("请先暂停计时器".to_string()));
+            return Err(AppError::InvalidState("计时器已在运行中".to_string()));
         }
 
-        let next_phase = self
-            .phase
-            .next(self.cycle_count, self.config.cycles_until_long_break);
-        self.switch_to_phase(next_phase)?;
-        Ok(next_phase)
+        self.switch_to_phase(PomodoroPhase::DeepWork)?;
+        self.start()
+    }
+
+    /// 开始一个包含 `total` 个工作阶段的专注块，从新的工作阶段开始计时
+    pub fn start_focus_block(&mut self, total: u32) -> Result<()> {
+        if self.is_running {
+            return Err(AppError::InvalidState("计时器已在运行中".to_string()));
+        }
+        if total == 0 {
+            return Err(AppError::InvalidState("专注块至少包含一个番茄".to_string()));
+        }
+
+        self.switch_to_phase(PomodoroPhase::Work)?;
+        self.focus_block = Some(FocusBlock {
+            completed: 0,
+            total,
+        });
+        self.start()
+    }
+
+    /// 当前专注块进度 `(已完成, 总数)`，没有进行中的专注块时返回 `None`
+    pub fn focus_block_progress(&self) -> Option<(u32, u32)> {
+        self.focus_block.map(|block| (block.completed, block.total))
     }
 
     /// 切换到指定阶段
+    ///
+    /// 配置了自定义序列时，序列位置移到目标阶段下一次出现的位置，之后从那里继续前进。
     pub fn switch_to_phase(&mut self, phase: PomodoroPhase) -> Result<()> {
+        if let Some(sequence) = self.config.phase_sequence.as_deref() {
+            let current = self.sequence_index as usize;
+            if sequence.get(current) != Some(&phase) {
+                if let Some(index) = (1..sequence.len())
+                    .map(|offset| (current + offset) % sequence.len())
+                    .find(|&index| sequence[index] == phase)
+                {
+                    self.sequence_index = index as u32;
+                }
+            }
+        }
         self.phase = phase;
         self.duration = self.config.get_duration(phase);
         self.remaining = self.duration;
         self.is_running = false;
         self.started_at = None;
+        self.begin_new_run();
         Ok(())
     }
 
-    /// 更新倒计时
+    /// 恢复已保存的阶段进度，恢复后总是处于暂停状态
+    pub fn restore(&mut self, phase: PomodoroPhase, remaining: u64, cycle_count: u32) {
+        self.phase = phase;
+        self.duration = self.config.get_duration(phase);
+        self.remaining = remaining.min(self.duration);
+        self.cycle_count = cycle_count;
+        self.is_running = false;
+        self.started_at = None;
+        self.begin_new_run();
+    }
+
+    /// 按墙上时间更新倒计时
     pub fn tick(&mut self) -> Result<bool> {
+        self.tick_with_idle(false)
+    }
+
+    /// 按墙上时间更新倒计时；`user_idle` 为真时，休息结束后的工作阶段保持暂停而不是自动开始
+    pub fn tick_with_idle(&mut self, user_idle: bool) -> Result<bool> {
+        self.tick_at(Instant::now(), user_idle)
+    }
+
+    /// 以 `now` 为当前时刻更新倒计时，返回本次是否完成了阶段
+    ///
+    /// 扣减的是距上次结算实际经过的时间，事件循环卡顿或系统休眠后会一次补齐，
+    /// 不依赖 tick 的调用频率。
+    pub fn tick_at(&mut self, now: Instant, user_idle: bool) -> Result<bool> {
         if !self.is_running {
             return Ok(false);
         }
 
-        if self.remaining > 0 {
-            self.remaining -= 1;
-            Ok(false)
-        } else {
-            // 计时结束
-            self.complete_phase()?;
-            Ok(true)
+        self.settle_elapsed(now);
+        self.finish_if_elapsed(user_idle)
+    }
+
+    /// 直接扣减 `elapsed_ms` 毫秒更新倒计时，返回本次是否完成了阶段
+    pub fn tick_by(&mut self, elapsed_ms: u64, user_idle: bool) -> Result<bool> {
+        if !self.is_running {
+            return Ok(false);
+        }
+
+        self.last_tick_at = Some(Instant::now());
+        self.consume_ms(elapsed_ms);
+        self.finish_if_elapsed(user_idle)
+    }
+
+    /// 把上次结算到 `now` 之间经过的时间从剩余时间中扣除，最多减到零
+    fn settle_elapsed(&mut self, now: Instant) {
+        let Some(last) = self.last_tick_at else {
+            return;
+        };
+        let elapsed_ms = now.saturating_duration_since(last).as_millis() as u64;
+        self.last_tick_at = Some(now);
+        self.consume_ms(elapsed_ms);
+    }
+
+    /// 扣减剩余时间；需要确认的工作阶段把超出零点的部分计入超时
+    fn consume_ms(&mut self, elapsed_ms: u64) {
+        let remaining_ms = self.remaining_ms();
+        if self.holds_at_zero() && elapsed_ms > remaining_ms {
+            self.overrun_ms = self.overrun_ms.saturating_add(elapsed_ms - remaining_ms);
+        }
+        self.set_remaining_ms(remaining_ms.saturating_sub(elapsed_ms));
+    }
+
+    /// 剩余时间为零时完成阶段；需要确认的工作阶段停在零点继续计时
+    fn finish_if_elapsed(&mut self, user_idle: bool) -> Result<bool> {
+        if self.remaining_ms() > 0 || self.holds_at_zero() {
+            return Ok(false);
+        }
+        self.complete_phase(user_idle)?;
+        Ok(true)
+    }
+
+    /// 当前阶段结束时是否需要用户确认（开启 `require_phase_ack` 的工作阶段）
+    fn holds_at_zero(&self) -> bool {
+        self.config.require_phase_ack && self.phase.is_work()
+    }
+
+    /// 工作阶段已到时，正在等待用户确认后进入休息
+    pub fn awaiting_phase_ack(&self) -> bool {
+        self.holds_at_zero() && self.remaining_ms() == 0
+    }
+
+    /// 等待确认的工作阶段超出设定时长的秒数
+    pub fn overrun_seconds(&self) -> u64 {
+        self.overrun_ms / 1000
+    }
+
+    /// 确认已到时的工作阶段并进入下一阶段
+    pub fn acknowledge_phase(&mut self, user_idle: bool) -> Result<()> {
+        if !self.awaiting_phase_ack() {
+            return Err(AppError::InvalidState("当前阶段无需确认".to_string()));
+        }
+        self.complete_phase(user_idle)
+    }
+
+    /// 本次结算是否让超时越过了 `reminder_grace_secs`（需开启 `break_reminders`）
+    ///
+    /// 与阶段结束前的提醒一样只在越过阈值的那一次结算返回真，每次阶段运行只提醒一次。
+    fn crossed_reminder_grace(&self, overrun_ms_before: u64) -> bool {
+        if !self.config.break_reminders || !self.awaiting_phase_ack() {
+            return false;
+        }
+        let grace_ms = self.config.reminder_grace_secs.saturating_mul(1000);
+        overrun_ms_before <= grace_ms && self.overrun_ms > grace_ms
+    }
+
+    /// 本次结算是否让剩余时间越过了阶段结束前的提醒阈值
+    ///
+    /// 只在从阈值以上降到阈值及以下的那一次结算返回真，计时间隔再短也只提醒一次。
+    fn crossed_warning_threshold(&self, remaining_ms_before: u64) -> bool {
+        let Some(threshold) = self.config.warn_before_seconds else {
+            return false;
+        };
+        let threshold_ms = threshold.saturating_mul(1000);
+        remaining_ms_before > threshold_ms && self.remaining_ms() <= threshold_ms
+    }
+
+    /// 剩余时间（毫秒）
+    pub fn remaining_ms(&self) -> u64 {
+        (self.remaining * 1000).saturating_sub(self.consumed_ms)
+    }
+
+    fn set_remaining_ms(&mut self, remaining_ms: u64) {
+        self.remaining = remaining_ms.div_ceil(1000);
+        self.consumed_ms = self.remaining * 1000 - remaining_ms;
+    }
+
+    /// 确定下一阶段：配置了自定义序列时循环前进，否则按经典的工作/休息周期
+    fn advance_phase(&mut self) -> PomodoroPhase {
+        match self.config.phase_sequence.as_deref() {
+            Some(sequence) if !sequence.is_empty() => {
+                self.sequence_index = (self.sequence_index + 1) % sequence.len() as u32;
+                sequence[self.sequence_index as usize]
+            }
+            _ => self
+                .phase
+                .next(self.cycle_count, self.config.cycles_until_long_break),
         }
     }
 
     /// 完成当前阶段
-    fn complete_phase(&mut self) -> Result<()> {
+    fn complete_phase(&mut self, user_idle: bool) -> Result<()> {
         self.is_running = false;
         self.started_at = None;
+        self.last_tick_at = None;
 
         // 更新工作周期计数
-        if self.phase == PomodoroPhase::Work {
+        if self.phase.is_work() {
             self.cycle_count += 1;
+
+            if let Some(block) = self.focus_block.as_mut() {
+                block.completed += 1;
+                if block.completed >= block.total {
+                    self.focus_block = None;
+                }
+            }
         }
+        self.completed_phase = Some((self.phase, self.cycle_count));
 
         // 切换到下一阶段
-        let next_phase = self
-            .phase
-            .next(self.cycle_count, self.config.cycles_until_long_break);
+        let next_phase = self.advance_phase();
         self.switch_to_phase(next_phase)?;
+        // Users who are away when a break ends would only log empty focus,
+        // so in that case the new work phase waits for them paused.
+        if user_idle && next_phase.is_work() {
+            return Ok(());
+        }
         // Keep the timer loop fully backend-driven: the next phase starts immediately
         // after a phase completes, without requiring a UI listener to trigger start().
         self.start()?;
@@ -324,6 +802,11 @@ impl PomodoroSession {
         Ok(())
     }
 
+    /// 取出并清除“刚完成阶段”标记，每个完成的阶段只会返回一次
+    pub fn take_completed_phase(&mut self) -> Option<(PomodoroPhase, u32)> {
+        self.completed_phase.take()
+    }
+
     /// 获取剩余时间（格式：MM:SS）
     pub fn formatted_time(&self) -> String {
         let minutes = self.remaining / 60;
@@ -331,12 +814,13 @@ impl PomodoroSession {
         format!("{:02}:{:02}", minutes, seconds)
     }
 
-    /// 获取进度百分比
+    /// 获取进度百分比，精确到毫秒
     pub fn progress(&self) -> f32 {
         if self.duration == 0 {
             0.0
         } else {
-            ((self.duration - self.remaining) as f32 / self.duration as f32) * 100.0
+            let duration_ms = self.duration * 1000;
+            (duration_ms.saturating_sub(self.remaining_ms()) as f32 / duration_ms as f32) * 100.0
         }
     }
 
@@ -358,15 +842,28 @@ impl PomodoroSession {
         if !self.is_running {
             self.duration = self.config.get_duration(self.phase);
             self.remaining = self.duration;
+            self.consumed_ms = 0;
         }
         Ok(())
     }
 }
 
+/// Default interval between timer ticks.
+pub const DEFAULT_TICK_INTERVAL_MS: u64 = 1000;
+
+/// Shortest accepted tick interval.
+pub const MIN_TICK_INTERVAL_MS: u64 = 50;
+
 /// 番茄钟服务
 #[derive(Debug, Clone)]
 pub struct PomodoroService {
     session: Option<PomodoroSession>,
+    /// 计时循环的间隔（毫秒），只决定刷新频率，扣减的时长按墙上时间结算
+    tick_interval_ms: u64,
+    /// 空闲超过该秒数时，休息结束后不自动开始工作；None 表示关闭
+    idle_pause_after: Option<u64>,
+    /// 最近一次上报的系统空闲秒数
+    idle_seconds: u64,
 }
 
 // 安全实现 Send + Sync，因为所有字段都是基本类型
@@ -378,6 +875,8 @@ unsafe impl Sync for PomodoroService {}
 pub enum PomodoroEvent {
     Tick {
         remaining: u64,
+        /// Remaining time in milliseconds, for sub-second displays.
+        remaining_ms: u64,
         formatted: String,
         progress: f32,
     },
@@ -385,11 +884,26 @@ pub enum PomodoroEvent {
         completed_phase: PomodoroPhase,
         next_phase: PomodoroPhase,
         cycle_count: u32,
+        session_uuid: String,
+        /// Pauses taken during the completed phase.
+        pause_counts: PauseCounts,
+        /// Interruptions recorded during the completed phase.
+        interruptions: u32,
     },
     StateChanged {
         is_running: bool,
         phase: PomodoroPhase,
     },
+    /// Remaining time just crossed `warn_before_seconds`; sent instead of that tick's `Tick`.
+    PhaseEndingSoon {
+        remaining: u64,
+    },
+    /// A work phase waiting for acknowledgement over-ran `reminder_grace_secs`;
+    /// sent once per phase run instead of that tick's `Tick`.
+    BreakReminder {
+        /// Seconds past the planned work duration.
+        overrun: u64,
+    },
 }
 
 impl PomodoroService {
@@ -397,9 +911,38 @@ impl PomodoroService {
     pub fn new(config: PomodoroConfig) -> Self {
         Self {
             session: Some(PomodoroSession::new(config.clone())),
+            tick_interval_ms: DEFAULT_TICK_INTERVAL_MS,
+            idle_pause_after: None,
+            idle_seconds: 0,
         }
     }
 
+    /// 设置计时间隔（毫秒），限制在 [`MIN_TICK_INTERVAL_MS`] 到 1 秒之间
+    pub fn set_tick_interval_ms(&mut self, interval_ms: u64) {
+        self.tick_interval_ms = interval_ms.clamp(MIN_TICK_INTERVAL_MS, DEFAULT_TICK_INTERVAL_MS);
+    }
+
+    /// 当前计时间隔
+    pub fn tick_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.tick_interval_ms)
+    }
+
+    /// 设置空闲自动暂停阈值（秒），None 表示关闭
+    pub fn set_idle_pause_after(&mut self, threshold: Option<u64>) {
+        self.idle_pause_after = threshold;
+    }
+
+    /// 上报当前系统空闲秒数（由外壳的空闲检测定期调用）
+    pub fn report_idle_seconds(&mut self, seconds: u64) {
+        self.idle_seconds = seconds;
+    }
+
+    /// 是否已开启空闲自动暂停且空闲时长达到阈值
+    pub fn is_user_idle(&self) -> bool {
+        self.idle_pause_after
+            .is_some_and(|threshold| self.idle_seconds >= threshold)
+    }
+
     /// 获取当前会话
     pub fn get_session(&self) -> Option<&PomodoroSession> {
         self.session.as_ref()
@@ -420,8 +963,13 @@ impl PomodoroService {
 
     /// 暂停计时
     pub fn pause(&mut self) -> Result<()> {
+        self.pause_with_reason(PauseReason::Manual)
+    }
+
+    /// 按指定原因暂停计时
+    pub fn pause_with_reason(&mut self, reason: PauseReason) -> Result<()> {
         if let Some(session) = self.session.as_mut() {
-            session.pause()?;
+            session.pause_with_reason(reason)?;
         }
         Ok(())
     }
@@ -434,6 +982,30 @@ impl PomodoroService {
         Ok(())
     }
 
+    /// 清零工作周期计数
+    pub fn reset_cycle_count(&mut self) {
+        if let Some(session) = self.session.as_mut() {
+            session.reset_cycle_count();
+        }
+    }
+
+    /// 放弃当前会话
+    pub fn abandon(&mut self) {
+        if let Some(session) = self.session.as_mut() {
+            session.abandon();
+        }
+    }
+
+    /// 记录一次打断，返回本阶段累计的打断次数
+    pub fn record_interruption(&mut self) -> Result<u32> {
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| AppError::InvalidState("没有进行中的番茄钟会话".to_string()))?;
+        session.record_interruption();
+        Ok(session.interruptions)
+    }
+
     /// 跳过当前阶段
     pub fn skip(&mut self) -> Result<()> {
         if let Some(session) = self.session.as_mut() {
@@ -442,6 +1014,87 @@ impl PomodoroService {
         Ok(())
     }
 
+    /// 确认已到时的工作阶段，返回对应的阶段完成事件
+    pub fn acknowledge_phase(&mut self) -> Result<PomodoroEvent> {
+        let user_idle = self.is_user_idle();
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| AppError::InvalidState("没有进行中的番茄钟会话".to_string()))?;
+        let completed_phase = session.phase;
+        let session_uuid = session.session_uuid.clone();
+        let pause_counts = session.pause_counts;
+        let interruptions = session.interruptions;
+        session.acknowledge_phase(user_idle)?;
+
+        Ok(PomodoroEvent::PhaseCompleted {
+            completed_phase,
+            next_phase: session.phase,
+            cycle_count: session.cycle_count,
+            session_uuid,
+            pause_counts,
+            interruptions,
+        })
+    }
+
+    /// 取出并清除刚完成的阶段（阶段类型，完成后的周期数）
+    pub fn take_completed_phase(&mut self) -> Option<(PomodoroPhase, u32)> {
+        self.session
+            .as_mut()
+            .and_then(PomodoroSession::take_completed_phase)
+    }
+
+    /// 开始专注块
+    pub fn start_focus_block(&mut self, total: u32) -> Result<()> {
+        if let Some(session) = self.session.as_mut() {
+            session.start_focus_block(total)?;
+        }
+        Ok(())
+    }
+
+    /// 当前专注块进度 `(已完成, 总数)`
+    pub fn focus_block_progress(&self) -> Option<(u32, u32)> {
+        self.session
+            .as_ref()
+            .and_then(PomodoroSession::focus_block_progress)
+    }
+
+    /// 开始深度工作阶段
+    pub fn start_deep_work(&mut self) -> Result<()> {
+        if let Some(session) = self.session.as_mut() {
+            session.start_deep_work()?;
+        }
+        Ok(())
+    }
+
+    /// 提前结束休息并开始工作
+    pub fn end_break(&mut self, min_break_seconds: u64) -> Result<()> {
+        if let Some(session) = self.session.as_mut() {
+            session.end_break(min_break_seconds)?;
+        }
+        Ok(())
+    }
+
+    /// 用保存的活动会话替换当前会话，沿用当前配置，恢复后总是处于暂停状态
+    pub fn restore_active_session(&mut self, mut saved: PomodoroSession) {
+        if let Some(current) = self.session.as_ref() {
+            saved.config = current.config.clone();
+        }
+        saved.remaining = saved.remaining.min(saved.duration);
+        saved.consumed_ms = 0;
+        saved.last_tick_at = None;
+        saved.is_running = false;
+        saved.started_at = None;
+        self.session = Some(saved);
+    }
+
+    /// 恢复已保存的会话进度
+    pub fn restore_session(&mut self, phase: PomodoroPhase, remaining: u64, cycle_count: u32) {
+        if let Some(session) = self.session.as_mut() {
+            session.restore(phase, remaining, cycle_count);
+        }
+    }
+
     /// 更新配置
     pub fn update_config(&mut self, config: PomodoroConfig) -> Result<()> {
         if let Some(session) = self.session.as_mut() {
@@ -450,14 +1103,38 @@ impl PomodoroService {
         Ok(())
     }
 
-    /// 处理计时器 tick
+    /// 处理计时器 tick，按墙上时间扣减距上次结算经过的时间
     pub async fn tick(&mut self) -> Option<PomodoroEvent> {
+        self.tick_at(Instant::now()).await
+    }
+
+    /// 以 `now` 为当前时刻处理计时器 tick
+    pub async fn tick_at(&mut self, now: Instant) -> Option<PomodoroEvent> {
+        self.advance(|session, user_idle| session.tick_at(now, user_idle))
+    }
+
+    /// 处理计时器 tick，直接扣减 `elapsed_ms` 毫秒
+    pub async fn tick_elapsed(&mut self, elapsed_ms: u64) -> Option<PomodoroEvent> {
+        self.advance(|session, user_idle| session.tick_by(elapsed_ms, user_idle))
+    }
+
+    /// 推进运行中的会话并生成对应事件
+    fn advance(
+        &mut self,
+        step: impl FnOnce(&mut PomodoroSession, bool) -> Result<bool>,
+    ) -> Option<PomodoroEvent> {
+        let user_idle = self.is_user_idle();
         if let Some(session) = self.session.as_mut() {
             if !session.is_running {
                 return None;
             }
             let phase_before_tick = session.phase;
-            if let Ok(completed) = session.tick() {
+            let uuid_before_tick = session.session_uuid.clone();
+            let pauses_before_tick = session.pause_counts;
+            let interruptions_before_tick = session.interruptions;
+            let remaining_ms_before_tick = session.remaining_ms();
+            let overrun_ms_before_tick = session.overrun_ms;
+            if let Ok(completed) = step(session, user_idle) {
                 if completed {
                     let completed_phase = phase_before_tick;
                     let next_phase = session.phase;
@@ -466,10 +1143,22 @@ impl PomodoroService {
                         completed_phase,
                         next_phase,
                         cycle_count: session.cycle_count,
+                        session_uuid: uuid_before_tick,
+                        pause_counts: pauses_before_tick,
+                        interruptions: interruptions_before_tick,
+                    });
+                } else if session.crossed_reminder_grace(overrun_ms_before_tick) {
+                    return Some(PomodoroEvent::BreakReminder {
+                        overrun: session.overrun_seconds(),
+                    });
+                } else if session.crossed_warning_threshold(remaining_ms_before_tick) {
+                    return Some(PomodoroEvent::PhaseEndingSoon {
+                        remaining: session.remaining,
                     });
                 } else {
                     return Some(PomodoroEvent::Tick {
                         remaining: session.remaining,
+                        remaining_ms: session.remaining_ms(),
                         formatted: session.formatted_time(),
                         progress: session.progress(),
                     });
@@ -481,8 +1170,18 @@ impl PomodoroService {
     }
 }
 
+/// 番茄钟统计的时间范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsRange {
+    /// 今天（本地日期）
+    Today,
+    /// 有记录以来
+    AllTime,
+}
+
 /// 番茄钟统计数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PomodoroStats {
     pub total_sessions: u32,
     pub work_sessions: u32,
@@ -506,9 +1205,431 @@ impl PomodoroStats {
     }
 }
 
+/// A duration expressed in both seconds and minutes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DurationView {
+    pub seconds: u64,
+    /// Exactly `seconds / 60`, fractional when not a whole number of minutes
+    pub minutes: f64,
+}
+
+impl DurationView {
+    pub fn from_seconds(seconds: u64) -> Self {
+        Self {
+            seconds,
+            minutes: seconds as f64 / 60.0,
+        }
+    }
+}
+
+/// Read-only view of the effective pomodoro config with every duration in both units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PomodoroConfigView {
+    pub work: DurationView,
+    pub short_break: DurationView,
+    pub long_break: DurationView,
+    pub deep_work: DurationView,
+    pub cycles_until_long_break: u32,
+}
+
+impl From<&PomodoroConfig> for PomodoroConfigView {
+    fn from(config: &PomodoroConfig) -> Self {
+        Self {
+            work: DurationView::from_seconds(config.work_duration),
+            short_break: DurationView::from_seconds(config.short_break_duration),
+            long_break: DurationView::from_seconds(config.long_break_duration),
+            deep_work: DurationView::from_seconds(config.deep_work_duration),
+            cycles_until_long_break: config.cycles_until_long_break,
+        }
+    }
+}
+
+/// Maximum number of sessions returned by one history page
+pub const MAX_SESSION_PAGE_SIZE: u32 = 100;
+
+/// A recorded pomodoro phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub id: i64,
+    pub phase: PomodoroPhase,
+    pub duration_seconds: u32,
+    pub cycle_count: u32,
+    pub focus_todo_id: Option<String>,
+    pub completed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub pause_counts: PauseCounts,
+    #[serde(default)]
+    pub interruptions: u32,
+}
+
+/// One page of session history plus the total number of matching sessions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPage {
+    pub sessions: Vec<SessionRecord>,
+    pub total: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn pausing_with_reasons_updates_per_reason_counts() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        for reason in [
+            PauseReason::Manual,
+            PauseReason::IdleAuto,
+            PauseReason::Manual,
+            PauseReason::BreakEnforced,
+        ] {
+            session.start().unwrap();
+            session.pause_with_reason(reason).unwrap();
+        }
+        session.start().unwrap();
+        session.pause().unwrap();
+
+        assert_eq!(session.last_pause_reason, Some(PauseReason::Manual));
+        assert_eq!(session.pause_counts.get(PauseReason::Manual), 3);
+        assert_eq!(session.pause_counts.get(PauseReason::IdleAuto), 1);
+        assert_eq!(session.pause_counts.get(PauseReason::BreakEnforced), 1);
+        assert_eq!(session.pause_counts.total(), 5);
+
+        // 未运行时暂停失败，不计数
+        assert!(session.pause_with_reason(PauseReason::IdleAuto).is_err());
+        assert_eq!(session.pause_counts.total(), 5);
+
+        // 会话视图暴露原因与计数，反序列化后保持不变
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["last_pause_reason"], "manual");
+        assert_eq!(json["pause_counts"]["idle_auto"], 1);
+        let restored: PomodoroSession = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.pause_counts, session.pause_counts);
+
+        // 新的阶段运行重新计数
+        session.skip().unwrap();
+        assert_eq!(session.last_pause_reason, None);
+        assert_eq!(session.pause_counts, PauseCounts::default());
+    }
+
+    #[test]
+    fn skip_while_running_switches_phase() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+
+        assert_eq!(session.skip().unwrap(), PomodoroPhase::ShortBreak);
+        assert!(!session.is_running);
+        assert_eq!(session.remaining, session.config.short_break_duration);
+        // 跳过不计入完成的周期，也不产生暂停记录
+        assert_eq!(session.cycle_count, 0);
+        assert_eq!(session.pause_counts, PauseCounts::default());
+
+        session.config.auto_start_next = true;
+        session.start().unwrap();
+        assert_eq!(session.skip().unwrap(), PomodoroPhase::Work);
+        assert!(session.is_running);
+        assert!(session.started_at.is_some());
+    }
+
+    #[test]
+    fn phase_completed_reports_pauses_of_the_finished_phase() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        service.start().unwrap();
+        service.pause_with_reason(PauseReason::IdleAuto).unwrap();
+        service.start().unwrap();
+        service.get_session_mut().unwrap().remaining = 0;
+
+        let event = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async { service.tick().await });
+        match event {
+            Some(PomodoroEvent::PhaseCompleted { pause_counts, .. }) => {
+                assert_eq!(pause_counts.idle_auto, 1);
+                assert_eq!(pause_counts.total(), 1);
+            }
+            other => panic!("expected PhaseCompleted event, got {other:?}"),
+        }
+        assert_eq!(service.get_session().unwrap().pause_counts.total(), 0);
+    }
+
+    #[test]
+    fn interruptions_are_reported_on_completion_and_reset_per_phase() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        service.start().unwrap();
+        service.record_interruption().unwrap();
+        assert_eq!(service.record_interruption().unwrap(), 2);
+        service.get_session_mut().unwrap().remaining = 0;
+
+        let event = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async { service.tick().await });
+        match event {
+            Some(PomodoroEvent::PhaseCompleted { interruptions, .. }) => {
+                assert_eq!(interruptions, 2);
+            }
+            other => panic!("expected PhaseCompleted event, got {other:?}"),
+        }
+        assert_eq!(service.get_session().unwrap().interruptions, 0);
+
+        service.record_interruption().unwrap();
+        service.skip().unwrap();
+        assert_eq!(service.get_session().unwrap().interruptions, 0);
+    }
+
+    #[test]
+    fn sub_second_ticks_keep_whole_second_view() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+
+        assert!(!session.tick_by(250, false).unwrap());
+        assert_eq!(session.remaining, 1500);
+        assert_eq!(session.remaining_ms(), 1_499_750);
+        assert_eq!(session.formatted_time(), "25:00");
+        assert!(session.progress() > 0.0);
+
+        for _ in 0..3 {
+            session.tick_by(250, false).unwrap();
+        }
+        assert_eq!(session.remaining, 1499);
+        assert_eq!(session.remaining_ms(), 1_499_000);
+        assert_eq!(session.formatted_time(), "24:59");
+    }
+
+    #[test]
+    fn sub_second_ticks_complete_exactly_at_zero() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+        session.remaining = 1;
+
+        assert!(!session.tick_by(700, false).unwrap());
+        assert_eq!(session.remaining, 1);
+        assert_eq!(session.formatted_time(), "00:01");
+        // 超出剩余时间的部分被截断，不会变成负数，也不会带入下一阶段
+        assert!(session.tick_by(700, false).unwrap());
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(session.remaining_ms(), 300_000);
+    }
+
+    #[test]
+    fn wall_clock_tick_catches_up_after_a_gap() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+        let t0 = session.last_tick_at.unwrap();
+
+        assert!(!session.tick_at(t0 + Duration::from_secs(1), false).unwrap());
+        assert_eq!(session.remaining, 1499);
+        // 事件循环卡顿 3 秒后的下一次 tick 一次补齐，而不是只扣 1 秒
+        assert!(!session.tick_at(t0 + Duration::from_secs(4), false).unwrap());
+        assert_eq!(session.remaining, 1496);
+        assert!(!session.tick_at(t0 + Duration::from_millis(4500), false).unwrap());
+        assert_eq!(session.remaining_ms(), 1_495_500);
+    }
+
+    #[test]
+    fn wall_clock_tick_completes_when_elapsed_reaches_duration() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+        let t0 = session.last_tick_at.unwrap();
+
+        assert!(!session.tick_at(t0 + Duration::from_secs(1499), false).unwrap());
+        assert_eq!(session.remaining, 1);
+        // 系统休眠远超剩余时间：剩余时间截断为零并完成阶段，超出部分不带入休息
+        assert!(session.tick_at(t0 + Duration::from_secs(3600), false).unwrap());
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(session.remaining, 300);
+        assert_eq!(session.cycle_count, 1);
+    }
+
+    #[test]
+    fn pausing_banks_elapsed_time_and_skips_paused_time() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.start().unwrap();
+        // 模拟已经运行了 2 秒但期间没有 tick
+        session.last_tick_at = Some(Instant::now() - Duration::from_secs(2));
+        session.pause().unwrap();
+        assert_eq!(session.remaining, 1498);
+        let banked = session.remaining_ms();
+        assert!(banked <= 1_498_000);
+
+        // 暂停期间的时间不计入
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!session.tick().unwrap());
+        assert_eq!(session.remaining_ms(), banked);
+
+        session.start().unwrap();
+        let resumed = session.last_tick_at.unwrap();
+        session.tick_at(resumed + Duration::from_secs(1), false).unwrap();
+        assert_eq!(session.remaining_ms(), banked - 1000);
+    }
+
+    #[test]
+    fn service_tick_interval_is_clamped() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        assert_eq!(service.tick_interval().as_millis(), u128::from(DEFAULT_TICK_INTERVAL_MS));
+        service.set_tick_interval_ms(100);
+        service.start().unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        for _ in 0..10 {
+            runtime.block_on(service.tick_elapsed(100));
+        }
+        let session = service.get_session().unwrap();
+        assert_eq!(session.remaining, 1499);
+        assert_eq!(session.remaining_ms(), 1_499_000);
+
+        service.set_tick_interval_ms(1);
+        assert_eq!(service.tick_interval().as_millis(), u128::from(MIN_TICK_INTERVAL_MS));
+        service.set_tick_interval_ms(60_000);
+        assert_eq!(service.tick_interval().as_millis(), u128::from(DEFAULT_TICK_INTERVAL_MS));
+    }
+
+    #[test]
+    fn phase_ending_soon_fires_once_when_crossing_threshold() {
+        let count_warnings = |warn_before_seconds| {
+            let mut service = PomodoroService::new(PomodoroConfig {
+                warn_before_seconds,
+                ..PomodoroConfig::default()
+            });
+            service.start().unwrap();
+
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let mut warnings = Vec::new();
+            let mut record = |event| {
+                if let Some(PomodoroEvent::PhaseEndingSoon { remaining }) = event {
+                    warnings.push(remaining);
+                }
+            };
+            record(runtime.block_on(service.tick_elapsed(1_439_000)));
+            // 越过阈值前后都是亚秒级 tick
+            for _ in 0..30 {
+                record(runtime.block_on(service.tick_elapsed(100)));
+            }
+            warnings
+        };
+
+        assert_eq!(count_warnings(Some(60)), vec![60]);
+        assert!(count_warnings(None).is_empty());
+    }
+
+    #[test]
+    fn overrun_work_phase_reminds_once_until_acknowledged() {
+        let run_over = |break_reminders| {
+            let mut service = PomodoroService::new(PomodoroConfig {
+                require_phase_ack: true,
+                break_reminders,
+                reminder_grace_secs: 30,
+                ..PomodoroConfig::default()
+            });
+            service.start().unwrap();
+
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            let mut reminders = Vec::new();
+            // 到时后工作阶段停在零点，超时 10 秒、40 秒、70 秒
+            for elapsed_ms in [1_510_000, 30_000, 30_000] {
+                match runtime.block_on(service.tick_elapsed(elapsed_ms)) {
+                    Some(PomodoroEvent::BreakReminder { overrun }) => reminders.push(overrun),
+                    Some(PomodoroEvent::PhaseCompleted { .. }) => panic!("work phase must wait for acknowledgement"),
+                    _ => {}
+                }
+            }
+            (service, reminders)
+        };
+
+        let (mut service, reminders) = run_over(true);
+        assert_eq!(reminders, vec![40]);
+        let session = service.get_session().unwrap();
+        assert!(session.awaiting_phase_ack());
+        assert_eq!(session.overrun_seconds(), 70);
+
+        match service.acknowledge_phase().unwrap() {
+            PomodoroEvent::PhaseCompleted { completed_phase, next_phase, .. } => {
+                assert_eq!(completed_phase, PomodoroPhase::Work);
+                assert_eq!(next_phase, PomodoroPhase::ShortBreak);
+            }
+            other => panic!("expected PhaseCompleted event, got {other:?}"),
+        }
+        let session = service.get_session().unwrap();
+        assert!(session.is_running && !session.awaiting_phase_ack());
+        assert_eq!(session.overrun_seconds(), 0);
+        assert!(matches!(service.acknowledge_phase(), Err(AppError::InvalidState(_))));
+
+        let (_service, reminders) = run_over(false);
+        assert!(reminders.is_empty());
+    }
+
+    #[test]
+    fn pause_reason_parses_its_own_names() {
+        for reason in [PauseReason::Manual, PauseReason::IdleAuto, PauseReason::BreakEnforced] {
+            assert_eq!(PauseReason::parse(reason.as_str()).unwrap(), reason);
+        }
+        assert!(PauseReason::parse("coffee").is_err());
+    }
+
+    #[test]
+    fn config_view_reports_seconds_and_minutes() {
+        let view = PomodoroConfigView::from(&PomodoroConfig::default());
+        assert_eq!(view.work, DurationView { seconds: 1500, minutes: 25.0 });
+        assert_eq!(view.short_break, DurationView { seconds: 300, minutes: 5.0 });
+        assert_eq!(view.long_break, DurationView { seconds: 900, minutes: 15.0 });
+        assert_eq!(view.deep_work, DurationView { seconds: 5400, minutes: 90.0 });
+        assert_eq!(view.cycles_until_long_break, 4);
+
+        let custom = PomodoroConfig {
+            work_duration: 3000,
+            short_break_duration: 90,
+            ..PomodoroConfig::default()
+        }
+        .with_long_break(20)
+        .with_cycles_until_long_break(3);
+        let view = PomodoroConfigView::from(&custom);
+        assert_eq!(view.work.minutes, 50.0);
+        assert_eq!(view.short_break, DurationView { seconds: 90, minutes: 1.5 });
+        assert_eq!(view.long_break, DurationView { seconds: 1200, minutes: 20.0 });
+        assert_eq!(view.cycles_until_long_break, 3);
+        for duration in [view.work, view.short_break, view.long_break, view.deep_work] {
+            assert_eq!(duration.minutes, duration.seconds as f64 / 60.0);
+        }
+    }
+
+    #[test]
+    fn config_validation_reports_kind() {
+        let base = PomodoroConfig::default();
+        let cases = [
+            (
+                PomodoroConfig { work_duration: 30, ..base.clone() },
+                ValidationKind::WorkDurationOutOfRange,
+            ),
+            (
+                PomodoroConfig { short_break_duration: 3600, ..base.clone() },
+                ValidationKind::ShortBreakDurationOutOfRange,
+            ),
+            (
+                PomodoroConfig { long_break_duration: 60, ..base.clone() },
+                ValidationKind::LongBreakDurationOutOfRange,
+            ),
+            (
+                PomodoroConfig { cycles_until_long_break: 1, ..base.clone() },
+                ValidationKind::CyclesUntilLongBreakOutOfRange,
+            ),
+            (
+                PomodoroConfig { deep_work_duration: 600, ..base.clone() },
+                ValidationKind::DeepWorkDurationOutOfRange,
+            ),
+            (
+                base.clone().with_phase_sequence(Vec::new()),
+                ValidationKind::PhaseSequenceInvalid,
+            ),
+            (
+                base.clone().with_phase_sequence(vec![PomodoroPhase::ShortBreak, PomodoroPhase::LongBreak]),
+                ValidationKind::PhaseSequenceInvalid,
+            ),
+        ];
+
+        for (config, kind) in cases {
+            assert_eq!(config.validate().unwrap_err().validation_kind(), Some(kind));
+        }
+        assert!(base.validate().is_ok());
+    }
 
     #[test]
     fn pomodoro_phase_serializes_as_snake_case() {
@@ -553,4 +1674,255 @@ mod tests {
         assert!(session.is_running);
         assert_eq!(session.phase, PomodoroPhase::ShortBreak);
     }
+
+    #[test]
+    fn custom_phase_sequence_wraps_around() {
+        use PomodoroPhase::*;
+        let sequence = vec![Work, ShortBreak, Work, LongBreak, DeepWork, ShortBreak];
+        let config = PomodoroConfig::default().with_phase_sequence(sequence.clone());
+        assert!(config.validate().is_ok());
+
+        let mut service = PomodoroService::new(config);
+        assert_eq!(service.get_session().unwrap().phase, Work);
+        service.start().unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut phases = Vec::new();
+        for _ in 0..8 {
+            service.get_session_mut().unwrap().remaining = 0;
+            match runtime.block_on(service.tick()) {
+                Some(PomodoroEvent::PhaseCompleted { next_phase, .. }) => phases.push(next_phase),
+                other => panic!("expected PhaseCompleted event, got {other:?}"),
+            }
+        }
+
+        // 第六个阶段之后回到序列开头
+        assert_eq!(phases, [&sequence[1..], &sequence[..3]].concat());
+        let session = service.get_session().unwrap();
+        assert_eq!(session.sequence_index, 2);
+        assert_eq!(session.cycle_count, 4);
+
+        // 跳过同样沿序列前进
+        service.pause().unwrap();
+        service.skip().unwrap();
+        assert_eq!(service.get_session().unwrap().phase, LongBreak);
+    }
+
+    #[test]
+    fn jumping_to_a_phase_moves_the_sequence_position() {
+        use PomodoroPhase::*;
+        let config = PomodoroConfig::default().with_phase_sequence(vec![Work, ShortBreak, DeepWork, LongBreak]);
+        let mut session = PomodoroSession::new(config);
+
+        session.skip().unwrap();
+        assert_eq!(session.phase, ShortBreak);
+        // 提前结束休息回到序列开头的工作阶段，跳过后接着是它后面的短休息
+        session.end_break(0).unwrap();
+        assert_eq!((session.phase, session.sequence_index), (Work, 0));
+        session.pause().unwrap();
+        session.skip().unwrap();
+        assert_eq!(session.phase, ShortBreak);
+
+        session.start_deep_work().unwrap();
+        assert_eq!(session.sequence_index, 2);
+        session.pause().unwrap();
+        session.skip().unwrap();
+        assert_eq!(session.phase, LongBreak);
+    }
+
+    #[test]
+    fn reset_cycle_count_keeps_phase_and_remaining() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        {
+            let session = service.get_session_mut().unwrap();
+            session.switch_to_phase(PomodoroPhase::ShortBreak).unwrap();
+            session.cycle_count = 3;
+            session.remaining = 42;
+        }
+        service.start().unwrap();
+
+        service.reset_cycle_count();
+        let session = service.get_session().unwrap();
+        assert_eq!(session.cycle_count, 0);
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(session.remaining, 42);
+        assert!(session.is_running);
+    }
+
+    #[test]
+    fn idle_user_keeps_next_work_phase_paused() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let finish_break = |idle_seconds: u64| {
+            let mut service = PomodoroService::new(PomodoroConfig::default());
+            service.set_idle_pause_after(Some(120));
+            service.report_idle_seconds(idle_seconds);
+            let session = service.get_session_mut().unwrap();
+            session.switch_to_phase(PomodoroPhase::ShortBreak).unwrap();
+            session.start().unwrap();
+            session.remaining = 0;
+            runtime.block_on(service.tick());
+            service.get_session().unwrap().clone()
+        };
+
+        let away = finish_break(300);
+        assert_eq!(away.phase, PomodoroPhase::Work);
+        assert!(!away.is_running);
+
+        let present = finish_break(10);
+        assert_eq!(present.phase, PomodoroPhase::Work);
+        assert!(present.is_running);
+    }
+
+    #[test]
+    fn idle_pause_is_off_by_default_and_ignores_work_to_break() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        service.report_idle_seconds(10_000);
+        assert!(!service.is_user_idle());
+
+        // 开启后，工作结束进入休息仍然自动开始
+        service.set_idle_pause_after(Some(60));
+        assert!(service.is_user_idle());
+        service.start().unwrap();
+        service.get_session_mut().unwrap().remaining = 0;
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(service.tick());
+        let session = service.get_session().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert!(session.is_running);
+    }
+
+    #[test]
+    fn focus_block_progress_advances_with_work_phases() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        assert_eq!(service.focus_block_progress(), None);
+
+        service.start_focus_block(2).unwrap();
+        assert_eq!(service.focus_block_progress(), Some((0, 2)));
+
+        let finish_phase = |service: &mut PomodoroService| {
+            service.get_session_mut().unwrap().remaining = 0;
+            runtime.block_on(service.tick());
+        };
+
+        finish_phase(&mut service); // 工作
+        assert_eq!(service.focus_block_progress(), Some((1, 2)));
+        finish_phase(&mut service); // 休息不计入
+        assert_eq!(service.focus_block_progress(), Some((1, 2)));
+        finish_phase(&mut service); // 最后一个工作阶段结束专注块
+        assert_eq!(service.focus_block_progress(), None);
+    }
+
+    #[test]
+    fn focus_block_rejects_empty_block_and_running_timer() {
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        assert!(service.start_focus_block(0).is_err());
+
+        service.start().unwrap();
+        assert!(service.start_focus_block(3).is_err());
+        assert_eq!(service.focus_block_progress(), None);
+    }
+
+    #[test]
+    fn phase_completed_event_carries_uuid_of_finished_run() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        service.start().unwrap();
+        let work_uuid = service.get_session().unwrap().session_uuid.clone();
+
+        service.get_session_mut().unwrap().remaining = 0;
+        match runtime.block_on(service.tick()) {
+            Some(PomodoroEvent::PhaseCompleted { session_uuid, .. }) => {
+                assert_eq!(session_uuid, work_uuid);
+            }
+            _ => panic!("expected PhaseCompleted event"),
+        }
+
+        // 新阶段使用新的标识
+        assert_ne!(service.get_session().unwrap().session_uuid, work_uuid);
+    }
+
+    #[test]
+    fn completed_phase_marker_is_taken_once_per_phase() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+        service.start().unwrap();
+        assert_eq!(service.take_completed_phase(), None);
+
+        service.get_session_mut().unwrap().remaining = 0;
+        runtime.block_on(service.tick());
+        assert_eq!(service.take_completed_phase(), Some((PomodoroPhase::Work, 1)));
+        assert_eq!(service.take_completed_phase(), None);
+
+        // 普通 tick 不会产生标记
+        runtime.block_on(service.tick());
+        assert_eq!(service.take_completed_phase(), None);
+
+        service.get_session_mut().unwrap().remaining = 0;
+        runtime.block_on(service.tick());
+        assert_eq!(
+            service.take_completed_phase(),
+            Some((PomodoroPhase::ShortBreak, 1))
+        );
+        assert_eq!(service.take_completed_phase(), None);
+    }
+
+    #[test]
+    fn end_break_starts_fresh_work_phase() {
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        assert!(matches!(session.end_break(0), Err(AppError::InvalidState(_))));
+
+        session.switch_to_phase(PomodoroPhase::ShortBreak).unwrap();
+        session.start().unwrap();
+        session.remaining -= 90;
+        assert!(matches!(session.end_break(120), Err(AppError::InvalidState(_))));
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+
+        session.end_break(60).unwrap();
+        assert_eq!(session.phase, PomodoroPhase::Work);
+        assert_eq!(session.remaining, session.config.work_duration);
+        assert!(session.is_running);
+    }
+
+    #[test]
+    fn deep_work_phase_uses_own_duration_and_leads_to_break() {
+        let config = PomodoroConfig::default();
+        assert_eq!(config.get_duration(PomodoroPhase::DeepWork), 5400);
+        assert_eq!(
+            serde_json::to_string(&PomodoroPhase::DeepWork).unwrap(),
+            "\"deep_work\""
+        );
+        assert_eq!(PomodoroPhase::DeepWork.next(0, 4), PomodoroPhase::ShortBreak);
+        assert_eq!(PomodoroPhase::DeepWork.next(3, 4), PomodoroPhase::LongBreak);
+
+        let mut service = PomodoroService::new(config);
+        service.start_deep_work().unwrap();
+        {
+            let session = service.get_session_mut().unwrap();
+            assert_eq!(session.phase, PomodoroPhase::DeepWork);
+            assert_eq!(session.remaining, 5400);
+            assert!(session.is_running);
+            assert!(matches!(session.start_deep_work(), Err(AppError::InvalidState(_))));
+            session.remaining = 0;
+        }
+
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(service.tick());
+        assert_eq!(
+            service.take_completed_phase(),
+            Some((PomodoroPhase::DeepWork, 1))
+        );
+        assert_eq!(service.get_session().unwrap().phase, PomodoroPhase::ShortBreak);
+    }
+
+    #[test]
+    fn config_without_deep_work_duration_deserializes_with_default() {
+        let config: PomodoroConfig = serde_json::from_str(
+            r#"{"work_duration":1500,"short_break_duration":300,"long_break_duration":900,"cycles_until_long_break":4}"#,
+        )
+        .unwrap();
+        assert_eq!(config.deep_work_duration, 5400);
+    }
 }
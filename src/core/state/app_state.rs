@@ -1,11 +1,13 @@
 //! 应用状态管理
 
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
-use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
 
 use crate::core::error::{AppError, Result};
-use crate::core::pomodoro::{PomodoroConfig, PomodoroEvent, PomodoroSession};
-use crate::core::todo::{Todo, TodoFilter, TodoStats};
+use crate::core::pomodoro::{PomodoroConfig, PomodoroEvent, PomodoroPhase, PomodoroSession};
+use crate::core::todo::{Tag, Todo, TodoFilter, TodoStats, TodoStatus};
 
 /// 用户配置
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -20,9 +22,132 @@ pub struct UserConfig {
     pub pomodoro_short_break_duration: u64,
     pub pomodoro_long_break_duration: u64,
     pub pomodoro_cycles_until_long_break: u32,
+    /// 深度工作阶段时长（秒）
+    #[serde(default = "default_deep_work_duration")]
+    pub pomodoro_deep_work_duration: u64,
+    /// 跳过阶段后立即开始下一阶段，关闭时新阶段保持暂停
+    #[serde(default)]
+    pub pomodoro_auto_start_next: bool,
+    /// 阶段结束前多少秒发出提醒，None 表示不提醒
+    #[serde(default)]
+    pub pomodoro_warn_before_seconds: Option<u64>,
+    /// 自定义阶段顺序，按序循环；None 表示经典的工作/休息周期
+    #[serde(default)]
+    pub pomodoro_phase_sequence: Option<Vec<PomodoroPhase>>,
+    /// 工作阶段到时后等待确认再进入休息，关闭时自动切换
+    #[serde(default)]
+    pub pomodoro_require_phase_ack: bool,
+    /// 等待确认的工作阶段超时后提醒休息
+    #[serde(default)]
+    pub pomodoro_break_reminders: bool,
+    /// 超时多少秒后发出休息提醒
+    #[serde(default = "default_reminder_grace_secs")]
+    pub pomodoro_reminder_grace_secs: u64,
     pub notifications_enabled: bool,
     pub sound_enabled: bool,
+    /// 使用系统通知中心发送提醒
+    #[serde(default = "default_system_notifications")]
+    pub system_notifications: bool,
     pub theme: String,
+    /// 每天首次启动时将逾期未完成任务顺延到今天
+    #[serde(default)]
+    pub daily_carry_over: bool,
+    /// 最近一次执行顺延的日期（本地日期）
+    #[serde(default)]
+    pub last_carry_over_date: Option<chrono::NaiveDate>,
+    /// 每日专注目标（分钟），未设置时为 None
+    #[serde(default)]
+    pub daily_focus_goal_minutes: Option<u32>,
+    /// 每日完成工作番茄数目标，未设置时为 None
+    #[serde(default)]
+    pub daily_pomodoro_goal: Option<u32>,
+    /// 任务完成音效文件路径，未设置时使用默认成功音效
+    #[serde(default)]
+    pub task_complete_sound: Option<String>,
+    /// 开始工作阶段时将专注任务标记为进行中，暂停/放弃时改回待办
+    #[serde(default)]
+    pub sync_focus_status: bool,
+    /// 自动归档完成超过指定天数的任务，None 表示不归档
+    #[serde(default)]
+    pub auto_archive_done_after_days: Option<u32>,
+    /// 工作阶段颜色覆盖（`#RGB` 或 `#RRGGBB`）
+    #[serde(default)]
+    pub work_color: Option<String>,
+    /// 短休息阶段颜色覆盖
+    #[serde(default)]
+    pub short_break_color: Option<String>,
+    /// 长休息阶段颜色覆盖
+    #[serde(default)]
+    pub long_break_color: Option<String>,
+    /// 提前结束休息前至少需要休息的秒数，None 表示不限制
+    #[serde(default)]
+    pub enforced_break_seconds: Option<u32>,
+    /// 专注时长换算为分钟时的取整方式
+    #[serde(default)]
+    pub focus_minute_rounding: MinuteRounding,
+    /// 休息结束时系统空闲超过该秒数，则新的工作阶段保持暂停；None 表示关闭
+    #[serde(default)]
+    pub idle_auto_pause_seconds: Option<u32>,
+    /// 工作阶段循环播放专注背景音（需启用 `audio` 特性）
+    #[serde(default)]
+    pub focus_sound_enabled: bool,
+    /// 专注背景音文件路径
+    #[serde(default)]
+    pub focus_loop_sound: Option<String>,
+}
+
+/// 秒数换算为分钟时的取整方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinuteRounding {
+    /// 向下取整
+    Floor,
+    /// 四舍五入（半分钟进位）
+    #[default]
+    Round,
+    /// 向上取整
+    Ceil,
+}
+
+impl MinuteRounding {
+    /// 将秒数换算为分钟；应先汇总秒数再换算，避免逐条取整累积误差
+    pub fn minutes(self, seconds: u64) -> u64 {
+        match self {
+            MinuteRounding::Floor => seconds / 60,
+            MinuteRounding::Round => (seconds + 30) / 60,
+            MinuteRounding::Ceil => seconds.div_ceil(60),
+        }
+    }
+
+    /// 转换为存储使用的标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MinuteRounding::Floor => "floor",
+            MinuteRounding::Round => "round",
+            MinuteRounding::Ceil => "ceil",
+        }
+    }
+
+    /// 从存储标识解析，未知值按默认的四舍五入处理
+    pub fn from_db_string(value: &str) -> Self {
+        match value {
+            "floor" => MinuteRounding::Floor,
+            "ceil" => MinuteRounding::Ceil,
+            _ => MinuteRounding::Round,
+        }
+    }
+}
+
+fn default_deep_work_duration() -> u64 {
+    PomodoroPhase::DeepWork.default_duration()
+}
+
+fn default_system_notifications() -> bool {
+    true
+}
+
+fn default_reminder_grace_secs() -> u64 {
+    PomodoroConfig::default().reminder_grace_secs
 }
 
 impl Default for UserConfig {
@@ -38,13 +163,82 @@ impl Default for UserConfig {
             pomodoro_short_break_duration: 300,
             pomodoro_long_break_duration: 900,
             pomodoro_cycles_until_long_break: 4,
+            pomodoro_deep_work_duration: default_deep_work_duration(),
+            pomodoro_auto_start_next: false,
+            pomodoro_warn_before_seconds: None,
+            pomodoro_phase_sequence: None,
+            pomodoro_require_phase_ack: false,
+            pomodoro_break_reminders: false,
+            pomodoro_reminder_grace_secs: default_reminder_grace_secs(),
             notifications_enabled: true,
             sound_enabled: true,
+            system_notifications: default_system_notifications(),
             theme: "light".to_string(),
+            daily_carry_over: false,
+            last_carry_over_date: None,
+            daily_focus_goal_minutes: None,
+            daily_pomodoro_goal: None,
+            task_complete_sound: None,
+            sync_focus_status: false,
+            auto_archive_done_after_days: None,
+            work_color: None,
+            short_break_color: None,
+            long_break_color: None,
+            enforced_break_seconds: None,
+            focus_minute_rounding: MinuteRounding::default(),
+            idle_auto_pause_seconds: None,
+            focus_sound_enabled: false,
+            focus_loop_sound: None,
+        }
+    }
+}
+
+impl UserConfig {
+    /// 获取阶段的生效颜色：有效的覆盖值优先，未设置或格式无效时使用 [`PomodoroPhase::color`]
+    ///
+    /// 深度工作阶段暂不支持覆盖。
+    pub fn phase_color(&self, phase: PomodoroPhase) -> &str {
+        let override_color = match phase {
+            PomodoroPhase::Work => self.work_color.as_deref(),
+            PomodoroPhase::ShortBreak => self.short_break_color.as_deref(),
+            PomodoroPhase::LongBreak => self.long_break_color.as_deref(),
+            PomodoroPhase::DeepWork => None,
+        };
+        match override_color.map(str::trim) {
+            Some(color) if is_hex_color(color) => color,
+            _ => phase.color(),
         }
     }
 }
 
+/// 检查是否为 `#RGB` 或 `#RRGGBB` 格式的颜色
+fn is_hex_color(value: &str) -> bool {
+    value.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// 消息历史最多保留的条数
+pub const MAX_MESSAGE_HISTORY: usize = 50;
+
+/// 消息类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    Info,
+    Error,
+}
+
+/// 历史消息记录
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MessageRecord {
+    pub kind: MessageKind,
+    pub message: String,
+    pub at: DateTime<Utc>,
+    /// 是否已读
+    pub read: bool,
+}
+
 /// 应用全局状态
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -56,6 +250,8 @@ pub struct AppState {
     pub user_config: Option<UserConfig>,
     pub error_message: Option<String>,
     pub info_message: Option<String>,
+    /// 最近的消息（按时间先后），上限为 `MAX_MESSAGE_HISTORY`
+    pub message_history: VecDeque<MessageRecord>,
 }
 
 impl Default for AppState {
@@ -69,6 +265,7 @@ impl Default for AppState {
             user_config: None,
             error_message: None,
             info_message: None,
+            message_history: VecDeque::new(),
         }
     }
 }
@@ -109,11 +306,56 @@ impl AppState {
         self.error_message = None;
         self.info_message = None;
     }
+
+    /// 记录一条消息：更新当前消息并写入历史，超出上限时丢弃最旧的记录
+    pub fn push_message(&mut self, kind: MessageKind, message: String) {
+        match kind {
+            MessageKind::Info => self.info_message = Some(message.clone()),
+            MessageKind::Error => self.error_message = Some(message.clone()),
+        }
+
+        if self.message_history.len() >= MAX_MESSAGE_HISTORY {
+            self.message_history.pop_front();
+        }
+        self.message_history.push_back(MessageRecord {
+            kind,
+            message,
+            at: Utc::now(),
+            read: false,
+        });
+    }
+}
+
+/// 应用初始化步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitStep {
+    StateManagerReady,
+    DatabaseReady,
+    TaskManagerReady,
+    ConfigLoaded,
+    ServiceCreated,
+}
+
+impl InitStep {
+    /// 转换为人类可读的字符串
+    pub fn to_string(&self) -> &'static str {
+        match self {
+            InitStep::StateManagerReady => "状态管理器已就绪",
+            InitStep::DatabaseReady => "数据库已就绪",
+            InitStep::TaskManagerReady => "任务管理器已就绪",
+            InitStep::ConfigLoaded => "用户配置已加载",
+            InitStep::ServiceCreated => "番茄钟服务已创建",
+        }
+    }
 }
 
 /// 应用事件
 #[derive(Debug, Clone)]
 pub enum AppEvent {
+    // 启动事件
+    InitProgress { step: InitStep, elapsed_ms: u64 },
+
     // 待办事项事件
     TodoCreated(Todo),
     TodoUpdated(Todo),
@@ -142,6 +384,8 @@ pub enum AppEvent {
     ErrorMessage(String),
     InfoMessage(String),
     MessageCleared,
+    /// 未读消息数变化
+    UnreadMessagesChanged(usize),
 }
 
 /// 状态查询请求
@@ -160,39 +404,62 @@ pub enum StateQueryResponse {
     Todos(Vec<Todo>),
     FilteredTodos(Vec<Todo>),
     TodoStats(TodoStats),
-    PomodoroSession(Option<PomodoroSession>),
-    UserConfig(Option<UserConfig>),
+    PomodoroSession(Option<Box<PomodoroSession>>),
+    UserConfig(Option<Box<UserConfig>>),
+}
+
+/// 状态查询请求及其响应通道
+type QueryRequest = (StateQuery, oneshot::Sender<StateQueryResponse>);
+
+/// 尚无事件消费者时最多暂存的事件数
+const MAX_PENDING_EVENTS: usize = 64;
+
+/// 每个订阅者最多缓冲的未读事件数，落后更多时最旧的事件会被丢弃
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// 事件消费者创建前的暂存区
+#[derive(Debug, Default)]
+struct EventBacklog {
+    consumer_created: bool,
+    pending: VecDeque<AppEvent>,
 }
 
 /// 应用状态管理器
+///
+/// 事件约定：事件以广播方式发给所有订阅者（见 [`AppStateManager::subscribe`]）。
+/// 第一个订阅者出现之前，事件只在内存中暂存最近的 `MAX_PENDING_EVENTS` 条
+/// （更早的会被丢弃），并在第一次订阅时转交给该订阅者；之后的事件直接广播。
+///
+/// 每个订阅者最多缓冲 [`EVENT_CHANNEL_CAPACITY`] 条未读事件。处理过慢的订阅者
+/// 落后超过该数量时，最旧的事件会被覆盖，`broadcast::Receiver::recv` 随后返回
+/// `RecvError::Lagged(n)` 报告丢失条数，再从仍在缓冲区中的最旧事件继续读取；
+/// [`EventReceiver`] 会跳过这类报告。发送方不会因慢订阅者而阻塞。
 #[derive(Debug, Clone)]
 pub struct AppStateManager {
     state: Arc<RwLock<AppState>>,
-    event_sender: mpsc::UnboundedSender<AppEvent>,
-    query_sender: mpsc::UnboundedSender<(StateQuery, oneshot::Sender<StateQueryResponse>)>,
-    // 新增：存储接收端
-    event_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<AppEvent>>>>,
-    _query_receiver: Arc<
-        Mutex<Option<mpsc::UnboundedReceiver<(StateQuery, oneshot::Sender<StateQueryResponse>)>>>,
-    >,
+    event_sender: broadcast::Sender<AppEvent>,
+    event_backlog: Arc<Mutex<EventBacklog>>,
+    query_sender: mpsc::UnboundedSender<QueryRequest>,
+    /// 由 [`AppStateManager::start_query_loop`] 取走
+    query_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<QueryRequest>>>>,
 }
 
-// 安全实现 Send + Sync，因为 mpsc::UnboundedSender 可以安全地跨线程发送
+// 安全实现 Send + Sync，因为 broadcast::Sender 与 mpsc::UnboundedSender 可以安全地跨线程发送
 unsafe impl Send for AppStateManager {}
 unsafe impl Sync for AppStateManager {}
 
 impl AppStateManager {
     /// 创建新的状态管理器
     pub fn new() -> Self {
-        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         let (query_sender, query_receiver) = mpsc::unbounded_channel();
 
         Self {
             state: Arc::new(RwLock::new(AppState::new())),
             event_sender,
+            event_backlog: Arc::new(Mutex::new(EventBacklog::default())),
             query_sender,
-            event_receiver: Arc::new(Mutex::new(Some(event_receiver))),
-            _query_receiver: Arc::new(Mutex::new(Some(query_receiver))),
+            query_receiver: Arc::new(Mutex::new(Some(query_receiver))),
         }
     }
 
@@ -206,11 +473,101 @@ impl AppStateManager {
         self.state.write().await
     }
 
-    /// 发送事件
+    /// 发送事件（尚无订阅者时暂存，超出上限丢弃最旧的事件）
+    ///
+    /// 订阅者全部退出后发送的事件直接丢弃，不视为错误。
     pub fn send_event(&self, event: AppEvent) -> Result<()> {
-        self.event_sender
-            .send(event)
-            .map_err(|_| AppError::Other("事件通道已关闭".to_string()))
+        let mut backlog = self
+            .event_backlog
+            .lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock event backlog: {}", e)))?;
+
+        if !backlog.consumer_created {
+            if backlog.pending.len() >= MAX_PENDING_EVENTS {
+                backlog.pending.pop_front();
+            }
+            backlog.pending.push_back(event);
+            return Ok(());
+        }
+
+        // 没有活跃订阅者时 send 返回错误，此时直接丢弃事件
+        let _ = self.event_sender.send(event);
+        Ok(())
+    }
+
+    /// 订阅应用事件，可多次调用，每个订阅者都会收到之后的全部事件
+    ///
+    /// 第一次订阅时，之前暂存的事件会先投递给这个订阅者。
+    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        let mut backlog = self
+            .event_backlog
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let receiver = self.event_sender.subscribe();
+        if !backlog.consumer_created {
+            for event in backlog.pending.drain(..) {
+                let _ = self.event_sender.send(event);
+            }
+            backlog.consumer_created = true;
+        }
+        receiver
+    }
+
+    /// 是否已经有过事件订阅者
+    pub fn has_event_consumer(&self) -> bool {
+        self.event_backlog
+            .lock()
+            .map(|backlog| backlog.consumer_created)
+            .unwrap_or(false)
+    }
+
+    /// 尚未被消费者取走的暂存事件数
+    pub fn pending_event_count(&self) -> usize {
+        self.event_backlog
+            .lock()
+            .map(|backlog| backlog.pending.len())
+            .unwrap_or(0)
+    }
+
+    /// 启动状态查询循环（只能调用一次），之后 [`Self::send_query`] 才会得到响应
+    ///
+    /// 循环在后台任务中按当前状态逐个应答，所有发送端都被丢弃后退出。
+    pub fn start_query_loop(&self) -> Result<()> {
+        let mut receiver = self
+            .query_receiver
+            .lock()
+            .map_err(|e| AppError::Other(format!("Failed to lock query receiver: {}", e)))?
+            .take()
+            .ok_or_else(|| AppError::Other("Query loop already started".to_string()))?;
+
+        let state = Arc::clone(&self.state);
+        tokio::spawn(async move {
+            while let Some((query, reply)) = receiver.recv().await {
+                let response = {
+                    let state = state.read().await;
+                    match query {
+                        StateQuery::GetTodos => StateQueryResponse::Todos(state.todos.clone()),
+                        StateQuery::GetFilteredTodos => StateQueryResponse::FilteredTodos(
+                            state.get_filtered_todos().into_iter().cloned().collect(),
+                        ),
+                        StateQuery::GetTodoStats => {
+                            StateQueryResponse::TodoStats(state.todo_stats.clone())
+                        }
+                        StateQuery::GetPomodoroSession => StateQueryResponse::PomodoroSession(
+                            state.pomodoro_session.clone().map(Box::new),
+                        ),
+                        StateQuery::GetUserConfig => StateQueryResponse::UserConfig(
+                            state.user_config.clone().map(Box::new),
+                        ),
+                    }
+                };
+                // 查询方已放弃等待时直接丢弃响应
+                let _ = reply.send(response);
+            }
+        });
+
+        Ok(())
     }
 
     /// 发送状态查询
@@ -232,8 +589,8 @@ impl AppStateManager {
     pub async fn add_todo(&self, todo: Todo) -> Result<()> {
         {
             let mut state = self.state.write().await;
+            state.todo_stats.record_added(&todo.status);
             state.todos.push(todo.clone());
-            state.todo_stats = TodoStats::from_todos(&state.todos);
         }
 
         self.send_event(AppEvent::TodoCreated(todo))?;
@@ -249,8 +606,10 @@ impl AppStateManager {
         let mut updated_todo = None;
 
         {
-            let mut state = self.state.write().await;
+            let mut guard = self.state.write().await;
+            let state = &mut *guard;
             if let Some(todo) = state.todos.iter_mut().find(|t| t.id == id) {
+                let previous_status = todo.status.clone();
                 if let Some(title) = updates.title {
                     todo.update_title(title);
                 }
@@ -272,20 +631,134 @@ impl AppStateManager {
                     todo.github_issue_number = github_issue_number;
                     todo.updated_at = chrono::Utc::now();
                 }
+                if let Some(due_at) = updates.due_at {
+                    todo.update_due_at(due_at);
+                }
+                if let Some(estimate_minutes) = updates.estimate_minutes {
+                    todo.update_estimate_minutes(estimate_minutes);
+                }
+                if let Some(estimated_pomodoros) = updates.estimated_pomodoros {
+                    todo.update_estimated_pomodoros(estimated_pomodoros);
+                }
+                if let Some(priority) = updates.priority {
+                    todo.update_priority(priority);
+                }
+                if let Some(parent_id) = updates.parent_id {
+                    todo.update_parent(parent_id);
+                }
 
-                updated_todo = Some(todo.clone());
-                state.todo_stats = TodoStats::from_todos(&state.todos);
+                state
+                    .todo_stats
+                    .record_status_change(&previous_status, &todo.status);
+                updated_todo = Some((todo.clone(), previous_status));
             }
         }
 
-        if let Some(todo) = updated_todo {
-            self.send_event(AppEvent::TodoUpdated(todo.clone()))?;
+        if let Some((todo, previous_status)) = updated_todo {
+            self.send_todo_updated(&todo, &previous_status)?;
             Ok(Some(todo))
         } else {
             Ok(None)
         }
     }
 
+    /// 发送任务更新事件，状态发生变化时额外发送 `TodoStatusChanged`
+    fn send_todo_updated(&self, todo: &Todo, previous_status: &TodoStatus) -> Result<()> {
+        self.send_event(AppEvent::TodoUpdated(todo.clone()))?;
+        if todo.status != *previous_status {
+            self.send_event(AppEvent::TodoStatusChanged(
+                todo.id.clone(),
+                todo.status.as_str().to_string(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// 替换任务的标签，任务不存在时返回 None
+    pub async fn set_todo_tags(&self, id: &str, tags: Vec<Tag>) -> Result<Option<Todo>> {
+        let todo = {
+            let mut state = self.state.write().await;
+            state.todos.iter_mut().find(|t| t.id == id).map(|todo| {
+                todo.tags = tags;
+                todo.clone()
+            })
+        };
+
+        if let Some(todo) = &todo {
+            self.send_event(AppEvent::TodoUpdated(todo.clone()))?;
+        }
+        Ok(todo)
+    }
+
+    /// 交换两个任务的手动排序位置（与数据库保持一致），任一任务不存在时返回 false
+    pub async fn swap_todo_order(&self, id_a: &str, id_b: &str) -> Result<bool> {
+        let swapped = {
+            let mut state = self.state.write().await;
+            let pos_a = state.todos.iter().position(|t| t.id == id_a);
+            let pos_b = state.todos.iter().position(|t| t.id == id_b);
+            match (pos_a, pos_b) {
+                (Some(a), Some(b)) => {
+                    let order_a = state.todos[a].sort_order;
+                    state.todos[a].sort_order = state.todos[b].sort_order;
+                    state.todos[b].sort_order = order_a;
+                    Some((state.todos[a].clone(), state.todos[b].clone()))
+                }
+                _ => None,
+            }
+        };
+
+        let Some((a, b)) = swapped else {
+            return Ok(false);
+        };
+        self.send_event(AppEvent::TodoUpdated(a))?;
+        self.send_event(AppEvent::TodoUpdated(b))?;
+        Ok(true)
+    }
+
+    /// 用修改后的标签替换所有任务上的同 ID 标签
+    pub async fn replace_tag_everywhere(&self, tag: &Tag) -> Result<()> {
+        let changed: Vec<Todo> = {
+            let mut state = self.state.write().await;
+            state
+                .todos
+                .iter_mut()
+                .filter(|t| t.has_tag(&tag.id))
+                .map(|todo| {
+                    for existing in todo.tags.iter_mut().filter(|t| t.id == tag.id) {
+                        *existing = tag.clone();
+                    }
+                    todo.clone()
+                })
+                .collect()
+        };
+
+        for todo in changed {
+            self.send_event(AppEvent::TodoUpdated(todo))?;
+        }
+        Ok(())
+    }
+
+    /// 从所有任务中移除指定标签（标签被删除后调用）
+    pub async fn remove_tag_from_all(&self, tag_id: &str) -> Result<()> {
+        let changed: Vec<Todo> = {
+            let mut state = self.state.write().await;
+            state
+                .todos
+                .iter_mut()
+                .filter(|t| t.has_tag(tag_id))
+                .map(|todo| {
+                    todo.tags.retain(|tag| tag.id != tag_id);
+                    todo.clone()
+                })
+                .collect()
+        };
+
+        for todo in changed {
+            self.send_event(AppEvent::TodoUpdated(todo))?;
+        }
+        Ok(())
+    }
+
     /// 删除任务
     pub async fn delete_todo(&self, id: &str) -> Result<bool> {
         let mut deleted = false;
@@ -293,8 +766,12 @@ impl AppStateManager {
         {
             let mut state = self.state.write().await;
             if let Some(pos) = state.todos.iter().position(|t| t.id == id) {
-                state.todos.remove(pos);
-                state.todo_stats = TodoStats::from_todos(&state.todos);
+                let removed = state.todos.remove(pos);
+                state.todo_stats.record_removed(&removed.status);
+                // 与数据库一致：子任务保留并改为顶层任务
+                for child in state.todos.iter_mut().filter(|t| t.parent_id.as_deref() == Some(id)) {
+                    child.parent_id = None;
+                }
                 deleted = true;
             }
         }
@@ -311,16 +788,20 @@ impl AppStateManager {
         let mut updated_todo = None;
 
         {
-            let mut state = self.state.write().await;
+            let mut guard = self.state.write().await;
+            let state = &mut *guard;
             if let Some(todo) = state.todos.iter_mut().find(|t| t.id == id) {
+                let previous_status = todo.status.clone();
                 todo.toggle_status();
-                updated_todo = Some(todo.clone());
-                state.todo_stats = TodoStats::from_todos(&state.todos);
+                state
+                    .todo_stats
+                    .record_status_change(&previous_status, &todo.status);
+                updated_todo = Some((todo.clone(), previous_status));
             }
         }
 
-        if let Some(todo) = updated_todo {
-            self.send_event(AppEvent::TodoUpdated(todo.clone()))?;
+        if let Some((todo, previous_status)) = updated_todo {
+            self.send_todo_updated(&todo, &previous_status)?;
             Ok(Some(todo))
         } else {
             Ok(None)
@@ -336,16 +817,20 @@ impl AppStateManager {
         let mut updated_todo = None;
 
         {
-            let mut state = self.state.write().await;
+            let mut guard = self.state.write().await;
+            let state = &mut *guard;
             if let Some(todo) = state.todos.iter_mut().find(|t| t.id == id) {
+                let previous_status = todo.status.clone();
                 todo.update_status(status);
-                updated_todo = Some(todo.clone());
-                state.todo_stats = TodoStats::from_todos(&state.todos);
+                state
+                    .todo_stats
+                    .record_status_change(&previous_status, &todo.status);
+                updated_todo = Some((todo.clone(), previous_status));
             }
         }
 
-        if let Some(todo) = updated_todo {
-            self.send_event(AppEvent::TodoUpdated(todo.clone()))?;
+        if let Some((todo, previous_status)) = updated_todo {
+            self.send_todo_updated(&todo, &previous_status)?;
             Ok(Some(todo))
         } else {
             Ok(None)
@@ -366,6 +851,23 @@ impl AppStateManager {
         Ok(())
     }
 
+    /// 获取当前状态的完整快照
+    pub async fn snapshot(&self) -> AppState {
+        self.state.read().await.clone()
+    }
+
+    /// 用快照替换当前状态（仅内存状态，不涉及数据库）
+    pub async fn restore(&self, snapshot: AppState) -> Result<()> {
+        let todos = {
+            let mut state = self.state.write().await;
+            *state = snapshot;
+            state.todos.clone()
+        };
+
+        self.send_event(AppEvent::TodoBulkUpdated(todos))?;
+        Ok(())
+    }
+
     // ========================================================================
     // 番茄钟操作
     // ========================================================================
@@ -404,29 +906,79 @@ impl AppStateManager {
         let _ = self.send_event(AppEvent::UserConfigUpdated(config));
     }
 
-    /// 设置 GitHub 项目
     // ========================================================================
     // 消息操作
     // ========================================================================
 
-    /// 设置错误消息
-    pub async fn set_error_message(&self, message: String) {
-        {
+    /// 记录消息并发出对应事件
+    pub async fn push_message(&self, kind: MessageKind, message: String) {
+        // 未读数在持有写锁时算出，避免并发写入让事件里的计数与本条消息对不上
+        let unread = {
             let mut state = self.state.write().await;
-            state.error_message = Some(message.clone());
-        }
+            state.push_message(kind, message.clone());
+            state.message_history.iter().filter(|m| !m.read).count()
+        };
+
+        let _ = self.send_event(match kind {
+            MessageKind::Info => AppEvent::InfoMessage(message),
+            MessageKind::Error => AppEvent::ErrorMessage(message),
+        });
+        let _ = self.send_event(AppEvent::UnreadMessagesChanged(unread));
+    }
 
-        let _ = self.send_event(AppEvent::ErrorMessage(message));
+    /// 设置错误消息
+    pub async fn set_error_message(&self, message: String) {
+        self.push_message(MessageKind::Error, message).await;
     }
 
     /// 设置信息消息
     pub async fn set_info_message(&self, message: String) {
-        {
+        self.push_message(MessageKind::Info, message).await;
+    }
+
+    /// 获取最近的 `limit` 条消息（按时间先后）
+    pub async fn get_recent_messages(&self, limit: usize) -> Vec<MessageRecord> {
+        let state = self.state.read().await;
+        let skip = state.message_history.len().saturating_sub(limit);
+        state.message_history.iter().skip(skip).cloned().collect()
+    }
+
+    /// 清空消息历史（不影响当前显示的消息）
+    pub async fn clear_message_history(&self) {
+        let had_unread = {
             let mut state = self.state.write().await;
-            state.info_message = Some(message.clone());
+            let had_unread = state.message_history.iter().any(|m| !m.read);
+            state.message_history.clear();
+            had_unread
+        };
+
+        if had_unread {
+            let _ = self.send_event(AppEvent::UnreadMessagesChanged(0));
+        }
+    }
+
+    /// 将所有历史消息标记为已读，返回被标记的条数
+    pub async fn mark_messages_read(&self) -> usize {
+        let marked = {
+            let mut state = self.state.write().await;
+            let mut marked = 0;
+            for record in state.message_history.iter_mut().filter(|m| !m.read) {
+                record.read = true;
+                marked += 1;
+            }
+            marked
+        };
+
+        if marked > 0 {
+            let _ = self.send_event(AppEvent::UnreadMessagesChanged(0));
         }
+        marked
+    }
 
-        let _ = self.send_event(AppEvent::InfoMessage(message));
+    /// 未读消息数
+    pub async fn unread_message_count(&self) -> usize {
+        let state = self.state.read().await;
+        state.message_history.iter().filter(|m| !m.read).count()
     }
 
     /// 清除所有消息
@@ -490,47 +1042,320 @@ impl Default for AppStateManager {
     }
 }
 
-/// 事件接收器
+/// 事件接收器：包装广播订阅，跳过因处理过慢而丢失事件的报告
 #[derive(Debug)]
 pub struct EventReceiver {
-    receiver: mpsc::UnboundedReceiver<AppEvent>,
+    receiver: broadcast::Receiver<AppEvent>,
 }
 
 impl EventReceiver {
     /// 创建事件接收器
-    pub fn new(receiver: mpsc::UnboundedReceiver<AppEvent>) -> Self {
+    pub fn new(receiver: broadcast::Receiver<AppEvent>) -> Self {
         Self { receiver }
     }
 
-    /// 接收事件
+    /// 接收事件，通道关闭时返回 None
     pub async fn recv(&mut self) -> Option<AppEvent> {
-        self.receiver.recv().await
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("⚠️  事件处理过慢，丢失了 {} 条事件", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
     }
 
     /// 尝试接收事件（非阻塞）
     pub fn try_recv(&mut self) -> Option<Result<AppEvent>> {
-        match self.receiver.try_recv() {
-            Ok(event) => Some(Ok(event)),
-            Err(e) => match e {
-                tokio::sync::mpsc::error::TryRecvError::Empty => None,
-                tokio::sync::mpsc::error::TryRecvError::Disconnected => {
-                    Some(Err(AppError::ChannelError("通道已关闭".to_string())))
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(Ok(event)),
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    eprintln!("⚠️  事件处理过慢，丢失了 {} 条事件", skipped);
                 }
-            },
+                Err(broadcast::error::TryRecvError::Empty) => return None,
+                Err(broadcast::error::TryRecvError::Closed) => {
+                    return Some(Err(AppError::ChannelError("通道已关闭".to_string())))
+                }
+            }
         }
     }
 }
 
 impl AppStateManager {
-    /// 创建事件接收器
+    /// 创建事件接收器，等同于包装一次 [`Self::subscribe`]，可以多次调用
     pub fn create_event_receiver(&self) -> Result<EventReceiver> {
-        let receiver = self
-            .event_receiver
-            .lock()
-            .map_err(|e| AppError::Other(format!("Failed to lock event receiver: {}", e)))?
-            .take()
-            .ok_or_else(|| AppError::Other("Event receiver already taken".to_string()))?;
+        Ok(EventReceiver::new(self.subscribe()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AppEvent, AppStateManager, MessageKind, MinuteRounding, UserConfig, MAX_MESSAGE_HISTORY,
+        EVENT_CHANNEL_CAPACITY, MAX_PENDING_EVENTS, StateQuery, StateQueryResponse,
+    };
+    use crate::core::pomodoro::PomodoroPhase;
+    use crate::core::todo::{Todo, TodoStats, TodoStatus, TodoUpdate};
+
+    #[test]
+    fn minute_rounding_converts_summed_seconds_once() {
+        // 三个 90 秒的会话：先汇总为 270 秒（4.5 分钟）再取整
+        let total: u64 = [90u64, 90, 90].iter().sum();
+        assert_eq!(MinuteRounding::Round.minutes(total), 5);
+        assert_eq!(MinuteRounding::Floor.minutes(total), 4);
+        assert_eq!(MinuteRounding::Ceil.minutes(total), 5);
+        assert_eq!(MinuteRounding::Ceil.minutes(240), 4);
+        assert_eq!(MinuteRounding::Round.minutes(269), 4);
+
+        for rounding in [MinuteRounding::Floor, MinuteRounding::Round, MinuteRounding::Ceil] {
+            assert_eq!(MinuteRounding::from_db_string(rounding.as_str()), rounding);
+        }
+        assert_eq!(MinuteRounding::default(), MinuteRounding::Round);
+    }
+
+    #[test]
+    fn phase_color_prefers_valid_overrides() {
+        let config = UserConfig {
+            work_color: Some("#10b981".to_string()),
+            short_break_color: Some("blue".to_string()),
+            long_break_color: Some("#abc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.phase_color(PomodoroPhase::Work), "#10b981");
+        assert_eq!(config.phase_color(PomodoroPhase::ShortBreak), PomodoroPhase::ShortBreak.color());
+        assert_eq!(config.phase_color(PomodoroPhase::LongBreak), "#abc");
+
+        let defaults = UserConfig::default();
+        assert_eq!(defaults.phase_color(PomodoroPhase::Work), "#e74c3c");
+    }
+
+    #[tokio::test]
+    async fn cached_stats_track_mutations() {
+        let manager = AppStateManager::new();
+        let todos: Vec<Todo> = ["a", "b", "c"]
+            .iter()
+            .map(|title| Todo::new(title.to_string(), None))
+            .collect();
+        for todo in &todos {
+            manager.add_todo(todo.clone()).await.unwrap();
+        }
+
+        manager.toggle_todo_status(&todos[0].id).await.unwrap();
+        manager
+            .set_todo_status(&todos[1].id, TodoStatus::Done)
+            .await
+            .unwrap();
+        manager
+            .update_todo(&todos[2].id, TodoUpdate::new().with_status(TodoStatus::Done))
+            .await
+            .unwrap();
+        manager.delete_todo(&todos[1].id).await.unwrap();
+
+        let all = manager.get_all_todos().await;
+        assert_eq!(manager.get_todo_stats().await, TodoStats::from_todos(&all));
+    }
+
+    #[test]
+    fn events_without_consumer_are_bounded() {
+        let manager = AppStateManager::new();
+        assert!(!manager.has_event_consumer());
+
+        for i in 0..10_000 {
+            manager
+                .send_event(AppEvent::InfoMessage(i.to_string()))
+                .unwrap();
+        }
+        assert_eq!(manager.pending_event_count(), MAX_PENDING_EVENTS);
+
+        let mut receiver = manager.create_event_receiver().unwrap();
+        assert!(manager.has_event_consumer());
+        assert_eq!(manager.pending_event_count(), 0);
+
+        let mut received = Vec::new();
+        while let Some(Ok(AppEvent::InfoMessage(msg))) = receiver.try_recv() {
+            received.push(msg);
+        }
+        assert_eq!(received.len(), MAX_PENDING_EVENTS);
+        assert_eq!(received.last().map(String::as_str), Some("9999"));
+
+        manager.send_event(AppEvent::MessageCleared).unwrap();
+        assert!(matches!(receiver.try_recv(), Some(Ok(AppEvent::MessageCleared))));
+    }
+
+    #[test]
+    fn every_subscriber_receives_broadcast_events() {
+        let manager = AppStateManager::new();
+        let mut bridge = manager.subscribe();
+        let mut updater = manager.create_event_receiver().unwrap();
+
+        manager.send_event(AppEvent::MessageCleared).unwrap();
+
+        assert!(matches!(bridge.try_recv(), Ok(AppEvent::MessageCleared)));
+        assert!(matches!(updater.try_recv(), Some(Ok(AppEvent::MessageCleared))));
+        assert!(updater.try_recv().is_none());
+    }
+
+    #[test]
+    fn lagging_subscriber_skips_oldest_events() {
+        let manager = AppStateManager::new();
+        let mut receiver = manager.create_event_receiver().unwrap();
+
+        let total = EVENT_CHANNEL_CAPACITY + 10;
+        for i in 0..total {
+            manager
+                .send_event(AppEvent::InfoMessage(i.to_string()))
+                .unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Some(Ok(AppEvent::InfoMessage(msg))) = receiver.try_recv() {
+            received.push(msg);
+        }
+        assert_eq!(received.len(), EVENT_CHANNEL_CAPACITY);
+        assert_eq!(received.first().map(String::as_str), Some("10"));
+        assert_eq!(received.last(), Some(&(total - 1).to_string()));
+    }
+
+    #[tokio::test]
+    async fn status_transitions_emit_status_changed() {
+        let manager = AppStateManager::new();
+        let todo = Todo::new("sound".to_string(), None);
+        manager.add_todo(todo.clone()).await.unwrap();
+        let mut receiver = manager.create_event_receiver().unwrap();
+
+        manager
+            .set_todo_status(&todo.id, TodoStatus::Done)
+            .await
+            .unwrap();
+        manager
+            .update_todo(&todo.id, TodoUpdate::new().with_title("renamed".to_string()))
+            .await
+            .unwrap();
+
+        let mut changes = Vec::new();
+        while let Some(Ok(event)) = receiver.try_recv() {
+            if let AppEvent::TodoStatusChanged(id, status) = event {
+                changes.push((id, status));
+            }
+        }
+        assert_eq!(changes, vec![(todo.id, "done".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn message_history_is_bounded_and_ordered() {
+        let manager = AppStateManager::new();
+        manager.set_info_message("first".to_string()).await;
+        manager.set_error_message("second".to_string()).await;
+
+        let recent = manager.get_recent_messages(10).await;
+        let summary: Vec<_> = recent
+            .iter()
+            .map(|m| (m.kind, m.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(MessageKind::Info, "first"), (MessageKind::Error, "second")]
+        );
+
+        for i in 0..MAX_MESSAGE_HISTORY + 5 {
+            manager.push_message(MessageKind::Info, i.to_string()).await;
+        }
+        let all = manager.get_recent_messages(usize::MAX).await;
+        assert_eq!(all.len(), MAX_MESSAGE_HISTORY);
+        assert_eq!(all.first().unwrap().message, "5");
+        assert_eq!(all.last().unwrap().message, (MAX_MESSAGE_HISTORY + 4).to_string());
+
+        let latest = manager.get_recent_messages(2).await;
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[1].message, all.last().unwrap().message);
+
+        manager.clear_message_history().await;
+        assert!(manager.get_recent_messages(10).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_loop_answers_state_queries() {
+        let manager = AppStateManager::new();
+        manager.add_todo(Todo::new("queried".to_string(), None)).await.unwrap();
+        manager.start_query_loop().unwrap();
+        assert!(manager.start_query_loop().is_err());
+
+        let ask = |query| {
+            tokio::time::timeout(std::time::Duration::from_secs(1), manager.send_query(query))
+        };
+        match ask(StateQuery::GetTodos).await.expect("query timed out").unwrap() {
+            StateQueryResponse::Todos(todos) => {
+                assert_eq!(todos.len(), 1);
+                assert_eq!(todos[0].title, "queried");
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert!(matches!(
+            ask(StateQuery::GetFilteredTodos).await.unwrap().unwrap(),
+            StateQueryResponse::FilteredTodos(todos) if todos.len() == 1
+        ));
+        assert!(matches!(
+            ask(StateQuery::GetTodoStats).await.unwrap().unwrap(),
+            StateQueryResponse::TodoStats(stats) if stats.total == 1
+        ));
+        assert!(matches!(
+            ask(StateQuery::GetPomodoroSession).await.unwrap().unwrap(),
+            StateQueryResponse::PomodoroSession(None)
+        ));
+        assert!(matches!(
+            ask(StateQuery::GetUserConfig).await.unwrap().unwrap(),
+            StateQueryResponse::UserConfig(None)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unread_count_tracks_new_and_read_messages() {
+        let manager = AppStateManager::new();
+        let mut receiver = manager.create_event_receiver().unwrap();
+
+        manager.set_info_message("one".to_string()).await;
+        manager.set_error_message("two".to_string()).await;
+        assert_eq!(manager.unread_message_count().await, 2);
+        assert!(manager.get_recent_messages(10).await.iter().all(|m| !m.read));
+
+        assert_eq!(manager.mark_messages_read().await, 2);
+        assert_eq!(manager.unread_message_count().await, 0);
+        assert!(manager.get_recent_messages(10).await.iter().all(|m| m.read));
+        assert_eq!(manager.mark_messages_read().await, 0);
+
+        manager.set_info_message("three".to_string()).await;
+        assert_eq!(manager.unread_message_count().await, 1);
+
+        let mut counts = Vec::new();
+        while let Some(Ok(event)) = receiver.try_recv() {
+            if let AppEvent::UnreadMessagesChanged(count) = event {
+                counts.push(count);
+            }
+        }
+        assert_eq!(counts, vec![1, 2, 0, 1]);
+    }
 
-        Ok(EventReceiver::new(receiver))
+    #[tokio::test]
+    async fn restore_brings_back_cleared_todos() {
+        let manager = AppStateManager::new();
+        let mut done = Todo::new("done".to_string(), None);
+        done.update_status(TodoStatus::Done);
+        let open = Todo::new("open".to_string(), None);
+        manager
+            .bulk_update_todos(vec![done.clone(), open.clone()])
+            .await
+            .unwrap();
+
+        let snapshot = manager.snapshot().await;
+        manager.delete_todo(&done.id).await.unwrap();
+        assert_eq!(manager.get_all_todos().await.len(), 1);
+
+        manager.restore(snapshot).await.unwrap();
+        let ids: Vec<_> = manager.get_all_todos().await.into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![done.id, open.id]);
+        assert_eq!(manager.get_todo_stats().await.done, 1);
     }
 }
@@ -2,4 +2,7 @@
 
 pub mod app_state;
 
-pub use app_state::{AppState, AppStateManager, UserConfig};
+pub use app_state::{
+    AppEvent, AppState, AppStateManager, EventReceiver, InitStep, MessageKind, MessageRecord,
+    MinuteRounding, UserConfig, EVENT_CHANNEL_CAPACITY,
+};
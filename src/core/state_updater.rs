@@ -166,18 +166,18 @@ impl StateUpdater {
                     }
 
                     // 更新番茄钟状态（带超时保护）
-                    if let Err(_) = tokio::time::timeout(
+                    if tokio::time::timeout(
                         Duration::from_millis(50),
                         Self::update_pomodoro_state(&state_manager, &pomodoro_service)
-                    ).await {
+                    ).await.is_err() {
                         eprintln!("Pomodoro state update timeout");
                     }
 
                     // 更新数据库状态（带超时保护）
-                    if let Err(_) = tokio::time::timeout(
+                    if tokio::time::timeout(
                         Duration::from_millis(100),
                         Self::update_database_state(&state_manager, &database)
-                    ).await {
+                    ).await.is_err() {
                         eprintln!("Database state update timeout");
                     }
 
@@ -197,14 +197,17 @@ impl StateUpdater {
     ) {
         let mut service = pomodoro_service.write().await;
 
-        // 处理番茄钟 tick
+        // 处理番茄钟 tick，按墙上时间结算，与更新间隔无关
         if let Some(event) = service.tick().await {
             match event {
                 PomodoroEvent::Tick {
                     remaining: _,
+                    remaining_ms: _,
                     formatted: _,
                     progress: _,
-                } => {
+                }
+                | PomodoroEvent::PhaseEndingSoon { remaining: _ }
+                | PomodoroEvent::BreakReminder { overrun: _ } => {
                     // 更新 UI 显示
                     let _ = state_manager.send_event(AppEvent::PomodoroEvent(event));
                 }
@@ -212,6 +215,9 @@ impl StateUpdater {
                     completed_phase: _,
                     next_phase: _,
                     cycle_count: _,
+                    session_uuid: _,
+                    pause_counts: _,
+                    interruptions: _,
                 } => {
                     // 阶段完成，发送通知
                     let _ = state_manager.send_event(AppEvent::PomodoroStarted);
@@ -233,7 +239,7 @@ impl StateUpdater {
     /// 更新数据库状态
     async fn update_database_state(state_manager: &Arc<AppStateManager>, database: &Arc<Database>) {
         // 加载最新的任务列表
-        if let Ok(todos) = Arc::clone(&database).get_all_todos().await {
+        if let Ok(todos) = Arc::clone(database).get_all_todos().await {
             let _ = state_manager.bulk_update_todos(todos).await;
         }
     }
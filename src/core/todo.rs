@@ -1,10 +1,10 @@
 //! 待办事项数据模型和服务
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::core::error::{AppError, Result};
+use crate::core::error::{AppError, Result, ValidationKind};
 
 /// 任务状态枚举
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -25,6 +25,15 @@ impl TodoStatus {
         }
     }
 
+    /// 转换为存储与事件中使用的标识（与 [`Self::from_string`] 对应）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TodoStatus::Todo => "todo",
+            TodoStatus::InProgress => "in_progress",
+            TodoStatus::Done => "done",
+        }
+    }
+
     /// 从字符串解析状态
     pub fn from_string(s: &str) -> Result<Self> {
         match s {
@@ -36,8 +45,71 @@ impl TodoStatus {
     }
 }
 
+/// 任务优先级
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl TodoPriority {
+    /// 转换为存储中使用的标识（与 [`Self::from_string`] 对应）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TodoPriority::Low => "low",
+            TodoPriority::Medium => "medium",
+            TodoPriority::High => "high",
+        }
+    }
+
+    /// 从字符串解析优先级
+    pub fn from_string(s: &str) -> Result<Self> {
+        match s {
+            "low" => Ok(TodoPriority::Low),
+            "medium" => Ok(TodoPriority::Medium),
+            "high" => Ok(TodoPriority::High),
+            _ => Err(AppError::Validation(format!("未知的优先级: {}", s))),
+        }
+    }
+}
+
+/// 任务备注允许的最大长度（字节）
+pub const MAX_NOTE_LENGTH: usize = 2000;
+
+/// 任务下按时间追加的备注
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoNote {
+    pub id: String,
+    pub todo_id: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TodoNote {
+    /// 校验备注内容，返回去除首尾空白后的文本
+    pub fn validate_body(body: &str) -> Result<String> {
+        let body = body.trim();
+        if body.is_empty() {
+            return Err(AppError::validation(
+                ValidationKind::NoteEmpty,
+                "备注内容不能为空",
+            ));
+        }
+        if body.len() > MAX_NOTE_LENGTH {
+            return Err(AppError::validation(
+                ValidationKind::NoteTooLong,
+                format!("备注不能超过 {} 字符", MAX_NOTE_LENGTH),
+            ));
+        }
+        Ok(body.to_string())
+    }
+}
+
 /// 标签实体
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tag {
     pub id: String,
     pub name: String,
@@ -55,6 +127,131 @@ impl Tag {
             created_at: Utc::now(),
         }
     }
+
+    /// 标签名的规范化形式（去除首尾空白并忽略大小写），用于判断重名
+    pub fn normalize_name(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+}
+
+/// 标签排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSort {
+    /// 按创建时间倒序（最新在前），同一时间按插入顺序倒序
+    #[default]
+    CreatedAt,
+    /// 按名称升序（忽略大小写），同名按插入顺序
+    Name,
+}
+
+impl TagSort {
+    /// 从字符串解析排序方式
+    pub fn from_string(s: &str) -> Result<Self> {
+        match s {
+            "created_at" => Ok(TagSort::CreatedAt),
+            "name" => Ok(TagSort::Name),
+            _ => Err(AppError::Validation(format!("未知的标签排序方式: {}", s))),
+        }
+    }
+}
+
+/// 任务排序字段，排序方向由调用方指定，相同值按 ID 升序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// 按更新时间
+    #[default]
+    UpdatedAt,
+    /// 按创建时间
+    CreatedAt,
+    /// 按截止时间，无论方向没有截止时间的任务都排在最后
+    DueDate,
+    /// 按标题（忽略大小写）
+    Title,
+    /// 按优先级，升序为低到高
+    Priority,
+    /// 按手动排序位置，升序为列表中从上到下
+    Manual,
+}
+
+impl SortKey {
+    /// 从字符串解析排序字段
+    pub fn from_string(s: &str) -> Result<Self> {
+        match s {
+            "updated_at" => Ok(SortKey::UpdatedAt),
+            "created_at" => Ok(SortKey::CreatedAt),
+            "due_date" => Ok(SortKey::DueDate),
+            "title" => Ok(SortKey::Title),
+            "priority" => Ok(SortKey::Priority),
+            "manual" => Ok(SortKey::Manual),
+            _ => Err(AppError::Validation(format!("未知的任务排序字段: {}", s))),
+        }
+    }
+
+    /// 该字段的默认方向：时间与优先级倒序（最新、最高在前），截止时间、标题与手动顺序升序
+    pub fn default_ascending(&self) -> bool {
+        matches!(self, SortKey::DueDate | SortKey::Title | SortKey::Manual)
+    }
+
+    /// 按默认方向比较两个任务，相同值按 ID 升序（与数据库排序一致）
+    pub fn compare(&self, a: &Todo, b: &Todo) -> std::cmp::Ordering {
+        let ordering = match self {
+            SortKey::UpdatedAt => b.updated_at.cmp(&a.updated_at),
+            SortKey::CreatedAt => b.created_at.cmp(&a.created_at),
+            SortKey::DueDate => {
+                (a.due_at.is_none(), a.due_at).cmp(&(b.due_at.is_none(), b.due_at))
+            }
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortKey::Priority => b.priority.cmp(&a.priority),
+            SortKey::Manual => a.sort_order.cmp(&b.sort_order),
+        };
+        ordering.then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+/// 手动调整任务顺序的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoveDirection {
+    /// 与前一个任务交换
+    Up,
+    /// 与后一个任务交换
+    Down,
+}
+
+/// 新建任务时对非 `Todo` 初始状态的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialStatusPolicy {
+    /// 允许任意初始状态
+    #[default]
+    Allow,
+    /// 允许，但输出警告
+    Warn,
+    /// 拒绝非 `Todo` 的初始状态
+    Block,
+}
+
+impl InitialStatusPolicy {
+    /// 按策略检查新任务的初始状态
+    pub fn check(&self, status: &TodoStatus) -> Result<()> {
+        if *status == TodoStatus::Todo {
+            return Ok(());
+        }
+
+        match self {
+            InitialStatusPolicy::Allow => Ok(()),
+            InitialStatusPolicy::Warn => {
+                eprintln!("⚠️  新任务以非待办状态创建: {}", status.to_string());
+                Ok(())
+            }
+            InitialStatusPolicy::Block => Err(AppError::Validation(format!(
+                "不允许以「{}」状态创建任务",
+                status.to_string()
+            ))),
+        }
+    }
 }
 
 /// 任务实体
@@ -68,6 +265,30 @@ pub struct Todo {
     pub github_issue_id: Option<i64>,
     pub github_project_id: Option<i64>,
     pub github_issue_number: Option<i64>,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// 预估耗时（分钟）
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// 预估需要的番茄数
+    #[serde(default)]
+    pub estimated_pomodoros: Option<u32>,
+    /// 已归档的任务默认不出现在任务列表中
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    /// 任务的标签，从数据库加载时批量填充
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// 手动排序位置，越小越靠前
+    #[serde(default)]
+    pub sort_order: i64,
+    /// 父任务 ID，子任务才有；父任务删除后置空
+    #[serde(default)]
+    pub parent_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -84,11 +305,35 @@ impl Todo {
             github_issue_id: None,
             github_project_id: None,
             github_issue_number: None,
+            due_at: None,
+            completed_at: None,
+            estimate_minutes: None,
+            estimated_pomodoros: None,
+            archived: false,
+            priority: TodoPriority::default(),
+            tags: Vec::new(),
+            sort_order: 0,
+            parent_id: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// 是否带有指定 ID 的标签
+    pub fn has_tag(&self, tag_id: &str) -> bool {
+        self.tags.iter().any(|tag| tag.id == tag_id)
+    }
+
+    /// 标题或描述是否包含关键词（不区分大小写）
+    pub fn matches_search(&self, search: &str) -> bool {
+        let search_lower = search.to_lowercase();
+        self.title.to_lowercase().contains(&search_lower)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&search_lower))
+    }
+
     /// 更新任务标题
     pub fn update_title(&mut self, title: String) {
         self.title = title;
@@ -102,19 +347,26 @@ impl Todo {
     }
 
     /// 更新任务状态
+    ///
+    /// 进入 `Done` 时记录完成时间（已完成则保留原值），离开 `Done` 时清空。
     pub fn update_status(&mut self, status: TodoStatus) {
+        let now = Utc::now();
+        self.completed_at = match status {
+            TodoStatus::Done => self.completed_at.or(Some(now)),
+            _ => None,
+        };
         self.status = status;
-        self.updated_at = Utc::now();
+        self.updated_at = now;
     }
 
     /// 切换任务状态
     pub fn toggle_status(&mut self) {
-        self.status = match self.status {
+        let next = match self.status {
             TodoStatus::Todo => TodoStatus::InProgress,
             TodoStatus::InProgress => TodoStatus::Done,
             TodoStatus::Done => TodoStatus::Todo,
         };
-        self.updated_at = Utc::now();
+        self.update_status(next);
     }
 
     /// 检查任务是否完成
@@ -122,6 +374,36 @@ impl Todo {
         matches!(self.status, TodoStatus::Done)
     }
 
+    /// 更新截止时间
+    pub fn update_due_at(&mut self, due_at: Option<DateTime<Utc>>) {
+        self.due_at = due_at;
+        self.updated_at = Utc::now();
+    }
+
+    /// 更新预估耗时
+    pub fn update_estimate_minutes(&mut self, estimate_minutes: Option<u32>) {
+        self.estimate_minutes = estimate_minutes;
+        self.updated_at = Utc::now();
+    }
+
+    /// 更新预估番茄数
+    pub fn update_estimated_pomodoros(&mut self, estimated_pomodoros: Option<u32>) {
+        self.estimated_pomodoros = estimated_pomodoros;
+        self.updated_at = Utc::now();
+    }
+
+    /// 更新优先级
+    pub fn update_priority(&mut self, priority: TodoPriority) {
+        self.priority = priority;
+        self.updated_at = Utc::now();
+    }
+
+    /// 更新父任务（None 表示改为顶层任务）
+    pub fn update_parent(&mut self, parent_id: Option<String>) {
+        self.parent_id = parent_id;
+        self.updated_at = Utc::now();
+    }
+
     /// 绑定 GitHub Issue / Project 元数据
     pub fn set_github_info(&mut self, issue_id: i64, issue_number: i64, project_id: i64) {
         self.github_issue_id = Some(issue_id);
@@ -145,6 +427,17 @@ pub struct NewTodo {
     pub title: String,
     pub description: Option<String>,
     pub status: TodoStatus,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    #[serde(default)]
+    pub estimated_pomodoros: Option<u32>,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    /// 父任务 ID，设置后创建为该任务的子任务
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 impl Default for NewTodo {
@@ -153,27 +446,78 @@ impl Default for NewTodo {
             title: String::new(),
             description: None,
             status: TodoStatus::Todo,
+            due_at: None,
+            estimate_minutes: None,
+            estimated_pomodoros: None,
+            priority: TodoPriority::default(),
+            parent_id: None,
         }
     }
 }
 
 impl NewTodo {
+    /// 设置父任务
+    pub fn with_parent(mut self, parent_id: String) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    /// 解析 Markdown 清单：`- [ ] 任务` 为待办，`- [x] 任务` 为已完成
+    ///
+    /// 支持 `-`、`*`、`+` 列表符号；标题、正文等非清单行被忽略，缩进的子项按普通任务平铺。
+    pub fn parse_markdown_checklist(md: &str) -> Vec<NewTodo> {
+        md.lines()
+            .filter_map(|line| {
+                let item = line
+                    .trim_start()
+                    .strip_prefix(['-', '*', '+'])?
+                    .strip_prefix(' ')?
+                    .trim_start();
+                let (status, title) = if let Some(rest) = item.strip_prefix("[ ]") {
+                    (TodoStatus::Todo, rest)
+                } else if let Some(rest) = item
+                    .strip_prefix("[x]")
+                    .or_else(|| item.strip_prefix("[X]"))
+                {
+                    (TodoStatus::Done, rest)
+                } else {
+                    return None;
+                };
+
+                let title = title.trim();
+                if title.is_empty() {
+                    return None;
+                }
+                Some(NewTodo {
+                    title: title.to_string(),
+                    status,
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
     /// 验证数据有效性
     pub fn validate(&self) -> Result<()> {
         if self.title.trim().is_empty() {
-            return Err(AppError::Validation("任务标题不能为空".to_string()));
+            return Err(AppError::validation(
+                ValidationKind::TitleEmpty,
+                "任务标题不能为空",
+            ));
         }
 
         if self.title.len() > 200 {
-            return Err(AppError::Validation(
-                "任务标题不能超过 200 字符".to_string(),
+            return Err(AppError::validation(
+                ValidationKind::TitleTooLong,
+                "任务标题不能超过 200 字符",
             ));
         }
 
         if let Some(desc) = &self.description {
             if desc.len() > 5000 {
-                return Err(AppError::Validation(
-                    "任务描述不能超过 5000 字符".to_string(),
+                return Err(AppError::validation(
+                    ValidationKind::DescriptionTooLong,
+                    "任务描述不能超过 5000 字符",
                 ));
             }
         }
@@ -191,6 +535,16 @@ pub struct TodoUpdate {
     pub github_issue_id: Option<Option<i64>>,
     pub github_project_id: Option<Option<i64>>,
     pub github_issue_number: Option<Option<i64>>,
+    #[serde(default)]
+    pub due_at: Option<Option<DateTime<Utc>>>, // Some(None) 表示清除截止时间
+    #[serde(default)]
+    pub estimate_minutes: Option<Option<u32>>, // Some(None) 表示清除预估
+    #[serde(default)]
+    pub estimated_pomodoros: Option<Option<u32>>, // Some(None) 表示清除预估番茄数
+    #[serde(default)]
+    pub priority: Option<TodoPriority>,
+    #[serde(default)]
+    pub parent_id: Option<Option<String>>, // Some(None) 表示改为顶层任务
 }
 
 impl TodoUpdate {
@@ -235,6 +589,36 @@ impl TodoUpdate {
         self
     }
 
+    /// 设置截止时间（Some(None) 表示清空）
+    pub fn with_due_at(mut self, due_at: Option<DateTime<Utc>>) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    /// 设置预估耗时（Some(None) 表示清空）
+    pub fn with_estimate_minutes(mut self, estimate_minutes: Option<u32>) -> Self {
+        self.estimate_minutes = Some(estimate_minutes);
+        self
+    }
+
+    /// 设置预估番茄数（Some(None) 表示清空）
+    pub fn with_estimated_pomodoros(mut self, estimated_pomodoros: Option<u32>) -> Self {
+        self.estimated_pomodoros = Some(estimated_pomodoros);
+        self
+    }
+
+    /// 设置优先级
+    pub fn with_priority(mut self, priority: TodoPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// 设置父任务（Some(None) 表示改为顶层任务）
+    pub fn with_parent(mut self, parent_id: Option<String>) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
     /// 检查是否有任何更新
     pub fn has_updates(&self) -> bool {
         self.title.is_some()
@@ -243,15 +627,34 @@ impl TodoUpdate {
             || self.github_issue_id.is_some()
             || self.github_project_id.is_some()
             || self.github_issue_number.is_some()
+            || self.due_at.is_some()
+            || self.estimate_minutes.is_some()
+            || self.estimated_pomodoros.is_some()
+            || self.priority.is_some()
+            || self.parent_id.is_some()
     }
 }
 
+/// 设置了搜索关键词但没有指定 `limit` 时的默认结果上限
+pub const DEFAULT_SEARCH_LIMIT: usize = 200;
+
 /// 任务筛选器
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TodoFilter {
     pub status: Option<TodoStatus>,
     pub search: Option<String>,
     pub show_completed: bool,
+    #[serde(default)]
+    pub show_archived: bool,
+    /// 只保留截止时间已过且未完成的任务，没有截止时间的任务不算逾期
+    #[serde(default)]
+    pub overdue_only: bool,
+    /// 排序字段，未指定时按更新时间倒序
+    #[serde(default)]
+    pub sort_by: Option<SortKey>,
+    /// 只保留同时带有这些标签的任务，为空时不按标签筛选
+    #[serde(default)]
+    pub tag_ids: Vec<String>,
     pub limit: Option<usize>,
 }
 
@@ -261,6 +664,10 @@ impl Default for TodoFilter {
             status: None,
             search: None,
             show_completed: true,
+            show_archived: false,
+            overdue_only: false,
+            sort_by: None,
+            tag_ids: Vec::new(),
             limit: None,
         }
     }
@@ -290,24 +697,47 @@ impl TodoFilter {
         }
     }
 
-    /// 设置搜索关键词
+    /// 设置搜索关键词，空白关键词视为不搜索
     pub fn with_search(mut self, search: String) -> Self {
-        self.search = Some(search);
+        self.search = if search.trim().is_empty() {
+            None
+        } else {
+            Some(search)
+        };
         self
     }
 
+    /// 实际生效的搜索关键词；直接构造或反序列化得到的空白关键词同样视为 `None`
+    pub fn search_term(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .filter(|search| !search.trim().is_empty())
+    }
+
     /// 设置状态筛选
     pub fn with_status(mut self, status: TodoStatus) -> Self {
         self.status = Some(status);
         self
     }
 
+    /// 设置标签筛选，任务需要带有全部指定标签
+    pub fn with_tags(mut self, tag_ids: Vec<String>) -> Self {
+        self.tag_ids = tag_ids;
+        self
+    }
+
     /// 设置结果限制
     pub fn with_limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    /// 实际生效的结果上限：显式 `limit` 优先，搜索时默认为 [`DEFAULT_SEARCH_LIMIT`]
+    pub fn effective_limit(&self) -> Option<usize> {
+        self.limit
+            .or_else(|| self.search_term().map(|_| DEFAULT_SEARCH_LIMIT))
+    }
+
     /// 应用筛选器到任务列表
     pub fn apply<'a>(&self, todos: &'a [Todo]) -> Vec<&'a Todo> {
         let mut filtered: Vec<&Todo> = todos.iter().collect();
@@ -322,23 +752,33 @@ impl TodoFilter {
             filtered.retain(|t| !t.is_done());
         }
 
+        // 隐藏已归档的任务
+        if !self.show_archived {
+            filtered.retain(|t| !t.archived);
+        }
+
+        // 只保留逾期任务
+        if self.overdue_only {
+            let now = Utc::now();
+            filtered.retain(|t| !t.is_done() && t.due_at.is_some_and(|due| due < now));
+        }
+
+        // 按标签筛选
+        if !self.tag_ids.is_empty() {
+            filtered.retain(|t| self.tag_ids.iter().all(|tag_id| t.has_tag(tag_id)));
+        }
+
         // 按关键词搜索
-        if let Some(search) = &self.search {
-            let search_lower = search.to_lowercase();
-            filtered.retain(|t| {
-                t.title.to_lowercase().contains(&search_lower)
-                    || t.description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&search_lower))
-                        .unwrap_or(false)
-            });
+        if let Some(search) = self.search_term() {
+            filtered.retain(|t| t.matches_search(search));
         }
 
-        // 按更新时间倒序排序
-        filtered.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        // 按指定字段排序，默认按更新时间倒序
+        let sort_key = self.sort_by.unwrap_or_default();
+        filtered.sort_by(|a, b| sort_key.compare(a, b));
 
         // 应用限制
-        if let Some(limit) = self.limit {
+        if let Some(limit) = self.effective_limit() {
             filtered.truncate(limit);
         }
 
@@ -346,6 +786,205 @@ impl TodoFilter {
     }
 }
 
+/// 任务状态的一次变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: TodoStatus,
+    pub to: TodoStatus,
+}
+
+/// 切换任务状态的结果：更新后的任务及发生的状态变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleResult {
+    pub todo: Todo,
+    pub transition: StatusTransition,
+}
+
+/// 带截断标记的搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoSearchResult {
+    pub todos: Vec<Todo>,
+    /// 匹配数超过上限、只返回了前若干条
+    pub truncated: bool,
+}
+
+/// 任务及其预估与实际完成的番茄数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoWithProgress {
+    pub todo: Todo,
+    pub estimated_pomodoros: Option<u32>,
+    /// 已完成的工作阶段数（含深度工作），不含休息
+    pub actual_pomodoros: u32,
+}
+
+impl TodoWithProgress {
+    /// 组合任务与实际番茄数，预估值取自任务本身
+    pub fn new(todo: Todo, actual_pomodoros: u32) -> Self {
+        Self {
+            estimated_pomodoros: todo.estimated_pomodoros,
+            todo,
+            actual_pomodoros,
+        }
+    }
+
+    /// 实际番茄数减去预估，正数表示超出预估；未预估时为 None
+    pub fn variance(&self) -> Option<i64> {
+        self.estimated_pomodoros
+            .map(|estimate| i64::from(self.actual_pomodoros) - i64::from(estimate))
+    }
+}
+
+/// 分页查询的一页任务及未删除任务总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoPage {
+    pub todos: Vec<Todo>,
+    pub total: usize,
+}
+
+/// 挑选下一个建议开始的任务
+///
+/// 排序规则：
+/// 1. 只考虑未完成且未归档的任务；
+/// 2. 优先级高的优先；
+/// 3. 优先级相同时有截止时间的优先，截止越早越靠前；
+/// 4. 再按手动排序位置（`sort_order`）从小到大，最后按创建时间和 id 保证结果稳定。
+pub fn suggest_next_todo(todos: &[Todo]) -> Option<&Todo> {
+    todos.iter().filter(|t| !t.is_done() && !t.archived).min_by(|a, b| {
+        let due_key = |t: &Todo| (t.due_at.is_none(), t.due_at);
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| due_key(a).cmp(&due_key(b)))
+            .then_with(|| a.sort_order.cmp(&b.sort_order))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+            .then_with(|| a.id.cmp(&b.id))
+    })
+}
+
+/// 未完成任务的总耗时估算
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EstimatedBacklog {
+    pub estimated_minutes: u64,
+    /// 自带预估的未完成任务数
+    pub todos_with_estimate: usize,
+    /// 没有预估、按平均值估算的未完成任务数
+    pub todos_without: usize,
+}
+
+impl EstimatedBacklog {
+    /// 汇总未完成且未归档任务的预估耗时，没有预估的任务按 `fallback_minutes` 计
+    pub fn compute(todos: &[Todo], fallback_minutes: u64) -> Self {
+        let mut backlog = Self {
+            estimated_minutes: 0,
+            todos_with_estimate: 0,
+            todos_without: 0,
+        };
+        for todo in todos.iter().filter(|t| !t.is_done() && !t.archived) {
+            match todo.estimate_minutes {
+                Some(minutes) => {
+                    backlog.estimated_minutes += u64::from(minutes);
+                    backlog.todos_with_estimate += 1;
+                }
+                None => {
+                    backlog.estimated_minutes += fallback_minutes;
+                    backlog.todos_without += 1;
+                }
+            }
+        }
+        backlog
+    }
+}
+
+/// 打印计划的时间范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanScope {
+    Day,
+    /// 自然周（周一至周日）
+    Week,
+}
+
+impl PlanScope {
+    /// 计算包含 `today` 的起止日期（含首尾）
+    pub fn date_range(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            PlanScope::Day => (today, today),
+            PlanScope::Week => {
+                let start = today - chrono::Duration::days(i64::from(today.weekday().num_days_from_monday()));
+                (start, start + chrono::Duration::days(6))
+            }
+        }
+    }
+}
+
+/// 生成可打印的 Markdown 计划
+///
+/// 包含截止时间不晚于 `due_before` 的未完成任务（含已逾期）以及所有进行中的任务，
+/// 按优先级从高到低分组，组内进行中的任务在前，其余按截止时间（无截止时间的在后）、创建时间排序。
+/// 已归档的任务不列出。
+pub fn render_plan(todos: &[Todo], scope: PlanScope, today: NaiveDate, due_before: DateTime<Utc>) -> String {
+    let (start, end) = scope.date_range(today);
+    let mut plan = match scope {
+        PlanScope::Day => format!("# 今日计划（{}）\n", start),
+        PlanScope::Week => format!("# 本周计划（{} – {}）\n", start, end),
+    };
+
+    let mut planned: Vec<&Todo> = todos
+        .iter()
+        .filter(|t| !t.is_done() && !t.archived)
+        .filter(|t| {
+            t.status == TodoStatus::InProgress || t.due_at.is_some_and(|due| due <= due_before)
+        })
+        .collect();
+    planned.sort_by(|a, b| {
+        let due_key = |t: &Todo| (t.status != TodoStatus::InProgress, t.due_at.is_none(), t.due_at);
+        due_key(a)
+            .cmp(&due_key(b))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    if planned.is_empty() {
+        plan.push_str("\n暂无待完成的任务\n");
+        return plan;
+    }
+
+    for (priority, heading) in [
+        (TodoPriority::High, "高优先级"),
+        (TodoPriority::Medium, "中优先级"),
+        (TodoPriority::Low, "低优先级"),
+    ] {
+        let group: Vec<&&Todo> = planned.iter().filter(|t| t.priority == priority).collect();
+        if group.is_empty() {
+            continue;
+        }
+
+        plan.push_str(&format!("\n## {}\n\n", heading));
+        for todo in group {
+            let mut details = Vec::new();
+            if todo.status == TodoStatus::InProgress {
+                details.push(String::from(todo.status.to_string()));
+            }
+            if let Some(minutes) = todo.estimate_minutes {
+                details.push(format!("预估 {} 分钟", minutes));
+            }
+            if let Some(due) = todo.due_at {
+                details.push(format!(
+                    "截止 {}",
+                    due.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ));
+            }
+
+            plan.push_str(&format!("- [ ] {}", todo.title));
+            if !details.is_empty() {
+                plan.push_str(&format!("（{}）", details.join("，")));
+            }
+            plan.push('\n');
+        }
+    }
+
+    plan
+}
+
 /// 待办事项服务
 #[derive(Debug)]
 pub struct TodoService {
@@ -353,7 +992,7 @@ pub struct TodoService {
 }
 
 /// 任务统计信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TodoStats {
     pub total: usize,
     pub todo: usize,
@@ -384,6 +1023,57 @@ impl TodoStats {
         stats
     }
 
+    /// 统计指定父任务的直接子任务，没有子任务时返回 None
+    ///
+    /// 父任务的完成度可由 [`Self::completion_percent`] 换算。
+    pub fn from_subtasks(todos: &[Todo], parent_id: &str) -> Option<Self> {
+        let children: Vec<Todo> = todos
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == Some(parent_id))
+            .cloned()
+            .collect();
+        (!children.is_empty()).then(|| Self::from_todos(&children))
+    }
+
+    /// 已完成任务占比（0-100，向下取整），没有任务时为 0
+    pub fn completion_percent(&self) -> u8 {
+        if self.total == 0 {
+            return 0;
+        }
+        (self.done * 100 / self.total) as u8
+    }
+
+    /// 记录新增一个指定状态的任务
+    pub fn record_added(&mut self, status: &TodoStatus) {
+        self.total += 1;
+        *self.count_mut(status) += 1;
+    }
+
+    /// 记录移除一个指定状态的任务
+    pub fn record_removed(&mut self, status: &TodoStatus) {
+        self.total = self.total.saturating_sub(1);
+        let count = self.count_mut(status);
+        *count = count.saturating_sub(1);
+    }
+
+    /// 记录单个任务的状态变化
+    pub fn record_status_change(&mut self, from: &TodoStatus, to: &TodoStatus) {
+        if from == to {
+            return;
+        }
+        let count = self.count_mut(from);
+        *count = count.saturating_sub(1);
+        *self.count_mut(to) += 1;
+    }
+
+    fn count_mut(&mut self, status: &TodoStatus) -> &mut usize {
+        match status {
+            TodoStatus::Todo => &mut self.todo,
+            TodoStatus::InProgress => &mut self.in_progress,
+            TodoStatus::Done => &mut self.done,
+        }
+    }
+
     /// 计算完成率
     pub fn completion_rate(&self) -> f32 {
         if self.total == 0 {
@@ -394,6 +1084,92 @@ impl TodoStats {
     }
 }
 
+/// 导出为 iCalendar（RFC 5545）文本
+///
+/// 每个任务生成一个 VTODO；有截止时间的任务额外生成一个 VEVENT，便于在日历中显示。
+pub fn export_todos_ics(todos: &[Todo]) -> String {
+    let stamp = ics_datetime(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//PomodoroFlow//{}//ZH", env!("CARGO_PKG_VERSION")),
+    ];
+
+    for todo in todos {
+        let status = match todo.status {
+            TodoStatus::Todo => "NEEDS-ACTION",
+            TodoStatus::InProgress => "IN-PROCESS",
+            TodoStatus::Done => "COMPLETED",
+        };
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!("UID:{}@pomodoroflow", todo.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("SUMMARY:{}", ics_escape(&todo.title)));
+        if let Some(description) = &todo.description {
+            lines.push(format!("DESCRIPTION:{}", ics_escape(description)));
+        }
+        if let Some(due) = todo.due_at {
+            lines.push(format!("DUE:{}", ics_datetime(due)));
+        }
+        if let Some(completed) = todo.completed_at {
+            lines.push(format!("COMPLETED:{}", ics_datetime(completed)));
+        }
+        lines.push(format!("STATUS:{}", status));
+        lines.push("END:VTODO".to_string());
+
+        if let Some(due) = todo.due_at {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}-due@pomodoroflow", todo.id));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART:{}", ics_datetime(due)));
+            lines.push(format!("SUMMARY:{}", ics_escape(&todo.title)));
+            if let Some(description) = &todo.description {
+                lines.push(format!("DESCRIPTION:{}", ics_escape(description)));
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| ics_fold(line) + "\r\n").collect()
+}
+
+/// 转义 TEXT 值中的反斜杠、分号、逗号和换行
+fn ics_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// UTC 时间格式 `YYYYMMDDTHHMMSSZ`
+fn ics_datetime(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// 按 75 字节折行，续行以空格开头，不拆分 UTF-8 字符
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
 /// 任务导出格式
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TodoExport {
@@ -401,6 +1177,11 @@ pub struct TodoExport {
     pub exported_at: DateTime<Utc>,
     pub todos: Vec<Todo>,
     pub stats: TodoStats,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// 任务与标签的关联 (todo_id, tag_id)
+    #[serde(default)]
+    pub todo_tags: Vec<(String, String)>,
 }
 
 impl TodoExport {
@@ -411,13 +1192,544 @@ impl TodoExport {
             exported_at: Utc::now(),
             todos: todos.to_vec(),
             stats: TodoStats::from_todos(todos),
+            tags: Vec::new(),
+            todo_tags: Vec::new(),
+        }
+    }
+
+    /// 附带标签及任务标签关联
+    pub fn with_tags(mut self, tags: Vec<Tag>, todo_tags: Vec<(String, String)>) -> Self {
+        self.tags = tags;
+        self.todo_tags = todo_tags;
+        self
+    }
+
+    /// 从备份 JSON 解析导出数据
+    ///
+    /// `version` 须为 `主.次.修订` 格式，且不晚于当前程序版本，否则返回校验错误。
+    pub fn import(json: &str) -> Result<Self> {
+        let export: Self = serde_json::from_str(json)?;
+        let version = parse_version(&export.version)
+            .ok_or_else(|| AppError::Validation(format!("无效的备份版本: {}", export.version)))?;
+        let current = parse_version(env!("CARGO_PKG_VERSION")).unwrap_or_default();
+        if version > current {
+            return Err(AppError::Validation(format!(
+                "备份版本 {} 高于当前版本 {}，请先升级应用",
+                export.version,
+                env!("CARGO_PKG_VERSION")
+            )));
+        }
+        Ok(export)
+    }
+}
+
+/// 解析 `主.次.修订` 版本号，忽略预发布与构建标识
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// 导入计划：按任务 id 列出导入会产生的变化
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportPlan {
+    /// 本地不存在，将新建
+    pub to_create: Vec<String>,
+    /// 备份中的版本更新，将覆盖本地
+    pub to_update: Vec<String>,
+    /// 内容与本地一致，跳过
+    pub to_skip: Vec<String>,
+    /// 本地版本更新（或时间相同但内容不同），不会被覆盖
+    pub conflicts: Vec<String>,
+}
+
+impl ImportPlan {
+    /// 比较本地任务与备份任务，计算导入计划
+    pub fn compute(existing: &[Todo], incoming: &[Todo]) -> Self {
+        let mut plan = Self::default();
+
+        for todo in incoming {
+            match existing.iter().find(|t| t.id == todo.id) {
+                None => plan.to_create.push(todo.id.clone()),
+                Some(local) if same_content(local, todo) => plan.to_skip.push(todo.id.clone()),
+                Some(local) if todo.updated_at > local.updated_at => {
+                    plan.to_update.push(todo.id.clone())
+                }
+                Some(_) => plan.conflicts.push(todo.id.clone()),
+            }
         }
+
+        plan
+    }
+
+    /// 需要写入数据库的任务 id（新建 + 更新）
+    pub fn ids_to_write(&self) -> impl Iterator<Item = &String> {
+        self.to_create.iter().chain(self.to_update.iter())
+    }
+}
+
+/// 比较所有会持久化、用户可编辑的字段；标签按名称比较，与顺序无关
+fn same_content(a: &Todo, b: &Todo) -> bool {
+    fn tag_names(todo: &Todo) -> Vec<&str> {
+        let mut names: Vec<&str> = todo.tags.iter().map(|tag| tag.name.as_str()).collect();
+        names.sort_unstable();
+        names
     }
+
+    a.title == b.title
+        && a.description == b.description
+        && a.status == b.status
+        && a.github_issue_id == b.github_issue_id
+        && a.github_project_id == b.github_project_id
+        && a.github_issue_number == b.github_issue_number
+        && a.due_at == b.due_at
+        && a.completed_at == b.completed_at
+        && a.estimate_minutes == b.estimate_minutes
+        && a.archived == b.archived
+        && a.priority == b.priority
+        && a.sort_order == b.sort_order
+        && a.parent_id == b.parent_id
+        && a.estimated_pomodoros == b.estimated_pomodoros
+        && tag_names(a) == tag_names(b)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Todo, TodoUpdate};
+    use super::{
+        export_todos_ics, render_plan, suggest_next_todo, EstimatedBacklog, ImportPlan, InitialStatusPolicy,
+        NewTodo, PlanScope, SortKey, Tag, Todo, TodoExport, TodoFilter, TodoPriority, TodoStats,
+        TodoStatus, TodoUpdate, DEFAULT_SEARCH_LIMIT,
+    };
+    use crate::core::error::{AppError, ValidationKind};
+    use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn new_todo_validation_reports_kind() {
+        let new_todo = |title: &str, description: Option<String>| NewTodo {
+            title: title.to_string(),
+            description,
+            ..Default::default()
+        };
+
+        let cases = [
+            (new_todo("   ", None), ValidationKind::TitleEmpty),
+            (new_todo(&"a".repeat(201), None), ValidationKind::TitleTooLong),
+            (
+                new_todo("ok", Some("d".repeat(5001))),
+                ValidationKind::DescriptionTooLong,
+            ),
+        ];
+        for (todo, kind) in cases {
+            assert_eq!(todo.validate().unwrap_err().validation_kind(), Some(kind));
+        }
+        assert!(new_todo("ok", None).validate().is_ok());
+    }
+
+    #[test]
+    fn import_plan_classifies_incoming_todos() {
+        let now = Utc::now();
+        let local_same = Todo::new("same".to_string(), None);
+        let mut local_old = Todo::new("old".to_string(), None);
+        local_old.updated_at = now - Duration::hours(1);
+        let mut local_new = Todo::new("local".to_string(), None);
+        local_new.updated_at = now + Duration::hours(1);
+
+        let mut newer = local_old.clone();
+        newer.title = "old (edited)".to_string();
+        newer.updated_at = now;
+        let mut stale = local_new.clone();
+        stale.title = "stale".to_string();
+        stale.updated_at = now;
+        let fresh = Todo::new("fresh".to_string(), None);
+
+        let existing = vec![local_same.clone(), local_old, local_new];
+        let incoming = vec![local_same.clone(), newer.clone(), stale.clone(), fresh.clone()];
+        let plan = ImportPlan::compute(&existing, &incoming);
+
+        assert_eq!(plan.to_create, vec![fresh.id]);
+        assert_eq!(plan.to_update, vec![newer.id]);
+        assert_eq!(plan.to_skip, vec![local_same.id]);
+        assert_eq!(plan.conflicts, vec![stale.id]);
+    }
+
+    #[test]
+    fn import_plan_detects_changes_in_every_editable_field() {
+        let mut local = Todo::new("local".to_string(), None);
+        local.tags = vec![
+            Tag::new("a".to_string(), "#000000".to_string()),
+            Tag::new("b".to_string(), "#000000".to_string()),
+        ];
+        let mut reordered = local.clone();
+        reordered.tags.reverse();
+        assert_eq!(
+            ImportPlan::compute(&[local.clone()], &[reordered]).to_skip,
+            vec![local.id.clone()]
+        );
+
+        let edits: [fn(&mut Todo); 4] = [
+            |todo| todo.sort_order += 1,
+            |todo| todo.parent_id = Some("parent".to_string()),
+            |todo| todo.estimated_pomodoros = Some(3),
+            |todo| todo.tags.truncate(1),
+        ];
+        for edit in edits {
+            let mut incoming = local.clone();
+            edit(&mut incoming);
+            let plan = ImportPlan::compute(&[local.clone()], &[incoming]);
+            assert!(plan.to_skip.is_empty());
+            assert_eq!(plan.conflicts, vec![local.id.clone()]);
+        }
+    }
+
+    #[test]
+    fn subtask_stats_report_parent_completion() {
+        let parent = Todo::new("parent".to_string(), None);
+        let mut todos = vec![parent.clone(), Todo::new("unrelated".to_string(), None)];
+        for status in [TodoStatus::Done, TodoStatus::Todo, TodoStatus::Done] {
+            let mut child = Todo::new("child".to_string(), None);
+            child.parent_id = Some(parent.id.clone());
+            child.update_status(status);
+            todos.push(child);
+        }
+
+        let stats = TodoStats::from_subtasks(&todos, &parent.id).unwrap();
+        assert_eq!((stats.total, stats.done, stats.todo), (3, 2, 1));
+        assert_eq!(stats.completion_percent(), 66);
+        assert_eq!(TodoStats::from_subtasks(&todos, &todos[1].id), None);
+    }
+
+    #[test]
+    fn incremental_stats_match_full_recompute() {
+        let mut todos: Vec<Todo> = Vec::new();
+        let mut stats = TodoStats::from_todos(&todos);
+
+        for title in ["a", "b", "c", "d"] {
+            let todo = Todo::new(title.to_string(), None);
+            stats.record_added(&todo.status);
+            todos.push(todo);
+        }
+        assert_eq!(stats, TodoStats::from_todos(&todos));
+
+        let changes = [
+            (0, TodoStatus::Done),
+            (1, TodoStatus::InProgress),
+            (0, TodoStatus::Done),
+            (2, TodoStatus::Done),
+            (2, TodoStatus::Todo),
+        ];
+        for (index, status) in changes {
+            let from = todos[index].status.clone();
+            todos[index].update_status(status.clone());
+            stats.record_status_change(&from, &status);
+            assert_eq!(stats, TodoStats::from_todos(&todos));
+        }
+
+        let removed = todos.remove(1);
+        stats.record_removed(&removed.status);
+        assert_eq!(stats, TodoStats::from_todos(&todos));
+    }
+
+    #[test]
+    fn suggest_next_todo_prefers_soonest_due_unfinished() {
+        let now = Utc::now();
+        let todo_with = |title: &str, status: TodoStatus, due_in_hours: Option<i64>, age: i64| {
+            let mut todo = Todo::new(title.to_string(), None);
+            todo.status = status;
+            todo.due_at = due_in_hours.map(|h| now + Duration::hours(h));
+            todo.created_at = now - Duration::minutes(age);
+            todo
+        };
+
+        let todos = vec![
+            todo_with("undated-old", TodoStatus::Todo, None, 60),
+            todo_with("done-overdue", TodoStatus::Done, Some(-5), 50),
+            todo_with("due-later", TodoStatus::InProgress, Some(24), 40),
+            todo_with("due-soon-new", TodoStatus::Todo, Some(2), 10),
+            todo_with("due-soon-old", TodoStatus::Todo, Some(2), 30),
+        ];
+        assert_eq!(suggest_next_todo(&todos).unwrap().title, "due-soon-old");
+
+        let undated = vec![
+            todo_with("newer", TodoStatus::Todo, None, 5),
+            todo_with("older", TodoStatus::InProgress, None, 15),
+        ];
+        assert_eq!(suggest_next_todo(&undated).unwrap().title, "older");
+
+        // 优先级高于截止时间：没有截止时间的高优先级任务排在最前
+        let mut urgent = todo_with("high-undated", TodoStatus::Todo, None, 1);
+        urgent.priority = TodoPriority::High;
+        let mut low = todo_with("low-overdue", TodoStatus::Todo, Some(-1), 90);
+        low.priority = TodoPriority::Low;
+        let prioritized = vec![low, todo_with("medium-due", TodoStatus::Todo, Some(1), 80), urgent];
+        assert_eq!(suggest_next_todo(&prioritized).unwrap().title, "high-undated");
+
+        // 优先级和截止时间都相同时，手动排序位置靠前的优先（先于创建时间）
+        let mut first = todo_with("placed-first", TodoStatus::Todo, Some(2), 5);
+        first.sort_order = 1;
+        let mut second = todo_with("placed-second", TodoStatus::Todo, Some(2), 50);
+        second.sort_order = 2;
+        assert_eq!(suggest_next_todo(&[second, first]).unwrap().title, "placed-first");
+    }
+
+    #[test]
+    fn filter_sorts_by_priority_and_defaults_to_updated_at() {
+        let now = Utc::now();
+        let todo_with = |title: &str, priority: TodoPriority, updated_ago: i64| {
+            let mut todo = Todo::new(title.to_string(), None);
+            todo.id = title.to_string();
+            todo.priority = priority;
+            todo.updated_at = now - Duration::minutes(updated_ago);
+            todo
+        };
+        let todos = vec![
+            todo_with("low-new", TodoPriority::Low, 1),
+            todo_with("high-old", TodoPriority::High, 30),
+            todo_with("medium", TodoPriority::Medium, 10),
+            todo_with("high-new", TodoPriority::High, 5),
+        ];
+        let titles = |filter: &TodoFilter| -> Vec<String> {
+            filter.apply(&todos).iter().map(|t| t.title.clone()).collect()
+        };
+
+        let by_priority = TodoFilter {
+            sort_by: Some(SortKey::Priority),
+            ..TodoFilter::all()
+        };
+        // 同一优先级按 ID 升序，而不是更新时间
+        assert_eq!(titles(&by_priority), vec!["high-new", "high-old", "medium", "low-new"]);
+        let mut renamed = todos.clone();
+        renamed[1].id = "a-high-old".to_string();
+        let renamed_titles: Vec<&str> = by_priority.apply(&renamed).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(renamed_titles, vec!["high-old", "high-new", "medium", "low-new"]);
+        assert_eq!(
+            titles(&TodoFilter::all()),
+            vec!["low-new", "high-new", "medium", "high-old"]
+        );
+        assert_eq!(Todo::new("t".to_string(), None).priority, TodoPriority::Medium);
+        assert_eq!(NewTodo::default().priority, TodoPriority::Medium);
+    }
+
+    #[test]
+    fn export_import_validates_version() {
+        let todo = Todo::new("backup".to_string(), None);
+        let mut export = TodoExport::export(std::slice::from_ref(&todo));
+        let json = serde_json::to_string(&export).unwrap();
+        let imported = TodoExport::import(&json).unwrap();
+        assert_eq!(imported.todos[0].id, todo.id);
+
+        export.version = "999.0.0".to_string();
+        let newer = serde_json::to_string(&export).unwrap();
+        assert!(matches!(TodoExport::import(&newer), Err(AppError::Validation(_))));
+
+        export.version = "not-a-version".to_string();
+        let invalid = serde_json::to_string(&export).unwrap();
+        assert!(matches!(TodoExport::import(&invalid), Err(AppError::Validation(_))));
+
+        export.version = "0.0.1-beta".to_string();
+        let older = serde_json::to_string(&export).unwrap();
+        assert!(TodoExport::import(&older).is_ok());
+        assert!(TodoExport::import("{").is_err());
+    }
+
+    #[test]
+    fn overdue_filter_keeps_only_past_due_unfinished_todos() {
+        let now = Utc::now();
+        let todo_with = |title: &str, status: TodoStatus, due_in_hours: Option<i64>| {
+            let mut todo = Todo::new(title.to_string(), None);
+            todo.status = status;
+            todo.due_at = due_in_hours.map(|h| now + Duration::hours(h));
+            todo
+        };
+        let todos = vec![
+            todo_with("overdue", TodoStatus::InProgress, Some(-1)),
+            todo_with("done-overdue", TodoStatus::Done, Some(-5)),
+            todo_with("due-later", TodoStatus::Todo, Some(3)),
+            todo_with("undated", TodoStatus::Todo, None),
+        ];
+
+        let filter = TodoFilter {
+            overdue_only: true,
+            ..TodoFilter::all()
+        };
+        let titles: Vec<_> = filter.apply(&todos).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["overdue"]);
+        assert_eq!(TodoFilter::all().apply(&todos).len(), 4);
+    }
+
+    #[test]
+    fn tag_filter_requires_all_requested_tags() {
+        let work = Tag::new("work".to_string(), "#007AFF".to_string());
+        let urgent = Tag::new("urgent".to_string(), "#FF0000".to_string());
+        let todo_with = |title: &str, tags: &[&Tag]| {
+            let mut todo = Todo::new(title.to_string(), None);
+            todo.tags = tags.iter().map(|tag| (*tag).clone()).collect();
+            todo
+        };
+        let todos = vec![
+            todo_with("both", &[&work, &urgent]),
+            todo_with("work", &[&work]),
+            todo_with("none", &[]),
+        ];
+
+        let titles = |filter: TodoFilter| {
+            let mut titles: Vec<_> = filter.apply(&todos).iter().map(|t| t.title.clone()).collect();
+            titles.sort();
+            titles
+        };
+        assert_eq!(titles(TodoFilter::all().with_tags(Vec::new())), vec!["both", "none", "work"]);
+        assert_eq!(titles(TodoFilter::all().with_tags(vec![work.id.clone()])), vec!["both", "work"]);
+        assert_eq!(
+            titles(TodoFilter::all().with_tags(vec![work.id.clone(), urgent.id.clone()])),
+            vec!["both"]
+        );
+        assert!(titles(TodoFilter::all().with_tags(vec!["missing".to_string()])).is_empty());
+    }
+
+    #[test]
+    fn suggest_next_todo_returns_none_without_eligible_todos() {
+        assert!(suggest_next_todo(&[]).is_none());
+
+        let mut done = Todo::new("done".to_string(), None);
+        done.update_status(TodoStatus::Done);
+        assert!(suggest_next_todo(&[done]).is_none());
+    }
+
+    #[test]
+    fn parse_markdown_checklist_reads_checked_and_unchecked_items() {
+        let md = "- [ ] 写测试\n* [x] 修复构建\n+ [X] 发布版本\n  - [ ]   缩进的子任务  \n";
+        let parsed: Vec<(String, TodoStatus)> = NewTodo::parse_markdown_checklist(md)
+            .into_iter()
+            .map(|t| (t.title, t.status))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("写测试".to_string(), TodoStatus::Todo),
+                ("修复构建".to_string(), TodoStatus::Done),
+                ("发布版本".to_string(), TodoStatus::Done),
+                ("缩进的子任务".to_string(), TodoStatus::Todo),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_markdown_checklist_ignores_headings_and_prose() {
+        let md = "# 本周任务\n\n这周要完成以下事项：\n- 普通列表项\n-[ ] 缺少空格\n- [ ]\n1. [ ] 有序列表\n- [ ] 唯一的任务\n> - [x] 引用块\n";
+        let parsed = NewTodo::parse_markdown_checklist(md);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, "唯一的任务");
+        assert_eq!(parsed[0].status, TodoStatus::Todo);
+    }
+
+    #[test]
+    fn plan_scope_week_spans_monday_to_sunday() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(PlanScope::Day.date_range(wednesday), (wednesday, wednesday));
+        assert_eq!(
+            PlanScope::Week.date_range(wednesday),
+            (
+                NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn render_plan_lists_open_todos_in_scope() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let end_of = |day: u32| {
+            NaiveDate::from_ymd_opt(2026, 10, day)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+                .and_utc()
+        };
+        let todo_with = |title: &str, status: TodoStatus, due: Option<DateTime<Utc>>| {
+            let mut todo = Todo::new(title.to_string(), None);
+            todo.status = status;
+            todo.due_at = due;
+            todo
+        };
+
+        let with_priority = |mut todo: Todo, priority: TodoPriority| {
+            todo.priority = priority;
+            todo
+        };
+
+        let mut estimated = todo_with("写周报", TodoStatus::Todo, Some(end_of(14) - Duration::hours(6)));
+        estimated.estimate_minutes = Some(50);
+        let todos = vec![
+            estimated,
+            with_priority(todo_with("周五评审", TodoStatus::Todo, Some(end_of(16))), TodoPriority::High),
+            todo_with("下周计划", TodoStatus::Todo, Some(end_of(20))),
+            todo_with("已交付", TodoStatus::Done, Some(end_of(14))),
+            with_priority(todo_with("正在重构", TodoStatus::InProgress, None), TodoPriority::High),
+            with_priority(todo_with("整理书签", TodoStatus::Todo, Some(end_of(13))), TodoPriority::Low),
+            todo_with("某天再说", TodoStatus::Todo, None),
+        ];
+
+        let day = render_plan(&todos, PlanScope::Day, today, end_of(14));
+        assert!(day.starts_with("# 今日计划（2026-10-14）"));
+        assert!(day.contains("## 高优先级\n\n- [ ] 正在重构（进行中）\n"));
+        assert!(day.contains("## 中优先级\n\n- [ ] 写周报（预估 50 分钟，截止"));
+        assert!(day.contains("## 低优先级\n\n- [ ] 整理书签（截止"));
+        assert!(day.find("## 高优先级") < day.find("## 中优先级"));
+        assert!(day.find("## 中优先级") < day.find("## 低优先级"));
+        assert!(!day.contains("周五评审"));
+        assert!(!day.contains("已交付"));
+        assert!(!day.contains("某天再说"));
+
+        let week = render_plan(&todos, PlanScope::Week, today, end_of(18));
+        assert!(week.starts_with("# 本周计划（2026-10-12 – 2026-10-18）"));
+        // 组内进行中的任务在前，其余按截止时间排序
+        assert!(week.contains("## 高优先级\n\n- [ ] 正在重构（进行中）\n- [ ] 周五评审（截止"));
+        assert!(week.find("周五评审") < week.find("写周报"));
+        assert!(!week.contains("下周计划"));
+        assert!(!week.contains("已交付"));
+    }
+
+    #[test]
+    fn render_plan_handles_empty_list() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let mut done = Todo::new("done".to_string(), None);
+        done.update_status(TodoStatus::Done);
+
+        let plan = render_plan(&[done], PlanScope::Week, today, Utc::now());
+        assert!(plan.contains("暂无待完成的任务"));
+        assert!(!plan.contains("done"));
+    }
+
+    #[test]
+    fn update_status_tracks_completed_at() {
+        let mut todo = Todo::new("test".to_string(), None);
+        assert_eq!(todo.completed_at, None);
+
+        todo.update_status(TodoStatus::Done);
+        let completed_at = todo.completed_at.expect("completed_at should be set");
+
+        todo.update_status(TodoStatus::Done);
+        assert_eq!(todo.completed_at, Some(completed_at));
+
+        todo.toggle_status();
+        assert_eq!(todo.status, TodoStatus::Todo);
+        assert_eq!(todo.completed_at, None);
+
+        // 重新完成时记录最近一次完成时间
+        todo.toggle_status();
+        todo.toggle_status();
+        assert_eq!(todo.status, TodoStatus::Done);
+        assert!(todo.completed_at.expect("completed_at should be set") >= completed_at);
+    }
+
+    #[test]
+    fn initial_status_policy_only_blocks_non_todo_when_enabled() {
+        assert!(InitialStatusPolicy::Allow.check(&TodoStatus::Done).is_ok());
+        assert!(InitialStatusPolicy::Warn.check(&TodoStatus::Done).is_ok());
+        assert!(InitialStatusPolicy::Block.check(&TodoStatus::Todo).is_ok());
+        assert!(InitialStatusPolicy::Block.check(&TodoStatus::InProgress).is_err());
+        assert!(InitialStatusPolicy::Block.check(&TodoStatus::Done).is_err());
+    }
 
     #[test]
     fn todo_new_initializes_github_fields_to_none() {
@@ -446,4 +1758,99 @@ mod tests {
         let updates = TodoUpdate::new().with_github_issue_id(Some(1));
         assert!(updates.has_updates());
     }
+
+    #[test]
+    fn backlog_estimate_applies_fallback_to_unestimated_todos() {
+        let mut estimated = Todo::new("estimated".to_string(), None);
+        estimated.estimate_minutes = Some(50);
+        let unestimated = Todo::new("unestimated".to_string(), None);
+        let mut done = Todo::new("done".to_string(), None);
+        done.estimate_minutes = Some(999);
+        done.update_status(TodoStatus::Done);
+        let mut archived = Todo::new("archived".to_string(), None);
+        archived.estimate_minutes = Some(999);
+        archived.archived = true;
+
+        let backlog = EstimatedBacklog::compute(&[estimated, unestimated, done, archived], 30);
+        assert_eq!(
+            backlog,
+            EstimatedBacklog {
+                estimated_minutes: 80,
+                todos_with_estimate: 1,
+                todos_without: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn blank_search_behaves_like_no_search() {
+        let todos: Vec<Todo> = ["Write report", "Buy milk"]
+            .iter()
+            .map(|title| Todo::new(title.to_string(), None))
+            .collect();
+        let titles = |filter: &TodoFilter| -> Vec<String> {
+            filter.apply(&todos).iter().map(|t| t.title.clone()).collect()
+        };
+        let unfiltered = titles(&TodoFilter::all());
+
+        for blank in ["", "   ", "\t\n"] {
+            let built = TodoFilter::all().with_search(blank.to_string());
+            assert_eq!(built.search, None);
+            assert_eq!(titles(&built), unfiltered);
+
+            // 绕过构建器（例如前端传入）时同样忽略
+            let raw = TodoFilter {
+                search: Some(blank.to_string()),
+                ..TodoFilter::all()
+            };
+            assert_eq!(raw.search_term(), None);
+            assert_eq!(raw.effective_limit(), None);
+            assert_eq!(titles(&raw), unfiltered);
+        }
+
+        let real = TodoFilter::all().with_search("report".to_string());
+        assert_eq!(titles(&real), vec!["Write report".to_string()]);
+        assert_eq!(real.effective_limit(), Some(DEFAULT_SEARCH_LIMIT));
+    }
+
+    #[test]
+    fn ics_export_has_one_vtodo_per_todo_and_escapes_text() {
+        let due = Utc.with_ymd_and_hms(2024, 6, 10, 9, 30, 0).unwrap();
+        let mut dated = Todo::new(
+            "Plan; review, ship\\now".to_string(),
+            Some("line one\nline two".to_string()),
+        );
+        dated.due_at = Some(due);
+        let undated = Todo::new("No date".to_string(), None);
+
+        let ics = export_todos_ics(&[dated, undated]);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VTODO\r\n").count(), 2);
+        assert_eq!(ics.matches("END:VTODO\r\n").count(), 2);
+        // 只有带截止时间的任务生成日历事件
+        assert_eq!(ics.matches("BEGIN:VEVENT\r\n").count(), 1);
+        assert!(ics.contains("DUE:20240610T093000Z\r\n"));
+        assert!(ics.contains("DTSTART:20240610T093000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Plan\\; review\\, ship\\\\now\r\n"));
+        assert!(ics.contains("DESCRIPTION:line one\\nline two\r\n"));
+    }
+
+    #[test]
+    fn ics_export_folds_long_lines_on_char_boundaries() {
+        let todo = Todo::new("番茄".repeat(40), None);
+        let ics = export_todos_ics(&[todo]);
+
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {}", line);
+        }
+        let summary: String = ics
+            .split("\r\n")
+            .skip_while(|line| !line.starts_with("SUMMARY:"))
+            .take_while(|line| line.starts_with("SUMMARY:") || line.starts_with(' '))
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect();
+        assert_eq!(summary, format!("SUMMARY:{}", "番茄".repeat(40)));
+    }
 }
@@ -5,22 +5,58 @@
 pub mod async_utils;
 pub mod core;
 pub mod storage;
+pub mod utils;
 
 // 重新导出核心类型
+pub use async_utils::TaskMetadataView;
+pub use utils::sound::{SoundEffect, SoundPlayer};
+
 pub use core::{
     error::{AppError, Result},
-    pomodoro::{PomodoroConfig, PomodoroPhase, PomodoroService, PomodoroSession},
-    state::{AppStateManager, UserConfig},
-    todo::{NewTodo, Todo, TodoFilter, TodoService, TodoStatus, TodoUpdate},
+    pomodoro::{
+        DurationView, PauseCounts, PauseReason, PomodoroConfig, PomodoroConfigView, PomodoroEvent, PomodoroPhase, PomodoroService,
+        PomodoroSession, PomodoroStats, SessionPage, SessionRecord, StatsRange, DEFAULT_TICK_INTERVAL_MS,
+        MAX_SESSION_PAGE_SIZE, MIN_TICK_INTERVAL_MS,
+    },
+    state::{
+        AppEvent, AppState, AppStateManager, EventReceiver, InitStep, MessageKind, MessageRecord,
+        MinuteRounding, UserConfig, EVENT_CHANNEL_CAPACITY,
+    },
+    todo::{
+        EstimatedBacklog, ImportPlan, InitialStatusPolicy, MoveDirection, NewTodo, PlanScope, SortKey, Todo, TodoExport, TodoFilter,
+        StatusTransition, Tag, TodoNote, TodoPage, TodoPriority, TodoSearchResult, TodoService, TodoStats, TodoStatus, ToggleResult,
+        TodoUpdate, TodoWithProgress,
+    },
 };
 
 use async_utils::TaskManager;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
 use core::github_sync::{GithubIssue, GithubSyncClient};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use storage::database::Database;
+pub use storage::database::MaintenanceSummary;
 use tokio::sync::RwLock;
 
+/// 运行中会话进度的自动保存间隔，意外退出时最多丢失这么久的进度
+const SESSION_AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// 定期数据库维护（清理同步队列、VACUUM）的间隔
+const MAINTENANCE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+/// 恢复出厂设置时必须原样提供的确认口令
+pub const FACTORY_RESET_CONFIRMATION: &str = "RESET ALL DATA";
+
+/// 历史不足时建议的每日专注目标（分钟）
+pub const DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES: u32 = 120;
+
+/// 建议每日目标至少需要的有专注记录的天数
+const MIN_ACTIVE_DAYS_FOR_GOAL: usize = 3;
+
+/// 建议每日目标的取整粒度（分钟）
+const SUGGESTED_GOAL_STEP_MINUTES: u64 = 15;
+
 /// 全局应用实例管理器
 pub struct PomodoroAppManager {
     /// 应用状态管理器
@@ -32,8 +68,20 @@ pub struct PomodoroAppManager {
     /// 任务管理器
     task_manager: Arc<TaskManager>,
 
-    /// 番茄钟服务
+    /// 番茄钟服务，计时循环与各控制方法共享同一实例，均需加锁访问
     pomodoro_service: Arc<RwLock<PomodoroService>>,
+
+    /// 新建任务的初始状态策略
+    initial_status_policy: InitialStatusPolicy,
+
+    /// 最近一次批量操作的撤销信息
+    last_bulk_undo: Option<BulkUndo>,
+}
+
+/// 批量操作的撤销信息：内存快照 + 被删除的任务及其标签
+struct BulkUndo {
+    snapshot: AppState,
+    removed: Vec<(Todo, Vec<String>)>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -54,6 +102,89 @@ pub struct GithubSyncReport {
     pub errors: Vec<String>,
 }
 
+/// 前端启动时一次性加载的数据
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InitialPayload {
+    /// 按当前筛选器过滤后的任务
+    pub todos: Vec<Todo>,
+    pub stats: TodoStats,
+    pub pomodoro_session: Option<PomodoroSession>,
+    /// 生效中的用户配置（未保存过则为默认值）
+    pub user_config: UserConfig,
+    pub tags: Vec<Tag>,
+}
+
+/// 今日专注目标进度，供前端环形进度组件使用
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GoalProgress {
+    pub achieved_minutes: u32,
+    /// 未设置目标时为 0
+    pub goal_minutes: u32,
+    /// 完成百分比，限制在 0–100
+    pub percent: u8,
+    pub reached: bool,
+}
+
+impl GoalProgress {
+    /// 由已专注分钟数和目标计算进度，未设置目标时进度为 0 且视为未达成
+    pub fn new(achieved_minutes: u32, goal_minutes: Option<u32>) -> Self {
+        let goal_minutes = goal_minutes.unwrap_or(0);
+        if goal_minutes == 0 {
+            return Self {
+                achieved_minutes,
+                goal_minutes: 0,
+                percent: 0,
+                reached: false,
+            };
+        }
+
+        let percent = (u64::from(achieved_minutes) * 100 / u64::from(goal_minutes)).min(100) as u8;
+        Self {
+            achieved_minutes,
+            goal_minutes,
+            percent,
+            reached: achieved_minutes >= goal_minutes,
+        }
+    }
+}
+
+/// 累计专注时长，拆分为小时与剩余分钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LifetimeFocus {
+    pub hours: u64,
+    pub minutes: u64,
+}
+
+impl LifetimeFocus {
+    /// 由总秒数换算，不足一分钟的部分舍去
+    pub fn from_seconds(seconds: u64) -> Self {
+        Self::from_seconds_rounded(seconds, MinuteRounding::Floor)
+    }
+
+    /// 由总秒数换算，分钟按 `rounding` 取整
+    pub fn from_seconds_rounded(seconds: u64, rounding: MinuteRounding) -> Self {
+        let total_minutes = rounding.minutes(seconds);
+        Self {
+            hours: total_minutes / 60,
+            minutes: total_minutes % 60,
+        }
+    }
+}
+
+/// 桌面小组件一次刷新所需的数据
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WidgetPayload {
+    /// 连续有专注记录的天数
+    pub current_streak: u32,
+    pub today_focus_minutes: u32,
+    /// 今天完成的任务数（按完成时间统计）
+    pub completed_today: u64,
+    /// 未设置目标时为 0
+    pub goal_minutes: u32,
+    pub running: bool,
+    pub formatted_remaining: String,
+}
+
 // 安全实现 Send + Sync，因为所有内部字段都是 Arc<...> 包装的
 unsafe impl Send for PomodoroAppManager {}
 unsafe impl Sync for PomodoroAppManager {}
@@ -61,7 +192,15 @@ unsafe impl Sync for PomodoroAppManager {}
 impl PomodoroAppManager {
     /// 创建新的应用管理器
     pub async fn new() -> Result<Self> {
-        println!("🔧 Initializing PomodoroFlow-Rs core library...");
+        Self::new_with_progress(|_| {}).await
+    }
+
+    /// 创建新的应用管理器，并通过回调上报每个初始化步骤
+    pub async fn new_with_progress<F>(on_progress: F) -> Result<Self>
+    where
+        F: Fn(&AppEvent) + Send + Sync,
+    {
+        tracing::info!("Initializing PomodoroFlow-Rs core library");
 
         // 初始化数据库
         let data_dir = dirs::data_dir()
@@ -72,28 +211,46 @@ impl PomodoroAppManager {
             .map_err(|e| AppError::Other(format!("Failed to create data directory: {}", e)))?;
 
         let db_path = data_dir.join("database.sqlite");
-        println!("📊 Initializing database: {:?}", db_path);
+        Self::with_database_path_and_progress(&db_path, on_progress).await
+    }
 
-        let database = Arc::new(Database::init(&db_path).await?);
-        println!("✅ Database initialized successfully");
+    /// 使用指定的数据库文件创建应用管理器
+    pub async fn with_database_path(db_path: &Path) -> Result<Self> {
+        Self::with_database_path_and_progress(db_path, |_| {}).await
+    }
 
-        // 初始化状态管理器
-        println!("🔄 Creating state manager...");
+    /// 使用指定的数据库文件创建应用管理器，并上报初始化进度
+    ///
+    /// 每完成一步都会产生 `AppEvent::InitProgress`：先交给 `on_progress`，
+    /// 再写入状态管理器的事件通道，供之后取出事件接收器的一方读取。
+    pub async fn with_database_path_and_progress<F>(db_path: &Path, on_progress: F) -> Result<Self>
+    where
+        F: Fn(&AppEvent) + Send + Sync,
+    {
+        let started = std::time::Instant::now();
+
+        // 状态管理器最先创建，以便承载初始化事件
         let state_manager = Arc::new(AppStateManager::new());
-        println!("✅ State manager created successfully");
+        state_manager.start_query_loop()?;
+        let report = |step: InitStep| {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            tracing::debug!(elapsed_ms, "{}", step.to_string());
+            let event = AppEvent::InitProgress { step, elapsed_ms };
+            on_progress(&event);
+            let _ = state_manager.send_event(event);
+        };
+        report(InitStep::StateManagerReady);
+
+        tracing::info!("Initializing database: {:?}", db_path);
+        let database = Arc::new(Database::init(db_path).await?);
+        report(InitStep::DatabaseReady);
 
-        // 初始化任务管理器
-        println!("📋 Creating task manager...");
         let task_manager = Arc::new(TaskManager::new());
-        println!("✅ Task manager created successfully");
+        report(InitStep::TaskManagerReady);
 
-        // 加载用户配置
-        println!("⚙️ Loading user configuration...");
         let user_config = database.load_user_config().await?;
-        println!("✅ User configuration loaded successfully");
+        report(InitStep::ConfigLoaded);
 
-        // 初始化番茄钟服务
-        println!("🍅 Creating Pomodoro service...");
         // 使用用户配置，如果不存在则使用默认值
         let pomodoro_config = if let Some(ref config) = user_config {
             // 将 UserConfig 转换为 PomodoroConfig
@@ -102,30 +259,63 @@ impl PomodoroAppManager {
                 short_break_duration: config.pomodoro_short_break_duration,
                 long_break_duration: config.pomodoro_long_break_duration,
                 cycles_until_long_break: config.pomodoro_cycles_until_long_break,
+                deep_work_duration: config.pomodoro_deep_work_duration,
+                auto_start_next: config.pomodoro_auto_start_next,
+                warn_before_seconds: config.pomodoro_warn_before_seconds,
+                phase_sequence: config.pomodoro_phase_sequence.clone(),
+                require_phase_ack: config.pomodoro_require_phase_ack,
+                break_reminders: config.pomodoro_break_reminders,
+                reminder_grace_secs: config.pomodoro_reminder_grace_secs,
             }
         } else {
             // 如果没有用户配置，使用默认值
             PomodoroConfig::default()
         };
-        let pomodoro_service = Arc::new(RwLock::new(PomodoroService::new(pomodoro_config)));
-        println!("✅ Pomodoro service created successfully");
+        let mut service = PomodoroService::new(pomodoro_config);
+        service.set_idle_pause_after(
+            user_config
+                .as_ref()
+                .and_then(|config| config.idle_auto_pause_seconds)
+                .map(u64::from),
+        );
+        if let Some(saved) = database.load_active_session().await? {
+            service.restore_active_session(saved);
+        } else if let Some((phase, remaining, cycle_count)) = database.load_session_state().await? {
+            // 旧版本只保存了阶段进度
+            service.restore_session(phase, remaining, cycle_count);
+        }
+        let pomodoro_service = Arc::new(RwLock::new(service));
+        report(InitStep::ServiceCreated);
 
-        println!("✅ Application core initialized successfully");
+        tracing::info!("Application core initialized");
 
         Ok(Self {
             state_manager,
             database,
             task_manager,
             pomodoro_service,
+            initial_status_policy: InitialStatusPolicy::default(),
+            last_bulk_undo: None,
         })
     }
 
+    /// 设置新建任务的初始状态策略
+    pub fn set_initial_status_policy(&mut self, policy: InitialStatusPolicy) {
+        self.initial_status_policy = policy;
+    }
+
+    /// 获取新建任务的初始状态策略
+    pub fn initial_status_policy(&self) -> InitialStatusPolicy {
+        self.initial_status_policy
+    }
+
     /// 启动应用
     pub async fn start(&mut self) -> Result<()> {
-        println!("🚀 Starting PomodoroFlow-Rs...");
+        tracing::info!("Starting PomodoroFlow-Rs");
 
         if let Some(config) = self.database.load_user_config().await? {
-            self.state_manager.set_user_config(config).await;
+            self.state_manager.set_user_config(config.clone()).await;
+            self.run_daily_maintenance(&config).await;
         }
 
         let todos = self.database.get_all_todos().await?;
@@ -136,8 +326,10 @@ impl PomodoroAppManager {
         }
 
         self.ensure_pomodoro_tick_task().await?;
+        self.ensure_session_autosave_task().await?;
+        self.ensure_maintenance_task().await?;
 
-        println!("🎉 PomodoroFlow-Rs started successfully!");
+        tracing::info!("PomodoroFlow-Rs started");
         Ok(())
     }
 
@@ -146,15 +338,247 @@ impl PomodoroAppManager {
         Arc::clone(&self.database)
     }
 
+    /// 一次性获取前端初始化所需的全部数据
+    pub async fn get_initial_payload(&self) -> Result<InitialPayload> {
+        Ok(InitialPayload {
+            todos: self.state_manager.get_filtered_todos().await,
+            stats: self.state_manager.get_todo_stats().await,
+            pomodoro_session: self.get_pomodoro_session().await?,
+            user_config: self.get_user_config().await?.unwrap_or_default(),
+            tags: self.database.get_all_tags().await?,
+        })
+    }
+
+    /// 获取带有指定标签的任务累计的专注时长（分钟）
+    pub async fn get_tag_focus_time(&self, tag_id: &str) -> Result<u64> {
+        let rounding = self.minute_rounding().await?;
+        self.database.get_focus_time_for_tag(tag_id, rounding).await
+    }
+
+    /// 专注时长最多的任务排行（分钟，从高到低），最多 `limit` 个
+    pub async fn get_top_focused_todos(&self, limit: u32) -> Result<Vec<(Todo, u64)>> {
+        let rounding = self.minute_rounding().await?;
+        self.database.get_top_focused_todos(limit, rounding).await
+    }
+
+    /// 配置的专注分钟取整方式，未保存配置时为默认的四舍五入
+    async fn minute_rounding(&self) -> Result<MinuteRounding> {
+        Ok(self
+            .get_user_config()
+            .await?
+            .map(|config| config.focus_minute_rounding)
+            .unwrap_or_default())
+    }
+
+    /// 获取没有任何标签的任务，已完成和已归档的任务不计入
+    pub async fn get_untagged_todos(&self) -> Result<Vec<Todo>> {
+        self.database.get_untagged_todos().await
+    }
+
+    /// 按天统计任务状态分布（本地日期，含首尾），用于堆叠面积图
+    pub async fn get_status_distribution_by_day(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, TodoStats)>> {
+        self.database.get_status_distribution_by_day(from, to).await
+    }
+
+    /// 批量获取任务的工作番茄数，没有记录的任务为 0
+    pub async fn get_pomodoro_counts(&self, ids: &[String]) -> Result<HashMap<String, u32>> {
+        self.database.get_pomodoro_counts_for_todos(ids).await
+    }
+
+    /// 最近 `days` 天平均每天完成的任务数
+    pub async fn get_completion_velocity(&self, days: u32) -> Result<f32> {
+        self.database.get_completion_velocity(days).await
+    }
+
+    /// 今日（本地日期）完成的工作会话 (completed_at, duration_seconds)
+    async fn today_work_sessions(&self) -> Result<Vec<(DateTime<Utc>, u32)>> {
+        let (day_start, day_end) = local_day_bounds(chrono::Local::now().date_naive());
+        self.database.get_work_sessions_between(day_start, day_end).await
+    }
+
+    /// 按今日节奏预测达成每日专注目标的时间
+    ///
+    /// 目标已达成、未设置目标或今日工作会话不足两个时返回 None，
+    /// 推算方式见 [`project_goal_completion_at`]。
+    pub async fn project_goal_completion(&self) -> Result<Option<DateTime<Utc>>> {
+        let goal_minutes = self
+            .get_user_config()
+            .await?
+            .and_then(|config| config.daily_focus_goal_minutes);
+        let sessions = self.today_work_sessions().await?;
+        Ok(project_goal_completion_at(&sessions, goal_minutes, Utc::now()))
+    }
+
+    /// 获取今日专注目标进度
+    pub async fn get_goal_progress(&self) -> Result<GoalProgress> {
+        let config = self.get_user_config().await?.unwrap_or_default();
+        let achieved_seconds: u64 = self
+            .today_work_sessions()
+            .await?
+            .iter()
+            .map(|(_, secs)| u64::from(*secs))
+            .sum();
+        let achieved_minutes =
+            u32::try_from(config.focus_minute_rounding.minutes(achieved_seconds)).unwrap_or(u32::MAX);
+        Ok(GoalProgress::new(achieved_minutes, config.daily_focus_goal_minutes))
+    }
+
+    /// 获取每日番茄数目标进度：(今日完成的工作番茄数, 目标)
+    ///
+    /// 完成数取自会话记录，重启后不会丢失。
+    pub async fn get_daily_goal_progress(&self) -> Result<(u32, Option<u32>)> {
+        let goal = self
+            .get_user_config()
+            .await?
+            .and_then(|config| config.daily_pomodoro_goal);
+        let completed = today_work_pomodoro_count(&self.database).await?;
+        Ok((completed, goal))
+    }
+
+    /// 今日专注是否已达到每日目标，未设置目标时返回 false
+    pub async fn is_daily_goal_met(&self) -> Result<bool> {
+        Ok(self.get_goal_progress().await?.reached)
+    }
+
+    /// 今天完成的任务数，之前完成、今天只是被编辑过的任务不计入
+    pub async fn get_completed_today_count(&self) -> Result<u64> {
+        self.database
+            .count_completed_on(chrono::Local::now().date_naive())
+            .await
+    }
+
+    /// 获取当前连续专注天数（按本地日期，今天还没有专注记录时从昨天算起）
+    pub async fn get_streak(&self) -> Result<u32> {
+        self.database.get_current_streak().await
+    }
+
+    /// 汇总小组件需要的数据，一次调用即可刷新
+    pub async fn get_widget_payload(&self) -> Result<WidgetPayload> {
+        let progress = self.get_goal_progress().await?;
+        let running = self
+            .get_pomodoro_session()
+            .await?
+            .is_some_and(|session| session.is_running);
+        Ok(WidgetPayload {
+            current_streak: self.get_streak().await?,
+            today_focus_minutes: progress.achieved_minutes,
+            completed_today: self.get_completed_today_count().await?,
+            goal_minutes: progress.goal_minutes,
+            running,
+            formatted_remaining: self.get_formatted_remaining().await?,
+        })
+    }
+
+    /// 获取番茄钟统计：今天（本地日期）或有记录以来
+    pub async fn get_pomodoro_stats(&self, range: StatsRange) -> Result<PomodoroStats> {
+        let since = match range {
+            StatsRange::Today => local_day_bounds(chrono::Local::now().date_naive()).0,
+            StatsRange::AllTime => DateTime::<Utc>::UNIX_EPOCH,
+        };
+        let rounding = self.minute_rounding().await?;
+        self.database.get_pomodoro_stats(since, rounding).await
+    }
+
+    /// 分页获取某个本地日期完成的会话（最新在前）及当天会话总数
+    pub async fn get_session_history_page(
+        &self,
+        date: NaiveDate,
+        offset: u32,
+        limit: u32,
+    ) -> Result<SessionPage> {
+        let (from, to) = local_day_bounds(date);
+        self.database.get_sessions(from, to, offset, limit).await
+    }
+
+    /// 区间内专注分钟数最多的本地星期几及其总分钟数，没有专注记录时为 None
+    pub async fn get_best_weekday(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<(chrono::Weekday, u64)>> {
+        let rounding = self.minute_rounding().await?;
+        self.database.get_best_weekday(from, to, rounding).await
+    }
+
+    /// 根据最近 `lookback_days` 天（不含今天）的专注习惯建议每日目标（分钟）
+    ///
+    /// 取有专注记录的日子的专注分钟数中位数，见 [`suggest_goal_from_daily_minutes`]。
+    pub async fn suggest_daily_goal(&self, lookback_days: u32) -> Result<u32> {
+        let today = chrono::Local::now().date_naive();
+        if lookback_days == 0 {
+            return Ok(DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES);
+        }
+        let (start, _) = local_day_bounds(today - chrono::Duration::days(i64::from(lookback_days)));
+        let (_, end) = local_day_bounds(today - chrono::Duration::days(1));
+
+        let mut seconds_by_day: HashMap<chrono::NaiveDate, u64> = HashMap::new();
+        for (at, seconds) in self.database.get_work_sessions_between(start, end).await? {
+            *seconds_by_day
+                .entry(at.with_timezone(&chrono::Local).date_naive())
+                .or_default() += u64::from(seconds);
+        }
+
+        let rounding = self.minute_rounding().await?;
+        Ok(suggest_goal_from_daily_minutes(
+            seconds_by_day.into_values().map(|seconds| rounding.minutes(seconds)),
+        ))
+    }
+
+    /// 获取有记录以来的累计专注时长（仅工作阶段）
+    pub async fn get_lifetime_focus(&self) -> Result<LifetimeFocus> {
+        let seconds = self.database.get_lifetime_focus_seconds().await?;
+        let rounding = self.minute_rounding().await?;
+        Ok(LifetimeFocus::from_seconds_rounded(seconds, rounding))
+    }
+
     /// 获取当前番茄钟会话
     pub async fn get_pomodoro_session(&self) -> Result<Option<PomodoroSession>> {
         let service = self.pomodoro_service.read().await;
         Ok(service.get_session().cloned())
     }
 
+    /// 获取当前生效的番茄钟配置，各时长同时给出秒数与分钟数
+    pub async fn get_pomodoro_config_view(&self) -> Result<PomodoroConfigView> {
+        let service = self.pomodoro_service.read().await;
+        let config = service
+            .get_session()
+            .map(|session| session.config.clone())
+            .unwrap_or_default();
+        Ok(PomodoroConfigView::from(&config))
+    }
+
+    /// 取出并清除刚完成的阶段，供通知层轮询，每个阶段只返回一次
+    pub async fn take_completed_phase(&self) -> Result<Option<(PomodoroPhase, u32)>> {
+        Ok(self.pomodoro_service.write().await.take_completed_phase())
+    }
+
+    /// 获取当前阶段的生效颜色（配置覆盖优先），没有会话时按工作阶段计
+    pub async fn get_current_phase_color(&self) -> Result<String> {
+        let phase = self
+            .pomodoro_service
+            .read()
+            .await
+            .get_session()
+            .map(|session| session.phase)
+            .unwrap_or(PomodoroPhase::Work);
+        let config = self.get_user_config().await?.unwrap_or_default();
+        Ok(config.phase_color(phase).to_string())
+    }
+
+    /// 获取剩余时间（MM:SS），没有会话时返回 `--:--`
+    pub async fn get_formatted_remaining(&self) -> Result<String> {
+        let service = self.pomodoro_service.read().await;
+        Ok(format_remaining(service.get_session()))
+    }
+
     /// 开始番茄钟
-    pub async fn start_pomodoro(&mut self) -> Result<()> {
-        self.ensure_pomodoro_tick_task().await?;
+    pub async fn start_pomodoro(&self) -> Result<()> {
+        self.restart_pomodoro_tick_task().await?;
+        self.ensure_session_autosave_task().await?;
 
         let latest_session = {
             let mut service = self.pomodoro_service.write().await;
@@ -163,766 +587,3253 @@ impl PomodoroAppManager {
         };
 
         if let Some(session) = latest_session {
+            self.persist_session_state(&session).await;
+            self.sync_focus_todo_status(&session, TodoStatus::InProgress)
+                .await;
             self.state_manager.set_pomodoro_session(session).await;
         }
 
         Ok(())
     }
 
-    /// 暂停番茄钟
-    pub async fn pause_pomodoro(&mut self) -> Result<()> {
-        let session = {
+    /// 设置计时间隔（毫秒，限制在 50ms 到 1 秒之间），返回实际生效的间隔
+    ///
+    /// 计时任务正在运行时会立即按新间隔重启。
+    pub async fn set_tick_interval_ms(&self, interval_ms: u64) -> Result<u64> {
+        let effective = {
+            let mut service = self.pomodoro_service.write().await;
+            service.set_tick_interval_ms(interval_ms);
+            service.tick_interval().as_millis() as u64
+        };
+        let tick = crate::async_utils::task_manager::TaskNames::POMODORO_TICK;
+        if self.task_manager.exists(tick).await {
+            self.restart_pomodoro_tick_task().await?;
+        }
+        Ok(effective)
+    }
+
+    /// 上报系统空闲秒数，供休息结束时判断是否保持工作阶段暂停
+    pub async fn report_idle_seconds(&self, seconds: u64) {
+        self.pomodoro_service
+            .write()
+            .await
+            .report_idle_seconds(seconds);
+    }
+
+    /// 开始一个包含 `total` 个番茄的专注块
+    pub async fn start_focus_block(&self, total: u32) -> Result<()> {
+        self.ensure_pomodoro_tick_task().await?;
+        self.ensure_session_autosave_task().await?;
+
+        let latest_session = {
             let mut service = self.pomodoro_service.write().await;
-            service.pause()?;
+            service.start_focus_block(total)?;
             service.get_session().cloned()
         };
-        if let Some(session) = session {
+
+        if let Some(session) = latest_session {
+            self.persist_session_state(&session).await;
+            self.sync_focus_todo_status(&session, TodoStatus::InProgress)
+                .await;
             self.state_manager.set_pomodoro_session(session).await;
         }
+
         Ok(())
     }
 
-    /// 重置番茄钟
-    pub async fn reset_pomodoro(&mut self) -> Result<()> {
-        let session = {
+    /// 获取当前专注块进度 `(已完成, 总数)`，没有进行中的专注块时返回 `None`
+    pub async fn get_focus_block_progress(&self) -> Result<Option<(u32, u32)>> {
+        Ok(self.pomodoro_service.read().await.focus_block_progress())
+    }
+
+    /// 开始一段深度工作（使用独立时长），完成后与普通工作阶段一样进入休息
+    pub async fn start_deep_work(&self) -> Result<()> {
+        self.ensure_pomodoro_tick_task().await?;
+        self.ensure_session_autosave_task().await?;
+
+        let latest_session = {
             let mut service = self.pomodoro_service.write().await;
-            service.reset()?;
+            service.start_deep_work()?;
             service.get_session().cloned()
         };
-        if let Some(session) = session {
+
+        if let Some(session) = latest_session {
+            self.persist_session_state(&session).await;
+            self.sync_focus_todo_status(&session, TodoStatus::InProgress)
+                .await;
             self.state_manager.set_pomodoro_session(session).await;
         }
+
         Ok(())
     }
 
-    /// 跳过当前阶段
-    pub async fn skip_pomodoro_phase(&mut self) -> Result<()> {
+    /// 提前结束休息并立即开始工作，需满足 `enforced_break_seconds` 的最短休息时长
+    pub async fn end_break_now(&self) -> Result<()> {
+        let min_break_seconds = self
+            .get_user_config()
+            .await?
+            .and_then(|config| config.enforced_break_seconds)
+            .unwrap_or(0);
+
+        self.ensure_pomodoro_tick_task().await?;
+        self.ensure_session_autosave_task().await?;
+
         let session = {
             let mut service = self.pomodoro_service.write().await;
-            service.skip()?;
+            service.end_break(u64::from(min_break_seconds))?;
             service.get_session().cloned()
         };
         if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.sync_focus_todo_status(&session, TodoStatus::InProgress)
+                .await;
             self.state_manager.set_pomodoro_session(session).await;
         }
         Ok(())
     }
 
-    /// 获取所有待办事项
-    pub async fn get_todos(&self) -> Result<Vec<Todo>> {
-        let todos = self.state_manager.get_all_todos().await;
-        Ok(todos)
-    }
+    /// 设置当前会话专注的任务，`None` 表示取消专注
+    pub async fn set_focus_todo(&self, todo_id: Option<String>) -> Result<()> {
+        if let Some(id) = &todo_id {
+            if self.database.get_todo_by_id(id).await?.is_none() {
+                return Err(AppError::NotFound(format!("Todo with id {} not found", id)));
+            }
+        }
 
-    /// 创建新任务（带指定状态）
-    pub async fn create_todo_with_status(
-        &mut self,
-        title: String,
-        description: Option<String>,
-        status: crate::core::todo::TodoStatus,
-    ) -> Result<Todo> {
-        let new_todo = crate::core::todo::NewTodo {
-            title,
-            description,
-            status,
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            service.get_session_mut().map(|session| {
+                session.focus_todo_id = todo_id;
+                session.clone()
+            })
         };
-
-        // 保存到数据库
-        let todo = self.database.create_todo(&new_todo).await?;
-
-        // 添加到状态
-        self.state_manager.add_todo(todo.clone()).await?;
-
-        Ok(todo)
+        if let Some(session) = session {
+            self.state_manager.set_pomodoro_session(session).await;
+        }
+        Ok(())
     }
 
-    /// 创建新任务（默认状态为 Todo）
-    pub async fn create_todo(
-        &mut self,
-        title: String,
-        description: Option<String>,
-    ) -> Result<Todo> {
-        self.create_todo_with_status(title, description, crate::core::todo::TodoStatus::Todo)
-            .await
+    /// 专注指定任务并开始计时，阶段完成时记录到该任务名下
+    pub async fn start_pomodoro_for_todo(&self, todo_id: String) -> Result<()> {
+        self.set_focus_todo(Some(todo_id)).await?;
+        self.start_pomodoro().await
     }
 
-    /// 更新任务
-    pub async fn update_todo(&mut self, id: &str, updates: TodoUpdate) -> Result<Todo> {
-        // 更新数据库
-        let updated = self.database.update_todo(id, &updates).await?;
+    /// 启用 `sync_focus_status` 时，将工作阶段的专注任务切换到指定状态
+    ///
+    /// 已完成的任务保持不变；失败只记录日志，不影响计时操作。
+    async fn sync_focus_todo_status(&self, session: &PomodoroSession, status: TodoStatus) {
+        if !session.phase.is_work() {
+            return;
+        }
+        let Some(todo_id) = session.focus_todo_id.as_deref() else {
+            return;
+        };
+        let enabled = matches!(
+            self.get_user_config().await,
+            Ok(Some(config)) if config.sync_focus_status
+        );
+        if !enabled {
+            return;
+        }
 
-        if let Some(todo) = updated.clone() {
-            // 更新状态
-            self.state_manager.update_todo(id, updates).await?;
-            let _ = self.enqueue_todo_issue_sync(&todo, "todo_updated").await;
-            Ok(todo)
-        } else {
-            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        let current = self
+            .state_manager
+            .get_all_todos()
+            .await
+            .into_iter()
+            .find(|t| t.id == todo_id);
+        match current {
+            Some(todo) if !todo.is_done() && todo.status != status => {
+                if let Err(err) = self.set_todo_status(todo_id, status).await {
+                    eprintln!("⚠️  同步专注任务状态失败: {}", err);
+                }
+            }
+            _ => {}
         }
     }
 
-    /// 删除任务
-    pub async fn delete_todo(&mut self, id: &str) -> Result<()> {
-        // 从数据库删除
-        self.database.delete_todo(id).await?;
+    /// 保存会话进度，失败只记录日志，不影响计时操作
+    async fn persist_session_state(&self, session: &PomodoroSession) {
+        if let Err(err) = self.database.save_active_session(session).await {
+            eprintln!("⚠️  保存番茄钟进度失败: {}", err);
+        }
+    }
 
-        // 从状态删除
-        self.state_manager.delete_todo(id).await?;
+    /// 暂停番茄钟（手动暂停）
+    pub async fn pause_pomodoro(&self) -> Result<()> {
+        self.pause_pomodoro_with_reason(PauseReason::Manual).await
+    }
 
+    /// 按指定原因暂停番茄钟，原因会计入当前阶段的暂停统计
+    pub async fn pause_pomodoro_with_reason(&self, reason: PauseReason) -> Result<()> {
+        self.stop_pomodoro_tick_task().await?;
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            service.pause_with_reason(reason)?;
+            service.get_session().cloned()
+        };
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.sync_focus_todo_status(&session, TodoStatus::Todo).await;
+            self.state_manager.set_pomodoro_session(session).await;
+        }
         Ok(())
     }
 
-    /// 切换任务状态
-    pub async fn toggle_todo_status(&mut self, id: &str) -> Result<Todo> {
-        self.state_manager.toggle_todo_status(id).await?;
-
-        // 同步到数据库并获取更新的任务
-        let todos = self.state_manager.get_all_todos().await;
-        for todo in todos {
-            if todo.id == id {
-                let updates = TodoUpdate::new().with_status(todo.status);
-                let updated = self.database.update_todo(id, &updates).await?;
-                if let Some(updated_todo) = updated {
-                    let _ = self
-                        .enqueue_todo_issue_sync(&updated_todo, "todo_status_toggled")
-                        .await;
-                    return Ok(updated_todo);
-                }
-                break;
-            }
+    /// 放弃当前会话：回到工作阶段开头并清零周期，专注任务一并取消
+    pub async fn abandon_pomodoro(&self) -> Result<()> {
+        let (previous, session) = {
+            let mut service = self.pomodoro_service.write().await;
+            let previous = service.get_session().cloned();
+            service.abandon();
+            (previous, service.get_session().cloned())
+        };
+        if let Some(previous) = previous {
+            self.sync_focus_todo_status(&previous, TodoStatus::Todo).await;
         }
-
-        Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
+        }
+        Ok(())
     }
 
-    /// 显式设置任务状态
-    pub async fn set_todo_status(&mut self, id: &str, status: TodoStatus) -> Result<Todo> {
-        self.state_manager
-            .set_todo_status(id, status.clone())
-            .await?;
-        let updates = TodoUpdate::new().with_status(status);
-        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
-            let _ = self
-                .enqueue_todo_issue_sync(&updated_todo, "todo_status_set")
-                .await;
-            Ok(updated_todo)
-        } else {
-            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+    /// 重置番茄钟
+    pub async fn reset_pomodoro(&self) -> Result<()> {
+        self.stop_pomodoro_tick_task().await?;
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            service.reset()?;
+            service.get_session().cloned()
+        };
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
         }
+        Ok(())
     }
 
-    /// 关联待办与 GitHub Issue / Project
-    pub async fn link_todo_github(
-        &mut self,
-        id: &str,
-        issue_id: i64,
-        issue_number: i64,
-        project_id: i64,
-    ) -> Result<Todo> {
-        if issue_id <= 0 || issue_number <= 0 || project_id <= 0 {
-            return Err(AppError::Validation(
-                "issue_id, issue_number, project_id 必须为正整数".to_string(),
-            ));
+    /// 清零工作周期计数，不影响当前阶段与计时
+    pub async fn reset_cycle_count(&self) -> Result<()> {
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            service.reset_cycle_count();
+            service.get_session().cloned()
+        };
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
         }
+        Ok(())
+    }
 
-        let updates = TodoUpdate::new()
-            .with_github_issue_id(Some(issue_id))
-            .with_github_issue_number(Some(issue_number))
-            .with_github_project_id(Some(project_id));
+    /// 记录当前阶段的一次打断，返回本阶段累计的打断次数
+    ///
+    /// 打断次数在阶段完成时写入会话记录。
+    pub async fn record_pomodoro_interruption(&self) -> Result<u32> {
+        let (interruptions, session) = {
+            let mut service = self.pomodoro_service.write().await;
+            let interruptions = service.record_interruption()?;
+            (interruptions, service.get_session().cloned())
+        };
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
+        }
+        Ok(interruptions)
+    }
 
-        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
-            self.state_manager.update_todo(id, updates).await?;
-            let payload = serde_json::json!({
-                "id": id,
-                "github_issue_id": issue_id,
-                "github_issue_number": issue_number,
-                "github_project_id": project_id,
-                "action": "link_github",
-            });
-            let _ = self.database.add_to_sync_queue("update", id, &payload).await;
-            let _ = self
-                .enqueue_todo_issue_sync(&updated_todo, "todo_github_linked")
-                .await;
-            Ok(updated_todo)
+    /// 跳过当前阶段
+    ///
+    /// 计时中跳过也会成功；新阶段是否继续计时决定计时任务重启还是停止。
+    pub async fn skip_pomodoro_phase(&self) -> Result<()> {
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            service.skip()?;
+            service.get_session().cloned()
+        };
+        if session.as_ref().is_some_and(|session| session.is_running) {
+            self.restart_pomodoro_tick_task().await?;
         } else {
-            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+            self.stop_pomodoro_tick_task().await?;
+        }
+        if let Some(session) = session {
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
         }
+        Ok(())
     }
 
-    /// 清除待办的 GitHub 关联
-    pub async fn clear_todo_github_link(&mut self, id: &str) -> Result<Todo> {
-        let updates = TodoUpdate::new()
-            .with_github_issue_id(None)
-            .with_github_issue_number(None)
-            .with_github_project_id(None);
+    /// 确认已到时的工作阶段（开启 `require_phase_ack` 时），记录完成并进入休息
+    pub async fn acknowledge_pomodoro_phase(&self) -> Result<()> {
+        let (event, session, overrun_seconds) = {
+            let mut service = self.pomodoro_service.write().await;
+            let overrun_seconds = service.get_session().map_or(0, PomodoroSession::overrun_seconds);
+            let event = service.acknowledge_phase()?;
+            (event, service.get_session().cloned(), overrun_seconds)
+        };
+        if let Some(session) = session {
+            if record_completed_phase(&self.database, &session, Some(&event), overrun_seconds)
+                .await
+            {
+                announce_daily_pomodoro_goal(&self.database, &self.state_manager, Some(&event))
+                    .await;
+            }
+            if session.is_running {
+                self.restart_pomodoro_tick_task().await?;
+            } else {
+                self.stop_pomodoro_tick_task().await?;
+            }
+            self.persist_session_state(&session).await;
+            self.state_manager.set_pomodoro_session(session).await;
+        }
+        let _ = self.state_manager.send_event(AppEvent::PomodoroEvent(event));
+        Ok(())
+    }
 
-        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
-            self.state_manager.update_todo(id, updates).await?;
-            let payload = serde_json::json!({
-                "id": id,
-                "github_issue_id": serde_json::Value::Null,
-                "github_issue_number": serde_json::Value::Null,
-                "github_project_id": serde_json::Value::Null,
-                "action": "clear_github_link",
-            });
-            let _ = self.database.add_to_sync_queue("update", id, &payload).await;
-            Ok(updated_todo)
-        } else {
-            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+    /// 强制将指定任务重新放回同步队列
+    pub async fn requeue_todo_sync(&mut self, id: &str) -> Result<()> {
+        if !self.database.mark_todo_pending_sync(id).await? {
+            return Err(AppError::NotFound(format!("任务不存在: {}", id)));
         }
+        Ok(())
     }
 
-    /// 获取用户配置
-    pub async fn get_user_config(&self) -> Result<Option<UserConfig>> {
-        let config = self.database.load_user_config().await?;
-        Ok(config)
+    /// 强制将所有任务重新放回同步队列，返回新加入的任务数
+    pub async fn requeue_all_sync(&mut self) -> Result<usize> {
+        self.database.mark_all_todos_pending_sync().await
     }
 
-    /// 保存用户配置
-    pub async fn save_user_config(&mut self, config: UserConfig) -> Result<()> {
-        let config = normalize_user_config(config);
-        let previous_config = self.database.load_user_config().await?;
-        let next_pomodoro_config = PomodoroConfig {
-            work_duration: config.pomodoro_work_duration,
-            short_break_duration: config.pomodoro_short_break_duration,
-            long_break_duration: config.pomodoro_long_break_duration,
-            cycles_until_long_break: config.pomodoro_cycles_until_long_break,
-        };
-        next_pomodoro_config.validate()?;
+    /// 创建应用事件接收器，供外层事件处理使用，可多次调用
+    pub fn create_event_receiver(&self) -> Result<EventReceiver> {
+        self.state_manager.create_event_receiver()
+    }
 
-        self.database.save_user_config(&config).await?;
+    /// 订阅应用事件广播，多个订阅者互不影响
+    ///
+    /// 单个订阅者落后超过 [`EVENT_CHANNEL_CAPACITY`] 条时会丢失最旧的事件。
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<AppEvent> {
+        self.state_manager.subscribe()
+    }
 
-        if let Err(err) = self.sync_runtime_config(&config).await {
-            if let Some(previous) = previous_config {
-                let _ = self.database.save_user_config(&previous).await;
-                let _ = self.sync_runtime_config(&previous).await;
-            }
-            return Err(err);
-        }
+    /// 获取最近的提示/错误消息（按时间先后）
+    pub async fn get_recent_messages(&self, limit: usize) -> Result<Vec<MessageRecord>> {
+        Ok(self.state_manager.get_recent_messages(limit).await)
+    }
 
-        self.state_manager.set_user_config(config).await;
-        Ok(())
+    /// 运行时间超过 `threshold` 仍未结束的后台任务，可能已卡住
+    pub async fn get_stuck_tasks(
+        &self,
+        threshold: std::time::Duration,
+    ) -> Result<Vec<TaskMetadataView>> {
+        Ok(self.task_manager.long_running_tasks(threshold).await)
     }
 
-    /// 更新番茄钟配置（仅运行时，不落盘）
-    pub async fn update_pomodoro_config(&mut self, config: PomodoroConfig) -> Result<()> {
-        self.pomodoro_service
-            .write()
-            .await
-            .update_config(config.clone())?;
-        self.state_manager.update_pomodoro_config(config).await?;
+    /// 清空消息历史
+    pub async fn clear_message_history(&self) -> Result<()> {
+        self.state_manager.clear_message_history().await;
         Ok(())
     }
 
-    async fn sync_runtime_config(&mut self, config: &UserConfig) -> Result<()> {
-        let pomodoro_config = PomodoroConfig {
-            work_duration: config.pomodoro_work_duration,
-            short_break_duration: config.pomodoro_short_break_duration,
-            long_break_duration: config.pomodoro_long_break_duration,
-            cycles_until_long_break: config.pomodoro_cycles_until_long_break,
-        };
+    /// 将所有消息标记为已读，返回被标记的条数
+    pub async fn mark_messages_read(&self) -> Result<usize> {
+        Ok(self.state_manager.mark_messages_read().await)
+    }
 
-        self.update_pomodoro_config(pomodoro_config).await
+    /// 未读消息数
+    pub async fn unread_message_count(&self) -> Result<usize> {
+        Ok(self.state_manager.unread_message_count().await)
     }
 
-    async fn enqueue_todo_issue_sync(&self, todo: &Todo, reason: &str) -> Result<()> {
-        if !has_linked_issue(todo) {
-            return Ok(());
+    /// 获取所有待办事项
+    pub async fn get_todos(&self) -> Result<Vec<Todo>> {
+        let todos = self.state_manager.get_all_todos().await;
+        Ok(todos)
+    }
+
+    /// 分页获取任务，同时返回未删除任务总数
+    pub async fn get_todos_page(&self, offset: usize, limit: usize) -> Result<TodoPage> {
+        let todos = self.database.get_todos_paginated(offset, limit).await?;
+        let total = self.database.count_todos().await?;
+        Ok(TodoPage { todos, total })
+    }
+
+    /// 全文搜索任务标题和描述
+    pub async fn full_text_search_todos(&self, query: &str) -> Result<Vec<Todo>> {
+        self.database.search_todos(query).await
+    }
+
+    /// 按筛选器搜索任务，未指定 `limit` 的搜索最多返回 [`core::todo::DEFAULT_SEARCH_LIMIT`] 条
+    pub async fn search_todos(&self, filter: &TodoFilter) -> Result<TodoSearchResult> {
+        self.database.search_todos_capped(filter).await
+    }
+
+    /// 按筛选器获取任务并按指定字段排序
+    pub async fn get_todos_sorted(
+        &self,
+        filter: &TodoFilter,
+        sort_key: SortKey,
+        ascending: bool,
+    ) -> Result<Vec<Todo>> {
+        self.database.query_todos_sorted(filter, sort_key, ascending).await
+    }
+
+    /// 获取在 [from, to] 内创建的任务（含当前状态），用于周回顾
+    pub async fn get_todos_for_review(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Todo>> {
+        if from > to {
+            return Err(AppError::Validation(
+                "开始时间不能晚于结束时间".to_string(),
+            ));
         }
-        let issue_number = match todo.github_issue_number {
-            Some(v) if v > 0 => v,
-            _ => return Ok(()),
-        };
-        let payload = serde_json::json!({
-            "id": todo.id,
-            "github_issue_number": issue_number,
-            "github_project_id": todo.github_project_id,
-            "title": todo.title,
-            "status": todo.status,
-            "project_status": map_todo_status_to_project_status(&todo.status),
-            "action": "sync_issue",
-            "reason": reason,
-        });
-        let _ = self
-            .database
-            .add_to_sync_queue("update", &todo.id, &payload)
-            .await?;
-        Ok(())
+        self.database.get_todos_created_between(from, to).await
     }
 
-    /// 执行 GitHub 同步流程
-    pub async fn run_github_sync(&mut self, dry_run: bool) -> Result<GithubSyncReport> {
-        let config = self
+    /// 获取在 [from, to] 内完成的任务，用于每日成果报告
+    pub async fn get_todos_completed_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Todo>> {
+        if from > to {
+            return Err(AppError::Validation(
+                "开始时间不能晚于结束时间".to_string(),
+            ));
+        }
+        self.database.get_todos_completed_between(from, to).await
+    }
+
+    /// 估算未完成任务的总耗时
+    ///
+    /// 有预估的任务按 `estimate_minutes` 计；没有预估的任务按已完成任务的平均专注时长计，
+    /// 还没有任何历史记录时按一个番茄钟的工作时长计。
+    pub async fn estimate_backlog_time(&self) -> Result<EstimatedBacklog> {
+        let fallback_seconds = match self
             .database
-            .load_user_config()
+            .get_average_focus_seconds_per_completed_todo()
             .await?
-            .map(normalize_user_config)
-            .ok_or_else(|| AppError::NotFound("用户配置不存在".to_string()))?;
+        {
+            Some(seconds) => seconds,
+            None => self
+                .pomodoro_service
+                .read()
+                .await
+                .get_session()
+                .map(|session| session.config.work_duration)
+                .unwrap_or_else(|| PomodoroConfig::default().work_duration),
+        };
+        let todos = self.state_manager.get_all_todos().await;
+        let rounding = self.minute_rounding().await?;
+        Ok(EstimatedBacklog::compute(&todos, rounding.minutes(fallback_seconds)))
+    }
 
-        let target = build_github_sync_target(&config)?;
-        let pending = self.database.get_pending_sync_queue().await?;
+    /// 生成今日/本周的可打印计划（Markdown），内容规则见 [`core::todo::render_plan`]
+    pub async fn generate_plan(&self, scope: PlanScope) -> Result<String> {
+        let todos = self.state_manager.get_all_todos().await;
+        let today = chrono::Local::now().date_naive();
+        let (_, last_day) = scope.date_range(today);
+        let (_, due_before) = local_day_bounds(last_day);
+        Ok(core::todo::render_plan(&todos, scope, today, due_before))
+    }
 
-        let mut report = GithubSyncReport {
-            dry_run,
-            pending_items: pending.len(),
-            supported_items: 0,
-            unsupported_items: 0,
-            invalid_items: 0,
-            target,
-            errors: Vec::new(),
-        };
-        let sync_cursor = config.last_sync_cursor.clone();
-        let github_client = if dry_run {
-            None
-        } else {
-            Some(GithubSyncClient::new(
-                &config.github_token_encrypted,
-                &report.target.owner,
-                &report.target.repo,
-            )?)
-        };
+    /// 将未归档的任务导出为 iCalendar 文本，格式见 [`core::todo::export_todos_ics`]
+    pub async fn export_todos_ics(&self) -> Result<String> {
+        let todos: Vec<Todo> = self
+            .state_manager
+            .get_all_todos()
+            .await
+            .into_iter()
+            .filter(|todo| !todo.archived)
+            .collect();
+        Ok(core::todo::export_todos_ics(&todos))
+    }
 
-        for (queue_id, operation_type, _record_id, payload_raw) in pending {
-            let payload: serde_json::Value = match serde_json::from_str(&payload_raw) {
-                Ok(v) => v,
-                Err(err) => {
-                    report.invalid_items += 1;
-                    let message = format!("queue #{queue_id}: payload 解析失败: {err}");
-                    report.errors.push(message.clone());
-                    if !dry_run {
-                        let _ = self
-                            .database
-                            .mark_sync_queue_failed(queue_id, &message)
-                            .await;
-                    }
-                    continue;
-                }
-            };
+    /// 获取建议下一个开始的任务，排序规则见 [`core::todo::suggest_next_todo`]
+    pub async fn suggest_next_todo(&self) -> Result<Option<Todo>> {
+        let todos = self.state_manager.get_all_todos().await;
+        Ok(core::todo::suggest_next_todo(&todos).cloned())
+    }
 
-            if is_supported_sync_item(&operation_type, &payload) {
-                report.supported_items += 1;
-                if !dry_run {
-                    match self
-                        .execute_supported_sync_item(
-                            github_client.as_ref().expect("client initialized"),
-                            report.target.project_number,
-                            queue_id,
-                            &payload,
-                        )
-                        .await
-                    {
-                        Ok(_) => {
-                            let _ = self.database.mark_sync_queue_synced(queue_id).await;
-                        }
-                        Err(err) => {
-                            let message = err.to_string();
-                            report.errors.push(format!("queue #{queue_id}: {message}"));
-                            let _ = self
-                                .database
-                                .mark_sync_queue_failed(queue_id, &message)
-                                .await;
-                        }
-                    }
-                }
-            } else {
-                report.unsupported_items += 1;
-                if !dry_run {
-                    let message = format!(
-                        "queue #{queue_id}: 暂不支持的同步项 (operation_type={operation_type}, action={})",
-                        sync_action_from_payload(&payload).unwrap_or("unknown"),
-                    );
-                    report.errors.push(message.clone());
-                    let _ = self
-                        .database
-                        .mark_sync_queue_failed(queue_id, &message)
-                        .await;
-                }
-            }
-        }
+    /// 创建新任务（带指定状态）
+    ///
+    /// 非 `Todo` 的初始状态按 [`InitialStatusPolicy`] 处理；以 `Done` 创建时会记录完成时间。
+    pub async fn create_todo_with_status(
+        &mut self,
+        title: String,
+        description: Option<String>,
+        status: crate::core::todo::TodoStatus,
+    ) -> Result<Todo> {
+        self.initial_status_policy.check(&status)?;
 
-        if !dry_run {
-            if let Some(client) = github_client.as_ref() {
-                self.pull_remote_issue_updates(client, sync_cursor.as_deref(), &mut report)
-                    .await;
-            }
-            let _ = self.database.cleanup_sync_queue().await;
-            let _ = self
-                .persist_last_sync_cursor(&config, Utc::now().to_rfc3339())
-                .await;
-        }
+        let new_todo = crate::core::todo::NewTodo {
+            title,
+            description,
+            status,
+            ..Default::default()
+        };
 
-        Ok(report)
+        // 保存到数据库
+        let todo = self.database.create_todo(&new_todo).await?;
+
+        // 添加到状态
+        self.state_manager.add_todo(todo.clone()).await?;
+
+        Ok(todo)
     }
 
-    async fn execute_supported_sync_item(
+    /// 在指定父任务下创建子任务（状态为 Todo）
+    pub async fn create_subtask(
         &mut self,
-        client: &GithubSyncClient,
-        project_number: i64,
-        queue_id: i64,
-        payload: &serde_json::Value,
-    ) -> Result<()> {
-        let action = sync_action_from_payload(payload).unwrap_or_default();
-        match action {
-            "link_github" => {
-                let issue_number = sync_issue_number_from_payload(payload).ok_or_else(|| {
-                    AppError::Validation(format!(
-                        "queue #{queue_id}: link_github 缺少 github_issue_number"
-                    ))
-                })?;
-                let _ = client.get_issue(issue_number).await?;
-                Ok(())
-            }
-            "clear_github_link" => Ok(()),
-            "sync_issue" => {
-                let issue_number = sync_issue_number_from_payload(payload).ok_or_else(|| {
-                    AppError::Validation(format!(
-                        "queue #{queue_id}: sync_issue 缺少 github_issue_number"
-                    ))
-                })?;
-                let title = payload
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .map(|v| v.trim())
-                    .filter(|v| !v.is_empty());
-                let status = payload
-                    .get("status")
-                    .and_then(|v| v.as_str())
-                    .and_then(parse_todo_status);
-                let _ = client.update_issue(issue_number, title, status.as_ref()).await?;
-                if sync_project_id_from_payload(payload).is_some() {
-                    if let Some(project_status) = sync_project_status_from_payload(payload) {
-                        client
-                            .update_project_item_status(
-                                project_number,
-                                issue_number,
-                                project_status,
-                            )
-                            .await?;
-                    }
-                }
-                Ok(())
-            }
-            _ => Err(AppError::InvalidState(format!(
-                "queue #{queue_id}: 未知 action={action}"
-            ))),
+        parent_id: &str,
+        title: String,
+        description: Option<String>,
+    ) -> Result<Todo> {
+        let new_todo = crate::core::todo::NewTodo {
+            title,
+            description,
+            ..Default::default()
         }
+        .with_parent(parent_id.to_string());
+
+        let todo = self.database.create_todo(&new_todo).await?;
+        self.state_manager.add_todo(todo.clone()).await?;
+        Ok(todo)
     }
 
-    async fn pull_remote_issue_updates(
+    /// 获取任务的直接子任务
+    pub async fn get_subtasks(&self, parent_id: &str) -> Result<Vec<Todo>> {
+        self.database.get_subtasks(parent_id).await
+    }
+
+    /// 获取任务的预估番茄数与实际完成的工作番茄数
+    pub async fn get_todo_progress(&self, todo_id: &str) -> Result<TodoWithProgress> {
+        let todo = self
+            .database
+            .get_todo_by_id(todo_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Todo with id {} not found", todo_id)))?;
+        let actual = self.database.get_todo_pomodoro_count(todo_id).await?;
+        Ok(TodoWithProgress::new(todo, actual))
+    }
+
+    /// 按子任务完成情况统计父任务进度，没有子任务时返回 None
+    pub async fn get_subtask_stats(&self, parent_id: &str) -> Option<TodoStats> {
+        let todos = self.state_manager.get_all_todos().await;
+        TodoStats::from_subtasks(&todos, parent_id)
+    }
+
+    /// 创建新任务（默认状态为 Todo）
+    pub async fn create_todo(
         &mut self,
-        client: &GithubSyncClient,
-        since: Option<&str>,
-        report: &mut GithubSyncReport,
-    ) {
-        let todos = match self.database.get_all_todos().await {
-            Ok(v) => v,
-            Err(err) => {
-                report.errors.push(format!("回拉阶段读取本地 todo 失败: {}", err));
-                return;
-            }
-        };
-        let recent_issues = match client.list_issues_since(since).await {
-            Ok(v) => v,
-            Err(err) => {
-                report.errors.push(format!("回拉阶段拉取 issue 列表失败: {}", err));
-                return;
-            }
-        };
-        let issue_map: std::collections::HashMap<i64, GithubIssue> = recent_issues
+        title: String,
+        description: Option<String>,
+    ) -> Result<Todo> {
+        self.create_todo_with_status(title, description, crate::core::todo::TodoStatus::Todo)
+            .await
+    }
+
+    /// 更新任务
+    pub async fn update_todo(&mut self, id: &str, updates: TodoUpdate) -> Result<Todo> {
+        // 更新数据库
+        let updated = self.database.update_todo(id, &updates).await?;
+
+        if let Some(todo) = updated.clone() {
+            // 更新状态
+            self.state_manager.update_todo(id, updates).await?;
+            let _ = self.enqueue_todo_issue_sync(&todo, "todo_updated").await;
+            Ok(todo)
+        } else {
+            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        }
+    }
+
+    /// 为任务追加一条备注
+    pub async fn add_todo_note(&self, todo_id: &str, body: &str) -> Result<TodoNote> {
+        self.database.add_todo_note(todo_id, body).await
+    }
+
+    /// 获取任务的全部备注（按添加顺序）
+    pub async fn get_todo_notes(&self, todo_id: &str) -> Result<Vec<TodoNote>> {
+        self.database.get_todo_notes(todo_id).await
+    }
+
+    /// 将任务在手动顺序中上移或下移一位，返回是否发生了移动
+    ///
+    /// 只在未归档的任务之间移动；已在最前（或最后）时不做修改。
+    pub async fn move_todo(&mut self, id: &str, direction: MoveDirection) -> Result<bool> {
+        let mut todos: Vec<Todo> = self
+            .database
+            .get_all_todos()
+            .await?
             .into_iter()
-            .map(|issue| (issue.number, issue))
+            .filter(|t| !t.archived || t.id == id)
             .collect();
+        todos.sort_by(|a, b| {
+            a.sort_order
+                .cmp(&b.sort_order)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
 
-        for todo in todos {
-            if !has_linked_issue(&todo) {
-                continue;
-            }
-            let issue_number = match todo.github_issue_number {
-                Some(v) => v,
-                None => continue,
-            };
+        let index = todos
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Todo with id {} not found", id)))?;
+        let neighbor = match direction {
+            MoveDirection::Up => index.checked_sub(1),
+            MoveDirection::Down => Some(index + 1).filter(|next| *next < todos.len()),
+        };
+        let Some(neighbor) = neighbor else {
+            return Ok(false);
+        };
 
-            let issue_result = if let Some(issue) = issue_map.get(&issue_number).cloned() {
-                Ok(issue)
-            } else if since.is_some() {
-                // 增量模式下未命中列表说明该 issue 未更新，无需请求详情。
-                continue;
-            } else {
-                client.get_issue(issue_number).await
-            };
+        let neighbor_id = todos[neighbor].id.clone();
+        if !self.database.swap_todo_order(id, &neighbor_id).await? {
+            return Ok(false);
+        }
+        self.state_manager.swap_todo_order(id, &neighbor_id).await?;
+        Ok(true)
+    }
 
-            match issue_result {
-                Ok(issue) => {
-                    let has_pending_local = self
-                        .database
-                        .has_pending_sync_for_record(&todo.id)
-                        .await
-                        .unwrap_or(false);
-                    if has_pending_local {
-                        report.errors.push(format!(
-                            "conflict: todo {} 存在待推送本地改动，跳过远端覆盖 (issue #{})",
-                            todo.id, issue_number
-                        ));
-                        continue;
-                    }
-                    if issue.updated_at <= todo.updated_at {
-                        continue;
-                    }
-                    if let Err(err) = self.apply_remote_issue_to_todo(&todo.id, &issue).await {
-                        report.errors.push(format!(
-                            "pull todo {} from issue #{} 失败: {}",
-                            todo.id, issue_number, err
-                        ));
-                    }
-                }
-                Err(err) => {
-                    report.errors.push(format!(
-                        "pull issue #{} 失败: {}",
-                        issue_number, err
-                    ));
-                }
+    /// 按给定顺序重排任务（拖拽排序），任一任务不存在时不做修改
+    pub async fn reorder_todos(&mut self, ordered_ids: &[String]) -> Result<()> {
+        self.database.reorder_todos(ordered_ids).await?;
+        let todos = self.database.get_all_todos().await?;
+        self.state_manager.bulk_update_todos(todos).await
+    }
+
+    /// 为任务添加标签，并同步内存中任务的标签列表
+    pub async fn add_tag_to_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
+        self.database.add_tag_to_todo(todo_id, tag_id).await?;
+        self.refresh_todo_tags(todo_id).await
+    }
+
+    /// 从任务移除标签，并同步内存中任务的标签列表
+    pub async fn remove_tag_from_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
+        self.database.remove_tag_from_todo(todo_id, tag_id).await?;
+        self.refresh_todo_tags(todo_id).await
+    }
+
+    /// 修改标签名称和/或颜色，同时更新内存中任务上的标签
+    pub async fn update_tag(
+        &self,
+        tag_id: &str,
+        name: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Tag> {
+        let tag = self
+            .database
+            .update_tag(tag_id, name, color)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Tag with id {} not found", tag_id)))?;
+        self.state_manager.replace_tag_everywhere(&tag).await?;
+        Ok(tag)
+    }
+
+    /// 删除标签，同时从内存中的任务上移除
+    pub async fn delete_tag(&self, tag_id: &str) -> Result<bool> {
+        let deleted = self.database.delete_tag(tag_id).await?;
+        if deleted {
+            self.state_manager.remove_tag_from_all(tag_id).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn refresh_todo_tags(&self, todo_id: &str) -> Result<()> {
+        let tags = self.database.get_todo_tags(todo_id).await?;
+        self.state_manager.set_todo_tags(todo_id, tags).await?;
+        Ok(())
+    }
+
+    /// 删除一条备注
+    pub async fn delete_todo_note(&self, note_id: &str) -> Result<()> {
+        if self.database.delete_todo_note(note_id).await? {
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!("Note with id {} not found", note_id)))
+        }
+    }
+
+    /// 删除任务（移入回收站）
+    pub async fn delete_todo(&mut self, id: &str) -> Result<()> {
+        // 从数据库删除
+        self.database.delete_todo(id).await?;
+
+        // 从状态删除
+        self.state_manager.delete_todo(id).await?;
+
+        Ok(())
+    }
+
+    /// 从回收站恢复任务
+    pub async fn restore_todo(&mut self, id: &str) -> Result<Todo> {
+        let todo = self
+            .database
+            .restore_todo(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Deleted todo with id {} not found", id)))?;
+
+        self.state_manager.add_todo(todo.clone()).await?;
+
+        Ok(todo)
+    }
+
+    /// 获取回收站中的任务
+    pub async fn get_deleted_todos(&self) -> Result<Vec<Todo>> {
+        self.database.get_deleted_todos().await
+    }
+
+    /// 永久删除回收站中的任务
+    pub async fn purge_deleted_todo(&mut self, id: &str) -> Result<()> {
+        let in_trash = self
+            .database
+            .get_deleted_todos()
+            .await?
+            .iter()
+            .any(|t| t.id == id);
+        if !in_trash {
+            return Err(AppError::NotFound(format!("Deleted todo with id {} not found", id)));
+        }
+
+        self.database.permanently_delete_todo(id).await?;
+        Ok(())
+    }
+
+    /// 清除所有已完成任务，返回清除数量，可通过 [`Self::undo_last_bulk_operation`] 撤销
+    pub async fn clear_completed_todos(&mut self) -> Result<usize> {
+        let snapshot = self.state_manager.snapshot().await;
+        let completed: Vec<Todo> = snapshot.todos.iter().filter(|t| t.is_done()).cloned().collect();
+
+        let mut removed = Vec::with_capacity(completed.len());
+        for todo in completed {
+            let tag_ids = self
+                .database
+                .get_todo_tags(&todo.id)
+                .await?
+                .into_iter()
+                .map(|tag| tag.id)
+                .collect();
+            self.database.delete_todo(&todo.id).await?;
+            self.state_manager.delete_todo(&todo.id).await?;
+            removed.push((todo, tag_ids));
+        }
+
+        let count = removed.len();
+        if count > 0 {
+            self.last_bulk_undo = Some(BulkUndo { snapshot, removed });
+        }
+        Ok(count)
+    }
+
+    /// 撤销最近一次批量操作，返回是否有可撤销的操作
+    ///
+    /// 被删除的任务（及其标签关联）会写回数据库，任务列表恢复为操作前的快照；
+    /// 番茄钟会话和配置保持当前值，不随快照回退。
+    pub async fn undo_last_bulk_operation(&mut self) -> Result<bool> {
+        let Some(undo) = self.last_bulk_undo.take() else {
+            return Ok(false);
+        };
+
+        // 父任务移入回收站时子任务会被改为顶层任务，这里按快照一并恢复父子关系
+        let mut todos: Vec<Todo> = undo.removed.iter().map(|(todo, _)| todo.clone()).collect();
+        let orphaned: Vec<Todo> = undo
+            .snapshot
+            .todos
+            .iter()
+            .filter(|t| t.parent_id.as_ref().is_some_and(|p| todos.iter().any(|r| r.id == *p)))
+            .filter(|t| !todos.iter().any(|r| r.id == t.id))
+            .cloned()
+            .collect();
+        todos.extend(orphaned);
+        self.database.restore_todos(&todos).await?;
+        for (todo, tag_ids) in &undo.removed {
+            for tag_id in tag_ids {
+                self.database.add_tag_to_todo(&todo.id, tag_id).await?;
+            }
+        }
+
+        let mut snapshot = undo.snapshot;
+        {
+            let current = self.state_manager.get_state().await;
+            snapshot.pomodoro_session = current.pomodoro_session.clone();
+            snapshot.pomodoro_config = current.pomodoro_config.clone();
+            snapshot.user_config = current.user_config.clone();
+        }
+        self.state_manager.restore(snapshot).await?;
+        Ok(true)
+    }
+
+    /// 恢复出厂设置：清空全部用户数据并回到首次启动的状态
+    ///
+    /// `confirmation` 必须等于 [`FACTORY_RESET_CONFIRMATION`]，否则不做任何修改。
+    /// 数据表全部删除后重新迁移并写入默认配置，内存状态与番茄钟会话一并重置。
+    pub async fn factory_reset(&mut self, confirmation: &str) -> Result<()> {
+        if confirmation != FACTORY_RESET_CONFIRMATION {
+            return Err(AppError::Validation(format!(
+                "请输入 \"{}\" 以确认清除全部数据",
+                FACTORY_RESET_CONFIRMATION
+            )));
+        }
+
+        self.database.reset_all_data().await?;
+        self.last_bulk_undo = None;
+
+        let session = {
+            let mut service = self.pomodoro_service.write().await;
+            *service = PomodoroService::new(PomodoroConfig::default());
+            service.get_session().cloned()
+        };
+
+        self.state_manager.restore(AppState::new()).await?;
+        if let Some(config) = self.database.load_user_config().await? {
+            self.state_manager.set_user_config(config).await;
+        }
+        if let Some(session) = session {
+            self.state_manager.set_pomodoro_session(session).await;
+        }
+
+        println!("🧹 已恢复出厂设置");
+        Ok(())
+    }
+
+    /// 预览导入备份的效果，不修改数据库
+    pub async fn import_all_dry_run(&self, backup: &TodoExport) -> Result<ImportPlan> {
+        let existing = self.database.get_all_todos().await?;
+        Ok(ImportPlan::compute(&existing, &backup.todos))
+    }
+
+    /// 从 Markdown 清单批量创建任务，解析规则见 [`NewTodo::parse_markdown_checklist`]
+    ///
+    /// 导入反映已有清单状态，不受初始状态策略限制；任一条目校验失败时不创建任何任务。
+    pub async fn import_markdown(&mut self, md: &str) -> Result<Vec<Todo>> {
+        let new_todos = NewTodo::parse_markdown_checklist(md);
+        for new_todo in &new_todos {
+            new_todo.validate()?;
+        }
+
+        let mut created = Vec::with_capacity(new_todos.len());
+        for new_todo in &new_todos {
+            let todo = self.database.create_todo(new_todo).await?;
+            self.state_manager.add_todo(todo.clone()).await?;
+            created.push(todo);
+        }
+        Ok(created)
+    }
+
+    /// 导入备份中的任务，规则与 [`Self::import_all_dry_run`] 相同，冲突项保留本地版本
+    pub async fn import_all(&mut self, backup: &TodoExport) -> Result<ImportPlan> {
+        let plan = self.import_all_dry_run(backup).await?;
+        let to_write: Vec<Todo> = backup
+            .todos
+            .iter()
+            .filter(|todo| plan.ids_to_write().any(|id| *id == todo.id))
+            .cloned()
+            .collect();
+
+        if !to_write.is_empty() {
+            self.database.restore_todos(&to_write).await?;
+        }
+
+        // 标签 id 可能与本地不同标签冲突，按名称映射到本地标签或新建标签
+        let tag_ids = self.import_tags(&backup.tags).await?;
+        for (todo_id, tag_id) in &backup.todo_tags {
+            if plan.conflicts.contains(todo_id) || !backup.todos.iter().any(|t| t.id == *todo_id) {
+                continue;
+            }
+            if let Some(local_tag_id) = tag_ids.get(tag_id) {
+                self.database.add_tag_to_todo(todo_id, local_tag_id).await?;
+            }
+        }
+
+        if !to_write.is_empty() {
+            let todos = self.database.get_all_todos().await?;
+            self.state_manager.bulk_update_todos(todos).await?;
+        }
+        Ok(plan)
+    }
+
+    /// 从备份恢复任务，保留原有的 id、状态与时间戳，返回导入的任务数
+    ///
+    /// `merge` 为 false 时先清空现有任务；为 true 时跳过 id 已存在的任务。
+    /// 导入任务的标签按名称映射到本地标签。
+    pub async fn import_todos(&mut self, mut export: TodoExport, merge: bool) -> Result<usize> {
+        if merge {
+            let existing: std::collections::HashSet<String> = self
+                .database
+                .get_all_todos()
+                .await?
+                .into_iter()
+                .map(|todo| todo.id)
+                .collect();
+            export.todos.retain(|todo| !existing.contains(&todo.id));
+        } else {
+            self.database.delete_all_todos().await?;
+        }
+        self.restore_exported_todos(export).await
+    }
+
+    /// 写入备份中的任务及其标签关联，并刷新状态
+    async fn restore_exported_todos(&mut self, export: TodoExport) -> Result<usize> {
+        if !export.todos.is_empty() {
+            self.database.restore_todos(&export.todos).await?;
+        }
+
+        let tag_ids = self.import_tags(&export.tags).await?;
+        for (todo_id, tag_id) in &export.todo_tags {
+            if !export.todos.iter().any(|t| t.id == *todo_id) {
+                continue;
+            }
+            if let Some(local_tag_id) = tag_ids.get(tag_id) {
+                self.database.add_tag_to_todo(todo_id, local_tag_id).await?;
+            }
+        }
+
+        let todos = self.database.get_all_todos().await?;
+        self.state_manager.bulk_update_todos(todos).await?;
+        Ok(export.todos.len())
+    }
+
+    /// 导入标签，返回备份标签 id 到本地标签 id 的映射
+    ///
+    /// 同名标签复用本地 id，其余标签以新生成的 id 创建，从不沿用备份中的 id。
+    async fn import_tags(
+        &self,
+        tags: &[crate::core::todo::Tag],
+    ) -> Result<std::collections::HashMap<String, String>> {
+        let mut local_tags = self.database.get_all_tags().await?;
+        let mut id_map = std::collections::HashMap::new();
+
+        for tag in tags {
+            let normalized = crate::core::todo::Tag::normalize_name(&tag.name);
+            let local_id = match local_tags
+                .iter()
+                .find(|local| crate::core::todo::Tag::normalize_name(&local.name) == normalized)
+            {
+                Some(local) => local.id.clone(),
+                None => {
+                    let created = self.database.create_tag(&tag.name, &tag.color).await?;
+                    let id = created.id.clone();
+                    local_tags.push(created);
+                    id
+                }
+            };
+            id_map.insert(tag.id.clone(), local_id);
+        }
+
+        Ok(id_map)
+    }
+
+    /// 切换任务状态
+    pub async fn toggle_todo_status(&mut self, id: &str) -> Result<ToggleResult> {
+        let from = self
+            .state_manager
+            .get_all_todos()
+            .await
+            .into_iter()
+            .find(|t| t.id == id)
+            .map(|t| t.status)
+            .ok_or_else(|| AppError::NotFound(format!("Todo with id {} not found", id)))?;
+        self.state_manager.toggle_todo_status(id).await?;
+
+        // 同步到数据库并获取更新的任务
+        let todos = self.state_manager.get_all_todos().await;
+        for todo in todos {
+            if todo.id == id {
+                let updates = TodoUpdate::new().with_status(todo.status);
+                let updated = self.database.update_todo(id, &updates).await?;
+                if let Some(updated_todo) = updated {
+                    let _ = self
+                        .enqueue_todo_issue_sync(&updated_todo, "todo_status_toggled")
+                        .await;
+                    let transition = StatusTransition {
+                        from,
+                        to: updated_todo.status.clone(),
+                    };
+                    return Ok(ToggleResult {
+                        todo: updated_todo,
+                        transition,
+                    });
+                }
+                break;
+            }
+        }
+
+        Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+    }
+
+    /// 显式设置任务状态
+    pub async fn set_todo_status(&self, id: &str, status: TodoStatus) -> Result<Todo> {
+        self.state_manager
+            .set_todo_status(id, status.clone())
+            .await?;
+        let updates = TodoUpdate::new().with_status(status);
+        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
+            let _ = self
+                .enqueue_todo_issue_sync(&updated_todo, "todo_status_set")
+                .await;
+            Ok(updated_todo)
+        } else {
+            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        }
+    }
+
+    /// 将逾期未完成任务的截止时间顺延到今天（本地日期的当天结束）
+    ///
+    /// 已完成任务和未逾期任务保持不变，返回被顺延的任务数。
+    pub async fn carry_over_todos(&mut self) -> Result<usize> {
+        let (day_start, day_end) = local_day_bounds(chrono::Local::now().date_naive());
+        let count = self
+            .database
+            .carry_over_due_dates(day_start, Some(day_end))
+            .await?;
+
+        if count > 0 {
+            let todos = self.database.get_all_todos().await?;
+            self.state_manager.bulk_update_todos(todos).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// 启动时的日常维护：任务顺延与自动归档，失败只记录日志
+    async fn run_daily_maintenance(&mut self, config: &UserConfig) {
+        if let Err(err) = self.run_daily_carry_over_if_due(config).await {
+            eprintln!("⚠️  每日任务顺延失败: {}", err);
+        }
+        if let Err(err) = self.auto_archive_done_todos(config).await {
+            eprintln!("⚠️  自动归档任务失败: {}", err);
+        }
+    }
+
+    /// 按 `auto_archive_done_after_days` 归档早已完成的任务，返回归档数量
+    async fn auto_archive_done_todos(&mut self, config: &UserConfig) -> Result<usize> {
+        let Some(days) = config.auto_archive_done_after_days else {
+            return Ok(0);
+        };
+
+        let count = self.database.auto_archive_done_todos(days).await?;
+        if count > 0 {
+            let todos = self.database.get_all_todos().await?;
+            self.state_manager.bulk_update_todos(todos).await?;
+        }
+        Ok(count)
+    }
+
+    /// 每天首次启动时执行一次任务顺延（需启用 `daily_carry_over`）
+    async fn run_daily_carry_over_if_due(&mut self, config: &UserConfig) -> Result<()> {
+        let today = chrono::Local::now().date_naive();
+        if !config.daily_carry_over || config.last_carry_over_date == Some(today) {
+            return Ok(());
+        }
+
+        self.carry_over_todos().await?;
+
+        let mut next = config.clone();
+        next.last_carry_over_date = Some(today);
+        self.database.save_user_config(&next).await?;
+        self.state_manager.set_user_config(next).await;
+        Ok(())
+    }
+
+    /// 关联待办与 GitHub Issue / Project
+    pub async fn link_todo_github(
+        &mut self,
+        id: &str,
+        issue_id: i64,
+        issue_number: i64,
+        project_id: i64,
+    ) -> Result<Todo> {
+        if issue_id <= 0 || issue_number <= 0 || project_id <= 0 {
+            return Err(AppError::Validation(
+                "issue_id, issue_number, project_id 必须为正整数".to_string(),
+            ));
+        }
+
+        let updates = TodoUpdate::new()
+            .with_github_issue_id(Some(issue_id))
+            .with_github_issue_number(Some(issue_number))
+            .with_github_project_id(Some(project_id));
+
+        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
+            self.state_manager.update_todo(id, updates).await?;
+            let payload = serde_json::json!({
+                "id": id,
+                "github_issue_id": issue_id,
+                "github_issue_number": issue_number,
+                "github_project_id": project_id,
+                "action": "link_github",
+            });
+            let _ = self.database.add_to_sync_queue("update", id, &payload).await;
+            let _ = self
+                .enqueue_todo_issue_sync(&updated_todo, "todo_github_linked")
+                .await;
+            Ok(updated_todo)
+        } else {
+            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        }
+    }
+
+    /// 清除待办的 GitHub 关联
+    pub async fn clear_todo_github_link(&mut self, id: &str) -> Result<Todo> {
+        let updates = TodoUpdate::new()
+            .with_github_issue_id(None)
+            .with_github_issue_number(None)
+            .with_github_project_id(None);
+
+        if let Some(updated_todo) = self.database.update_todo(id, &updates).await? {
+            self.state_manager.update_todo(id, updates).await?;
+            let payload = serde_json::json!({
+                "id": id,
+                "github_issue_id": serde_json::Value::Null,
+                "github_issue_number": serde_json::Value::Null,
+                "github_project_id": serde_json::Value::Null,
+                "action": "clear_github_link",
+            });
+            let _ = self.database.add_to_sync_queue("update", id, &payload).await;
+            Ok(updated_todo)
+        } else {
+            Err(AppError::NotFound(format!("Todo with id {} not found", id)))
+        }
+    }
+
+    /// 获取用户配置
+    pub async fn get_user_config(&self) -> Result<Option<UserConfig>> {
+        let config = self.database.load_user_config().await?;
+        Ok(config)
+    }
+
+    /// 保存用户配置
+    pub async fn save_user_config(&mut self, config: UserConfig) -> Result<()> {
+        let config = normalize_user_config(config);
+        let previous_config = self.database.load_user_config().await?;
+        let next_pomodoro_config = PomodoroConfig {
+            work_duration: config.pomodoro_work_duration,
+            short_break_duration: config.pomodoro_short_break_duration,
+            long_break_duration: config.pomodoro_long_break_duration,
+            cycles_until_long_break: config.pomodoro_cycles_until_long_break,
+            deep_work_duration: config.pomodoro_deep_work_duration,
+            auto_start_next: config.pomodoro_auto_start_next,
+            warn_before_seconds: config.pomodoro_warn_before_seconds,
+            phase_sequence: config.pomodoro_phase_sequence.clone(),
+            require_phase_ack: config.pomodoro_require_phase_ack,
+            break_reminders: config.pomodoro_break_reminders,
+            reminder_grace_secs: config.pomodoro_reminder_grace_secs,
+        };
+        next_pomodoro_config.validate()?;
+
+        self.database.save_user_config(&config).await?;
+
+        if let Err(err) = self.sync_runtime_config(&config).await {
+            if let Some(previous) = previous_config {
+                let _ = self.database.save_user_config(&previous).await;
+                let _ = self.sync_runtime_config(&previous).await;
+            }
+            return Err(err);
+        }
+
+        self.state_manager.set_user_config(config).await;
+        Ok(())
+    }
+
+    /// 更新番茄钟配置（仅运行时，不落盘）
+    pub async fn update_pomodoro_config(&self, config: PomodoroConfig) -> Result<()> {
+        self.pomodoro_service
+            .write()
+            .await
+            .update_config(config.clone())?;
+        self.state_manager.update_pomodoro_config(config).await?;
+        Ok(())
+    }
+
+    async fn sync_runtime_config(&mut self, config: &UserConfig) -> Result<()> {
+        let pomodoro_config = PomodoroConfig {
+            work_duration: config.pomodoro_work_duration,
+            short_break_duration: config.pomodoro_short_break_duration,
+            long_break_duration: config.pomodoro_long_break_duration,
+            cycles_until_long_break: config.pomodoro_cycles_until_long_break,
+            deep_work_duration: config.pomodoro_deep_work_duration,
+            auto_start_next: config.pomodoro_auto_start_next,
+            warn_before_seconds: config.pomodoro_warn_before_seconds,
+            phase_sequence: config.pomodoro_phase_sequence.clone(),
+            require_phase_ack: config.pomodoro_require_phase_ack,
+            break_reminders: config.pomodoro_break_reminders,
+            reminder_grace_secs: config.pomodoro_reminder_grace_secs,
+        };
+
+        self.pomodoro_service
+            .write()
+            .await
+            .set_idle_pause_after(config.idle_auto_pause_seconds.map(u64::from));
+        self.update_pomodoro_config(pomodoro_config).await
+    }
+
+    async fn enqueue_todo_issue_sync(&self, todo: &Todo, reason: &str) -> Result<()> {
+        if !has_linked_issue(todo) {
+            return Ok(());
+        }
+        let issue_number = match todo.github_issue_number {
+            Some(v) if v > 0 => v,
+            _ => return Ok(()),
+        };
+        let payload = serde_json::json!({
+            "id": todo.id,
+            "github_issue_number": issue_number,
+            "github_project_id": todo.github_project_id,
+            "title": todo.title,
+            "status": todo.status,
+            "project_status": map_todo_status_to_project_status(&todo.status),
+            "action": "sync_issue",
+            "reason": reason,
+        });
+        let _ = self
+            .database
+            .add_to_sync_queue("update", &todo.id, &payload)
+            .await?;
+        Ok(())
+    }
+
+    /// 执行 GitHub 同步流程
+    pub async fn run_github_sync(&mut self, dry_run: bool) -> Result<GithubSyncReport> {
+        let config = self
+            .database
+            .load_user_config()
+            .await?
+            .map(normalize_user_config)
+            .ok_or_else(|| AppError::NotFound("用户配置不存在".to_string()))?;
+
+        let target = build_github_sync_target(&config)?;
+        let pending = self.database.get_pending_sync_queue().await?;
+
+        let mut report = GithubSyncReport {
+            dry_run,
+            pending_items: pending.len(),
+            supported_items: 0,
+            unsupported_items: 0,
+            invalid_items: 0,
+            target,
+            errors: Vec::new(),
+        };
+        let sync_cursor = config.last_sync_cursor.clone();
+        let github_client = if dry_run {
+            None
+        } else {
+            Some(GithubSyncClient::new(
+                &config.github_token_encrypted,
+                &report.target.owner,
+                &report.target.repo,
+            )?)
+        };
+
+        for (queue_id, operation_type, _record_id, payload_raw) in pending {
+            let payload: serde_json::Value = match serde_json::from_str(&payload_raw) {
+                Ok(v) => v,
+                Err(err) => {
+                    report.invalid_items += 1;
+                    let message = format!("queue #{queue_id}: payload 解析失败: {err}");
+                    report.errors.push(message.clone());
+                    if !dry_run {
+                        let _ = self
+                            .database
+                            .mark_sync_queue_failed(queue_id, &message)
+                            .await;
+                    }
+                    continue;
+                }
+            };
+
+            if is_supported_sync_item(&operation_type, &payload) {
+                report.supported_items += 1;
+                if !dry_run {
+                    match self
+                        .execute_supported_sync_item(
+                            github_client.as_ref().expect("client initialized"),
+                            report.target.project_number,
+                            queue_id,
+                            &payload,
+                        )
+                        .await
+                    {
+                        Ok(_) => {
+                            let _ = self.database.mark_sync_queue_synced(queue_id).await;
+                        }
+                        Err(err) => {
+                            let message = err.to_string();
+                            report.errors.push(format!("queue #{queue_id}: {message}"));
+                            let _ = self
+                                .database
+                                .mark_sync_queue_failed(queue_id, &message)
+                                .await;
+                        }
+                    }
+                }
+            } else {
+                report.unsupported_items += 1;
+                if !dry_run {
+                    let message = format!(
+                        "queue #{queue_id}: 暂不支持的同步项 (operation_type={operation_type}, action={})",
+                        sync_action_from_payload(&payload).unwrap_or("unknown"),
+                    );
+                    report.errors.push(message.clone());
+                    let _ = self
+                        .database
+                        .mark_sync_queue_failed(queue_id, &message)
+                        .await;
+                }
+            }
+        }
+
+        if !dry_run {
+            if let Some(client) = github_client.as_ref() {
+                self.pull_remote_issue_updates(client, sync_cursor.as_deref(), &mut report)
+                    .await;
+            }
+            let _ = self.database.cleanup_sync_queue().await;
+            let _ = self
+                .persist_last_sync_cursor(&config, Utc::now().to_rfc3339())
+                .await;
+        }
+
+        Ok(report)
+    }
+
+    async fn execute_supported_sync_item(
+        &mut self,
+        client: &GithubSyncClient,
+        project_number: i64,
+        queue_id: i64,
+        payload: &serde_json::Value,
+    ) -> Result<()> {
+        let action = sync_action_from_payload(payload).unwrap_or_default();
+        match action {
+            "link_github" => {
+                let issue_number = sync_issue_number_from_payload(payload).ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "queue #{queue_id}: link_github 缺少 github_issue_number"
+                    ))
+                })?;
+                let _ = client.get_issue(issue_number).await?;
+                Ok(())
+            }
+            "clear_github_link" => Ok(()),
+            "sync_issue" => {
+                let issue_number = sync_issue_number_from_payload(payload).ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "queue #{queue_id}: sync_issue 缺少 github_issue_number"
+                    ))
+                })?;
+                let title = payload
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.trim())
+                    .filter(|v| !v.is_empty());
+                let status = payload
+                    .get("status")
+                    .and_then(|v| v.as_str())
+                    .and_then(parse_todo_status);
+                let _ = client.update_issue(issue_number, title, status.as_ref()).await?;
+                if sync_project_id_from_payload(payload).is_some() {
+                    if let Some(project_status) = sync_project_status_from_payload(payload) {
+                        client
+                            .update_project_item_status(
+                                project_number,
+                                issue_number,
+                                project_status,
+                            )
+                            .await?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(AppError::InvalidState(format!(
+                "queue #{queue_id}: 未知 action={action}"
+            ))),
+        }
+    }
+
+    async fn pull_remote_issue_updates(
+        &mut self,
+        client: &GithubSyncClient,
+        since: Option<&str>,
+        report: &mut GithubSyncReport,
+    ) {
+        let todos = match self.database.get_all_todos().await {
+            Ok(v) => v,
+            Err(err) => {
+                report.errors.push(format!("回拉阶段读取本地 todo 失败: {}", err));
+                return;
+            }
+        };
+        let recent_issues = match client.list_issues_since(since).await {
+            Ok(v) => v,
+            Err(err) => {
+                report.errors.push(format!("回拉阶段拉取 issue 列表失败: {}", err));
+                return;
+            }
+        };
+        let issue_map: std::collections::HashMap<i64, GithubIssue> = recent_issues
+            .into_iter()
+            .map(|issue| (issue.number, issue))
+            .collect();
+
+        for todo in todos {
+            if !has_linked_issue(&todo) {
+                continue;
+            }
+            let issue_number = match todo.github_issue_number {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let issue_result = if let Some(issue) = issue_map.get(&issue_number).cloned() {
+                Ok(issue)
+            } else if since.is_some() {
+                // 增量模式下未命中列表说明该 issue 未更新，无需请求详情。
+                continue;
+            } else {
+                client.get_issue(issue_number).await
+            };
+
+            match issue_result {
+                Ok(issue) => {
+                    let has_pending_local = self
+                        .database
+                        .has_pending_sync_for_record(&todo.id)
+                        .await
+                        .unwrap_or(false);
+                    if has_pending_local {
+                        report.errors.push(format!(
+                            "conflict: todo {} 存在待推送本地改动，跳过远端覆盖 (issue #{})",
+                            todo.id, issue_number
+                        ));
+                        continue;
+                    }
+                    if issue.updated_at <= todo.updated_at {
+                        continue;
+                    }
+                    if let Err(err) = self.apply_remote_issue_to_todo(&todo.id, &issue).await {
+                        report.errors.push(format!(
+                            "pull todo {} from issue #{} 失败: {}",
+                            todo.id, issue_number, err
+                        ));
+                    }
+                }
+                Err(err) => {
+                    report.errors.push(format!(
+                        "pull issue #{} 失败: {}",
+                        issue_number, err
+                    ));
+                }
+            }
+        }
+    }
+
+    async fn apply_remote_issue_to_todo(&mut self, todo_id: &str, issue: &GithubIssue) -> Result<()> {
+        let mut updates = TodoUpdate::new();
+        let mut changed = false;
+
+        let current = self.database.get_todo_by_id(todo_id).await?;
+        let current = match current {
+            Some(todo) => todo,
+            None => return Ok(()),
+        };
+
+        if current.title != issue.title {
+            updates = updates.with_title(issue.title.clone());
+            changed = true;
+        }
+
+        let next_status = if issue.state.eq_ignore_ascii_case("closed") {
+            Some(TodoStatus::Done)
+        } else {
+            Some(TodoStatus::Todo)
+        };
+        if let Some(next_status) = next_status {
+            if current.status != next_status {
+                updates = updates.with_status(next_status);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return Ok(());
+        }
+
+        if let Some(updated_todo) = self.database.update_todo(todo_id, &updates).await? {
+            self.state_manager.update_todo(todo_id, updates).await?;
+            let _ = self
+                .enqueue_todo_issue_sync(&updated_todo, "remote_pull_reconciled")
+                .await;
+        }
+
+        Ok(())
+    }
+
+    async fn persist_last_sync_cursor(&mut self, base_config: &UserConfig, cursor: String) -> Result<()> {
+        let mut next = base_config.clone();
+        next.last_sync_cursor = Some(cursor);
+        self.database.save_user_config(&next).await?;
+        self.state_manager.set_user_config(next).await;
+        Ok(())
+    }
+
+    /// 停止计时任务，暂停和重置前调用，避免遗留的计时循环继续扣减时间
+    async fn stop_pomodoro_tick_task(&self) -> Result<()> {
+        self.task_manager
+            .cancel(crate::async_utils::task_manager::TaskNames::POMODORO_TICK)
+            .await?;
+        Ok(())
+    }
+
+    /// 先停止已有的计时任务再重新启动，保证任何时候只有一个计时循环
+    async fn restart_pomodoro_tick_task(&self) -> Result<()> {
+        self.stop_pomodoro_tick_task().await?;
+        self.ensure_pomodoro_tick_task().await
+    }
+
+    async fn ensure_pomodoro_tick_task(&self) -> Result<()> {
+        let task_name = crate::async_utils::task_manager::TaskNames::POMODORO_TICK.to_string();
+        if self.task_manager.exists(&task_name).await {
+            return Ok(());
+        }
+
+        let state_manager = Arc::clone(&self.state_manager);
+        let pomodoro_service = Arc::clone(&self.pomodoro_service);
+        let database = Arc::clone(&self.database);
+        self.task_manager
+            .spawn(task_name, move || {
+                let state_manager = state_manager.clone();
+                let pomodoro_service = pomodoro_service.clone();
+                let database = database.clone();
+                async move {
+                    let tick_interval = pomodoro_service.read().await.tick_interval();
+                    let mut interval = tokio::time::interval(tick_interval);
+                    loop {
+                        interval.tick().await;
+
+                        let mut service = pomodoro_service.write().await;
+                        let event = service.tick().await;
+                        let updated_session = service.get_session().cloned();
+                        drop(service);
+
+                        if let Some(session) = updated_session {
+                            // 阶段切换时记录完成的阶段并保存进度，保证重启后从新阶段继续
+                            if record_completed_phase(&database, &session, event.as_ref(), 0).await {
+                                let _ = database.save_active_session(&session).await;
+                                announce_daily_pomodoro_goal(&database, &state_manager, event.as_ref())
+                                    .await;
+                            }
+                            let _ = state_manager.set_pomodoro_session(session).await;
+                        }
+                        remind_overdue_break(&state_manager, event.as_ref()).await;
+
+                        if let Some(event) = event {
+                            let _ = state_manager.send_event(
+                                crate::core::state::app_state::AppEvent::PomodoroEvent(event),
+                            );
+                        }
+                    }
+                }
+            })
+            .await
+    }
+
+    /// 启动会话进度自动保存任务（已存在时跳过）
+    async fn ensure_session_autosave_task(&self) -> Result<()> {
+        let task_name = crate::async_utils::task_manager::TaskNames::SESSION_AUTOSAVE.to_string();
+        if self.task_manager.exists(&task_name).await {
+            return Ok(());
+        }
+
+        let pomodoro_service = Arc::clone(&self.pomodoro_service);
+        let database = Arc::clone(&self.database);
+        self.task_manager
+            .spawn(task_name, move || async move {
+                let mut interval = tokio::time::interval(SESSION_AUTOSAVE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    autosave_running_session(&pomodoro_service, &database).await;
+                }
+            })
+            .await
+    }
+
+    /// 启动定期数据库维护任务（已存在时跳过），首次执行在一个间隔之后
+    async fn ensure_maintenance_task(&self) -> Result<()> {
+        let task_name = crate::async_utils::task_manager::TaskNames::MAINTENANCE.to_string();
+        if self.task_manager.exists(&task_name).await {
+            return Ok(());
+        }
+
+        let database = Arc::clone(&self.database);
+        let interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + MAINTENANCE_INTERVAL,
+            MAINTENANCE_INTERVAL,
+        );
+        self.task_manager
+            .spawn_interval(task_name, interval, move || {
+                let database = Arc::clone(&database);
+                async move { database.run_maintenance().await.map(|_| ()) }
+            })
+            .await
+    }
+
+    /// 立即执行一次数据库维护
+    pub async fn run_maintenance(&self) -> Result<MaintenanceSummary> {
+        self.database.run_maintenance().await
+    }
+
+    /// 立即保存运行中会话的进度（如应用退出前），返回是否写入
+    pub async fn autosave_session_state(&self) -> bool {
+        autosave_running_session(&self.pomodoro_service, &self.database).await
+    }
+
+    /// 获取应用版本
+    pub fn get_version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+}
+
+fn build_github_sync_target(config: &UserConfig) -> Result<GithubSyncTarget> {
+    let owner = config
+        .selected_project_owner
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let repo = config
+        .selected_project_repo
+        .clone()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let project_number = config.selected_project_number.unwrap_or_default();
+
+    if owner.is_empty() || repo.is_empty() || project_number <= 0 {
+        return Err(AppError::Validation(
+            "GitHub 项目配置不完整，请先在设置中配置 owner/repo/project number".to_string(),
+        ));
+    }
+
+    Ok(GithubSyncTarget {
+        owner,
+        repo,
+        project_number,
+    })
+}
+
+fn is_supported_sync_item(operation_type: &str, payload: &serde_json::Value) -> bool {
+    if operation_type != "update" {
+        return false;
+    }
+
+    matches!(
+        payload.get("action").and_then(|v| v.as_str()),
+        Some("link_github") | Some("clear_github_link") | Some("sync_issue")
+    )
+}
+
+fn sync_action_from_payload(payload: &serde_json::Value) -> Option<&str> {
+    payload.get("action").and_then(|v| v.as_str())
+}
+
+fn sync_issue_number_from_payload(payload: &serde_json::Value) -> Option<i64> {
+    payload.get("github_issue_number").and_then(|v| v.as_i64())
+}
+
+fn sync_project_status_from_payload(payload: &serde_json::Value) -> Option<&str> {
+    payload.get("project_status").and_then(|v| v.as_str())
+}
+
+fn sync_project_id_from_payload(payload: &serde_json::Value) -> Option<i64> {
+    payload.get("github_project_id").and_then(|v| v.as_i64())
+}
+
+fn parse_todo_status(status: &str) -> Option<TodoStatus> {
+    match status {
+        "todo" => Some(TodoStatus::Todo),
+        "in_progress" => Some(TodoStatus::InProgress),
+        "done" => Some(TodoStatus::Done),
+        _ => None,
+    }
+}
+
+fn has_linked_issue(todo: &Todo) -> bool {
+    todo.github_issue_number.is_some_and(|n| n > 0)
+}
+
+fn map_todo_status_to_project_status(status: &TodoStatus) -> &'static str {
+    match status {
+        TodoStatus::Todo => "Todo",
+        TodoStatus::InProgress => "In Progress",
+        TodoStatus::Done => "Done",
+    }
+}
+
+/// 保存运行中会话的进度，返回是否写入
+///
+/// 只在读锁下复制会话后立即释放，再写数据库，避免与每秒一次的计时循环争抢写锁；
+/// 暂停状态已在暂停时保存，这里跳过。
+async fn autosave_running_session(
+    pomodoro_service: &RwLock<PomodoroService>,
+    database: &Database,
+) -> bool {
+    let session = pomodoro_service.read().await.get_session().cloned();
+    match session {
+        Some(session) if session.is_running => {
+            if let Err(err) = database.save_active_session(&session).await {
+                eprintln!("⚠️  自动保存番茄钟进度失败: {}", err);
+                return false;
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 把计时器自然完成的阶段写入会话记录，返回事件是否为阶段完成
+///
+/// 只有 `PhaseCompleted` 会被记录，重置、跳过等操作不产生该事件。
+/// 周期数取事件携带的完成时计数，同一次阶段运行重复上报时由 `session_uuid` 去重。
+/// `overrun_seconds` 是确认前工作阶段超出设定时长的秒数，计入记录的时长。
+async fn record_completed_phase(
+    database: &Database,
+    session: &PomodoroSession,
+    event: Option<&PomodoroEvent>,
+    overrun_seconds: u64,
+) -> bool {
+    let Some(PomodoroEvent::PhaseCompleted {
+        completed_phase,
+        cycle_count,
+        session_uuid,
+        pause_counts,
+        interruptions,
+        ..
+    }) = event
+    else {
+        return false;
+    };
+
+    if let Err(err) = database
+        .record_pomodoro_session_with_pauses(
+            *completed_phase,
+            (session.config.get_duration(*completed_phase) + overrun_seconds) as u32,
+            *cycle_count,
+            session.focus_todo_id.as_deref(),
+            Some(session_uuid),
+            *pause_counts,
+            *interruptions,
+        )
+        .await
+    {
+        eprintln!("⚠️  记录番茄钟会话失败: {}", err);
+    }
+    true
+}
+
+/// 今日（本地日期）完成的工作番茄数
+async fn today_work_pomodoro_count(database: &Database) -> Result<u32> {
+    let (day_start, day_end) = local_day_bounds(chrono::Local::now().date_naive());
+    let sessions = database.get_work_sessions_between(day_start, day_end).await?;
+    Ok(u32::try_from(sessions.len()).unwrap_or(u32::MAX))
+}
+
+/// 工作阶段完成后今日完成数恰好达到每日番茄数目标时发出提示消息
+///
+/// 只在完成数等于目标的那一次提示，超过目标后的工作阶段不再重复提示。
+async fn announce_daily_pomodoro_goal(
+    database: &Database,
+    state_manager: &AppStateManager,
+    event: Option<&PomodoroEvent>,
+) {
+    let Some(PomodoroEvent::PhaseCompleted { completed_phase, .. }) = event else {
+        return;
+    };
+    if !completed_phase.is_work() {
+        return;
+    }
+
+    let goal = match database.load_user_config().await {
+        Ok(config) => config.and_then(|config| config.daily_pomodoro_goal),
+        Err(err) => {
+            eprintln!("⚠️  读取每日番茄目标失败: {}", err);
+            return;
+        }
+    };
+    let Some(goal) = goal.filter(|goal| *goal > 0) else {
+        return;
+    };
+
+    match today_work_pomodoro_count(database).await {
+        Ok(completed) if completed == goal => {
+            state_manager
+                .set_info_message(format!("今日已完成 {} 个番茄，达成每日目标", goal))
+                .await;
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("⚠️  统计今日番茄数失败: {}", err),
+    }
+}
+
+/// 等待确认的工作阶段超时后提示休息，每次阶段运行只会收到一次提醒事件
+async fn remind_overdue_break(state_manager: &AppStateManager, event: Option<&PomodoroEvent>) {
+    if let Some(PomodoroEvent::BreakReminder { .. }) = event {
+        state_manager
+            .set_info_message("工作阶段已超出计划时长，起来休息一下吧".to_string())
+            .await;
+    }
+}
+
+/// 格式化会话剩余时间，供托盘等只显示时钟的场景使用
+fn format_remaining(session: Option<&PomodoroSession>) -> String {
+    session
+        .map(PomodoroSession::formatted_time)
+        .unwrap_or_else(|| "--:--".to_string())
+}
+
+/// 计算截至 `today` 的连续专注天数
+///
+/// 今天已有记录时从今天往前数；今天还没有记录时从昨天往前数，避免一早打开就显示中断。
+pub(crate) fn current_streak(days: impl IntoIterator<Item = chrono::NaiveDate>, today: chrono::NaiveDate) -> u32 {
+    let days: std::collections::BTreeSet<_> = days.into_iter().collect();
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - chrono::Duration::days(1)
+    };
+
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= chrono::Duration::days(1);
+    }
+    streak
+}
+
+/// 按每日专注分钟数的中位数建议目标，四舍五入到 15 分钟且不低于 15 分钟
+///
+/// 没有专注的日子不计入；有效天数不足时返回 [`DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES`]。
+fn suggest_goal_from_daily_minutes(daily_minutes: impl IntoIterator<Item = u64>) -> u32 {
+    let mut minutes: Vec<u64> = daily_minutes.into_iter().filter(|m| *m > 0).collect();
+    if minutes.len() < MIN_ACTIVE_DAYS_FOR_GOAL {
+        return DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES;
+    }
+    minutes.sort_unstable();
+
+    let mid = minutes.len() / 2;
+    let median = if minutes.len().is_multiple_of(2) {
+        (minutes[mid - 1] + minutes[mid]) / 2
+    } else {
+        minutes[mid]
+    };
+    let step = SUGGESTED_GOAL_STEP_MINUTES;
+    let rounded = ((median + step / 2) / step * step).max(step);
+    u32::try_from(rounded).unwrap_or(u32::MAX)
+}
+
+/// 根据今日已完成的工作会话外推达成目标的时间
+///
+/// 以首末会话之间的平均间隔作为节奏、平均会话时长作为每次产出，
+/// 计算剩余目标还需要的会话数，再从最近一次会话（或现在，取较晚者）起按节奏外推。
+fn project_goal_completion_at(
+    sessions: &[(DateTime<Utc>, u32)],
+    goal_minutes: Option<u32>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let goal_seconds = u64::from(goal_minutes.filter(|goal| *goal > 0)?) * 60;
+    let achieved_seconds: u64 = sessions.iter().map(|(_, secs)| u64::from(*secs)).sum();
+    if achieved_seconds >= goal_seconds || sessions.len() < 2 {
+        return None;
+    }
+
+    let first = sessions.iter().map(|(at, _)| *at).min()?;
+    let last = sessions.iter().map(|(at, _)| *at).max()?;
+    let intervals = sessions.len() as i32 - 1;
+    let spacing = (last - first) / intervals;
+
+    let average_seconds = achieved_seconds / sessions.len() as u64;
+    if average_seconds == 0 {
+        return None;
+    }
+    let sessions_needed = (goal_seconds - achieved_seconds).div_ceil(average_seconds);
+
+    Some(last.max(now) + spacing * i32::try_from(sessions_needed).ok()?)
+}
+
+/// 将本地日期换算为 UTC 表示的当天起止时间
+pub(crate) fn local_day_bounds(date: chrono::NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let to_utc = |naive: chrono::NaiveDateTime| {
+        naive
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|| naive.and_utc())
+    };
+    let start = date.and_hms_opt(0, 0, 0).expect("midnight is always valid");
+    let end = date.and_hms_opt(23, 59, 59).expect("end of day is always valid");
+    (to_utc(start), to_utc(end))
+}
+
+fn normalize_optional_text(value: Option<String>) -> Option<String> {
+    value.and_then(|v| {
+        let trimmed = v.trim().to_string();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed)
+        }
+    })
+}
+
+fn normalize_user_config(mut config: UserConfig) -> UserConfig {
+    config.github_token_encrypted = config.github_token_encrypted.trim().to_string();
+    config.github_username = config.github_username.trim().to_string();
+    config.last_sync_cursor = normalize_optional_text(config.last_sync_cursor);
+    config.selected_project_owner = normalize_optional_text(config.selected_project_owner);
+    config.selected_project_repo = normalize_optional_text(config.selected_project_repo);
+    if config.selected_project_number.is_some_and(|n| n <= 0) {
+        config.selected_project_number = None;
+    }
+    config
+}
+
+impl Default for PomodoroAppManager {
+    fn default() -> Self {
+        // 返回一个标记为未初始化的实例
+        Self {
+            state_manager: Arc::new(AppStateManager::new()),
+            database: Arc::new(Database::init_uninitialized()),
+            task_manager: Arc::new(TaskManager::new()),
+            pomodoro_service: Arc::new(RwLock::new(
+                PomodoroService::new(PomodoroConfig::default()),
+            )),
+            initial_status_policy: InitialStatusPolicy::default(),
+            last_bulk_undo: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        current_streak, format_remaining, is_supported_sync_item, local_day_bounds,
+        announce_daily_pomodoro_goal, map_todo_status_to_project_status, remind_overdue_break,
+        project_goal_completion_at, record_completed_phase, suggest_goal_from_daily_minutes,
+        sync_action_from_payload, sync_project_id_from_payload, sync_project_status_from_payload,
+        DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES, FACTORY_RESET_CONFIRMATION,
+    };
+    use crate::core::todo::{StatusTransition, TodoStatus, TodoUpdate};
+    use crate::{
+        AppError, AppEvent, GoalProgress, InitStep, InitialStatusPolicy, LifetimeFocus, MoveDirection,
+        PomodoroAppManager, PomodoroConfig, PomodoroPhase, PomodoroService,
+    };
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    async fn create_test_manager() -> (tempfile::TempDir, PomodoroAppManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = PomodoroAppManager::with_database_path(&dir.path().join("test.sqlite"))
+            .await
+            .unwrap();
+        (dir, manager)
+    }
+
+    #[tokio::test]
+    async fn tick_loop_and_controls_share_one_service() {
+        let (_dir, manager) = create_test_manager().await;
+        let duration = manager.get_pomodoro_session().await.unwrap().unwrap().duration;
+
+        manager.start_pomodoro().await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(3), async {
+            while manager.get_pomodoro_session().await.unwrap().unwrap().remaining == duration {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("tick loop never ticked");
+        manager.pause_pomodoro().await.unwrap();
+
+        let paused = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert!(!paused.is_running);
+        assert!(paused.remaining < duration);
+        assert_eq!(
+            manager.state_manager.get_pomodoro_session().await.unwrap().remaining,
+            paused.remaining
+        );
+
+        // 暂停后计时循环已停止，之后的 tick 也不再修改会话
+        assert!(!manager
+            .task_manager
+            .exists(crate::async_utils::task_manager::TaskNames::POMODORO_TICK)
+            .await);
+        let later = tokio::time::Instant::now() + std::time::Duration::from_millis(1100);
+        manager.pomodoro_service.write().await.tick_at(later).await;
+        assert_eq!(
+            manager.get_pomodoro_session().await.unwrap().unwrap().remaining,
+            paused.remaining
+        );
+    }
+
+    #[tokio::test]
+    async fn tick_task_stops_on_pause_and_reset() {
+        let (_dir, manager) = create_test_manager().await;
+        let tick = crate::async_utils::task_manager::TaskNames::POMODORO_TICK;
+        let tick_tasks = || async {
+            manager
+                .task_manager
+                .list_tasks()
+                .await
+                .keys()
+                .filter(|name| name.as_str() == tick)
+                .count()
+        };
+
+        manager.start_pomodoro().await.unwrap();
+        assert_eq!(tick_tasks().await, 1);
+
+        manager.pause_pomodoro().await.unwrap();
+        assert!(!manager.task_manager.exists(tick).await);
+
+        manager.start_pomodoro().await.unwrap();
+        assert_eq!(tick_tasks().await, 1);
+
+        manager.reset_pomodoro().await.unwrap();
+        assert!(!manager.task_manager.exists(tick).await);
+
+        // 暂停后重新开始只剩一个计时循环；同一时刻的重复 tick 不会重复扣减
+        manager.start_pomodoro().await.unwrap();
+        assert_eq!(tick_tasks().await, 1);
+        let before = manager.get_pomodoro_session().await.unwrap().unwrap().remaining;
+        let later = tokio::time::Instant::now() + std::time::Duration::from_millis(2500);
+        for _ in 0..2 {
+            manager.pomodoro_service.write().await.tick_at(later).await;
+        }
+        let after = manager.get_pomodoro_session().await.unwrap().unwrap().remaining;
+        assert!((2..=3).contains(&(before - after)), "ticked {} seconds in 2.5s", before - after);
+
+        manager.task_manager.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn skipping_a_running_phase_keeps_tick_task_in_sync() {
+        let (_dir, manager) = create_test_manager().await;
+        let tick = crate::async_utils::task_manager::TaskNames::POMODORO_TICK;
+
+        manager.start_pomodoro().await.unwrap();
+        manager.skip_pomodoro_phase().await.unwrap();
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert!(!session.is_running);
+        assert!(!manager.task_manager.exists(tick).await);
+
+        manager
+            .update_pomodoro_config(PomodoroConfig {
+                auto_start_next: true,
+                ..PomodoroConfig::default()
+            })
+            .await
+            .unwrap();
+        manager.start_pomodoro().await.unwrap();
+        manager.skip_pomodoro_phase().await.unwrap();
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::Work);
+        assert!(session.is_running);
+        assert!(manager.task_manager.exists(tick).await);
+
+        manager.task_manager.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn factory_reset_requires_confirmation_and_clears_everything() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let todo = manager.create_todo("keep me?".to_string(), None).await.unwrap();
+        let tag = manager.database.create_tag("work", "#ff0000").await.unwrap();
+        manager.add_tag_to_todo(&todo.id, &tag.id).await.unwrap();
+        manager
+            .database
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, Some(&todo.id), None)
+            .await
+            .unwrap();
+        let mut config = manager.get_user_config().await.unwrap().unwrap_or_default();
+        config.github_username = "someone".to_string();
+        manager.save_user_config(config).await.unwrap();
+
+        assert!(matches!(
+            manager.factory_reset("yes").await,
+            Err(AppError::Validation(_))
+        ));
+        assert_eq!(manager.database.get_all_todos().await.unwrap().len(), 1);
+
+        manager.factory_reset(FACTORY_RESET_CONFIRMATION).await.unwrap();
+
+        assert!(manager.database.get_all_todos().await.unwrap().is_empty());
+        assert!(manager.database.get_deleted_todos().await.unwrap().is_empty());
+        assert!(manager.database.get_all_tags().await.unwrap().is_empty());
+        assert_eq!(manager.database.get_lifetime_focus_seconds().await.unwrap(), 0);
+        assert!(manager.state_manager.get_all_todos().await.is_empty());
+
+        let config = manager.get_user_config().await.unwrap().expect("default config row");
+        assert_eq!(config.github_username, "");
+        assert_eq!(
+            manager.get_pomodoro_session().await.unwrap().unwrap().cycle_count,
+            0
+        );
+
+        // 重置后可以正常继续使用
+        manager.create_todo("fresh".to_string(), None).await.unwrap();
+        assert_eq!(manager.database.get_all_todos().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn initialization_reports_progress_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let seen = std::sync::Mutex::new(Vec::new());
+        let manager = PomodoroAppManager::with_database_path_and_progress(
+            &dir.path().join("test.sqlite"),
+            |event| {
+                if let AppEvent::InitProgress { step, elapsed_ms } = event {
+                    seen.lock().unwrap().push((*step, *elapsed_ms));
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        let expected = vec![
+            InitStep::StateManagerReady,
+            InitStep::DatabaseReady,
+            InitStep::TaskManagerReady,
+            InitStep::ConfigLoaded,
+            InitStep::ServiceCreated,
+        ];
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.iter().map(|(step, _)| *step).collect::<Vec<_>>(), expected);
+        assert!(seen.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        let mut receiver = manager.state_manager.create_event_receiver().unwrap();
+        let mut queued = Vec::new();
+        while let Some(Ok(AppEvent::InitProgress { step, .. })) = receiver.try_recv() {
+            queued.push(step);
+        }
+        assert_eq!(queued, expected);
+    }
+
+    #[tokio::test]
+    async fn undo_restores_cleared_completed_todos() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let done = manager
+            .create_todo_with_status("done".to_string(), None, TodoStatus::Done)
+            .await
+            .unwrap();
+        let open = manager.create_todo("open".to_string(), None).await.unwrap();
+        let db = manager.get_database();
+        let tag_id = db.create_tag("work", "#007AFF").await.unwrap().id;
+        db.add_tag_to_todo(&done.id, &tag_id).await.unwrap();
+
+        assert_eq!(manager.clear_completed_todos().await.unwrap(), 1);
+        let remaining = manager.get_todos().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, open.id);
+        assert!(db.get_todo_by_id(&done.id).await.unwrap().is_none());
+
+        assert!(manager.undo_last_bulk_operation().await.unwrap());
+        let ids: Vec<_> = manager.get_todos().await.unwrap().into_iter().map(|t| t.id).collect();
+        assert!(ids.contains(&done.id) && ids.contains(&open.id));
+        let restored = db.get_todo_by_id(&done.id).await.unwrap().unwrap();
+        assert_eq!(restored.completed_at, done.completed_at);
+        assert_eq!(db.get_todo_tags(&done.id).await.unwrap().len(), 1);
+
+        assert!(!manager.undo_last_bulk_operation().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn undo_keeps_parent_links_of_cleared_parents() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let parent = manager
+            .create_todo_with_status("parent".to_string(), None, TodoStatus::Done)
+            .await
+            .unwrap();
+        let open_child = manager.create_subtask(&parent.id, "open".to_string(), None).await.unwrap();
+        let done_child = manager.create_subtask(&parent.id, "done".to_string(), None).await.unwrap();
+        manager
+            .update_todo(&done_child.id, TodoUpdate::new().with_status(TodoStatus::Done))
+            .await
+            .unwrap();
+
+        assert_eq!(manager.clear_completed_todos().await.unwrap(), 2);
+        let db = manager.get_database();
+        assert_eq!(db.get_todo_by_id(&open_child.id).await.unwrap().unwrap().parent_id, None);
+
+        assert!(manager.undo_last_bulk_operation().await.unwrap());
+        for child in [&open_child, &done_child] {
+            let stored = db.get_todo_by_id(&child.id).await.unwrap().unwrap();
+            assert_eq!(stored.parent_id.as_deref(), Some(parent.id.as_str()));
+        }
+        let children = manager.get_subtasks(&parent.id).await.unwrap();
+        assert_eq!(children.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn move_todo_swaps_with_neighbor() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let first = manager.create_todo("first".to_string(), None).await.unwrap();
+        let second = manager.create_todo("second".to_string(), None).await.unwrap();
+        let third = manager.create_todo("third".to_string(), None).await.unwrap();
+        let order = |todos: Vec<crate::Todo>| {
+            let mut todos = todos;
+            todos.sort_by_key(|t| t.sort_order);
+            todos.into_iter().map(|t| t.title).collect::<Vec<_>>()
+        };
+        // 新任务排在最前
+        assert_eq!(order(manager.get_todos().await.unwrap()), vec!["third", "second", "first"]);
+
+        assert!(manager.move_todo(&second.id, MoveDirection::Up).await.unwrap());
+        let expected = vec!["second", "third", "first"];
+        assert_eq!(order(manager.get_todos().await.unwrap()), expected);
+        assert_eq!(order(manager.get_database().get_all_todos().await.unwrap()), expected);
+
+        assert!(!manager.move_todo(&second.id, MoveDirection::Up).await.unwrap());
+        assert!(!manager.move_todo(&first.id, MoveDirection::Down).await.unwrap());
+        assert_eq!(order(manager.get_todos().await.unwrap()), expected);
+
+        assert!(manager.move_todo(&third.id, MoveDirection::Down).await.unwrap());
+        assert_eq!(order(manager.get_todos().await.unwrap()), vec!["second", "first", "third"]);
+        assert!(matches!(
+            manager.move_todo("missing", MoveDirection::Up).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn tag_changes_update_in_memory_todos() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let todo = manager.create_todo("tagged".to_string(), None).await.unwrap();
+        let tag = manager.get_database().create_tag("work", "#007AFF").await.unwrap();
+        let tags_of = |todos: Vec<crate::Todo>| todos[0].tags.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+
+        manager.add_tag_to_todo(&todo.id, &tag.id).await.unwrap();
+        assert_eq!(tags_of(manager.get_todos().await.unwrap()), vec![tag.id.clone()]);
+
+        manager.remove_tag_from_todo(&todo.id, &tag.id).await.unwrap();
+        assert!(tags_of(manager.get_todos().await.unwrap()).is_empty());
+
+        manager.add_tag_to_todo(&todo.id, &tag.id).await.unwrap();
+        manager.update_tag(&tag.id, Some("focus"), None).await.unwrap();
+        let todos = manager.get_todos().await.unwrap();
+        assert_eq!(todos[0].tags[0].name, "focus");
+        assert_eq!(todos[0].tags[0].color, "#007AFF");
+
+        assert!(manager.delete_tag(&tag.id).await.unwrap());
+        assert!(tags_of(manager.get_todos().await.unwrap()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn deleted_todo_moves_to_trash_and_restores() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let todo = manager.create_todo("trash me".to_string(), None).await.unwrap();
+        let db = manager.get_database();
+
+        manager.delete_todo(&todo.id).await.unwrap();
+        assert!(manager.get_todos().await.unwrap().is_empty());
+        assert!(db.get_todo_by_id(&todo.id).await.unwrap().is_none());
+        assert!(db.get_all_todos().await.unwrap().is_empty());
+        let trash = manager.get_deleted_todos().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, todo.id);
+        assert!(db.get_pending_sync_todos().await.unwrap().iter().any(|t| t.id == todo.id));
+
+        let restored = manager.restore_todo(&todo.id).await.unwrap();
+        assert_eq!(restored.title, "trash me");
+        assert_eq!(manager.get_todos().await.unwrap().len(), 1);
+        assert!(manager.get_deleted_todos().await.unwrap().is_empty());
+        assert!(matches!(
+            manager.restore_todo(&todo.id).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        manager.delete_todo(&todo.id).await.unwrap();
+        manager.purge_deleted_todo(&todo.id).await.unwrap();
+        assert!(manager.get_deleted_todos().await.unwrap().is_empty());
+        assert!(matches!(
+            manager.purge_deleted_todo(&todo.id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn initial_payload_matches_individual_getters() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager.create_todo("first".to_string(), None).await.unwrap();
+        manager
+            .create_todo_with_status("second".to_string(), None, TodoStatus::Done)
+            .await
+            .unwrap();
+        manager.get_database().create_tag("work", "#007AFF").await.unwrap();
+
+        let payload = manager.get_initial_payload().await.unwrap();
+
+        let todo_ids = |todos: &[crate::Todo]| {
+            let mut ids: Vec<_> = todos.iter().map(|t| t.id.clone()).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(todo_ids(&payload.todos), todo_ids(&manager.get_todos().await.unwrap()));
+        assert_eq!(payload.stats.total, 2);
+        assert_eq!(payload.stats.done, 1);
+        assert_eq!(
+            payload.pomodoro_session.map(|s| s.remaining),
+            manager.get_pomodoro_session().await.unwrap().map(|s| s.remaining)
+        );
+        assert_eq!(
+            payload.user_config.pomodoro_work_duration,
+            crate::UserConfig::default().pomodoro_work_duration
+        );
+        assert_eq!(payload.tags, manager.get_database().get_all_tags().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn import_dry_run_matches_real_import() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let kept = manager.create_todo("kept".to_string(), None).await.unwrap();
+        let edited = manager.create_todo("edited".to_string(), None).await.unwrap();
+        let local = manager.create_todo("local".to_string(), None).await.unwrap();
+
+        let mut backup_edited = edited.clone();
+        backup_edited.title = "edited in backup".to_string();
+        backup_edited.updated_at = edited.updated_at + chrono::Duration::minutes(5);
+        let mut backup_local = local.clone();
+        backup_local.title = "older copy".to_string();
+        backup_local.updated_at = local.updated_at - chrono::Duration::minutes(5);
+        let fresh = crate::Todo::new("fresh".to_string(), None);
+        let backup = crate::TodoExport::export(&[
+            kept.clone(),
+            backup_edited.clone(),
+            backup_local,
+            fresh.clone(),
+        ]);
+
+        let plan = manager.import_all_dry_run(&backup).await.unwrap();
+        assert_eq!(manager.get_database().get_all_todos().await.unwrap().len(), 3);
+        assert_eq!(plan.to_create, vec![fresh.id.clone()]);
+        assert_eq!(plan.to_update, vec![edited.id.clone()]);
+        assert_eq!(plan.to_skip, vec![kept.id.clone()]);
+        assert_eq!(plan.conflicts, vec![local.id.clone()]);
+
+        let applied = manager.import_all(&backup).await.unwrap();
+        assert_eq!(applied, plan);
+
+        let db = manager.get_database();
+        assert!(db.get_todo_by_id(&fresh.id).await.unwrap().is_some());
+        let stored_edited = db.get_todo_by_id(&edited.id).await.unwrap().unwrap();
+        assert_eq!(stored_edited.title, "edited in backup");
+        let stored_local = db.get_todo_by_id(&local.id).await.unwrap().unwrap();
+        assert_eq!(stored_local.title, "local");
+        assert_eq!(manager.get_todos().await.unwrap().len(), 4);
+
+        let replay = manager.import_all_dry_run(&backup).await.unwrap();
+        assert!(replay.to_create.is_empty() && replay.to_update.is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_todos_replaces_or_merges_preserving_identity() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let existing = manager.create_todo("existing".to_string(), None).await.unwrap();
+
+        let mut done = crate::Todo::new("done in backup".to_string(), None);
+        done.update_status(TodoStatus::Done);
+        done.created_at -= chrono::Duration::days(3);
+        let mut clashing = existing.clone();
+        clashing.title = "backup copy".to_string();
+        let json = serde_json::to_string(&crate::TodoExport::export(&[done.clone(), clashing]))
+            .unwrap();
+
+        // 合并时跳过 id 已存在的任务
+        let export = crate::TodoExport::import(&json).unwrap();
+        assert_eq!(manager.import_todos(export, true).await.unwrap(), 1);
+        let db = manager.get_database();
+        let restored = db.get_todo_by_id(&done.id).await.unwrap().unwrap();
+        assert_eq!(restored.status, TodoStatus::Done);
+        assert_eq!(restored.created_at, done.created_at);
+        assert_eq!(db.get_todo_by_id(&existing.id).await.unwrap().unwrap().title, "existing");
+        assert_eq!(manager.get_todos().await.unwrap().len(), 2);
+
+        // 替换时先清空现有任务
+        let extra = manager.create_todo("extra".to_string(), None).await.unwrap();
+        let export = crate::TodoExport::import(&json).unwrap();
+        assert_eq!(manager.import_todos(export, false).await.unwrap(), 2);
+        assert!(db.get_todo_by_id(&extra.id).await.unwrap().is_none());
+        assert_eq!(db.get_todo_by_id(&existing.id).await.unwrap().unwrap().title, "backup copy");
+        assert_eq!(manager.get_todos().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn import_remaps_clashing_tag_ids() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let db = manager.get_database();
+        let local_todo = manager.create_todo("local".to_string(), None).await.unwrap();
+        let home_id = db.create_tag("home", "#00FF00").await.unwrap().id;
+        db.add_tag_to_todo(&local_todo.id, &home_id).await.unwrap();
+
+        let imported = crate::Todo::new("imported".to_string(), None);
+        let mut clashing = crate::core::todo::Tag::new("work".to_string(), "#FF0000".to_string());
+        clashing.id = home_id.clone();
+        let backup = crate::TodoExport::export(std::slice::from_ref(&imported))
+            .with_tags(vec![clashing], vec![(imported.id.clone(), home_id.clone())]);
+
+        manager.import_all(&backup).await.unwrap();
+
+        let mut names: Vec<_> = db
+            .get_all_tags()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["home", "work"]);
+
+        let local_tags = db.get_todo_tags(&local_todo.id).await.unwrap();
+        assert_eq!(local_tags.len(), 1);
+        assert_eq!(local_tags[0].name, "home");
+        let imported_tags = db.get_todo_tags(&imported.id).await.unwrap();
+        assert_eq!(imported_tags.len(), 1);
+        assert_eq!(imported_tags[0].name, "work");
+        assert_ne!(imported_tags[0].id, home_id);
+    }
+
+    #[tokio::test]
+    async fn create_todo_as_done_records_completed_at() {
+        let (_dir, mut manager) = create_test_manager().await;
+
+        let todo = manager
+            .create_todo_with_status("done".to_string(), None, TodoStatus::Done)
+            .await
+            .unwrap();
+
+        assert_eq!(todo.status, TodoStatus::Done);
+        assert!(todo.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn blocking_policy_rejects_non_todo_initial_status() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager.set_initial_status_policy(InitialStatusPolicy::Block);
+
+        let result = manager
+            .create_todo_with_status("done".to_string(), None, TodoStatus::Done)
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        assert!(manager.get_todos().await.unwrap().is_empty());
+
+        let todo = manager
+            .create_todo_with_status("todo".to_string(), None, TodoStatus::Todo)
+            .await
+            .unwrap();
+        assert_eq!(todo.completed_at, None);
+    }
+
+    #[test]
+    fn supported_sync_item_requires_update_and_known_action() {
+        let payload = serde_json::json!({ "action": "link_github" });
+        assert!(is_supported_sync_item("update", &payload));
+        let payload = serde_json::json!({ "action": "sync_issue" });
+        assert!(is_supported_sync_item("update", &payload));
+        assert!(!is_supported_sync_item("create", &payload));
+    }
+
+    #[test]
+    fn sync_action_from_payload_reads_action_field() {
+        let payload = serde_json::json!({ "action": "clear_github_link" });
+        assert_eq!(sync_action_from_payload(&payload), Some("clear_github_link"));
+        let invalid = serde_json::json!({ "foo": "bar" });
+        assert_eq!(sync_action_from_payload(&invalid), None);
+    }
+
+    #[test]
+    fn todo_status_maps_to_project_status() {
+        assert_eq!(map_todo_status_to_project_status(&TodoStatus::Todo), "Todo");
+        assert_eq!(
+            map_todo_status_to_project_status(&TodoStatus::InProgress),
+            "In Progress"
+        );
+        assert_eq!(map_todo_status_to_project_status(&TodoStatus::Done), "Done");
+    }
+
+    #[tokio::test]
+    async fn todo_reads_proceed_while_timer_is_busy() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager.create_todo("读取".to_string(), None).await.unwrap();
+        let manager = Arc::new(RwLock::new(manager));
+
+        // 模拟计时器正忙：持有番茄钟服务的写锁，让 start_pomodoro 挂起
+        let service_lock = Arc::clone(&manager.read().await.pomodoro_service);
+        let busy = service_lock.write().await;
+        let starting = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            async move { manager.read().await.start_pomodoro().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!starting.is_finished());
+
+        let todos = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            manager.read().await.get_todos().await
+        })
+        .await
+        .expect("todo read should not wait for the timer")
+        .unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title, "读取");
+
+        drop(busy);
+        starting.await.unwrap().unwrap();
+        let session = manager.read().await.get_pomodoro_session().await.unwrap();
+        assert!(session.unwrap().is_running);
+    }
+
+    #[tokio::test]
+    async fn current_phase_color_uses_saved_override() {
+        let (_dir, mut manager) = create_test_manager().await;
+        assert_eq!(manager.get_current_phase_color().await.unwrap(), "#e74c3c");
+
+        let config = crate::UserConfig {
+            work_color: Some("#22c55e".to_string()),
+            ..Default::default()
+        };
+        manager.save_user_config(config).await.unwrap();
+        assert_eq!(manager.get_current_phase_color().await.unwrap(), "#22c55e");
+
+        manager.pomodoro_service.write().await.skip().unwrap();
+        assert_eq!(
+            manager.get_current_phase_color().await.unwrap(),
+            PomodoroPhase::ShortBreak.color()
+        );
+    }
+
+    #[tokio::test]
+    async fn end_break_now_respects_enforced_minimum() {
+        let (_dir, mut manager) = create_test_manager().await;
+        assert!(matches!(
+            manager.end_break_now().await,
+            Err(AppError::InvalidState(_))
+        ));
+
+        let config = crate::UserConfig {
+            enforced_break_seconds: Some(120),
+            ..Default::default()
+        };
+        manager.save_user_config(config).await.unwrap();
+        {
+            let mut service = manager.pomodoro_service.write().await;
+            service.skip().unwrap();
+            service.start().unwrap();
+            service.get_session_mut().unwrap().remaining -= 60;
+        }
+        assert!(matches!(
+            manager.end_break_now().await,
+            Err(AppError::InvalidState(_))
+        ));
+
+        manager.pomodoro_service.write().await.get_session_mut().unwrap().remaining -= 60;
+        manager.end_break_now().await.unwrap();
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::Work);
+        assert!(session.is_running);
+    }
+
+    #[tokio::test]
+    async fn formatted_remaining_tracks_running_session() {
+        let (_dir, manager) = create_test_manager().await;
+        {
+            let mut service = manager.pomodoro_service.write().await;
+            service.start().unwrap();
+            service.get_session_mut().unwrap().remaining = 599;
+        }
+
+        assert_eq!(manager.get_formatted_remaining().await.unwrap(), "09:59");
+    }
+
+    #[tokio::test]
+    async fn paused_session_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.sqlite");
+        {
+            let manager = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+            let session = {
+                let mut service = manager.pomodoro_service.write().await;
+                service.get_session_mut().unwrap().restore(PomodoroPhase::Work, 600, 2);
+                service.get_session().cloned().unwrap()
+            };
+            manager.persist_session_state(&session).await;
+        }
+
+        let restarted = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+        let session = restarted.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::Work);
+        assert_eq!(session.remaining, 600);
+        assert_eq!(session.duration, session.config.work_duration);
+        assert_eq!(session.cycle_count, 2);
+        assert!(!session.is_running);
+    }
+
+    #[test]
+    fn goal_projection_extrapolates_from_todays_pace() {
+        let start = chrono::Utc::now();
+        let sessions = [(start, 1500), (start + chrono::Duration::minutes(30), 1500)];
+
+        // 已完成 50 分钟，目标 100 分钟：还需两次会话，每次间隔 30 分钟
+        let projected = project_goal_completion_at(&sessions, Some(100), start).unwrap();
+        assert_eq!(projected, start + chrono::Duration::minutes(90));
+        assert!(projected > start);
+
+        assert_eq!(project_goal_completion_at(&sessions[..1], Some(100), start), None);
+        assert_eq!(project_goal_completion_at(&sessions, None, start), None);
+    }
+
+    #[tokio::test]
+    async fn goal_projection_is_none_once_goal_is_met() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .save_user_config(crate::UserConfig {
+                daily_focus_goal_minutes: Some(40),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            manager
+                .database
+                .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(manager.project_goal_completion().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn toggle_reports_each_transition_in_the_cycle() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let id = manager.create_todo("循环".to_string(), None).await.unwrap().id;
+
+        for (from, to) in [
+            (TodoStatus::Todo, TodoStatus::InProgress),
+            (TodoStatus::InProgress, TodoStatus::Done),
+            (TodoStatus::Done, TodoStatus::Todo),
+        ] {
+            let result = manager.toggle_todo_status(&id).await.unwrap();
+            assert_eq!(result.transition, StatusTransition { from, to: to.clone() });
+            assert_eq!(result.todo.status, to);
+        }
+
+        assert!(matches!(
+            manager.toggle_todo_status("missing").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn import_markdown_creates_checklist_items() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager.set_initial_status_policy(InitialStatusPolicy::Block);
+
+        let md = "## 购物\n- [ ] 买牛奶\n- [x] 买面包\n随手记一句\n";
+        let created = manager.import_markdown(md).await.unwrap();
+        assert_eq!(created.len(), 2);
+        assert_eq!(created[0].status, TodoStatus::Todo);
+        assert_eq!(created[1].status, TodoStatus::Done);
+        assert!(created[1].completed_at.is_some());
+        assert_eq!(manager.get_todos().await.unwrap().len(), 2);
+
+        let too_long = format!("- [ ] ok\n- [ ] {}\n", "x".repeat(201));
+        assert!(manager.import_markdown(&too_long).await.is_err());
+        assert_eq!(manager.get_todos().await.unwrap().len(), 2);
+    }
+
+    async fn create_focused_todo(manager: &mut PomodoroAppManager, sync_focus_status: bool) -> String {
+        manager
+            .save_user_config(crate::UserConfig {
+                sync_focus_status,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let todo = manager
+            .create_todo("focus".to_string(), None)
+            .await
+            .unwrap();
+        manager.set_focus_todo(Some(todo.id.clone())).await.unwrap();
+        todo.id
+    }
+
+    async fn todo_status(manager: &PomodoroAppManager, id: &str) -> TodoStatus {
+        manager
+            .get_todos()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|t| t.id == id)
+            .unwrap()
+            .status
+    }
+
+    #[tokio::test]
+    async fn focus_todo_follows_timer_when_sync_enabled() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let id = create_focused_todo(&mut manager, true).await;
+
+        manager.start_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::InProgress);
+
+        manager.pause_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::Todo);
+
+        manager.start_pomodoro().await.unwrap();
+        manager.abandon_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::Todo);
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.focus_todo_id, None);
+        assert!(!session.is_running);
+
+        // 已完成的任务不会被改回
+        manager.set_focus_todo(Some(id.clone())).await.unwrap();
+        manager.set_todo_status(&id, TodoStatus::Done).await.unwrap();
+        manager.start_pomodoro().await.unwrap();
+        manager.pause_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::Done);
+    }
+
+    #[tokio::test]
+    async fn focus_todo_untouched_when_sync_disabled() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let id = create_focused_todo(&mut manager, false).await;
+
+        manager.start_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::Todo);
+        manager.set_todo_status(&id, TodoStatus::InProgress).await.unwrap();
+        manager.pause_pomodoro().await.unwrap();
+        assert_eq!(todo_status(&manager, &id).await, TodoStatus::InProgress);
+    }
+
+    #[tokio::test]
+    async fn backlog_estimate_uses_average_focus_of_completed_todos() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let db = manager.get_database().clone();
+        for (title, focus_seconds) in [("short", 1200), ("long", 3600)] {
+            let todo = manager.create_todo(title.to_string(), None).await.unwrap();
+            db.record_pomodoro_session(PomodoroPhase::Work, focus_seconds, 1, Some(&todo.id), None)
+                .await
+                .unwrap();
+            manager.set_todo_status(&todo.id, TodoStatus::Done).await.unwrap();
+        }
+
+        let estimated = manager.create_todo("estimated".to_string(), None).await.unwrap();
+        manager
+            .update_todo(
+                &estimated.id,
+                crate::TodoUpdate::new().with_estimate_minutes(Some(15)),
+            )
+            .await
+            .unwrap();
+        manager.create_todo("unestimated".to_string(), None).await.unwrap();
+
+        // 平均专注时长 (20 + 60) / 2 = 40 分钟
+        let backlog = manager.estimate_backlog_time().await.unwrap();
+        assert_eq!(backlog.estimated_minutes, 15 + 40);
+        assert_eq!(backlog.todos_with_estimate, 1);
+        assert_eq!(backlog.todos_without, 1);
+    }
+
+    #[tokio::test]
+    async fn backlog_estimate_without_history_uses_one_pomodoro() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager.create_todo("unestimated".to_string(), None).await.unwrap();
+
+        let backlog = manager.estimate_backlog_time().await.unwrap();
+        assert_eq!(backlog.estimated_minutes, 25);
+    }
+
+    #[test]
+    fn lifetime_focus_splits_hours_and_minutes() {
+        assert_eq!(
+            LifetimeFocus::from_seconds(2 * 3600 + 5 * 60 + 59),
+            LifetimeFocus { hours: 2, minutes: 5 }
+        );
+        assert_eq!(LifetimeFocus::from_seconds(0), LifetimeFocus { hours: 0, minutes: 0 });
+    }
+
+    #[test]
+    fn goal_progress_percent_is_clamped() {
+        let under = GoalProgress::new(30, Some(120));
+        assert_eq!((under.percent, under.reached), (25, false));
+
+        let exact = GoalProgress::new(120, Some(120));
+        assert_eq!((exact.percent, exact.reached), (100, true));
+
+        let over = GoalProgress::new(300, Some(120));
+        assert_eq!((over.percent, over.reached), (100, true));
+
+        let unset = GoalProgress::new(45, None);
+        assert_eq!(
+            unset,
+            GoalProgress {
+                achieved_minutes: 45,
+                goal_minutes: 0,
+                percent: 0,
+                reached: false,
             }
-        }
+        );
     }
 
-    async fn apply_remote_issue_to_todo(&mut self, todo_id: &str, issue: &GithubIssue) -> Result<()> {
-        let mut updates = TodoUpdate::new();
-        let mut changed = false;
+    #[tokio::test]
+    async fn goal_progress_counts_todays_work_sessions() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .save_user_config(crate::UserConfig {
+                daily_focus_goal_minutes: Some(100),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        manager
+            .database
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, None)
+            .await
+            .unwrap();
+        manager
+            .database
+            .record_pomodoro_session(PomodoroPhase::ShortBreak, 300, 1, None, None)
+            .await
+            .unwrap();
 
-        let current = self.database.get_todo_by_id(todo_id).await?;
-        let current = match current {
-            Some(todo) => todo,
-            None => return Ok(()),
-        };
+        let progress = manager.get_goal_progress().await.unwrap();
+        assert_eq!(progress.achieved_minutes, 25);
+        assert_eq!(progress.goal_minutes, 100);
+        assert_eq!(progress.percent, 25);
+        assert!(!progress.reached);
+    }
 
-        if current.title != issue.title {
-            updates = updates.with_title(issue.title.clone());
-            changed = true;
+    #[tokio::test]
+    async fn daily_goal_met_compares_today_focus_with_goal() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .database
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, None)
+            .await
+            .unwrap();
+
+        // 未设置目标
+        assert!(!manager.is_daily_goal_met().await.unwrap());
+
+        for (goal, met) in [(30, false), (25, true), (20, true)] {
+            manager
+                .save_user_config(crate::UserConfig {
+                    daily_focus_goal_minutes: Some(goal),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(manager.is_daily_goal_met().await.unwrap(), met, "goal {}", goal);
         }
+    }
 
-        let next_status = if issue.state.eq_ignore_ascii_case("closed") {
-            Some(TodoStatus::Done)
-        } else {
-            Some(TodoStatus::Todo)
-        };
-        if let Some(next_status) = next_status {
-            if current.status != next_status {
-                updates = updates.with_status(next_status);
-                changed = true;
-            }
+    #[tokio::test]
+    async fn goal_progress_rounds_summed_seconds_by_config() {
+        let (_dir, mut manager) = create_test_manager().await;
+        for cycle in 1..=3 {
+            manager
+                .database
+                .record_pomodoro_session(PomodoroPhase::Work, 90, cycle, None, None)
+                .await
+                .unwrap();
         }
 
-        if !changed {
-            return Ok(());
-        }
+        // 270 秒 = 4.5 分钟，默认四舍五入为 5（逐条取整会得到 3 或 6）
+        assert_eq!(manager.get_goal_progress().await.unwrap().achieved_minutes, 5);
 
-        if let Some(updated_todo) = self.database.update_todo(todo_id, &updates).await? {
-            self.state_manager.update_todo(todo_id, updates).await?;
-            let _ = self
-                .enqueue_todo_issue_sync(&updated_todo, "remote_pull_reconciled")
-                .await;
-        }
+        manager
+            .save_user_config(crate::UserConfig {
+                focus_minute_rounding: crate::MinuteRounding::Floor,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(manager.get_goal_progress().await.unwrap().achieved_minutes, 4);
+        assert_eq!(
+            manager.get_lifetime_focus().await.unwrap(),
+            LifetimeFocus { hours: 0, minutes: 4 }
+        );
+    }
 
-        Ok(())
+    #[test]
+    fn current_streak_counts_consecutive_days() {
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        assert_eq!(current_streak([day(12), day(13), day(14)], day(14)), 3);
+        // 今天还没有记录，昨天之前的连续天数仍然有效
+        assert_eq!(current_streak([day(12), day(13)], day(14)), 2);
+        assert_eq!(current_streak([day(10), day(12), day(14), day(14)], day(14)), 1);
+        assert_eq!(current_streak([day(11), day(12)], day(14)), 0);
+        assert_eq!(current_streak([], day(14)), 0);
     }
 
-    async fn persist_last_sync_cursor(&mut self, base_config: &UserConfig, cursor: String) -> Result<()> {
-        let mut next = base_config.clone();
-        next.last_sync_cursor = Some(cursor);
-        self.database.save_user_config(&next).await?;
-        self.state_manager.set_user_config(next).await;
-        Ok(())
+    #[tokio::test]
+    async fn pomodoro_config_view_follows_runtime_config() {
+        let (_dir, manager) = create_test_manager().await;
+        let view = manager.get_pomodoro_config_view().await.unwrap();
+        assert_eq!(view, crate::PomodoroConfigView::from(&PomodoroConfig::default()));
+
+        let custom = PomodoroConfig::default().with_work_duration(45).with_short_break(10);
+        manager.update_pomodoro_config(custom.clone()).await.unwrap();
+        let view = manager.get_pomodoro_config_view().await.unwrap();
+        assert_eq!(view.work.seconds, 2700);
+        assert_eq!(view.work.minutes, 45.0);
+        assert_eq!(view.short_break.minutes, view.short_break.seconds as f64 / 60.0);
+        assert_eq!(view, crate::PomodoroConfigView::from(&custom));
     }
 
-    async fn ensure_pomodoro_tick_task(&self) -> Result<()> {
-        let task_name = crate::async_utils::task_manager::TaskNames::POMODORO_TICK.to_string();
-        if self.task_manager.exists(&task_name).await {
-            return Ok(());
-        }
+    #[test]
+    fn suggested_goal_uses_median_of_active_days() {
+        assert_eq!(suggest_goal_from_daily_minutes([30, 100, 200]), 105);
+        // 偶数天取中间两天的平均值，未专注的日子不计入
+        assert_eq!(suggest_goal_from_daily_minutes([0, 50, 70, 90, 400, 0]), 75);
+        assert_eq!(suggest_goal_from_daily_minutes([5, 6, 7]), 15);
+        assert_eq!(
+            suggest_goal_from_daily_minutes([60, 0, 90]),
+            DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES
+        );
+        assert_eq!(
+            suggest_goal_from_daily_minutes([]),
+            DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES
+        );
+    }
 
-        let state_manager = Arc::clone(&self.state_manager);
-        let pomodoro_service = Arc::clone(&self.pomodoro_service);
-        self.task_manager
-            .spawn(task_name, move || {
-                let state_manager = state_manager.clone();
-                let pomodoro_service = pomodoro_service.clone();
-                async move {
-                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
-                    loop {
-                        interval.tick().await;
+    #[tokio::test]
+    async fn suggested_goal_ignores_today_and_defaults_without_history() {
+        let (_dir, manager) = create_test_manager().await;
+        for cycle in 1..=3 {
+            manager
+                .database
+                .record_pomodoro_session(PomodoroPhase::Work, 3600, cycle, None, None)
+                .await
+                .unwrap();
+        }
 
-                        let mut service = pomodoro_service.write().await;
-                        let event = service.tick().await;
-                        let updated_session = service.get_session().cloned();
-                        drop(service);
+        for lookback in [0, 14] {
+            assert_eq!(
+                manager.suggest_daily_goal(lookback).await.unwrap(),
+                DEFAULT_SUGGESTED_DAILY_GOAL_MINUTES
+            );
+        }
+    }
 
-                        if let Some(session) = updated_session {
-                            let _ = state_manager.set_pomodoro_session(session).await;
-                        }
+    #[tokio::test]
+    async fn daily_pomodoro_goal_counts_recorded_work_and_announces_once() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .save_user_config(crate::UserConfig {
+                daily_pomodoro_goal: Some(2),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(manager.get_daily_goal_progress().await.unwrap(), (0, Some(2)));
+
+        let mut events = manager.create_event_receiver().unwrap();
+        // 只有达到目标的那一个工作阶段发出提示
+        for expected_announcements in [0, 1, 0] {
+            let mut service = manager.pomodoro_service.write().await;
+            service.start().unwrap();
+            service.get_session_mut().unwrap().remaining = 0;
+            let event = service.tick().await;
+            let session = service.get_session().unwrap().clone();
+            assert!(record_completed_phase(&manager.database, &session, event.as_ref(), 0).await);
+            announce_daily_pomodoro_goal(&manager.database, &manager.state_manager, event.as_ref())
+                .await;
+            service.pause().unwrap();
+            service.skip().unwrap();
 
-                        if let Some(event) = event {
-                            let _ = state_manager.send_event(
-                                crate::core::state::app_state::AppEvent::PomodoroEvent(event),
-                            );
-                        }
-                    }
+            let mut announcements = 0;
+            while let Some(Ok(event)) = events.try_recv() {
+                if matches!(event, AppEvent::InfoMessage(_)) {
+                    announcements += 1;
                 }
+            }
+            assert_eq!(announcements, expected_announcements);
+        }
+        assert_eq!(manager.get_daily_goal_progress().await.unwrap(), (3, Some(2)));
+    }
+
+    #[tokio::test]
+    async fn overrun_work_phase_sends_one_break_reminder_and_waits_for_ack() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .save_user_config(crate::UserConfig {
+                pomodoro_require_phase_ack: true,
+                pomodoro_break_reminders: true,
+                pomodoro_reminder_grace_secs: 30,
+                ..Default::default()
             })
             .await
-    }
+            .unwrap();
+
+        let mut events = manager.create_event_receiver().unwrap();
+        {
+            let mut service = manager.pomodoro_service.write().await;
+            service.start().unwrap();
+            // 工作阶段到时后继续计时，超时 10 秒、40 秒、70 秒
+            for elapsed_ms in [1_510_000, 30_000, 30_000] {
+                let event = service.tick_elapsed(elapsed_ms).await;
+                remind_overdue_break(&manager.state_manager, event.as_ref()).await;
+            }
+            assert!(service.get_session().unwrap().awaiting_phase_ack());
+        }
 
-    /// 获取应用版本
-    pub fn get_version(&self) -> String {
-        env!("CARGO_PKG_VERSION").to_string()
-    }
-}
+        let mut reminders = 0;
+        while let Some(Ok(event)) = events.try_recv() {
+            if matches!(event, AppEvent::InfoMessage(_)) {
+                reminders += 1;
+            }
+        }
+        assert_eq!(reminders, 1);
 
-fn build_github_sync_target(config: &UserConfig) -> Result<GithubSyncTarget> {
-    let owner = config
-        .selected_project_owner
-        .clone()
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    let repo = config
-        .selected_project_repo
-        .clone()
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-    let project_number = config.selected_project_number.unwrap_or_default();
+        manager.acknowledge_pomodoro_phase().await.unwrap();
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(manager.get_daily_goal_progress().await.unwrap().0, 1);
+        // 记录的时长包含确认前超出的 70 秒
+        assert_eq!(
+            manager.database.get_today_pomodoro_sessions().await.unwrap()[0].1,
+            1500 + 70
+        );
+        manager.pause_pomodoro().await.unwrap();
+    }
 
-    if owner.is_empty() || repo.is_empty() || project_number <= 0 {
-        return Err(AppError::Validation(
-            "GitHub 项目配置不完整，请先在设置中配置 owner/repo/project number".to_string(),
-        ));
+    #[tokio::test]
+    async fn widget_payload_matches_dedicated_getters() {
+        let (_dir, mut manager) = create_test_manager().await;
+        manager
+            .save_user_config(crate::UserConfig {
+                daily_focus_goal_minutes: Some(50),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        manager
+            .database
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, None)
+            .await
+            .unwrap();
+        manager.start_pomodoro().await.unwrap();
+
+        let payload = manager.get_widget_payload().await.unwrap();
+        let progress = manager.get_goal_progress().await.unwrap();
+        assert_eq!(payload.current_streak, manager.get_streak().await.unwrap());
+        assert_eq!(payload.current_streak, 1);
+        assert_eq!(payload.today_focus_minutes, progress.achieved_minutes);
+        assert_eq!(payload.today_focus_minutes, 25);
+        assert_eq!(payload.completed_today, manager.get_completed_today_count().await.unwrap());
+        assert_eq!(payload.goal_minutes, 50);
+        assert!(payload.running);
+        assert_eq!(
+            payload.formatted_remaining,
+            manager.get_formatted_remaining().await.unwrap()
+        );
     }
 
-    Ok(GithubSyncTarget {
-        owner,
-        repo,
-        project_number,
-    })
-}
+    #[tokio::test]
+    async fn autosave_restores_running_session_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.sqlite");
+        let work_duration = {
+            let manager = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+            // 暂停状态不会被自动保存
+            assert!(!manager.autosave_session_state().await);
+
+            let work_duration = {
+                let mut service = manager.pomodoro_service.write().await;
+                service.start().unwrap();
+                for _ in 0..5 {
+                    service.tick_elapsed(1000).await;
+                }
+                service.get_session().unwrap().duration
+            };
+            assert!(manager.autosave_session_state().await);
+            work_duration
+        };
 
-fn is_supported_sync_item(operation_type: &str, payload: &serde_json::Value) -> bool {
-    if operation_type != "update" {
-        return false;
+        let restarted = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+        let session = restarted.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::Work);
+        assert_eq!(session.remaining, work_duration - 5);
+        assert!(!session.is_running);
     }
 
-    matches!(
-        payload.get("action").and_then(|v| v.as_str()),
-        Some("link_github") | Some("clear_github_link") | Some("sync_issue")
-    )
-}
+    #[tokio::test]
+    async fn pomodoro_stats_cover_today_and_all_time() {
+        let (_dir, manager) = create_test_manager().await;
+        assert_eq!(
+            manager.get_pomodoro_stats(crate::StatsRange::Today).await.unwrap(),
+            crate::PomodoroStats::empty()
+        );
+        for cycle in 1..=2 {
+            manager
+                .database
+                .record_pomodoro_session(PomodoroPhase::Work, 1500, cycle, None, None)
+                .await
+                .unwrap();
+        }
 
-fn sync_action_from_payload(payload: &serde_json::Value) -> Option<&str> {
-    payload.get("action").and_then(|v| v.as_str())
-}
+        for range in [crate::StatsRange::Today, crate::StatsRange::AllTime] {
+            let stats = manager.get_pomodoro_stats(range).await.unwrap();
+            assert_eq!(stats.work_sessions, 2);
+            assert_eq!(stats.total_focus_time, 50);
+            assert_eq!(stats.average_daily_sessions, 2.0);
+        }
+    }
 
-fn sync_issue_number_from_payload(payload: &serde_json::Value) -> Option<i64> {
-    payload.get("github_issue_number").and_then(|v| v.as_i64())
-}
+    #[tokio::test]
+    async fn only_completed_phases_are_recorded_with_completion_cycle_count() {
+        let (_dir, manager) = create_test_manager().await;
+        let mut service = PomodoroService::new(PomodoroConfig::default());
+
+        // 普通 tick、重置和跳过都不会写入记录
+        service.start().unwrap();
+        let tick = service.tick().await;
+        let session = service.get_session().unwrap().clone();
+        assert!(!record_completed_phase(&manager.database, &session, tick.as_ref(), 0).await);
+        service.pause().unwrap();
+        service.reset().unwrap();
+        service.skip().unwrap();
+        service.skip().unwrap();
+        assert!(manager.database.get_today_pomodoro_sessions().await.unwrap().is_empty());
+
+        // 跳回工作阶段后自然完成两次工作
+        for _ in 0..2 {
+            service.start().unwrap();
+            service.get_session_mut().unwrap().remaining = 0;
+            let event = service.tick().await;
+            let session = service.get_session().unwrap().clone();
+            assert!(record_completed_phase(&manager.database, &session, event.as_ref(), 0).await);
+            // 重复上报同一完成事件不会重复记录
+            record_completed_phase(&manager.database, &session, event.as_ref(), 0).await;
+            service.pause().unwrap();
+            service.skip().unwrap();
+        }
 
-fn sync_project_status_from_payload(payload: &serde_json::Value) -> Option<&str> {
-    payload.get("project_status").and_then(|v| v.as_str())
-}
+        let mut recorded = manager.database.get_today_pomodoro_sessions().await.unwrap();
+        recorded.sort_by_key(|(_, _, cycle, _, _)| *cycle);
+        assert_eq!(
+            recorded,
+            vec![
+                (PomodoroPhase::Work, 1500, 1, None, 0),
+                (PomodoroPhase::Work, 1500, 2, None, 0)
+            ]
+        );
+    }
 
-fn sync_project_id_from_payload(payload: &serde_json::Value) -> Option<i64> {
-    payload.get("github_project_id").and_then(|v| v.as_i64())
-}
+    #[tokio::test]
+    async fn pomodoro_for_todo_records_focus_and_survives_todo_deletion() {
+        let (_dir, mut manager) = create_test_manager().await;
+        let todo = manager.create_todo("focus".to_string(), None).await.unwrap();
+
+        manager.start_pomodoro_for_todo(todo.id.clone()).await.unwrap();
+        manager.pause_pomodoro().await.unwrap();
+        let session = manager.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.focus_todo_id.as_deref(), Some(todo.id.as_str()));
+
+        let complete_work = |manager: &PomodoroAppManager| {
+            let service = Arc::clone(&manager.pomodoro_service);
+            let database = Arc::clone(&manager.database);
+            async move {
+                let mut service = service.write().await;
+                service.start().unwrap();
+                service.get_session_mut().unwrap().remaining = 0;
+                let event = service.tick().await;
+                let session = service.get_session().unwrap().clone();
+                assert!(record_completed_phase(&database, &session, event.as_ref(), 0).await);
+                service.pause().unwrap();
+                service.skip().unwrap();
+            }
+        };
 
-fn parse_todo_status(status: &str) -> Option<TodoStatus> {
-    match status {
-        "todo" => Some(TodoStatus::Todo),
-        "in_progress" => Some(TodoStatus::InProgress),
-        "done" => Some(TodoStatus::Done),
-        _ => None,
-    }
-}
+        complete_work(&manager).await;
+        assert_eq!(
+            manager.database.get_today_pomodoro_sessions().await.unwrap()[0].3,
+            Some(todo.id.clone())
+        );
 
-fn has_linked_issue(todo: &Todo) -> bool {
-    todo.github_issue_number.is_some_and(|n| n > 0)
-}
+        // 会话进行中删除任务，记录仍然成功且不再引用该任务
+        manager.set_focus_todo(Some(todo.id.clone())).await.unwrap();
+        manager.delete_todo(&todo.id).await.unwrap();
+        complete_work(&manager).await;
 
-fn map_todo_status_to_project_status(status: &TodoStatus) -> &'static str {
-    match status {
-        TodoStatus::Todo => "Todo",
-        TodoStatus::InProgress => "In Progress",
-        TodoStatus::Done => "Done",
+        let mut focus: Vec<_> = manager
+            .database
+            .get_today_pomodoro_sessions()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, _, cycle, todo_id, _)| (cycle, todo_id))
+            .collect();
+        focus.sort();
+        assert_eq!(focus, vec![(1, Some(todo.id.clone())), (2, None)]);
     }
-}
 
-fn normalize_optional_text(value: Option<String>) -> Option<String> {
-    value.and_then(|v| {
-        let trimmed = v.trim().to_string();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
+    #[tokio::test]
+    async fn reset_cycle_count_updates_state_manager() {
+        let (_dir, manager) = create_test_manager().await;
+        {
+            let mut service = manager.pomodoro_service.write().await;
+            let session = service.get_session_mut().unwrap();
+            session.switch_to_phase(PomodoroPhase::LongBreak).unwrap();
+            session.cycle_count = 4;
         }
-    })
-}
 
-fn normalize_user_config(mut config: UserConfig) -> UserConfig {
-    config.github_token_encrypted = config.github_token_encrypted.trim().to_string();
-    config.github_username = config.github_username.trim().to_string();
-    config.last_sync_cursor = normalize_optional_text(config.last_sync_cursor);
-    config.selected_project_owner = normalize_optional_text(config.selected_project_owner);
-    config.selected_project_repo = normalize_optional_text(config.selected_project_repo);
-    if config.selected_project_number.is_some_and(|n| n <= 0) {
-        config.selected_project_number = None;
+        manager.reset_cycle_count().await.unwrap();
+        let session = manager.state_manager.get_pomodoro_session().await.unwrap();
+        assert_eq!(session.cycle_count, 0);
+        assert_eq!(session.phase, PomodoroPhase::LongBreak);
+        assert_eq!(session.remaining, session.duration);
     }
-    config
-}
 
-impl Default for PomodoroAppManager {
-    fn default() -> Self {
-        // 返回一个标记为未初始化的实例
-        Self {
-            state_manager: Arc::new(AppStateManager::new()),
-            database: Arc::new(Database::init_uninitialized()),
-            task_manager: Arc::new(TaskManager::new()),
-            pomodoro_service: Arc::new(RwLock::new(
-                PomodoroService::new(PomodoroConfig::default()),
-            )),
+    #[tokio::test]
+    async fn restart_restores_active_session_phase_and_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.sqlite");
+        {
+            let manager = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+            let session = {
+                let mut service = manager.pomodoro_service.write().await;
+                let session = service.get_session_mut().unwrap();
+                session.switch_to_phase(PomodoroPhase::ShortBreak).unwrap();
+                session.start().unwrap();
+                session.remaining = 100;
+                session.cycle_count = 3;
+                session.clone()
+            };
+            manager.database.save_active_session(&session).await.unwrap();
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        is_supported_sync_item, map_todo_status_to_project_status, sync_action_from_payload,
-        sync_project_id_from_payload, sync_project_status_from_payload,
-    };
-    use crate::core::todo::TodoStatus;
-
-    #[test]
-    fn supported_sync_item_requires_update_and_known_action() {
-        let payload = serde_json::json!({ "action": "link_github" });
-        assert!(is_supported_sync_item("update", &payload));
-        let payload = serde_json::json!({ "action": "sync_issue" });
-        assert!(is_supported_sync_item("update", &payload));
-        assert!(!is_supported_sync_item("create", &payload));
+        let restarted = PomodoroAppManager::with_database_path(&db_path).await.unwrap();
+        let session = restarted.get_pomodoro_session().await.unwrap().unwrap();
+        assert_eq!(session.phase, PomodoroPhase::ShortBreak);
+        assert_eq!(session.remaining, 100);
+        assert_eq!(session.cycle_count, 3);
+        assert!(!session.is_running);
     }
 
     #[test]
-    fn sync_action_from_payload_reads_action_field() {
-        let payload = serde_json::json!({ "action": "clear_github_link" });
-        assert_eq!(sync_action_from_payload(&payload), Some("clear_github_link"));
-        let invalid = serde_json::json!({ "foo": "bar" });
-        assert_eq!(sync_action_from_payload(&invalid), None);
+    fn formatted_remaining_without_session_is_placeholder() {
+        assert_eq!(format_remaining(None), "--:--");
     }
 
     #[test]
-    fn todo_status_maps_to_project_status() {
-        assert_eq!(map_todo_status_to_project_status(&TodoStatus::Todo), "Todo");
-        assert_eq!(
-            map_todo_status_to_project_status(&TodoStatus::InProgress),
-            "In Progress"
-        );
-        assert_eq!(map_todo_status_to_project_status(&TodoStatus::Done), "Done");
+    fn local_day_bounds_span_a_single_day() {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let (start, end) = local_day_bounds(date);
+        assert!(start < end);
+        assert_eq!((end - start).num_seconds(), 24 * 3600 - 1);
     }
 
     #[test]
@@ -0,0 +1,385 @@
+//! 敏感配置（GitHub 令牌）的静态加密
+//!
+//! 使用 AES-256-GCM 加密，密文格式为 `enc:v1:` 前缀加 base64(nonce ‖ 密文 ‖ 认证标签)。
+//! 密钥优先保存在系统钥匙串中；钥匙串不可用时退回到数据库旁的密钥文件，
+//! 此时能读取整个数据目录的人仍可解密令牌。
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::fmt;
+use std::path::Path;
+
+use crate::core::error::{AppError, Result};
+
+/// 密钥长度（字节）
+pub const KEY_LEN: usize = 32;
+
+/// nonce 长度（字节）
+const NONCE_LEN: usize = 12;
+
+/// 密文前缀，没有该前缀的值视为旧版本写入的明文
+const CIPHERTEXT_PREFIX: &str = "enc:v1:";
+
+/// 系统钥匙串中保存密钥的服务名
+const KEYRING_SERVICE: &str = "pomoflow-rs";
+
+/// 绑定到密文的附加认证数据，防止密文被挪作他用
+const TOKEN_AAD: &[u8] = b"pomoflow/github_token";
+
+/// 保存加密密钥的位置
+pub trait KeyStore {
+    /// 读取密钥，尚未保存过时返回 `Ok(None)`
+    fn load(&self) -> Result<Option<Vec<u8>>>;
+
+    /// 保存密钥，覆盖已有的值
+    fn save(&self, key: &[u8; KEY_LEN]) -> Result<()>;
+}
+
+/// 系统钥匙串（macOS 钥匙串、Windows 凭据管理器或 Secret Service）中的一个条目
+pub struct OsKeyring {
+    entry: keyring::Entry,
+}
+
+impl OsKeyring {
+    /// 打开 `account` 对应的条目，系统钥匙串不可用时返回 `None`
+    ///
+    /// 单元测试中始终返回 `None`，避免往开发机的钥匙串里写入临时数据库的密钥。
+    pub fn open(account: &str) -> Option<Self> {
+        if cfg!(test) {
+            return None;
+        }
+        match keyring::Entry::new(KEYRING_SERVICE, account) {
+            Ok(entry) => Some(Self { entry }),
+            Err(err) => {
+                tracing::warn!("系统钥匙串不可用，改用密钥文件: {}", err);
+                None
+            }
+        }
+    }
+}
+
+impl KeyStore for OsKeyring {
+    fn load(&self) -> Result<Option<Vec<u8>>> {
+        match self.entry.get_secret() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(AppError::Other(format!("读取钥匙串失败: {}", err))),
+        }
+    }
+
+    fn save(&self, key: &[u8; KEY_LEN]) -> Result<()> {
+        self.entry
+            .set_secret(key)
+            .map_err(|err| AppError::Other(format!("写入钥匙串失败: {}", err)))
+    }
+}
+
+/// 令牌加解密器
+pub struct TokenCipher {
+    cipher: Aes256Gcm,
+}
+
+impl fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCipher").finish_non_exhaustive()
+    }
+}
+
+impl TokenCipher {
+    /// 使用指定密钥创建加解密器
+    pub fn new(key: [u8; KEY_LEN]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&key.into()),
+        }
+    }
+
+    /// 使用随机密钥创建加解密器（内存数据库等不需要持久化密钥的场景）
+    pub fn ephemeral() -> Result<Self> {
+        Ok(Self::new(generate_key()?))
+    }
+
+    /// 从密钥文件加载密钥，文件不存在时生成新密钥并写入（Unix 下权限为 0600）
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if let Some(key) = read_key_file(path)? {
+            return Ok(Self::new(key));
+        }
+        let key = generate_key()?;
+        write_key_file(path, &key)?;
+        Ok(Self::new(key))
+    }
+
+    /// 优先从 `store`（通常是系统钥匙串）加载密钥，`key_file` 只作后备
+    ///
+    /// 钥匙串中还没有密钥时，沿用已有密钥文件中的密钥（没有则生成新密钥）存入钥匙串，
+    /// 成功后删除密钥文件；写入钥匙串失败时改用密钥文件。读取钥匙串失败时只使用已有的
+    /// 密钥文件，没有则返回错误：密钥可能已迁入钥匙串，此时生成新密钥会让已加密的令牌无法解密。
+    pub fn load_or_create_in(store: Option<&dyn KeyStore>, key_file: &Path) -> Result<Self> {
+        let Some(store) = store else {
+            return Self::load_or_create(key_file);
+        };
+
+        match store.load() {
+            Ok(Some(bytes)) => {
+                let key: [u8; KEY_LEN] = bytes
+                    .try_into()
+                    .map_err(|_| AppError::Other("钥匙串中的密钥已损坏".to_string()))?;
+                return Ok(Self::new(key));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let Some(key) = read_key_file(key_file)? else {
+                    return Err(AppError::Other(format!("{}，且没有可用的密钥文件", err)));
+                };
+                tracing::warn!("{}，改用密钥文件", err);
+                return Ok(Self::new(key));
+            }
+        }
+
+        let existing = read_key_file(key_file)?;
+        let key = match existing {
+            Some(key) => key,
+            None => generate_key()?,
+        };
+        if let Err(err) = store.save(&key) {
+            tracing::warn!("{}，改用密钥文件", err);
+            if existing.is_none() {
+                write_key_file(key_file, &key)?;
+            }
+            return Ok(Self::new(key));
+        }
+        if existing.is_some() {
+            std::fs::remove_file(key_file).map_err(AppError::Io)?;
+        }
+        Ok(Self::new(key))
+    }
+
+    /// 加密令牌，空字符串原样保留以表示“未配置”
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        if plaintext.is_empty() {
+            return Ok(String::new());
+        }
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: TOKEN_AAD,
+                },
+            )
+            .map_err(|_| AppError::Other("加密令牌失败".to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&sealed);
+        Ok(format!("{}{}", CIPHERTEXT_PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// 解密令牌；旧版本写入的明文原样返回，密文损坏或密钥不匹配时返回 `None`
+    pub fn decrypt(&self, stored: &str) -> Option<String> {
+        let Some(encoded) = stored.strip_prefix(CIPHERTEXT_PREFIX) else {
+            return Some(stored.to_string());
+        };
+
+        let payload = STANDARD.decode(encoded).ok()?;
+        if payload.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: sealed,
+                    aad: TOKEN_AAD,
+                },
+            )
+            .ok()?;
+        String::from_utf8(plaintext).ok()
+    }
+}
+
+/// 存储值是否为本模块写入的密文
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(CIPHERTEXT_PREFIX)
+}
+
+fn generate_key() -> Result<[u8; KEY_LEN]> {
+    Ok(Aes256Gcm::generate_key(OsRng).into())
+}
+
+/// 读取密钥文件，文件不存在时返回 `Ok(None)`
+fn read_key_file(path: &Path) -> Result<Option<[u8; KEY_LEN]>> {
+    match std::fs::read(path) {
+        Ok(bytes) => bytes
+            .try_into()
+            .map(Some)
+            .map_err(|_| AppError::Other(format!("密钥文件 {} 已损坏", path.display()))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(AppError::Io(err)),
+    }
+}
+
+fn write_key_file(path: &Path, key: &[u8; KEY_LEN]) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(AppError::Io)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).map_err(AppError::Io)?;
+    file.write_all(key).map_err(AppError::Io)?;
+    file.sync_all().map_err(AppError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// 内存中的钥匙串，`read_only` 为真时写入失败，`successful_loads` 限制读取成功的次数
+    #[derive(Default)]
+    struct MemoryKeyStore {
+        key: Mutex<Option<Vec<u8>>>,
+        read_only: bool,
+        successful_loads: Option<u32>,
+        loads: AtomicU32,
+    }
+
+    impl KeyStore for MemoryKeyStore {
+        fn load(&self) -> Result<Option<Vec<u8>>> {
+            let loads = self.loads.fetch_add(1, Ordering::SeqCst);
+            if self.successful_loads.is_some_and(|limit| loads >= limit) {
+                return Err(AppError::Other("locked".to_string()));
+            }
+            Ok(self.key.lock().unwrap().clone())
+        }
+
+        fn save(&self, key: &[u8; KEY_LEN]) -> Result<()> {
+            if self.read_only {
+                return Err(AppError::Other("locked".to_string()));
+            }
+            *self.key.lock().unwrap() = Some(key.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn round_trips_and_uses_fresh_nonces() {
+        let cipher = TokenCipher::new([7; KEY_LEN]);
+        let first = cipher.encrypt("ghp_secret").unwrap();
+        let second = cipher.encrypt("ghp_secret").unwrap();
+
+        assert!(is_encrypted(&first));
+        assert!(!first.contains("ghp_secret"));
+        assert_ne!(first, second);
+        assert_eq!(cipher.decrypt(&first).as_deref(), Some("ghp_secret"));
+        assert_eq!(cipher.encrypt("").unwrap(), "");
+    }
+
+    #[test]
+    fn decrypts_tokens_written_by_earlier_versions() {
+        // 旧版本基于 ring 的实现写入的密文，格式与现在相同
+        let stored = "enc:v1:yvuGBygkMyccgfKMsmWL7xIfhdK6pOetDLvJvMvzd+0hh62LA18=";
+        assert_eq!(
+            TokenCipher::new([7; KEY_LEN]).decrypt(stored).as_deref(),
+            Some("ghp_secret")
+        );
+    }
+
+    #[test]
+    fn wrong_key_or_garbage_fails_to_decrypt() {
+        let stored = TokenCipher::new([1; KEY_LEN]).encrypt("ghp_secret").unwrap();
+        let rotated = TokenCipher::new([2; KEY_LEN]);
+
+        assert_eq!(rotated.decrypt(&stored), None);
+        assert_eq!(rotated.decrypt("enc:v1:not base64!"), None);
+        assert_eq!(rotated.decrypt("enc:v1:AAAA"), None);
+        assert_eq!(rotated.decrypt("legacy-plaintext").as_deref(), Some("legacy-plaintext"));
+    }
+
+    #[test]
+    fn key_file_is_created_once_and_reused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("db.key");
+
+        let stored = TokenCipher::load_or_create(&path).unwrap().encrypt("token").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap().len(), KEY_LEN);
+        let reloaded = TokenCipher::load_or_create(&path).unwrap();
+        assert_eq!(reloaded.decrypt(&stored).as_deref(), Some("token"));
+
+        std::fs::write(&path, b"short").unwrap();
+        assert!(TokenCipher::load_or_create(&path).is_err());
+    }
+
+    #[test]
+    fn keyring_takes_over_an_existing_key_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.key");
+        let stored = TokenCipher::load_or_create(&path).unwrap().encrypt("token").unwrap();
+
+        let keyring = MemoryKeyStore::default();
+        let migrated = TokenCipher::load_or_create_in(Some(&keyring), &path).unwrap();
+        assert_eq!(migrated.decrypt(&stored).as_deref(), Some("token"));
+        assert!(!path.exists());
+        assert_eq!(keyring.key.lock().unwrap().as_ref().map(Vec::len), Some(KEY_LEN));
+
+        let reloaded = TokenCipher::load_or_create_in(Some(&keyring), &path).unwrap();
+        assert_eq!(reloaded.decrypt(&stored).as_deref(), Some("token"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn keyring_read_error_uses_the_key_file_but_never_mints_a_new_key() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.key");
+        let stored = TokenCipher::load_or_create(&path).unwrap().encrypt("token").unwrap();
+
+        // 第一次打开把密钥迁入钥匙串，第二次读取钥匙串失败
+        let keyring = MemoryKeyStore {
+            successful_loads: Some(1),
+            ..MemoryKeyStore::default()
+        };
+        TokenCipher::load_or_create_in(Some(&keyring), &path).unwrap();
+        assert!(!path.exists());
+        let migrated_key = keyring.key.lock().unwrap().clone();
+        assert!(TokenCipher::load_or_create_in(Some(&keyring), &path).is_err());
+        assert!(!path.exists());
+        assert_eq!(*keyring.key.lock().unwrap(), migrated_key);
+
+        // 仍有密钥文件时读取失败改用该文件
+        std::fs::write(&path, migrated_key.unwrap()).unwrap();
+        let reloaded = TokenCipher::load_or_create_in(Some(&keyring), &path).unwrap();
+        assert_eq!(reloaded.decrypt(&stored).as_deref(), Some("token"));
+    }
+
+    #[test]
+    fn read_only_keyring_falls_back_to_key_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db.key");
+        let keyring = MemoryKeyStore {
+            read_only: true,
+            ..MemoryKeyStore::default()
+        };
+
+        let stored = TokenCipher::load_or_create_in(Some(&keyring), &path)
+            .unwrap()
+            .encrypt("token")
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap().len(), KEY_LEN);
+        let reloaded = TokenCipher::load_or_create_in(None, &path).unwrap();
+        assert_eq!(reloaded.decrypt(&stored).as_deref(), Some("token"));
+    }
+}
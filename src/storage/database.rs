@@ -1,14 +1,25 @@
 //! SQLite 数据库操作
 
-use chrono::Utc;
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc, Weekday};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, TryLockError};
+use std::time::Duration;
 
+use super::crypto::{self, KeyStore, OsKeyring, TokenCipher, KEY_LEN};
 use crate::core::error::{AppError, Result};
-use crate::core::pomodoro::PomodoroPhase;
-use crate::core::state::UserConfig;
-use crate::core::todo::{NewTodo, Todo, TodoStatus, TodoUpdate};
+use crate::core::pomodoro::{
+    PauseCounts, PomodoroConfig, PomodoroPhase, PomodoroSession, PomodoroStats, SessionPage, SessionRecord,
+    MAX_SESSION_PAGE_SIZE,
+};
+use crate::core::state::{MinuteRounding, UserConfig};
+use crate::core::todo::{
+    NewTodo, SortKey, Tag, TagSort, Todo, TodoFilter, TodoNote, TodoPriority, TodoSearchResult, TodoStats, TodoStatus,
+    TodoUpdate,
+};
 
 /// Helper function to convert TodoStatus to database string
 fn todo_status_to_db_string(status: &TodoStatus) -> &str {
@@ -19,6 +30,15 @@ fn todo_status_to_db_string(status: &TodoStatus) -> &str {
     }
 }
 
+fn row_to_tag(row: &rusqlite::Row<'_>) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        color: row.get("color")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
 fn row_to_todo(row: &rusqlite::Row<'_>) -> rusqlite::Result<Todo> {
     let status_str: String = row.get("status")?;
     let status = match status_str.as_str() {
@@ -36,15 +56,334 @@ fn row_to_todo(row: &rusqlite::Row<'_>) -> rusqlite::Result<Todo> {
         github_issue_id: row.get("github_issue_id")?,
         github_project_id: row.get("github_project_id")?,
         github_issue_number: row.get("github_issue_number")?,
+        due_at: row.get("due_at")?,
+        completed_at: row.get("completed_at")?,
+        estimate_minutes: row.get("estimate_minutes")?,
+        estimated_pomodoros: row.get("estimated_pomodoros")?,
+        archived: row.get::<_, Option<bool>>("archived")?.unwrap_or(false),
+        priority: row
+            .get::<_, Option<String>>("priority")?
+            .and_then(|p| TodoPriority::from_string(&p).ok())
+            .unwrap_or_default(),
+        tags: Vec::new(),
+        sort_order: row.get::<_, Option<i64>>("sort_order")?.unwrap_or(0),
+        parent_id: row.get("parent_id")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
 }
 
+/// 检查 `parent_id` 能否作为 `todo_id` 的父任务：父任务须存在且未删除，且不能形成环
+fn check_parent(conn: &Connection, todo_id: Option<&str>, parent_id: &str) -> Result<()> {
+    let exists = conn
+        .query_row(
+            "SELECT 1 FROM todos WHERE id = ?1 AND deleted_at IS NULL",
+            params![parent_id],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(AppError::Database)?;
+    if exists.is_none() {
+        return Err(AppError::Validation(format!("父任务 {} 不存在", parent_id)));
+    }
+
+    let Some(todo_id) = todo_id else {
+        return Ok(());
+    };
+    // 沿父任务链向上查找，遇到自身说明会形成环
+    let cycle = conn
+        .query_row(
+            "WITH RECURSIVE ancestors(id) AS (
+                 SELECT ?1
+                 UNION
+                 SELECT todos.parent_id FROM todos JOIN ancestors ON todos.id = ancestors.id
+                 WHERE todos.parent_id IS NOT NULL
+             )
+             SELECT 1 FROM ancestors WHERE id = ?2",
+            params![parent_id, todo_id],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(AppError::Database)?;
+    if cycle.is_some() {
+        return Err(AppError::Validation("不能把任务设为自身或其子任务的子任务".to_string()));
+    }
+    Ok(())
+}
+
+/// 用一次查询为一批任务填充标签，避免逐个任务查询
+fn attach_tags(conn: &Connection, todos: &mut [Todo]) -> rusqlite::Result<()> {
+    if todos.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT tt.todo_id, t.id, t.name, t.color, t.created_at FROM todo_tags tt
+         INNER JOIN tags t ON t.id = tt.tag_id
+         ORDER BY t.created_at DESC, t.rowid DESC",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>("todo_id")?, row_to_tag(row)?)))?;
+
+    let mut tags_by_todo: HashMap<String, Vec<Tag>> = HashMap::new();
+    for row in rows {
+        let (todo_id, tag) = row?;
+        tags_by_todo.entry(todo_id).or_default().push(tag);
+    }
+    for todo in todos {
+        todo.tags = tags_by_todo.remove(&todo.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// 按字段与方向生成排序子句，截止时间为空的任务始终排在最后
+fn todo_order_sql(sort_key: SortKey, ascending: bool) -> String {
+    let direction = if ascending { "ASC" } else { "DESC" };
+    match sort_key {
+        SortKey::UpdatedAt => format!("updated_at {direction}"),
+        SortKey::CreatedAt => format!("created_at {direction}"),
+        SortKey::DueDate => format!("due_at IS NULL, due_at {direction}"),
+        SortKey::Title => format!("title COLLATE NOCASE {direction}"),
+        SortKey::Priority => {
+            format!("CASE priority WHEN 'high' THEN 2 WHEN 'medium' THEN 1 ELSE 0 END {direction}")
+        }
+        SortKey::Manual => format!("sort_order {direction}"),
+    }
+}
+
+/// trigram 分词下 FTS 查询词的最少字符数，更短的关键词回退到内存筛选
+const FTS_MIN_QUERY_CHARS: usize = 3;
+
+/// 把用户输入包成一个 FTS5 短语，内部双引号加倍转义，避免 `c++`、`"` 等触发语法错误
+fn fts_phrase(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+/// 按筛选器生成任务查询语句（不含排序与数量限制），参数追加到 `values`
+fn todo_filter_sql(filter: &TodoFilter, values: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+    let mut sql = String::from("SELECT * FROM todos WHERE deleted_at IS NULL");
+    if let Some(status) = &filter.status {
+        values.push(Box::new(todo_status_to_db_string(status).to_string()));
+        sql.push_str(&format!(" AND status = ?{}", values.len()));
+    }
+    if !filter.show_completed {
+        sql.push_str(" AND status != 'done'");
+    }
+    if !filter.show_archived {
+        sql.push_str(" AND archived = 0");
+    }
+    if filter.overdue_only {
+        values.push(Box::new(Utc::now()));
+        sql.push_str(&format!(
+            " AND status != 'done' AND due_at IS NOT NULL AND due_at < ?{}",
+            values.len()
+        ));
+    }
+    for tag_id in &filter.tag_ids {
+        values.push(Box::new(tag_id.clone()));
+        sql.push_str(&format!(
+            " AND id IN (SELECT todo_id FROM todo_tags WHERE tag_id = ?{})",
+            values.len()
+        ));
+    }
+    if let Some(search) = filter.search_term() {
+        let escaped = search
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        values.push(Box::new(format!("%{}%", escaped)));
+        let n = values.len();
+        sql.push_str(&format!(
+            " AND (lower(title) LIKE ?{n} ESCAPE '\\' OR lower(COALESCE(description, '')) LIKE ?{n} ESCAPE '\\')"
+        ));
+    }
+    sql
+}
+
+fn pomodoro_phase_to_db_string(phase: PomodoroPhase) -> &'static str {
+    match phase {
+        PomodoroPhase::Work => "work",
+        PomodoroPhase::ShortBreak => "short_break",
+        PomodoroPhase::LongBreak => "long_break",
+        PomodoroPhase::DeepWork => "deep_work",
+    }
+}
+
+fn pomodoro_phase_from_db_string(phase: &str) -> PomodoroPhase {
+    match phase {
+        "short_break" => PomodoroPhase::ShortBreak,
+        "long_break" => PomodoroPhase::LongBreak,
+        "deep_work" => PomodoroPhase::DeepWork,
+        _ => PomodoroPhase::Work,
+    }
+}
+
+/// 阶段序列以逗号分隔的阶段标识存储
+fn phase_sequence_to_db_string(sequence: &[PomodoroPhase]) -> String {
+    sequence
+        .iter()
+        .map(|phase| pomodoro_phase_to_db_string(*phase))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn phase_sequence_from_db_string(value: &str) -> Vec<PomodoroPhase> {
+    value
+        .split(',')
+        .filter(|phase| !phase.is_empty())
+        .map(pomodoro_phase_from_db_string)
+        .collect()
+}
+
+/// 检查表中是否已存在指定列
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(AppError::Database)?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(AppError::Database)?;
+    for col in rows {
+        if col.map_err(AppError::Database)? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 列不存在时追加列（用于增量迁移）
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
+        .map_err(AppError::Database)?;
+    }
+    Ok(())
+}
+
+/// SQLite 等待其他连接释放锁的默认时长
+const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 单次数据库操作（含等待连接锁）的默认最长时长，超过后调用方收到 `AppError::Timeout`
+const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 只读连接数；WAL 模式下读连接之间、读连接与写连接之间互不阻塞
+const READ_POOL_SIZE: usize = 4;
+
+/// 打开连接并应用每个连接都需要的 PRAGMA
+fn open_connection(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path).map_err(AppError::Database)?;
+
+    // 简化初始化，避免复杂的 PRAGMA 和迁移
+    // 只设置基本的 PRAGMA
+    let _ = conn.execute_batch(
+        "PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL; PRAGMA foreign_keys=ON;",
+    );
+    // 其他进程长时间持有锁时，等待一段时间后返回 SQLITE_BUSY 而不是无限等待
+    conn.busy_timeout(DEFAULT_BUSY_TIMEOUT)
+        .map_err(AppError::Database)?;
+    Ok(conn)
+}
+
+/// 只读连接池：优先取空闲连接，全部忙碌时轮流排队
+#[derive(Debug, Default)]
+struct ReadPool {
+    conns: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl ReadPool {
+    /// 打开 `size` 个只读连接，连接以 `query_only` 模式运行，误用于写入会直接报错
+    fn open(path: &Path, size: usize) -> Result<Self> {
+        let mut conns = Vec::with_capacity(size);
+        for _ in 0..size {
+            let conn = open_connection(path)?;
+            conn.execute_batch("PRAGMA query_only=ON;")
+                .map_err(AppError::Database)?;
+            conns.push(Mutex::new(conn));
+        }
+        Ok(Self {
+            conns,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// 取得一个读连接；池为空时（内存数据库无法共享）退回写连接
+    fn lock<'a>(&'a self, writer: &'a Mutex<Connection>) -> Result<MutexGuard<'a, Connection>> {
+        let lock_error = |e: String| AppError::Other(format!("Failed to lock database: {}", e));
+        if self.conns.is_empty() {
+            return writer.lock().map_err(|e| lock_error(e.to_string()));
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.conns.len() {
+            match self.conns[(start + offset) % self.conns.len()].try_lock() {
+                Ok(guard) => return Ok(guard),
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Poisoned(e)) => return Err(lock_error(e.to_string())),
+            }
+        }
+        self.conns[start % self.conns.len()]
+            .lock()
+            .map_err(|e| lock_error(e.to_string()))
+    }
+}
+
+/// 一次数据库维护的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceSummary {
+    /// 清理掉的已同步队列项数量
+    pub pruned_sync_rows: usize,
+    /// VACUUM 释放的空间（字节）
+    pub bytes_reclaimed: u64,
+}
+
+/// 数据库当前占用的空间（页数 × 页大小）
+fn database_size(conn: &Connection) -> Result<u64> {
+    let page_count: i64 = conn
+        .query_row("PRAGMA page_count", [], |row| row.get(0))
+        .map_err(AppError::Database)?;
+    let page_size: i64 = conn
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .map_err(AppError::Database)?;
+    Ok((page_count * page_size).max(0) as u64)
+}
+
+/// 在写连接上执行 VACUUM 并截断 WAL 文件，返回释放的字节数
+///
+/// SQLite 不允许在事务中执行 VACUUM，连接处于事务中时直接报错。
+fn vacuum_connection(conn: &Connection) -> Result<u64> {
+    if !conn.is_autocommit() {
+        return Err(AppError::Other("VACUUM 不能在事务中执行".to_string()));
+    }
+
+    let before = database_size(conn)?;
+    conn.execute_batch("VACUUM;").map_err(AppError::Database)?;
+    // WAL 模式下新页先写入 WAL，检查点后主文件才会变小
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(AppError::Database)?;
+    let after = database_size(conn)?;
+    Ok(before.saturating_sub(after))
+}
+
 /// 线程安全的数据库连接包装器
+///
+/// 所有写入共用一个写连接；只读查询从只读连接池取连接，不必等待写入或其他查询。
 #[derive(Debug, Clone)]
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
+    readers: Arc<ReadPool>,
+    /// 加解密 GitHub 令牌，令牌在库中只以密文保存
+    cipher: Arc<RwLock<TokenCipher>>,
+    /// 单次阻塞操作的超时（毫秒），见 [`Database::set_operation_timeout`]
+    operation_timeout_ms: Arc<AtomicU64>,
 }
 
 // 安全实现 Send + Sync，因为所有内部字段都是 Arc 包装的
@@ -56,22 +395,34 @@ impl Database {
     pub async fn init(path: &Path) -> Result<Self> {
         // 创建目录（如果不存在）
         if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| AppError::Io(e))?;
+            std::fs::create_dir_all(parent).map_err(AppError::Io)?;
         }
 
-        let conn = Connection::open(path).map_err(AppError::Database)?;
-
-        // 简化初始化，避免复杂的 PRAGMA 和迁移
-        // 只设置基本的 PRAGMA
-        let _ = conn.execute_batch(
-            "PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL; PRAGMA foreign_keys=ON;",
-        );
+        let conn = open_connection(path)?;
 
         // 运行迁移
         Self::run_migrations(&conn)?;
+        Self::repair_config_row(&conn)?;
+
+        // 密钥按数据库路径保存在系统钥匙串中；钥匙串不可用时才写入数据库旁的密钥文件，
+        // 这种情况下能读取整个数据目录的人仍可解密令牌
+        let keyring = OsKeyring::open(&path.display().to_string());
+        let cipher = TokenCipher::load_or_create_in(
+            keyring.as_ref().map(|keyring| keyring as &dyn KeyStore),
+            &path.with_extension("key"),
+        )?;
+        Self::encrypt_plaintext_token(&conn, &cipher)?;
+
+        // 迁移完成后再打开读连接，保证它们看到的是最新的表结构
+        let readers = ReadPool::open(path, READ_POOL_SIZE)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            readers: Arc::new(readers),
+            cipher: Arc::new(RwLock::new(cipher)),
+            operation_timeout_ms: Arc::new(AtomicU64::new(
+                DEFAULT_OPERATION_TIMEOUT.as_millis() as u64,
+            )),
         })
     }
 
@@ -82,7 +433,106 @@ impl Database {
 
         Self {
             conn: Arc::new(Mutex::new(conn)),
+            readers: Arc::new(ReadPool::default()),
+            cipher: Arc::new(RwLock::new(
+                TokenCipher::ephemeral().expect("Failed to generate encryption key"),
+            )),
+            operation_timeout_ms: Arc::new(AtomicU64::new(
+                DEFAULT_OPERATION_TIMEOUT.as_millis() as u64,
+            )),
+        }
+    }
+
+    /// 替换加密 GitHub 令牌所用的密钥（测试中注入固定密钥）
+    ///
+    /// 已用旧密钥写入的令牌之后无法解密，加载配置时会返回空令牌。
+    pub fn set_encryption_key(&self, key: [u8; KEY_LEN]) -> Result<()> {
+        *self
+            .cipher
+            .write()
+            .map_err(|e| AppError::Other(format!("Failed to lock cipher: {}", e)))? = TokenCipher::new(key);
+        Ok(())
+    }
+
+    /// 把旧版本以明文保存的令牌改写为密文
+    fn encrypt_plaintext_token(conn: &Connection, cipher: &TokenCipher) -> Result<()> {
+        let stored: Option<String> = conn
+            .query_row("SELECT github_token_encrypted FROM user_config WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(AppError::Database)?;
+
+        if let Some(token) = stored.filter(|t| !t.is_empty() && !crypto::is_encrypted(t)) {
+            conn.execute(
+                "UPDATE user_config SET github_token_encrypted = ?1 WHERE id = 1",
+                params![cipher.encrypt(&token)?],
+            )
+            .map_err(AppError::Database)?;
+        }
+        Ok(())
+    }
+
+    /// 设置 SQLite 等待锁的时长（`PRAGMA busy_timeout`），写连接和所有读连接一并生效
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<()> {
+        self.get_conn()
+            .busy_timeout(timeout)
+            .map_err(AppError::Database)?;
+        for reader in &self.readers.conns {
+            reader
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?
+                .busy_timeout(timeout)
+                .map_err(AppError::Database)?;
         }
+        Ok(())
+    }
+
+    /// 为数据库操作加上超时
+    ///
+    /// 超时或 SQLite 报告数据库被锁（BUSY/LOCKED）时返回 `AppError::Timeout`。
+    /// 内部的阻塞操作都经 [`Self::run_blocking`] 套用这一超时。
+    /// 注意：超时只让调用方尽快返回，已进入 `spawn_blocking` 的操作仍会在后台执行完。
+    pub async fn with_timeout<T, F>(duration: Duration, fut: F) -> Result<T>
+    where
+        F: std::future::Future<Output = Result<T>>,
+    {
+        match tokio::time::timeout(duration, fut).await {
+            Ok(Err(AppError::Database(rusqlite::Error::SqliteFailure(err, _))))
+                if matches!(
+                    err.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                ) =>
+            {
+                Err(AppError::Timeout)
+            }
+            Ok(result) => result,
+            Err(_) => Err(AppError::Timeout),
+        }
+    }
+
+    /// 设置单次数据库操作的超时，包括等待写连接和 SQLite 锁的时间
+    pub fn set_operation_timeout(&self, timeout: Duration) {
+        self.operation_timeout_ms
+            .store(timeout.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// 在阻塞线程池中执行数据库操作，并按 [`Self::set_operation_timeout`] 套上 [`Self::with_timeout`]
+    ///
+    /// 写连接被长时间占用或 SQLite 报告数据库被锁时，调用方按时收到 `AppError::Timeout`，
+    /// 不会让界面一直等待。
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let timeout = Duration::from_millis(self.operation_timeout_ms.load(Ordering::Relaxed));
+        Self::with_timeout(timeout, async move {
+            tokio::task::spawn_blocking(f)
+                .await
+                .map_err(|e| AppError::Other(e.to_string()))?
+        })
+        .await
     }
 
     /// 安全地获取数据库连接锁
@@ -90,6 +540,11 @@ impl Database {
         self.conn.lock().expect("Failed to lock database")
     }
 
+    /// 获取只读连接，只能用于查询
+    fn read_conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.readers.lock(&self.conn).expect("Failed to lock database")
+    }
+
     /// 运行数据库迁移
     fn run_migrations(conn: &Connection) -> Result<()> {
         // 首先创建版本表
@@ -112,7 +567,7 @@ impl Database {
             )
             .unwrap_or(0);
 
-        const CURRENT_SCHEMA_VERSION: i32 = 3;
+        const CURRENT_SCHEMA_VERSION: i32 = 36;
 
         // 如果已经是最新版本，跳过迁移
         if current_version >= CURRENT_SCHEMA_VERSION {
@@ -343,736 +798,4968 @@ impl Database {
             println!("✅ 数据库迁移到版本3完成（同步游标）");
         }
 
-        Ok(())
-    }
+        // 版本4：任务截止时间与每日顺延
+        if current_version < 4 {
+            add_column_if_missing(conn, "todos", "due_at", "TIMESTAMP NULL")?;
+            add_column_if_missing(conn, "user_config", "daily_carry_over", "BOOLEAN DEFAULT 0")?;
+            add_column_if_missing(conn, "user_config", "last_carry_over_date", "TEXT")?;
 
-    // ========================================================================
-    // 用户配置操作
-    // ========================================================================
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (4)",
+                [],
+            )
+            .map_err(AppError::Database)?;
 
-    /// 保存用户配置
-    pub async fn save_user_config(&self, config: &UserConfig) -> Result<()> {
-        let conn = Arc::clone(&self.conn);
-        let config = config.clone();
-        tokio::task::spawn_blocking(move || {
-            let mut conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
-            let tx = conn.transaction().map_err(AppError::Database)?;
+            println!("✅ 数据库迁移到版本4完成（截止时间）");
+        }
 
-            tx.execute(
-                r#"
-                INSERT OR REPLACE INTO user_config (
-                    id, github_token_encrypted, github_username,
-                    last_sync_cursor,
-                    selected_project_owner, selected_project_repo, selected_project_number,
-                    pomodoro_work_duration, pomodoro_short_break_duration,
-                    pomodoro_long_break_duration, pomodoro_cycles_until_long_break,
-                    notifications_enabled, sound_enabled, system_notifications, theme
-                ) VALUES (
-                    1, ?1, ?2, ?3,
-                    ?4, ?5, ?6,
-                    ?7, ?8, ?9, ?10,
-                    ?11, ?12, 0, ?13
-                )
-                "#,
-                params![
-                    config.github_token_encrypted,
-                    config.github_username,
-                    config.last_sync_cursor,
-                    config.selected_project_owner,
-                    config.selected_project_repo,
-                    config.selected_project_number,
-                    config.pomodoro_work_duration,
-                    config.pomodoro_short_break_duration,
-                    config.pomodoro_long_break_duration,
-                    config.pomodoro_cycles_until_long_break,
-                    config.notifications_enabled as i32,
-                    config.sound_enabled as i32,
-                    &config.theme,
-                ],
+        if current_version < 5 {
+            add_column_if_missing(conn, "todos", "completed_at", "TIMESTAMP NULL")?;
+            // 已完成的历史任务以最后更新时间作为完成时间
+            conn.execute(
+                "UPDATE todos SET completed_at = updated_at WHERE status = 'done' AND completed_at IS NULL",
+                [],
             )
             .map_err(AppError::Database)?;
 
-            tx.commit().map_err(AppError::Database)?;
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (5)",
+                [],
+            )
+            .map_err(AppError::Database)?;
 
-            Ok(())
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
-    }
+            println!("✅ 数据库迁移到版本5完成（完成时间）");
+        }
 
-    /// 加载用户配置
-    pub async fn load_user_config(&self) -> Result<Option<UserConfig>> {
-        let conn = Arc::clone(&self.conn);
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+        if current_version < 6 {
+            // 不加外键：任务删除后历史会话仍保留原 id
+            add_column_if_missing(conn, "pomodoro_sessions", "focus_todo_id", "TEXT NULL")?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_focus_todo ON pomodoro_sessions(focus_todo_id)",
+                [],
+            )
+            .map_err(AppError::Database)?;
 
-            let mut stmt = conn
-                .prepare(
-                    r#"
-                SELECT
-                    github_token_encrypted, github_username,
-                    last_sync_cursor,
-                    selected_project_owner, selected_project_repo, selected_project_number,
-                    pomodoro_work_duration, pomodoro_short_break_duration,
-                    pomodoro_long_break_duration, pomodoro_cycles_until_long_break,
-                    notifications_enabled, sound_enabled, theme
-                FROM user_config WHERE id = 1
-                "#,
-                )
-                .map_err(AppError::Database)?;
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (6)",
+                [],
+            )
+            .map_err(AppError::Database)?;
 
-            let config_iter = stmt
-                .query_map([], |row| {
-                    Ok(UserConfig {
-                        github_token_encrypted: row.get("github_token_encrypted")?,
-                        github_username: row.get("github_username")?,
-                        last_sync_cursor: row.get("last_sync_cursor")?,
-                        selected_project_owner: row.get("selected_project_owner")?,
-                        selected_project_repo: row.get("selected_project_repo")?,
-                        selected_project_number: row.get("selected_project_number")?,
-                        pomodoro_work_duration: row.get("pomodoro_work_duration")?,
-                        pomodoro_short_break_duration: row.get("pomodoro_short_break_duration")?,
-                        pomodoro_long_break_duration: row.get("pomodoro_long_break_duration")?,
-                        pomodoro_cycles_until_long_break: row
-                            .get("pomodoro_cycles_until_long_break")?,
-                        notifications_enabled: row.get("notifications_enabled")?,
-                        sound_enabled: row.get("sound_enabled")?,
-                        theme: row.get("theme")?,
-                    })
-                })
-                .map_err(AppError::Database)?;
+            println!("✅ 数据库迁移到版本6完成（会话关联任务）");
+        }
 
-            for config_result in config_iter {
-                return Ok(Some(config_result.map_err(AppError::Database)?));
-            }
+        if current_version < 7 {
+            // 单行表，保存当前番茄钟阶段进度
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS pomodoro_state (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    phase TEXT NOT NULL CHECK (phase IN ('work', 'short_break', 'long_break')),
+                    remaining_seconds INTEGER NOT NULL CHECK (remaining_seconds >= 0),
+                    cycle_count INTEGER NOT NULL DEFAULT 0,
+                    updated_at TIMESTAMP NOT NULL
+                )
+            "#,
+            )
+            .map_err(AppError::Database)?;
 
-            Ok(None)
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
-    }
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (7)",
+                [],
+            )
+            .map_err(AppError::Database)?;
 
-    // ========================================================================
-    // 任务操作
-    // ========================================================================
+            println!("✅ 数据库迁移到版本7完成（会话进度持久化）");
+        }
 
-    /// 创建新任务
-    pub async fn create_todo(&self, new_todo: &NewTodo) -> Result<Todo> {
-        let conn = Arc::clone(&self.conn);
+        if current_version < 8 {
+            add_column_if_missing(conn, "user_config", "daily_focus_goal_minutes", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (8)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本8完成（每日专注目标）");
+        }
+
+        if current_version < 9 {
+            add_column_if_missing(conn, "user_config", "task_complete_sound", "TEXT NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (9)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本9完成（任务完成音效）");
+        }
+
+        if current_version < 10 {
+            add_column_if_missing(conn, "tags", "normalized_name", "TEXT NULL")?;
+
+            // 回填规范化名称；已存在的重名标签保留 NULL，不参与唯一约束
+            let tags: Vec<(String, String)> = {
+                let mut stmt = conn
+                    .prepare("SELECT id, name FROM tags ORDER BY rowid ASC")
+                    .map_err(AppError::Database)?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(AppError::Database)?;
+                rows.collect::<rusqlite::Result<_>>()
+                    .map_err(AppError::Database)?
+            };
+            let mut seen = std::collections::HashSet::new();
+            for (id, name) in tags {
+                let normalized = Tag::normalize_name(&name);
+                if seen.insert(normalized.clone()) {
+                    conn.execute(
+                        "UPDATE tags SET normalized_name = ?1 WHERE id = ?2",
+                        params![normalized, id],
+                    )
+                    .map_err(AppError::Database)?;
+                }
+            }
+
+            conn.execute(
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_normalized_name ON tags(normalized_name)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (10)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本10完成（标签名规范化）");
+        }
+
+        if current_version < 11 {
+            add_column_if_missing(conn, "user_config", "sync_focus_status", "BOOLEAN DEFAULT 0")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (11)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本11完成（专注任务状态同步）");
+        }
+
+        if current_version < 12 {
+            add_column_if_missing(conn, "todos", "estimate_minutes", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (12)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本12完成（任务预估耗时）");
+        }
+
+        if current_version < 13 {
+            add_column_if_missing(conn, "todos", "archived", "BOOLEAN NOT NULL DEFAULT 0")?;
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "auto_archive_done_after_days",
+                "INTEGER NULL",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (13)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本13完成（任务归档）");
+        }
+
+        if current_version < 14 {
+            for column in ["work_color", "short_break_color", "long_break_color"] {
+                add_column_if_missing(conn, "user_config", column, "TEXT NULL")?;
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (14)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本14完成（阶段颜色）");
+        }
+
+        if current_version < 15 {
+            add_column_if_missing(conn, "user_config", "enforced_break_seconds", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (15)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本15完成（最短休息时长）");
+        }
+
+        if current_version < 16 {
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "focus_minute_rounding",
+                "TEXT NOT NULL DEFAULT 'round'",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (16)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本16完成（专注分钟取整方式）");
+        }
+
+        if current_version < 17 {
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS todo_notes (
+                    id TEXT PRIMARY KEY,
+                    todo_id TEXT NOT NULL,
+                    body TEXT NOT NULL,
+                    created_at TIMESTAMP NOT NULL,
+                    FOREIGN KEY (todo_id) REFERENCES todos(id) ON DELETE CASCADE
+                );
+                CREATE INDEX IF NOT EXISTS idx_todo_notes_todo_id ON todo_notes(todo_id, created_at);
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (17)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本17完成（任务备注）");
+        }
+
+        if current_version < 18 {
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "pomodoro_deep_work_duration",
+                "INTEGER DEFAULT 5400",
+            )?;
+
+            // SQLite 无法修改 CHECK 约束，重建阶段相关的表以允许 deep_work
+            conn.execute_batch(
+                r#"
+                CREATE TABLE pomodoro_sessions_new (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    phase TEXT NOT NULL CHECK (phase IN ('work', 'short_break', 'long_break', 'deep_work')),
+                    duration_seconds INTEGER NOT NULL CHECK (duration_seconds > 0),
+                    completed_at TIMESTAMP NOT NULL,
+                    cycle_count INTEGER NOT NULL DEFAULT 0,
+                    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                    focus_todo_id TEXT NULL
+                );
+                INSERT INTO pomodoro_sessions_new
+                    (id, phase, duration_seconds, completed_at, cycle_count, created_at, focus_todo_id)
+                SELECT id, phase, duration_seconds, completed_at, cycle_count, created_at, focus_todo_id
+                FROM pomodoro_sessions;
+                DROP TABLE pomodoro_sessions;
+                ALTER TABLE pomodoro_sessions_new RENAME TO pomodoro_sessions;
+                CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_focus_todo ON pomodoro_sessions(focus_todo_id);
+
+                CREATE TABLE pomodoro_state_new (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    phase TEXT NOT NULL CHECK (phase IN ('work', 'short_break', 'long_break', 'deep_work')),
+                    remaining_seconds INTEGER NOT NULL CHECK (remaining_seconds >= 0),
+                    cycle_count INTEGER NOT NULL DEFAULT 0,
+                    updated_at TIMESTAMP NOT NULL
+                );
+                INSERT INTO pomodoro_state_new (id, phase, remaining_seconds, cycle_count, updated_at)
+                SELECT id, phase, remaining_seconds, cycle_count, updated_at FROM pomodoro_state;
+                DROP TABLE pomodoro_state;
+                ALTER TABLE pomodoro_state_new RENAME TO pomodoro_state;
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (18)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本18完成（深度工作阶段）");
+        }
+
+        if current_version < 19 {
+            add_column_if_missing(conn, "pomodoro_sessions", "session_uuid", "TEXT NULL")?;
+
+            // 旧记录的 session_uuid 为 NULL，唯一索引不会约束它们
+            conn.execute(
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_pomodoro_sessions_uuid ON pomodoro_sessions(session_uuid)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (19)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本19完成（会话记录去重）");
+        }
+
+        if current_version < 20 {
+            add_column_if_missing(conn, "user_config", "idle_auto_pause_seconds", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (20)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本20完成（空闲自动暂停）");
+        }
+
+        if current_version < 21 {
+            conn.execute(
+                r#"
+                CREATE TABLE IF NOT EXISTS active_pomodoro (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    phase TEXT NOT NULL CHECK (phase IN ('work', 'short_break', 'long_break', 'deep_work')),
+                    duration_seconds INTEGER NOT NULL CHECK (duration_seconds > 0),
+                    remaining_seconds INTEGER NOT NULL CHECK (remaining_seconds >= 0),
+                    cycle_count INTEGER NOT NULL DEFAULT 0,
+                    is_running INTEGER NOT NULL DEFAULT 0,
+                    updated_at TIMESTAMP NOT NULL
+                )
+                "#,
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (21)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本21完成（活动会话持久化）");
+        }
+
+        if current_version < 22 {
+            // 用触发器记录每个任务的状态变化，删除的任务记为 deleted，不做外键级联以保留历史
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS todo_status_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    todo_id TEXT NOT NULL,
+                    status TEXT NOT NULL CHECK (status IN ('todo', 'in_progress', 'done', 'deleted')),
+                    changed_at TIMESTAMP NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_todo_status_history_changed_at
+                    ON todo_status_history(changed_at);
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_status_insert
+                AFTER INSERT ON todos
+                BEGIN
+                    INSERT INTO todo_status_history (todo_id, status, changed_at)
+                    VALUES (NEW.id, NEW.status, NEW.created_at);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_status_update
+                AFTER UPDATE OF status ON todos
+                WHEN OLD.status != NEW.status
+                BEGIN
+                    INSERT INTO todo_status_history (todo_id, status, changed_at)
+                    VALUES (NEW.id, NEW.status, NEW.updated_at);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_status_delete
+                AFTER DELETE ON todos
+                BEGIN
+                    -- 当前时间只有毫秒精度，不早于该任务最后一条记录，保证删除排在最后
+                    INSERT INTO todo_status_history (todo_id, status, changed_at)
+                    SELECT OLD.id, 'deleted', MAX(
+                        strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+                        COALESCE((SELECT MAX(changed_at) FROM todo_status_history WHERE todo_id = OLD.id), '')
+                    );
+                END;
+
+                -- 已有任务没有历史，按创建时为待办、最后更新时为当前状态近似回填
+                INSERT INTO todo_status_history (todo_id, status, changed_at)
+                SELECT id, 'todo', created_at FROM todos WHERE status != 'todo';
+                INSERT INTO todo_status_history (todo_id, status, changed_at)
+                SELECT id, status, CASE WHEN status = 'todo' THEN created_at ELSE updated_at END
+                FROM todos;
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (22)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本22完成（任务状态历史）");
+        }
+
+        if current_version < 23 {
+            add_column_if_missing(conn, "user_config", "focus_sound_enabled", "BOOLEAN DEFAULT 0")?;
+            add_column_if_missing(conn, "user_config", "focus_loop_sound", "TEXT NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (23)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本23完成（专注背景音）");
+        }
+
+        if current_version < 24 {
+            add_column_if_missing(conn, "todos", "priority", "TEXT NOT NULL DEFAULT 'medium'")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (24)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本24完成（任务优先级）");
+        }
+
+        if current_version < 25 {
+            conn.execute_batch(
+                r#"
+                CREATE INDEX IF NOT EXISTS idx_todos_deleted_at ON todos(deleted_at);
+
+                -- 移入回收站视同删除，恢复时按当前状态重新记录
+                CREATE TRIGGER IF NOT EXISTS trg_todos_status_soft_delete
+                AFTER UPDATE OF deleted_at ON todos
+                WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                BEGIN
+                    INSERT INTO todo_status_history (todo_id, status, changed_at)
+                    SELECT NEW.id, 'deleted', MAX(
+                        NEW.deleted_at,
+                        COALESCE((SELECT MAX(changed_at) FROM todo_status_history WHERE todo_id = NEW.id), '')
+                    );
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_status_restore
+                AFTER UPDATE OF deleted_at ON todos
+                WHEN OLD.deleted_at IS NOT NULL AND NEW.deleted_at IS NULL
+                BEGIN
+                    INSERT INTO todo_status_history (todo_id, status, changed_at)
+                    SELECT NEW.id, NEW.status, MAX(
+                        strftime('%Y-%m-%d %H:%M:%f+00:00', 'now'),
+                        COALESCE((SELECT MAX(changed_at) FROM todo_status_history WHERE todo_id = NEW.id), '')
+                    );
+                END;
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (25)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本25完成（任务回收站）");
+        }
+
+        if current_version < 26 {
+            add_column_if_missing(conn, "todos", "sort_order", "INTEGER NOT NULL DEFAULT 0")?;
+
+            // 按现有列表的默认顺序（最新在前）回填手动排序位置
+            conn.execute_batch(
+                r#"
+                UPDATE todos SET sort_order = (
+                    SELECT COUNT(*) FROM todos AS newer
+                    WHERE newer.created_at > todos.created_at
+                       OR (newer.created_at = todos.created_at AND newer.rowid > todos.rowid)
+                );
+                CREATE INDEX IF NOT EXISTS idx_todos_sort_order ON todos(sort_order);
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (26)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本26完成（任务手动排序）");
+        }
+
+        if current_version < 27 {
+            for column in ["manual_pauses", "idle_auto_pauses", "break_enforced_pauses"] {
+                add_column_if_missing(conn, "pomodoro_sessions", column, "INTEGER NOT NULL DEFAULT 0")?;
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (27)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本27完成（暂停原因统计）");
+        }
+
+        if current_version < 28 {
+            // trigram 分词支持中文等无空格文本的子串匹配，与内存筛选的语义一致
+            conn.execute_batch(
+                r#"
+                CREATE VIRTUAL TABLE IF NOT EXISTS todos_fts USING fts5(
+                    title, description,
+                    content='todos', content_rowid='rowid',
+                    tokenize='trigram'
+                );
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_fts_insert
+                AFTER INSERT ON todos
+                BEGIN
+                    INSERT INTO todos_fts (rowid, title, description)
+                    VALUES (NEW.rowid, NEW.title, NEW.description);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_fts_delete
+                AFTER DELETE ON todos
+                BEGIN
+                    INSERT INTO todos_fts (todos_fts, rowid, title, description)
+                    VALUES ('delete', OLD.rowid, OLD.title, OLD.description);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS trg_todos_fts_update
+                AFTER UPDATE OF title, description ON todos
+                BEGIN
+                    INSERT INTO todos_fts (todos_fts, rowid, title, description)
+                    VALUES ('delete', OLD.rowid, OLD.title, OLD.description);
+                    INSERT INTO todos_fts (rowid, title, description)
+                    VALUES (NEW.rowid, NEW.title, NEW.description);
+                END;
+
+                INSERT INTO todos_fts (todos_fts) VALUES ('rebuild');
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (28)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本28完成（任务全文搜索）");
+        }
+
+        if current_version < 29 {
+            add_column_if_missing(
+                conn,
+                "todos",
+                "parent_id",
+                "TEXT REFERENCES todos(id) ON DELETE SET NULL",
+            )?;
+
+            conn.execute_batch(
+                r#"
+                CREATE INDEX IF NOT EXISTS idx_todos_parent_id ON todos(parent_id);
+
+                -- 父任务移入回收站时子任务保留，改为顶层任务（永久删除由外键置空）
+                CREATE TRIGGER IF NOT EXISTS trg_todos_orphan_subtasks
+                AFTER UPDATE OF deleted_at ON todos
+                WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+                BEGIN
+                    UPDATE todos SET parent_id = NULL WHERE parent_id = NEW.id;
+                END;
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (29)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本29完成（子任务）");
+        }
+
+        if current_version < 30 {
+            add_column_if_missing(conn, "todos", "estimated_pomodoros", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (30)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本30完成（预估番茄数）");
+        }
+
+        if current_version < 31 {
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "pomodoro_auto_start_next",
+                "BOOLEAN DEFAULT 0",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (31)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本31完成（跳过后自动开始）");
+        }
+
+        if current_version < 32 {
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "pomodoro_warn_before_seconds",
+                "INTEGER NULL",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (32)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本32完成（阶段结束提醒）");
+        }
+
+        if current_version < 33 {
+            add_column_if_missing(
+                conn,
+                "pomodoro_sessions",
+                "interruptions",
+                "INTEGER NOT NULL DEFAULT 0",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (33)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本33完成（打断次数）");
+        }
+
+        if current_version < 34 {
+            add_column_if_missing(conn, "user_config", "pomodoro_phase_sequence", "TEXT NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (34)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本34完成（自定义阶段序列）");
+        }
+
+        if current_version < 35 {
+            add_column_if_missing(conn, "user_config", "daily_pomodoro_goal", "INTEGER NULL")?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (35)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本35完成（每日番茄数目标）");
+        }
+
+        if current_version < 36 {
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "pomodoro_require_phase_ack",
+                "BOOLEAN DEFAULT 0",
+            )?;
+            add_column_if_missing(conn, "user_config", "pomodoro_break_reminders", "BOOLEAN DEFAULT 0")?;
+            add_column_if_missing(
+                conn,
+                "user_config",
+                "pomodoro_reminder_grace_secs",
+                "INTEGER NULL",
+            )?;
+
+            conn.execute(
+                "INSERT OR REPLACE INTO schema_version (version) VALUES (36)",
+                [],
+            )
+            .map_err(AppError::Database)?;
+
+            println!("✅ 数据库迁移到版本36完成（工作超时休息提醒）");
+        }
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // 用户配置操作
+    // ========================================================================
+
+    /// 确保 user_config 有且仅有 id = 1 的一行，返回是否做了修复
+    pub async fn ensure_config_row(&self) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            Self::repair_config_row(&conn)
+        })
+        .await
+    }
+
+    /// 删除全部数据表后重新建表（重新执行全部迁移）并写入默认配置行
+    ///
+    /// 用于恢复出厂设置，执行后数据库与首次启动时相同。
+    pub async fn reset_all_data(&self) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let tables: Vec<String> = {
+                let mut stmt = conn
+                    // 先删虚拟表，其影子表随之删除
+                    .prepare(
+                        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+                         ORDER BY sql LIKE 'CREATE VIRTUAL TABLE%' DESC",
+                    )
+                    .map_err(AppError::Database)?;
+                let rows = stmt
+                    .query_map([], |row| row.get(0))
+                    .map_err(AppError::Database)?;
+                rows.collect::<std::result::Result<_, _>>()
+                    .map_err(AppError::Database)?
+            };
+
+            // 外键开关不能在事务内切换，删表期间临时关闭以免级联检查失败
+            conn.execute_batch("PRAGMA foreign_keys=OFF;")
+                .map_err(AppError::Database)?;
+            let dropped = (|| {
+                let tx = conn.transaction().map_err(AppError::Database)?;
+                for table in &tables {
+                    tx.execute(&format!("DROP TABLE IF EXISTS \"{}\"", table.replace('"', "\"\"")), [])
+                        .map_err(AppError::Database)?;
+                }
+                tx.commit().map_err(AppError::Database)
+            })();
+            conn.execute_batch("PRAGMA foreign_keys=ON;")
+                .map_err(AppError::Database)?;
+            dropped?;
+
+            Self::run_migrations(&conn)?;
+            Self::repair_config_row(&conn)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 修复 user_config 单行约束：缺少 id = 1 时优先把最近写入的多余行挪过去，
+    /// 没有任何行时插入默认配置，其余多余行删除
+    fn repair_config_row(conn: &Connection) -> Result<bool> {
+        let has_singleton = conn
+            .query_row("SELECT 1 FROM user_config WHERE id = 1", [], |_| Ok(()))
+            .optional()
+            .map_err(AppError::Database)?
+            .is_some();
+
+        let mut repaired = false;
+        if !has_singleton {
+            let promoted = conn
+                .execute(
+                    "UPDATE user_config SET id = 1
+                     WHERE rowid = (SELECT MAX(rowid) FROM user_config)",
+                    [],
+                )
+                .map_err(AppError::Database)?;
+            if promoted == 0 {
+                conn.execute(
+                    "INSERT INTO user_config (id, github_token_encrypted, github_username)
+                     VALUES (1, '', '')",
+                    [],
+                )
+                .map_err(AppError::Database)?;
+            }
+            repaired = true;
+        }
+
+        let removed = conn
+            .execute("DELETE FROM user_config WHERE id != 1", [])
+            .map_err(AppError::Database)?;
+        if removed > 0 {
+            repaired = true;
+        }
+
+        if repaired {
+            println!("⚠️  已修复 user_config 配置行");
+        }
+        Ok(repaired)
+    }
+
+    /// 保存用户配置
+    pub async fn save_user_config(&self, config: &UserConfig) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let encrypted_token = self
+            .cipher
+            .read()
+            .map_err(|e| AppError::Other(format!("Failed to lock cipher: {}", e)))?
+            .encrypt(&config.github_token_encrypted)?;
+        let config = config.clone();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO user_config (
+                    id, github_token_encrypted, github_username,
+                    last_sync_cursor,
+                    selected_project_owner, selected_project_repo, selected_project_number,
+                    pomodoro_work_duration, pomodoro_short_break_duration,
+                    pomodoro_long_break_duration, pomodoro_cycles_until_long_break,
+                    notifications_enabled, sound_enabled, system_notifications, theme,
+                    daily_carry_over, last_carry_over_date,
+                    daily_focus_goal_minutes, task_complete_sound,
+                    sync_focus_status, auto_archive_done_after_days,
+                    work_color, short_break_color, long_break_color,
+                    enforced_break_seconds, focus_minute_rounding,
+                    pomodoro_deep_work_duration, idle_auto_pause_seconds,
+                    focus_sound_enabled, focus_loop_sound,
+                    pomodoro_auto_start_next, pomodoro_warn_before_seconds,
+                    pomodoro_phase_sequence, daily_pomodoro_goal,
+                    pomodoro_require_phase_ack, pomodoro_break_reminders,
+                    pomodoro_reminder_grace_secs
+                ) VALUES (
+                    1, ?1, ?2, ?3,
+                    ?4, ?5, ?6,
+                    ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?29, ?13,
+                    ?14, ?15,
+                    ?16, ?17,
+                    ?18, ?19,
+                    ?20, ?21, ?22,
+                    ?23, ?24,
+                    ?25, ?26,
+                    ?27, ?28,
+                    ?30, ?31,
+                    ?32, ?33,
+                    ?34, ?35,
+                    ?36
+                )
+                "#,
+                params![
+                    encrypted_token,
+                    config.github_username,
+                    config.last_sync_cursor,
+                    config.selected_project_owner,
+                    config.selected_project_repo,
+                    config.selected_project_number,
+                    config.pomodoro_work_duration,
+                    config.pomodoro_short_break_duration,
+                    config.pomodoro_long_break_duration,
+                    config.pomodoro_cycles_until_long_break,
+                    config.notifications_enabled as i32,
+                    config.sound_enabled as i32,
+                    &config.theme,
+                    config.daily_carry_over as i32,
+                    config.last_carry_over_date,
+                    config.daily_focus_goal_minutes,
+                    config.task_complete_sound,
+                    config.sync_focus_status as i32,
+                    config.auto_archive_done_after_days,
+                    config.work_color,
+                    config.short_break_color,
+                    config.long_break_color,
+                    config.enforced_break_seconds,
+                    config.focus_minute_rounding.as_str(),
+                    config.pomodoro_deep_work_duration,
+                    config.idle_auto_pause_seconds,
+                    config.focus_sound_enabled as i32,
+                    config.focus_loop_sound,
+                    config.system_notifications as i32,
+                    config.pomodoro_auto_start_next as i32,
+                    config.pomodoro_warn_before_seconds,
+                    config
+                        .pomodoro_phase_sequence
+                        .as_deref()
+                        .map(phase_sequence_to_db_string),
+                    config.daily_pomodoro_goal,
+                    config.pomodoro_require_phase_ack as i32,
+                    config.pomodoro_break_reminders as i32,
+                    config.pomodoro_reminder_grace_secs,
+                ],
+            )
+            .map_err(AppError::Database)?;
+
+            tx.commit().map_err(AppError::Database)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// 加载用户配置，GitHub 令牌解密后返回
+    ///
+    /// 令牌无法解密（如密钥已更换）时只把令牌置空，其余配置照常返回。
+    pub async fn load_user_config(&self) -> Result<Option<UserConfig>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let cipher = Arc::clone(&self.cipher);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT
+                    github_token_encrypted, github_username,
+                    last_sync_cursor,
+                    selected_project_owner, selected_project_repo, selected_project_number,
+                    pomodoro_work_duration, pomodoro_short_break_duration,
+                    pomodoro_long_break_duration, pomodoro_cycles_until_long_break,
+                    notifications_enabled, sound_enabled, system_notifications, theme,
+                    daily_carry_over, last_carry_over_date,
+                    daily_focus_goal_minutes, task_complete_sound,
+                    sync_focus_status, auto_archive_done_after_days,
+                    work_color, short_break_color, long_break_color,
+                    enforced_break_seconds, focus_minute_rounding,
+                    pomodoro_deep_work_duration, idle_auto_pause_seconds,
+                    focus_sound_enabled, focus_loop_sound,
+                    pomodoro_auto_start_next, pomodoro_warn_before_seconds,
+                    pomodoro_phase_sequence, daily_pomodoro_goal,
+                    pomodoro_require_phase_ack, pomodoro_break_reminders,
+                    pomodoro_reminder_grace_secs
+                FROM user_config WHERE id = 1
+                "#,
+                )
+                .map_err(AppError::Database)?;
+
+            let mut config_iter = stmt
+                .query_map([], |row| {
+                    Ok(UserConfig {
+                        github_token_encrypted: row.get("github_token_encrypted")?,
+                        github_username: row.get("github_username")?,
+                        last_sync_cursor: row.get("last_sync_cursor")?,
+                        selected_project_owner: row.get("selected_project_owner")?,
+                        selected_project_repo: row.get("selected_project_repo")?,
+                        selected_project_number: row.get("selected_project_number")?,
+                        pomodoro_work_duration: row.get("pomodoro_work_duration")?,
+                        pomodoro_short_break_duration: row.get("pomodoro_short_break_duration")?,
+                        pomodoro_long_break_duration: row.get("pomodoro_long_break_duration")?,
+                        pomodoro_cycles_until_long_break: row
+                            .get("pomodoro_cycles_until_long_break")?,
+                        notifications_enabled: row.get("notifications_enabled")?,
+                        sound_enabled: row.get("sound_enabled")?,
+                        system_notifications: row
+                            .get::<_, Option<bool>>("system_notifications")?
+                            .unwrap_or(true),
+                        theme: row.get("theme")?,
+                        daily_carry_over: row
+                            .get::<_, Option<bool>>("daily_carry_over")?
+                            .unwrap_or(false),
+                        last_carry_over_date: row.get("last_carry_over_date")?,
+                        daily_focus_goal_minutes: row.get("daily_focus_goal_minutes")?,
+                        daily_pomodoro_goal: row.get("daily_pomodoro_goal")?,
+                        task_complete_sound: row.get("task_complete_sound")?,
+                        sync_focus_status: row
+                            .get::<_, Option<bool>>("sync_focus_status")?
+                            .unwrap_or(false),
+                        auto_archive_done_after_days: row.get("auto_archive_done_after_days")?,
+                        work_color: row.get("work_color")?,
+                        short_break_color: row.get("short_break_color")?,
+                        long_break_color: row.get("long_break_color")?,
+                        enforced_break_seconds: row.get("enforced_break_seconds")?,
+                        focus_minute_rounding: row
+                            .get::<_, Option<String>>("focus_minute_rounding")?
+                            .map(|value| MinuteRounding::from_db_string(&value))
+                            .unwrap_or_default(),
+                        pomodoro_deep_work_duration: row
+                            .get::<_, Option<u64>>("pomodoro_deep_work_duration")?
+                            .unwrap_or(5400),
+                        pomodoro_auto_start_next: row
+                            .get::<_, Option<bool>>("pomodoro_auto_start_next")?
+                            .unwrap_or(false),
+                        pomodoro_warn_before_seconds: row.get("pomodoro_warn_before_seconds")?,
+                        pomodoro_phase_sequence: row
+                            .get::<_, Option<String>>("pomodoro_phase_sequence")?
+                            .map(|value| phase_sequence_from_db_string(&value)),
+                        pomodoro_require_phase_ack: row
+                            .get::<_, Option<bool>>("pomodoro_require_phase_ack")?
+                            .unwrap_or(false),
+                        pomodoro_break_reminders: row
+                            .get::<_, Option<bool>>("pomodoro_break_reminders")?
+                            .unwrap_or(false),
+                        pomodoro_reminder_grace_secs: row
+                            .get::<_, Option<u64>>("pomodoro_reminder_grace_secs")?
+                            .unwrap_or(60),
+                        idle_auto_pause_seconds: row.get("idle_auto_pause_seconds")?,
+                        focus_sound_enabled: row
+                            .get::<_, Option<bool>>("focus_sound_enabled")?
+                            .unwrap_or(false),
+                        focus_loop_sound: row.get("focus_loop_sound")?,
+                    })
+                })
+                .map_err(AppError::Database)?;
+
+            let Some(config_result) = config_iter.next() else {
+                return Ok(None);
+            };
+            let mut config = config_result.map_err(AppError::Database)?;
+
+            let cipher = cipher
+                .read()
+                .map_err(|e| AppError::Other(format!("Failed to lock cipher: {}", e)))?;
+            config.github_token_encrypted = cipher
+                .decrypt(&config.github_token_encrypted)
+                .unwrap_or_else(|| {
+                    eprintln!("⚠️  GitHub 令牌无法解密（密钥可能已更换），请重新配置");
+                    String::new()
+                });
+            Ok(Some(config))
+        })
+        .await
+    }
+
+    // ========================================================================
+    // 任务操作
+    // ========================================================================
+
+    /// 创建新任务
+    pub async fn create_todo(&self, new_todo: &NewTodo) -> Result<Todo> {
+        let conn = Arc::clone(&self.conn);
         let new_todo = new_todo.clone();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+
+            if let Some(parent_id) = &new_todo.parent_id {
+                check_parent(&tx, None, parent_id)?;
+            }
+
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let completed_at = (new_todo.status == TodoStatus::Done).then_some(now);
+            // 新任务排在手动顺序的最前面，与默认的最新在前一致
+            let sort_order: i64 = tx
+                .query_row("SELECT COALESCE(MIN(sort_order), 0) - 1 FROM todos", [], |row| {
+                    row.get(0)
+                })
+                .map_err(AppError::Database)?;
+
+            tx.execute(
+                r#"
+                INSERT INTO todos (id, title, description, status, due_at, completed_at, estimate_minutes, estimated_pomodoros, priority, sort_order, parent_id, created_at, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                "#,
+                params![
+                    id,
+                    new_todo.title,
+                    new_todo.description,
+                    todo_status_to_db_string(&new_todo.status),
+                    new_todo.due_at,
+                    completed_at,
+                    new_todo.estimate_minutes,
+                    new_todo.estimated_pomodoros,
+                    new_todo.priority.as_str(),
+                    sort_order,
+                    new_todo.parent_id,
+                    now,
+                    now
+                ],
+            )
+            .map_err(AppError::Database)?;
+
+            tx.commit().map_err(AppError::Database)?;
+
+            // 返回创建的任务
+            Ok(Todo {
+                id,
+                title: new_todo.title.clone(),
+                description: new_todo.description.clone(),
+                status: new_todo.status.clone(),
+                github_issue_id: None,
+                github_project_id: None,
+                github_issue_number: None,
+                due_at: new_todo.due_at,
+                completed_at,
+                estimate_minutes: new_todo.estimate_minutes,
+                estimated_pomodoros: new_todo.estimated_pomodoros,
+                archived: false,
+                priority: new_todo.priority,
+                tags: Vec::new(),
+                sort_order,
+                parent_id: new_todo.parent_id.clone(),
+                created_at: now,
+                updated_at: now,
+            })
+        })
+        .await
+    }
+
+    /// 获取所有任务
+    pub async fn get_all_todos(&self) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE deleted_at IS NULL ORDER BY created_at DESC, rowid DESC",
+                )
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map([], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 分页获取未删除的任务，排序与 [`Database::get_all_todos`] 一致
+    pub async fn get_todos_paginated(&self, offset: usize, limit: usize) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE deleted_at IS NULL ORDER BY created_at DESC, rowid DESC
+                     LIMIT ?1 OFFSET ?2",
+                )
+                .map_err(AppError::Database)?;
+
+            let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+            let offset = i64::try_from(offset).unwrap_or(i64::MAX);
+            let mut todos = stmt
+                .query_map(params![limit, offset], row_to_todo)
+                .map_err(AppError::Database)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(AppError::Database)?;
+
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 统计未删除的任务总数
+    pub async fn count_todos(&self) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+            let count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM todos WHERE deleted_at IS NULL", [], |row| {
+                    row.get(0)
+                })
+                .map_err(AppError::Database)?;
+            Ok(count as usize)
+        })
+        .await
+    }
+
+    /// 获取指定任务的直接子任务（不含已删除），按创建时间先后排列
+    pub async fn get_subtasks(&self, parent_id: &str) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let parent_id = parent_id.to_string();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE parent_id = ?1 AND deleted_at IS NULL
+                     ORDER BY created_at ASC, rowid ASC",
+                )
+                .map_err(AppError::Database)?;
+            let mut todos = stmt
+                .query_map(params![parent_id], row_to_todo)
+                .map_err(AppError::Database)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .map_err(AppError::Database)?;
+
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 获取没有任何标签的未完成、未归档任务，按创建时间倒序
+    pub async fn get_untagged_todos(&self) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT t.* FROM todos t
+                     LEFT JOIN todo_tags tt ON tt.todo_id = t.id
+                     WHERE tt.tag_id IS NULL AND t.status != 'done' AND t.archived = 0
+                       AND t.deleted_at IS NULL
+                     ORDER BY t.created_at DESC",
+                )
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map([], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 获取创建时间在 [from, to] 内的任务（两端均包含），按创建时间升序
+    pub async fn get_todos_created_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE deleted_at IS NULL AND created_at >= ?1 AND created_at <= ?2 ORDER BY created_at ASC, rowid ASC",
+                )
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map(params![from, to], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 获取完成时间在 [start, end] 内的未删除任务（两端均包含），按完成时间升序
+    pub async fn get_todos_completed_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE deleted_at IS NULL AND status = 'done'
+                     AND completed_at >= ?1 AND completed_at <= ?2 ORDER BY completed_at ASC, rowid ASC",
+                )
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map(params![start, end], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 按筛选器搜索任务（未指定排序字段时按更新时间倒序），结果数受 [`TodoFilter::effective_limit`] 限制
+    pub async fn search_todos_capped(&self, filter: &TodoFilter) -> Result<TodoSearchResult> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let filter = filter.clone();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+            let mut sql = todo_filter_sql(&filter, &mut values);
+            // 与 TodoFilter::apply 的排序一致：指定字段按默认方向，相同值按 ID 升序
+            let sort_key = filter.sort_by.unwrap_or_default();
+            sql.push_str(&format!(
+                " ORDER BY {}, id ASC",
+                todo_order_sql(sort_key, sort_key.default_ascending())
+            ));
+
+            // 多取一条用于判断是否被截断
+            let limit = filter.effective_limit();
+            if let Some(limit) = limit {
+                values.push(Box::new(limit as i64 + 1));
+                sql.push_str(&format!(" LIMIT ?{}", values.len()));
+            }
+
+            let mut stmt = conn.prepare(&sql).map_err(AppError::Database)?;
+            let todo_iter = stmt
+                .query_map(
+                    rusqlite::params_from_iter(values.iter().map(|v| v.as_ref())),
+                    row_to_todo,
+                )
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            let truncated = limit.is_some_and(|limit| todos.len() > limit);
+            if let Some(limit) = limit {
+                todos.truncate(limit);
+            }
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(TodoSearchResult { todos, truncated })
+        })
+        .await
+    }
+
+    /// 全文搜索未删除任务的标题和描述（按更新时间倒序）
+    ///
+    /// 关键词不足 [`FTS_MIN_QUERY_CHARS`] 个字符时 trigram 索引无法匹配，回退到
+    /// 与 [`TodoFilter::apply`] 相同的内存子串匹配；空白关键词返回全部任务。
+    pub async fn search_todos(&self, query: &str) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let query = query.trim().to_string();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut todos = if query.chars().count() >= FTS_MIN_QUERY_CHARS {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT todos.* FROM todos_fts JOIN todos ON todos.rowid = todos_fts.rowid
+                         WHERE todos_fts MATCH ?1 AND todos.deleted_at IS NULL
+                         ORDER BY todos.updated_at DESC, todos.rowid DESC",
+                    )
+                    .map_err(AppError::Database)?;
+                let todos = stmt
+                    .query_map(params![fts_phrase(&query)], row_to_todo)
+                    .map_err(AppError::Database)?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+                    .map_err(AppError::Database)?;
+                todos
+            } else {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT * FROM todos WHERE deleted_at IS NULL
+                         ORDER BY updated_at DESC, rowid DESC",
+                    )
+                    .map_err(AppError::Database)?;
+                let mut todos = stmt
+                    .query_map([], row_to_todo)
+                    .map_err(AppError::Database)?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+                    .map_err(AppError::Database)?;
+                todos.retain(|todo| todo.matches_search(&query));
+                todos
+            };
+
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 按筛选器查询任务并按指定字段排序，相同值按 ID 升序保证顺序稳定
+    ///
+    /// 按截止时间排序时没有截止时间的任务始终排在最后；结果数受 [`TodoFilter::effective_limit`] 限制。
+    pub async fn query_todos_sorted(
+        &self,
+        filter: &TodoFilter,
+        sort_key: SortKey,
+        ascending: bool,
+    ) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let filter = filter.clone();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+            let mut sql = todo_filter_sql(&filter, &mut values);
+            sql.push_str(&format!(
+                " ORDER BY {}, id ASC",
+                todo_order_sql(sort_key, ascending)
+            ));
+            if let Some(limit) = filter.effective_limit() {
+                values.push(Box::new(limit as i64));
+                sql.push_str(&format!(" LIMIT ?{}", values.len()));
+            }
+
+            let mut stmt = conn.prepare(&sql).map_err(AppError::Database)?;
+            let todo_iter = stmt
+                .query_map(
+                    rusqlite::params_from_iter(values.iter().map(|v| v.as_ref())),
+                    row_to_todo,
+                )
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+            attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 根据ID获取任务（不含回收站中的任务）
+    pub async fn get_todo_by_id(&self, id: &str) -> Result<Option<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare("SELECT * FROM todos WHERE id = ?1 AND deleted_at IS NULL")
+                .map_err(AppError::Database)?;
+
+            let mut rows = stmt.query(params![id]).map_err(AppError::Database)?;
+
+            if let Some(row) = rows.next().map_err(AppError::Database)? {
+                return Ok(Some(row_to_todo(row).map_err(AppError::Database)?));
+            }
+
+            Ok(None)
+        })
+        .await
+    }
+
+    /// 更新任务
+    pub async fn update_todo(&self, id: &str, updates: &TodoUpdate) -> Result<Option<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        let updates = updates.clone();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+
+            // 回收站中的任务不可编辑
+            let active = tx
+                .query_row(
+                    "SELECT 1 FROM todos WHERE id = ?1 AND deleted_at IS NULL",
+                    params![id],
+                    |_| Ok(()),
+                )
+                .optional()
+                .map_err(AppError::Database)?;
+            if active.is_none() {
+                return Ok(None);
+            }
+
+            // 分别更新每个字段
+            if let Some(ref title) = updates.title {
+                tx.execute(
+                    "UPDATE todos SET title = ?, updated_at = ? WHERE id = ?",
+                    params![title, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref description) = updates.description {
+                // 处理 Option<Option<String>>
+                let desc_value = if let Some(ref desc) = *description {
+                    desc.as_str()
+                } else {
+                    ""
+                };
+                tx.execute(
+                    "UPDATE todos SET description = ?, updated_at = ? WHERE id = ?",
+                    params![desc_value, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref status) = updates.status {
+                tx.execute(
+                    r#"
+                    UPDATE todos SET status = ?1,
+                        completed_at = CASE WHEN ?1 = 'done' THEN COALESCE(completed_at, ?2) ELSE NULL END,
+                        updated_at = ?2
+                    WHERE id = ?3
+                    "#,
+                    params![todo_status_to_db_string(status), Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref github_issue_id) = updates.github_issue_id {
+                tx.execute(
+                    "UPDATE todos SET github_issue_id = ?, updated_at = ? WHERE id = ?",
+                    params![github_issue_id, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref github_project_id) = updates.github_project_id {
+                tx.execute(
+                    "UPDATE todos SET github_project_id = ?, updated_at = ? WHERE id = ?",
+                    params![github_project_id, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref github_issue_number) = updates.github_issue_number {
+                tx.execute(
+                    "UPDATE todos SET github_issue_number = ?, updated_at = ? WHERE id = ?",
+                    params![github_issue_number, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref due_at) = updates.due_at {
+                tx.execute(
+                    "UPDATE todos SET due_at = ?, updated_at = ? WHERE id = ?",
+                    params![due_at, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref estimate_minutes) = updates.estimate_minutes {
+                tx.execute(
+                    "UPDATE todos SET estimate_minutes = ?, updated_at = ? WHERE id = ?",
+                    params![estimate_minutes, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref estimated_pomodoros) = updates.estimated_pomodoros {
+                tx.execute(
+                    "UPDATE todos SET estimated_pomodoros = ?, updated_at = ? WHERE id = ?",
+                    params![estimated_pomodoros, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(priority) = updates.priority {
+                tx.execute(
+                    "UPDATE todos SET priority = ?, updated_at = ? WHERE id = ?",
+                    params![priority.as_str(), Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            if let Some(ref parent_id) = updates.parent_id {
+                if let Some(parent_id) = parent_id {
+                    check_parent(&tx, Some(&id), parent_id)?;
+                }
+                tx.execute(
+                    "UPDATE todos SET parent_id = ?, updated_at = ? WHERE id = ?",
+                    params![parent_id, Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            tx.commit().map_err(AppError::Database)?;
+
+            // 在同一个闭包内获取更新后的任务
+            let mut stmt = conn
+                .prepare("SELECT * FROM todos WHERE id = ?1")
+                .map_err(AppError::Database)?;
+
+            let mut rows = stmt.query(params![id]).map_err(AppError::Database)?;
+
+            if let Some(row) = rows.next().map_err(AppError::Database)? {
+                return Ok(Some(row_to_todo(row).map_err(AppError::Database)?));
+            }
+
+            Ok(None)
+        })
+        .await
+    }
+
+    /// 删除所有任务（关联的标签、备注等随之级联删除），返回删除数量
+    pub async fn delete_all_todos(&self) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute("DELETE FROM todos", [])
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected)
+        })
+        .await
+    }
+
+    /// 删除任务：移入回收站并标记待同步，可通过 [`Self::restore_todo`] 恢复
+    pub async fn delete_todo(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET deleted_at = ?1, sync_pending = 1
+                     WHERE id = ?2 AND deleted_at IS NULL",
+                    params![Utc::now(), id],
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected > 0)
+        })
+        .await
+    }
+
+    /// 从回收站恢复任务并标记待同步，任务不在回收站中时返回 None
+    pub async fn restore_todo(&self, id: &str) -> Result<Option<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET deleted_at = NULL, sync_pending = 1
+                     WHERE id = ?1 AND deleted_at IS NOT NULL",
+                    params![id],
+                )
+                .map_err(AppError::Database)?;
+            if rows_affected == 0 {
+                return Ok(None);
+            }
+
+            let mut todo = conn
+                .query_row("SELECT * FROM todos WHERE id = ?1", params![id], row_to_todo)
+                .optional()
+                .map_err(AppError::Database)?;
+            attach_tags(&conn, todo.as_mut_slice()).map_err(AppError::Database)?;
+            Ok(todo)
+        })
+        .await
+    }
+
+    /// 获取回收站中的任务（最近删除的在前）
+    pub async fn get_deleted_todos(&self) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM todos WHERE deleted_at IS NOT NULL
+                     ORDER BY deleted_at DESC, rowid DESC",
+                )
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map([], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 为任务追加一条备注，任务不存在时返回 NotFound
+    pub async fn add_todo_note(&self, todo_id: &str, body: &str) -> Result<TodoNote> {
+        let body = TodoNote::validate_body(body)?;
+        let conn = Arc::clone(&self.conn);
+        let todo_id = todo_id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let todo_exists = conn
+                .query_row("SELECT 1 FROM todos WHERE id = ?1", params![todo_id], |_| Ok(()))
+                .optional()
+                .map_err(AppError::Database)?
+                .is_some();
+            if !todo_exists {
+                return Err(AppError::NotFound(format!("Todo with id {} not found", todo_id)));
+            }
+
+            let note = TodoNote {
+                id: uuid::Uuid::new_v4().to_string(),
+                todo_id,
+                body,
+                created_at: Utc::now(),
+            };
+            conn.execute(
+                "INSERT INTO todo_notes (id, todo_id, body, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![note.id, note.todo_id, note.body, note.created_at],
+            )
+            .map_err(AppError::Database)?;
+
+            Ok(note)
+        })
+        .await
+    }
+
+    /// 获取任务的全部备注，按添加顺序排列
+    pub async fn get_todo_notes(&self, todo_id: &str) -> Result<Vec<TodoNote>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let todo_id = todo_id.to_string();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, todo_id, body, created_at FROM todo_notes
+                     WHERE todo_id = ?1 ORDER BY created_at ASC, rowid ASC",
+                )
+                .map_err(AppError::Database)?;
+
+            let note_iter = stmt
+                .query_map(params![todo_id], |row| {
+                    Ok(TodoNote {
+                        id: row.get("id")?,
+                        todo_id: row.get("todo_id")?,
+                        body: row.get("body")?,
+                        created_at: row.get("created_at")?,
+                    })
+                })
+                .map_err(AppError::Database)?;
+
+            let mut notes = Vec::new();
+            for note in note_iter {
+                notes.push(note.map_err(AppError::Database)?);
+            }
+
+            Ok(notes)
+        })
+        .await
+    }
+
+    /// 删除一条备注，返回是否存在
+    pub async fn delete_todo_note(&self, note_id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let note_id = note_id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute("DELETE FROM todo_notes WHERE id = ?1", params![note_id])
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected > 0)
+        })
+        .await
+    }
+
+    /// 按原样写回任务（用于撤销删除和导入），同 id 的记录会被更新，标签关联保留
+    ///
+    /// 父子关系一并写回；外键检查推迟到提交时，子任务可以排在父任务之前。
+    pub async fn restore_todos(&self, todos: &[Todo]) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let todos = todos.to_vec();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+            tx.execute_batch("PRAGMA defer_foreign_keys = ON;")
+                .map_err(AppError::Database)?;
+
+            for todo in &todos {
+                tx.execute(
+                    r#"
+                    INSERT INTO todos (
+                        id, title, description, status,
+                        github_issue_id, github_project_id, github_issue_number,
+                        due_at, completed_at, estimate_minutes, archived, priority, sort_order,
+                        created_at, updated_at, estimated_pomodoros, parent_id
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                    ON CONFLICT(id) DO UPDATE SET
+                        title = excluded.title,
+                        description = excluded.description,
+                        status = excluded.status,
+                        github_issue_id = excluded.github_issue_id,
+                        github_project_id = excluded.github_project_id,
+                        github_issue_number = excluded.github_issue_number,
+                        due_at = excluded.due_at,
+                        completed_at = excluded.completed_at,
+                        estimate_minutes = excluded.estimate_minutes,
+                        estimated_pomodoros = excluded.estimated_pomodoros,
+                        archived = excluded.archived,
+                        priority = excluded.priority,
+                        sort_order = excluded.sort_order,
+                        parent_id = excluded.parent_id,
+                        deleted_at = NULL,
+                        created_at = excluded.created_at,
+                        updated_at = excluded.updated_at
+                    "#,
+                    params![
+                        todo.id,
+                        todo.title,
+                        todo.description,
+                        todo_status_to_db_string(&todo.status),
+                        todo.github_issue_id,
+                        todo.github_project_id,
+                        todo.github_issue_number,
+                        todo.due_at,
+                        todo.completed_at,
+                        todo.estimate_minutes,
+                        todo.archived,
+                        todo.priority.as_str(),
+                        todo.sort_order,
+                        todo.created_at,
+                        todo.updated_at,
+                        todo.estimated_pomodoros,
+                        todo.parent_id
+                    ],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            tx.commit().map_err(AppError::Database)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 永久删除任务
+    pub async fn permanently_delete_todo(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute("DELETE FROM todos WHERE id = ?1", params![id])
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected > 0)
+        })
+        .await
+    }
+
+    /// 在一个事务中交换两个任务的手动排序位置，任一任务不存在时不做修改并返回 false
+    pub async fn swap_todo_order(&self, id_a: &str, id_b: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id_a = id_a.to_string();
+        let id_b = id_b.to_string();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+
+            let sort_order_of = |id: &str| -> Result<Option<i64>> {
+                tx.query_row(
+                    "SELECT sort_order FROM todos WHERE id = ?1 AND deleted_at IS NULL",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(AppError::Database)
+            };
+            let (Some(order_a), Some(order_b)) = (sort_order_of(&id_a)?, sort_order_of(&id_b)?) else {
+                return Ok(false);
+            };
+
+            tx.execute(
+                "UPDATE todos SET sort_order = ?1 WHERE id = ?2",
+                params![order_b, id_a],
+            )
+            .map_err(AppError::Database)?;
+            tx.execute(
+                "UPDATE todos SET sort_order = ?1 WHERE id = ?2",
+                params![order_a, id_b],
+            )
+            .map_err(AppError::Database)?;
+
+            tx.commit().map_err(AppError::Database)?;
+            Ok(true)
+        })
+        .await
+    }
+
+    /// 按 `ordered_ids` 的先后重排这些任务的手动顺序
+    ///
+    /// 只在这些任务原有的位置之间重新分配，列表外任务的位置不变；
+    /// 位置没有变化的行不会写入。任一 ID 不存在或重复时不做任何修改。
+    pub async fn reorder_todos(&self, ordered_ids: &[String]) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let ordered_ids = ordered_ids.to_vec();
+        self.run_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let tx = conn.transaction().map_err(AppError::Database)?;
+
+            let mut seen = std::collections::HashSet::new();
+            let mut current = Vec::with_capacity(ordered_ids.len());
+            for id in &ordered_ids {
+                if !seen.insert(id.as_str()) {
+                    return Err(AppError::Validation(format!("任务 {} 在排序列表中重复", id)));
+                }
+                let sort_order: i64 = tx
+                    .query_row(
+                        "SELECT sort_order FROM todos WHERE id = ?1 AND deleted_at IS NULL",
+                        params![id],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .map_err(AppError::Database)?
+                    .ok_or_else(|| AppError::NotFound(format!("Todo with id {} not found", id)))?;
+                current.push(sort_order);
+            }
+
+            // 现有位置从小到大依次分给新顺序中的任务，并列的位置拉开以保证顺序确定
+            let mut slots = current.clone();
+            slots.sort_unstable();
+            for i in 1..slots.len() {
+                if slots[i] <= slots[i - 1] {
+                    slots[i] = slots[i - 1] + 1;
+                }
+            }
+
+            for ((id, old), new) in ordered_ids.iter().zip(&current).zip(&slots) {
+                if old == new {
+                    continue;
+                }
+                tx.execute(
+                    "UPDATE todos SET sort_order = ?1 WHERE id = ?2",
+                    params![new, id],
+                )
+                .map_err(AppError::Database)?;
+            }
+
+            tx.commit().map_err(AppError::Database)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 批量顺延逾期任务：将截止时间早于 `before` 且未完成的任务改为 `new_due_at`
+    ///
+    /// `new_due_at` 为 `None` 时清除截止时间。返回受影响的任务数。
+    pub async fn carry_over_due_dates(
+        &self,
+        before: DateTime<Utc>,
+        new_due_at: Option<DateTime<Utc>>,
+    ) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET due_at = ?1, updated_at = ?2
+                     WHERE status != 'done' AND due_at IS NOT NULL AND due_at < ?3
+                       AND deleted_at IS NULL",
+                    params![new_due_at, Utc::now(), before],
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected)
+        })
+        .await
+    }
+
+    /// 归档完成时间早于 `older_than_days` 天前的已完成任务，返回新归档的任务数
+    pub async fn auto_archive_done_todos(&self, older_than_days: u32) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let now = Utc::now();
+            let cutoff = now - chrono::Duration::days(i64::from(older_than_days));
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET archived = 1, updated_at = ?1
+                     WHERE status = 'done' AND archived = 0 AND deleted_at IS NULL
+                       AND completed_at IS NOT NULL AND completed_at < ?2",
+                    params![now, cutoff],
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected)
+        })
+        .await
+    }
+
+    /// 最近 `days` 天内平均每天完成的任务数（按 `completed_at` 统计），`days` 为 0 时返回 0.0
+    pub async fn get_completion_velocity(&self, days: u32) -> Result<f32> {
+        if days == 0 {
+            return Ok(0.0);
+        }
+
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let cutoff = Utc::now() - chrono::Duration::days(i64::from(days));
+            let completed: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM todos
+                     WHERE status = 'done' AND deleted_at IS NULL
+                       AND completed_at IS NOT NULL AND completed_at >= ?1",
+                    params![cutoff],
+                    |row| row.get(0),
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(completed as f32 / days as f32)
+        })
+        .await
+    }
+
+    /// 统计在本地日期 `date` 完成的任务数
+    ///
+    /// 以 `completed_at` 为准：之前完成、当天只是被编辑过的任务不计入。
+    pub async fn count_completed_on(&self, date: NaiveDate) -> Result<u64> {
+        let (start, end) = crate::local_day_bounds(date);
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let completed: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM todos
+                     WHERE status = 'done' AND deleted_at IS NULL
+                       AND completed_at >= ?1 AND completed_at <= ?2",
+                    params![start, end],
+                    |row| row.get(0),
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(completed.max(0) as u64)
+        })
+        .await
+    }
+
+    /// 按天统计任务状态分布：`from` 到 `to`（含，本地日期）每天结束时各状态的任务数
+    ///
+    /// 基于 `todo_status_history`，当天结束前已删除的任务不计入。`from` 晚于 `to` 时返回空列表。
+    pub async fn get_status_distribution_by_day(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, TodoStats)>> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            // 以次日本地零点为界，与 `local_day_bounds` 划分的“一天”一致
+            let day_end = |day: NaiveDate| crate::local_day_bounds(day + chrono::Duration::days(1)).0;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT todo_id, status, changed_at FROM todo_status_history
+                     WHERE changed_at < ?1
+                     ORDER BY changed_at ASC, id ASC",
+                )
+                .map_err(AppError::Database)?;
+            let rows = stmt
+                .query_map(params![day_end(to)], |row| {
+                    Ok((
+                        row.get::<_, String>("todo_id")?,
+                        row.get::<_, String>("status")?,
+                        row.get::<_, DateTime<Utc>>("changed_at")?,
+                    ))
+                })
+                .map_err(AppError::Database)?;
+            let mut history = Vec::new();
+            for row in rows {
+                history.push(row.map_err(AppError::Database)?);
+            }
+
+            let mut current: std::collections::HashMap<String, String> =
+                std::collections::HashMap::new();
+            let mut pending = history.into_iter().peekable();
+            let mut distribution = Vec::new();
+            for day in from.iter_days().take_while(|day| *day <= to) {
+                let end = day_end(day);
+                while let Some((todo_id, status, _)) =
+                    pending.next_if(|(_, _, changed_at)| *changed_at < end)
+                {
+                    current.insert(todo_id, status);
+                }
+
+                let mut stats = TodoStats {
+                    total: 0,
+                    todo: 0,
+                    in_progress: 0,
+                    done: 0,
+                };
+                for status in current.values() {
+                    match status.as_str() {
+                        "todo" => stats.todo += 1,
+                        "in_progress" => stats.in_progress += 1,
+                        "done" => stats.done += 1,
+                        _ => continue,
+                    }
+                    stats.total += 1;
+                }
+                distribution.push((day, stats));
+            }
+
+            Ok(distribution)
+        })
+        .await
+    }
+
+    /// 获取需要同步的任务
+    pub async fn get_pending_sync_todos(&self) -> Result<Vec<Todo>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            let mut stmt = conn
+                .prepare("SELECT * FROM todos WHERE sync_pending = 1")
+                .map_err(AppError::Database)?;
+
+            let todo_iter = stmt
+                .query_map([], row_to_todo)
+                .map_err(AppError::Database)?;
+
+            let mut todos = Vec::new();
+            for todo_result in todo_iter {
+                todos.push(todo_result.map_err(AppError::Database)?);
+            }
+
+            Ok(todos)
+        })
+        .await
+    }
+
+    /// 标记任务为已同步
+    pub async fn mark_todo_synced(&self, id: &str) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            conn.execute(
+                "UPDATE todos SET sync_pending = 0 WHERE id = ?1",
+                params![id],
+            )
+            .map_err(AppError::Database)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// 将任务重新标记为待同步，返回任务是否存在
+    pub async fn mark_todo_pending_sync(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_string();
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET sync_pending = 1 WHERE id = ?1",
+                    params![id],
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected > 0)
+        })
+        .await
+    }
+
+    /// 将所有已同步任务重新标记为待同步，返回受影响的任务数
+    pub async fn mark_all_todos_pending_sync(&self) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        self.run_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+
+            let rows_affected = conn
+                .execute(
+                    "UPDATE todos SET sync_pending = 1 WHERE sync_pending IS NOT 1",
+                    [],
+                )
+                .map_err(AppError::Database)?;
+
+            Ok(rows_affected)
+        })
+        .await
+    }
+
+    // ========================================================================
+    // 同步队列操作
+    // ========================================================================
+
+    /// 添加到同步队列
+    pub async fn add_to_sync_queue(
+        &self,
+        operation_type: &str,
+        record_id: &str,
+        payload: &serde_json::Value,
+    ) -> Result<i64> {
+        let conn = self.get_conn();
+
+        conn.execute(
+            r#"
+            INSERT INTO sync_queue (operation_type, table_name, record_id, payload)
+            VALUES (?1, 'todos', ?2, ?3)
+            "#,
+            params![operation_type, record_id, payload.to_string()],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取待同步队列
+    pub async fn get_pending_sync_queue(&self) -> Result<Vec<(i64, String, String, String)>> {
+        let conn = self.read_conn();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, operation_type, record_id, payload FROM sync_queue WHERE status = 'pending' ORDER BY priority DESC, created_at ASC"
+        ).map_err(AppError::Database)?;
+
+        let mut items = Vec::new();
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get("id")?,
+                    row.get("operation_type")?,
+                    row.get("record_id")?,
+                    row.get("payload")?,
+                ))
+            })
+            .map_err(AppError::Database)?;
+
+        for item in rows {
+            items.push(item.map_err(AppError::Database)?);
+        }
+
+        Ok(items)
+    }
+
+    /// 检查某条记录是否仍有待处理同步
+    pub async fn has_pending_sync_for_record(&self, record_id: &str) -> Result<bool> {
+        let conn = self.read_conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT COUNT(*) FROM sync_queue WHERE record_id = ?1 AND status = 'pending'",
+            )
+            .map_err(AppError::Database)?;
+        let count: i64 = stmt
+            .query_row(params![record_id], |row| row.get(0))
+            .map_err(AppError::Database)?;
+        Ok(count > 0)
+    }
+
+    /// 标记同步队列项为已同步
+    pub async fn mark_sync_queue_synced(&self, id: i64) -> Result<()> {
+        let conn = self.get_conn();
+
+        conn.execute(
+            "UPDATE sync_queue SET status = 'synced', synced_at = ?1 WHERE id = ?2",
+            params![Utc::now(), id],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// 标记同步队列项为失败
+    pub async fn mark_sync_queue_failed(&self, id: i64, error_message: &str) -> Result<()> {
+        let conn = self.get_conn();
+
+        conn.execute(
+            "UPDATE sync_queue SET status = 'failed', error_message = ?1 WHERE id = ?2",
+            params![error_message, id],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// 清理已同步的队列项（保留最新的100条）
+    pub async fn cleanup_sync_queue(&self) -> Result<usize> {
+        let conn = self.get_conn();
+
+        // 获取需要清理的记录数
+        let mut stmt = conn
+            .prepare("SELECT COUNT(*) FROM sync_queue WHERE status = 'synced'")
+            .map_err(AppError::Database)?;
+
+        let count: i64 = stmt
+            .query_row([], |row| row.get(0))
+            .map_err(AppError::Database)?;
+
+        if count <= 100 {
+            return Ok(0);
+        }
+
+        let to_delete = count - 100;
+
+        conn.execute(
+            "DELETE FROM sync_queue WHERE status = 'synced' AND id IN (
+                SELECT id FROM sync_queue WHERE status = 'synced' ORDER BY synced_at ASC LIMIT ?
+            )",
+            params![to_delete],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(to_delete as usize)
+    }
+
+    /// 压缩数据库文件，回收删除数据留下的空闲页
+    pub async fn vacuum(&self) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
         tokio::task::spawn_blocking(move || {
-            let mut conn = conn
+            let conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
-            let tx = conn.transaction().map_err(AppError::Database)?;
+            vacuum_connection(&conn).map(|_| ())
+        })
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+    }
+
+    /// 执行一次完整维护：清理同步队列、VACUUM 以及 `PRAGMA optimize`
+    pub async fn run_maintenance(&self) -> Result<MaintenanceSummary> {
+        let pruned_sync_rows = self.cleanup_sync_queue().await?;
+
+        let conn = Arc::clone(&self.conn);
+        let bytes_reclaimed = tokio::task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+            let reclaimed = vacuum_connection(&conn)?;
+            conn.execute_batch("PRAGMA optimize;")
+                .map_err(AppError::Database)?;
+            Ok::<_, AppError>(reclaimed)
+        })
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))??;
+
+        Ok(MaintenanceSummary {
+            pruned_sync_rows,
+            bytes_reclaimed,
+        })
+    }
+
+    // ========================================================================
+    // 番茄钟会话操作
+    // ========================================================================
+
+    /// 记录番茄钟会话，`focus_todo_id` 为该阶段专注的任务
+    ///
+    /// `session_uuid` 标识一次阶段运行，同一标识重复记录时忽略，返回是否实际插入。
+    /// 专注任务已被删除时记录为空引用。
+    pub async fn record_pomodoro_session(
+        &self,
+        phase: PomodoroPhase,
+        duration_seconds: u32,
+        cycle_count: u32,
+        focus_todo_id: Option<&str>,
+        session_uuid: Option<&str>,
+    ) -> Result<bool> {
+        self.record_pomodoro_session_with_pauses(
+            phase,
+            duration_seconds,
+            cycle_count,
+            focus_todo_id,
+            session_uuid,
+            PauseCounts::default(),
+            0,
+        )
+        .await
+    }
+
+    /// 记录番茄钟会话，并附带该阶段按原因统计的暂停次数与打断次数
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_pomodoro_session_with_pauses(
+        &self,
+        phase: PomodoroPhase,
+        duration_seconds: u32,
+        cycle_count: u32,
+        focus_todo_id: Option<&str>,
+        session_uuid: Option<&str>,
+        pause_counts: PauseCounts,
+        interruptions: u32,
+    ) -> Result<bool> {
+        let conn = self.get_conn();
+
+        let phase_str = pomodoro_phase_to_db_string(phase);
+
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO pomodoro_sessions (phase, duration_seconds, completed_at, cycle_count, focus_todo_id, session_uuid, manual_pauses, idle_auto_pauses, break_enforced_pauses, interruptions) VALUES (?1, ?2, ?3, ?4, (SELECT id FROM todos WHERE id = ?5 AND deleted_at IS NULL), ?6, ?7, ?8, ?9, ?10)",
+            params![
+                phase_str,
+                duration_seconds,
+                Utc::now(),
+                cycle_count,
+                focus_todo_id,
+                session_uuid,
+                pause_counts.manual,
+                pause_counts.idle_auto,
+                pause_counts.break_enforced,
+                interruptions
+            ]
+        ).map_err(AppError::Database)?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 保存当前番茄钟阶段进度（phase, remaining_seconds, cycle_count）
+    pub async fn save_session_state(
+        &self,
+        phase: PomodoroPhase,
+        remaining_seconds: u64,
+        cycle_count: u32,
+    ) -> Result<()> {
+        let conn = self.get_conn();
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO pomodoro_state (id, phase, remaining_seconds, cycle_count, updated_at)
+            VALUES (1, ?1, ?2, ?3, ?4)
+            "#,
+            params![
+                pomodoro_phase_to_db_string(phase),
+                remaining_seconds as i64,
+                cycle_count,
+                Utc::now()
+            ],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// 读取保存的番茄钟阶段进度
+    pub async fn load_session_state(&self) -> Result<Option<(PomodoroPhase, u64, u32)>> {
+        let conn = self.read_conn();
+
+        let mut stmt = conn
+            .prepare("SELECT phase, remaining_seconds, cycle_count FROM pomodoro_state WHERE id = 1")
+            .map_err(AppError::Database)?;
+        let mut rows = stmt.query([]).map_err(AppError::Database)?;
+
+        match rows.next().map_err(AppError::Database)? {
+            Some(row) => {
+                let phase: String = row.get("phase").map_err(AppError::Database)?;
+                let remaining: i64 = row.get("remaining_seconds").map_err(AppError::Database)?;
+                let cycle_count: u32 = row.get("cycle_count").map_err(AppError::Database)?;
+                Ok(Some((
+                    pomodoro_phase_from_db_string(&phase),
+                    remaining.max(0) as u64,
+                    cycle_count,
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 保存当前活动的番茄钟会话（阶段、时长、剩余时间、周期数和运行状态）
+    pub async fn save_active_session(&self, session: &PomodoroSession) -> Result<()> {
+        let conn = self.get_conn();
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO active_pomodoro
+                (id, phase, duration_seconds, remaining_seconds, cycle_count, is_running, updated_at)
+            VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                pomodoro_phase_to_db_string(session.phase),
+                session.duration as i64,
+                session.remaining as i64,
+                session.cycle_count,
+                session.is_running as i32,
+                Utc::now()
+            ],
+        )
+        .map_err(AppError::Database)?;
+
+        Ok(())
+    }
+
+    /// 读取保存的活动会话
+    ///
+    /// `started_at` 无法持久化，恢复后为 `None`，`remaining` 直接取保存的值。
+    /// 剩余时间为 0 的会话已经结束，会被清除并返回 `None`。
+    pub async fn load_active_session(&self) -> Result<Option<PomodoroSession>> {
+        let conn = self.get_conn();
+
+        let saved = conn
+            .query_row(
+                r#"
+                SELECT phase, duration_seconds, remaining_seconds, cycle_count, is_running
+                FROM active_pomodoro WHERE id = 1
+                "#,
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, String>("phase")?,
+                        row.get::<_, i64>("duration_seconds")?,
+                        row.get::<_, i64>("remaining_seconds")?,
+                        row.get::<_, u32>("cycle_count")?,
+                        row.get::<_, bool>("is_running")?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(AppError::Database)?;
+
+        let Some((phase, duration, remaining, cycle_count, is_running)) = saved else {
+            return Ok(None);
+        };
+        if remaining <= 0 {
+            conn.execute("DELETE FROM active_pomodoro WHERE id = 1", [])
+                .map_err(AppError::Database)?;
+            return Ok(None);
+        }
+
+        let mut session = PomodoroSession::new(PomodoroConfig::default());
+        session.phase = pomodoro_phase_from_db_string(&phase);
+        session.duration = duration.max(0) as u64;
+        session.remaining = (remaining as u64).min(session.duration);
+        session.cycle_count = cycle_count;
+        session.is_running = is_running;
+        session.started_at = None;
+        Ok(Some(session))
+    }
+
+    /// 统计当前带有指定标签的任务累计的专注时长（分钟），汇总秒数后按 `rounding` 换算
+    ///
+    /// 按任务当前的标签计算：任务移除该标签后，它的历史会话不再计入。
+    pub async fn get_focus_time_for_tag(
+        &self,
+        tag_id: &str,
+        rounding: MinuteRounding,
+    ) -> Result<u64> {
+        let conn = self.read_conn();
+
+        let seconds: i64 = conn
+            .query_row(
+                r#"
+                SELECT COALESCE(SUM(ps.duration_seconds), 0)
+                FROM pomodoro_sessions ps
+                INNER JOIN todo_tags tt ON tt.todo_id = ps.focus_todo_id
+                WHERE tt.tag_id = ?1 AND ps.phase IN ('work', 'deep_work')
+                "#,
+                params![tag_id],
+                |row| row.get(0),
+            )
+            .map_err(AppError::Database)?;
+
+        Ok(rounding.minutes(seconds.max(0) as u64))
+    }
+
+    /// 按累计专注时长从高到低列出任务及其专注分钟数，最多 `limit` 个
+    ///
+    /// 只统计工作阶段，汇总秒数后按 `rounding` 换算；没有专注记录和已删除的任务不计入，
+    /// 时长相同时较早创建的任务在前。
+    pub async fn get_top_focused_todos(
+        &self,
+        limit: u32,
+        rounding: MinuteRounding,
+    ) -> Result<Vec<(Todo, u64)>> {
+        let conn = self.read_conn();
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT t.*, SUM(ps.duration_seconds) AS focus_seconds
+                FROM pomodoro_sessions ps
+                INNER JOIN todos t ON t.id = ps.focus_todo_id
+                WHERE ps.phase IN ('work', 'deep_work') AND t.deleted_at IS NULL
+                GROUP BY t.id
+                HAVING focus_seconds > 0
+                ORDER BY focus_seconds DESC, t.created_at ASC
+                LIMIT ?1
+                "#,
+            )
+            .map_err(AppError::Database)?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok((row_to_todo(row)?, row.get::<_, i64>("focus_seconds")?))
+            })
+            .map_err(AppError::Database)?;
+
+        let mut todos = Vec::new();
+        let mut seconds = Vec::new();
+        for row in rows {
+            let (todo, focus_seconds) = row.map_err(AppError::Database)?;
+            todos.push(todo);
+            seconds.push(focus_seconds.max(0) as u64);
+        }
+        attach_tags(&conn, &mut todos).map_err(AppError::Database)?;
+
+        Ok(todos
+            .into_iter()
+            .zip(seconds)
+            .map(|(todo, seconds)| (todo, rounding.minutes(seconds)))
+            .collect())
+    }
+
+    /// 获取时间范围内完成的工作会话 (completed_at, duration_seconds)，按完成时间升序
+    pub async fn get_work_sessions_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, u32)>> {
+        let conn = self.read_conn();
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT completed_at, duration_seconds FROM pomodoro_sessions
+                WHERE phase IN ('work', 'deep_work') AND completed_at >= ?1 AND completed_at <= ?2
+                ORDER BY completed_at ASC, id ASC
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((row.get("completed_at")?, row.get("duration_seconds")?))
+            })
+            .map_err(AppError::Database)?;
+
+        let mut sessions = Vec::new();
+        for session in rows {
+            sessions.push(session.map_err(AppError::Database)?);
+        }
+
+        Ok(sessions)
+    }
 
-            let id = uuid::Uuid::new_v4().to_string();
-            let now = Utc::now();
+    /// 当前连续专注天数：有完成的工作会话的本地日期，从今天往前数到第一个空档
+    ///
+    /// 只有休息会话的日子不计入；今天还没有工作会话时从昨天算起。
+    pub async fn get_current_streak(&self) -> Result<u32> {
+        let conn = self.read_conn();
 
-            tx.execute(
+        // 日期与“今天”都由 SQLite 按本地时区换算，两边口径一致
+        let today: NaiveDate = conn
+            .query_row("SELECT DATE('now', 'localtime')", [], |row| row.get(0))
+            .map_err(AppError::Database)?;
+
+        let mut stmt = conn
+            .prepare(
                 r#"
-                INSERT INTO todos (id, title, description, status, created_at, updated_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                SELECT DISTINCT DATE(completed_at, 'localtime') AS day FROM pomodoro_sessions
+                WHERE phase IN ('work', 'deep_work')
+                ORDER BY day DESC
                 "#,
-                params![
-                    id,
-                    new_todo.title,
-                    new_todo.description,
-                    todo_status_to_db_string(&new_todo.status),
-                    now,
-                    now
-                ],
             )
             .map_err(AppError::Database)?;
 
-            tx.commit().map_err(AppError::Database)?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, NaiveDate>("day"))
+            .map_err(AppError::Database)?;
 
-            // 返回创建的任务
-            Ok(Todo {
-                id,
-                title: new_todo.title.clone(),
-                description: new_todo.description.clone(),
-                status: new_todo.status.clone(),
-                github_issue_id: None,
-                github_project_id: None,
-                github_issue_number: None,
-                created_at: now,
-                updated_at: now,
+        let mut days = Vec::new();
+        for day in rows {
+            days.push(day.map_err(AppError::Database)?);
+        }
+
+        Ok(crate::current_streak(days, today))
+    }
+
+    /// 有记录以来所有工作阶段的累计时长（秒），不含休息阶段
+    pub async fn get_lifetime_focus_seconds(&self) -> Result<u64> {
+        let conn = self.read_conn();
+
+        let seconds: i64 = conn
+            .query_row(
+                "SELECT COALESCE(SUM(duration_seconds), 0) FROM pomodoro_sessions WHERE phase IN ('work', 'deep_work')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(AppError::Database)?;
+
+        Ok(seconds.max(0) as u64)
+    }
+
+    /// 批量统计任务完成的工作番茄数，一次分组查询，避免看板逐个任务查询
+    ///
+    /// 返回的映射包含 `ids` 中的每个任务，没有专注记录的任务为 0。
+    pub async fn get_pomodoro_counts_for_todos(
+        &self,
+        ids: &[String],
+    ) -> Result<HashMap<String, u32>> {
+        let mut counts: HashMap<String, u32> = ids.iter().map(|id| (id.clone(), 0)).collect();
+        if ids.is_empty() {
+            return Ok(counts);
+        }
+
+        let conn = self.read_conn();
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT focus_todo_id, COUNT(*) FROM pomodoro_sessions
+                 WHERE phase IN ('work', 'deep_work') AND focus_todo_id IN ({})
+                 GROUP BY focus_todo_id",
+                placeholders
+            ))
+            .map_err(AppError::Database)?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(ids.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
             })
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+            .map_err(AppError::Database)?;
+        for row in rows {
+            let (id, count) = row.map_err(AppError::Database)?;
+            counts.insert(id, count);
+        }
+
+        Ok(counts)
     }
 
-    /// 获取所有任务
-    pub async fn get_all_todos(&self) -> Result<Vec<Todo>> {
-        let conn = Arc::clone(&self.conn);
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+    /// 统计任务完成的工作番茄数（含深度工作，不含休息）
+    pub async fn get_todo_pomodoro_count(&self, todo_id: &str) -> Result<u32> {
+        let conn = self.read_conn();
+        conn.query_row(
+            "SELECT COUNT(*) FROM pomodoro_sessions
+             WHERE phase IN ('work', 'deep_work') AND focus_todo_id = ?1",
+            params![todo_id],
+            |row| row.get(0),
+        )
+        .map_err(AppError::Database)
+    }
 
-            let mut stmt = conn
-                .prepare("SELECT * FROM todos ORDER BY created_at DESC")
-                .map_err(AppError::Database)?;
+    /// 已完成任务平均累计的专注时长（秒），只统计有专注记录的任务
+    pub async fn get_average_focus_seconds_per_completed_todo(&self) -> Result<Option<u64>> {
+        let conn = self.read_conn();
 
-            let todo_iter = stmt
-                .query_map([], row_to_todo)
-                .map_err(AppError::Database)?;
+        let average: Option<f64> = conn
+            .query_row(
+                r#"
+                SELECT AVG(total) FROM (
+                    SELECT SUM(ps.duration_seconds) AS total
+                    FROM pomodoro_sessions ps
+                    INNER JOIN todos t ON t.id = ps.focus_todo_id
+                    WHERE ps.phase IN ('work', 'deep_work') AND t.status = 'done' AND t.deleted_at IS NULL
+                    GROUP BY ps.focus_todo_id
+                )
+                "#,
+                [],
+                |row| row.get(0),
+            )
+            .map_err(AppError::Database)?;
 
-            let mut todos = Vec::new();
-            for todo_result in todo_iter {
-                todos.push(todo_result.map_err(AppError::Database)?);
-            }
+        Ok(average.map(|avg| avg.round().max(0.0) as u64))
+    }
 
-            Ok(todos)
+    /// 汇总 `since` 之后完成的番茄钟会话
+    ///
+    /// 深度工作计入工作会话；专注时间汇总秒数后按 `rounding` 换算为分钟；
+    /// 日均会话数按有记录的本地日期数计算。没有会话时返回 [`PomodoroStats::empty`]。
+    pub async fn get_pomodoro_stats(
+        &self,
+        since: DateTime<Utc>,
+        rounding: MinuteRounding,
+    ) -> Result<PomodoroStats> {
+        let conn = self.read_conn();
+
+        let (total, work, short_break, long_break, focus_seconds): (u32, u32, u32, u32, i64) = conn
+            .query_row(
+                r#"
+                SELECT
+                    COUNT(*),
+                    COALESCE(SUM(phase IN ('work', 'deep_work')), 0),
+                    COALESCE(SUM(phase = 'short_break'), 0),
+                    COALESCE(SUM(phase = 'long_break'), 0),
+                    COALESCE(SUM(CASE WHEN phase IN ('work', 'deep_work') THEN duration_seconds ELSE 0 END), 0)
+                FROM pomodoro_sessions
+                WHERE completed_at >= ?1
+                "#,
+                params![since],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .map_err(AppError::Database)?;
+
+        if total == 0 {
+            return Ok(PomodoroStats::empty());
+        }
+
+        // 按本地日期计数，与 `local_day_bounds` 划分的“一天”一致
+        let mut stmt = conn
+            .prepare("SELECT completed_at FROM pomodoro_sessions WHERE completed_at >= ?1")
+            .map_err(AppError::Database)?;
+        let rows = stmt
+            .query_map(params![since], |row| row.get::<_, DateTime<Utc>>(0))
+            .map_err(AppError::Database)?;
+        let mut days = HashSet::new();
+        for completed_at in rows {
+            days.insert(completed_at.map_err(AppError::Database)?.with_timezone(&Local).date_naive());
+        }
+
+        Ok(PomodoroStats {
+            total_sessions: total,
+            work_sessions: work,
+            short_break_sessions: short_break,
+            long_break_sessions: long_break,
+            total_focus_time: rounding.minutes(focus_seconds.max(0) as u64),
+            average_daily_sessions: total as f32 / days.len().max(1) as f32,
         })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    /// 根据ID获取任务
-    pub async fn get_todo_by_id(&self, id: &str) -> Result<Option<Todo>> {
-        let conn = Arc::clone(&self.conn);
-        let id = id.to_string();
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+    /// 统计 `from` 到 `to`（含）之间专注分钟数最多的本地星期几，返回该星期及其总分钟数
+    ///
+    /// 工作与深度工作都计入，汇总秒数后按 `rounding` 换算；并列时取靠前的星期（周一最前），
+    /// 区间内没有专注记录时返回 None。
+    pub async fn get_best_weekday(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        rounding: MinuteRounding,
+    ) -> Result<Option<(Weekday, u64)>> {
+        let conn = self.read_conn();
 
-            let mut stmt = conn
-                .prepare("SELECT * FROM todos WHERE id = ?1")
-                .map_err(AppError::Database)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT completed_at, duration_seconds FROM pomodoro_sessions
+                 WHERE phase IN ('work', 'deep_work') AND completed_at >= ?1 AND completed_at <= ?2",
+            )
+            .map_err(AppError::Database)?;
+        let rows = stmt
+            .query_map(params![from, to], |row| {
+                Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(AppError::Database)?;
 
-            let mut rows = stmt.query(params![id]).map_err(AppError::Database)?;
+        let mut seconds_by_weekday = [0u64; 7];
+        for row in rows {
+            let (completed_at, seconds) = row.map_err(AppError::Database)?;
+            let weekday = completed_at.with_timezone(&Local).weekday();
+            seconds_by_weekday[weekday.num_days_from_monday() as usize] += seconds.max(0) as u64;
+        }
 
-            if let Some(row) = rows.next().map_err(AppError::Database)? {
-                return Ok(Some(row_to_todo(row).map_err(AppError::Database)?));
-            }
+        // 按周一到周日的顺序比较，取最大值时保留最先出现的星期
+        let best = (0u8..7)
+            .filter_map(|index| Weekday::try_from(index).ok())
+            .map(|weekday| (weekday, seconds_by_weekday[weekday.num_days_from_monday() as usize]))
+            .filter(|(_, seconds)| *seconds > 0)
+            .min_by_key(|(weekday, seconds)| {
+                (std::cmp::Reverse(*seconds), weekday.num_days_from_monday())
+            })
+            .map(|(weekday, seconds)| (weekday, rounding.minutes(seconds)));
+        Ok(best)
+    }
 
-            Ok(None)
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+    /// 获取今日番茄钟会话：(阶段, 时长秒数, 周期, 专注任务 ID, 打断次数)
+    pub async fn get_today_pomodoro_sessions(
+        &self,
+    ) -> Result<Vec<(PomodoroPhase, u32, u32, Option<String>, u32)>> {
+        let conn = self.read_conn();
+
+        let mut stmt = conn.prepare(
+            "SELECT phase, duration_seconds, cycle_count, focus_todo_id, interruptions FROM pomodoro_sessions WHERE DATE(completed_at) = DATE('now') ORDER BY completed_at DESC"
+        ).map_err(AppError::Database)?;
+
+        let mut sessions = Vec::new();
+        let rows = stmt
+            .query_map([], |row| {
+                let phase_str: String = row.get("phase")?;
+                let phase = pomodoro_phase_from_db_string(&phase_str);
+
+                Ok((
+                    phase,
+                    row.get("duration_seconds")?,
+                    row.get("cycle_count")?,
+                    row.get("focus_todo_id")?,
+                    row.get("interruptions")?,
+                ))
+            })
+            .map_err(AppError::Database)?;
+
+        for session in rows {
+            sessions.push(session.map_err(AppError::Database)?);
+        }
+
+        Ok(sessions)
     }
 
-    /// 更新任务
-    pub async fn update_todo(&self, id: &str, updates: &TodoUpdate) -> Result<Option<Todo>> {
+    /// 分页获取 [from, to] 内完成的会话（最新在前），同时返回符合条件的总数
+    ///
+    /// `limit` 最大为 [`MAX_SESSION_PAGE_SIZE`]。
+    pub async fn get_sessions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<SessionPage> {
+        let conn = self.read_conn();
+
+        let total: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pomodoro_sessions WHERE completed_at >= ?1 AND completed_at <= ?2",
+                params![from, to],
+                |row| row.get(0),
+            )
+            .map_err(AppError::Database)?;
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, phase, duration_seconds, cycle_count, focus_todo_id, completed_at,
+                       manual_pauses, idle_auto_pauses, break_enforced_pauses, interruptions
+                FROM pomodoro_sessions
+                WHERE completed_at >= ?1 AND completed_at <= ?2
+                ORDER BY completed_at DESC, id DESC
+                LIMIT ?3 OFFSET ?4
+                "#,
+            )
+            .map_err(AppError::Database)?;
+
+        let limit = limit.min(MAX_SESSION_PAGE_SIZE);
+        let rows = stmt
+            .query_map(params![from, to, limit, offset], |row| {
+                let phase: String = row.get("phase")?;
+                Ok(SessionRecord {
+                    id: row.get("id")?,
+                    phase: pomodoro_phase_from_db_string(&phase),
+                    duration_seconds: row.get("duration_seconds")?,
+                    cycle_count: row.get("cycle_count")?,
+                    focus_todo_id: row.get("focus_todo_id")?,
+                    completed_at: row.get("completed_at")?,
+                    pause_counts: PauseCounts {
+                        manual: row.get("manual_pauses")?,
+                        idle_auto: row.get("idle_auto_pauses")?,
+                        break_enforced: row.get("break_enforced_pauses")?,
+                    },
+                    interruptions: row.get("interruptions")?,
+                })
+            })
+            .map_err(AppError::Database)?;
+
+        let mut sessions = Vec::new();
+        for session in rows {
+            sessions.push(session.map_err(AppError::Database)?);
+        }
+
+        Ok(SessionPage { sessions, total })
+    }
+
+    // ========================================================================
+    // 标签操作
+    // ========================================================================
+
+    /// 创建标签
+    ///
+    /// 保存去除首尾空白后的显示名；规范化后重名（如 "Work" 与 "work "）视为重复并拒绝。
+    pub async fn create_tag(&self, name: &str, color: &str) -> Result<Tag> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_string();
-        let updates = updates.clone();
-        tokio::task::spawn_blocking(move || {
-            let mut conn = conn
+        let name = name.trim().to_string();
+        let color = color.to_string();
+
+        self.run_blocking(move || {
+            let conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
-            let tx = conn.transaction().map_err(AppError::Database)?;
 
-            // 分别更新每个字段
-            if let Some(ref title) = updates.title {
-                tx.execute(
-                    "UPDATE todos SET title = ?, updated_at = ? WHERE id = ?",
-                    params![title, Utc::now(), id],
-                )
-                .map_err(AppError::Database)?;
-            }
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let normalized = Tag::normalize_name(&name);
 
-            if let Some(ref description) = updates.description {
-                // 处理 Option<Option<String>>
-                let desc_value = if let Some(ref desc) = *description {
-                    desc.as_str()
-                } else {
-                    ""
-                };
-                tx.execute(
-                    "UPDATE todos SET description = ?, updated_at = ? WHERE id = ?",
-                    params![desc_value, Utc::now(), id],
+            let existing: Option<String> = conn
+                .query_row(
+                    "SELECT name FROM tags WHERE normalized_name = ?1",
+                    params![normalized],
+                    |row| row.get(0),
                 )
+                .optional()
                 .map_err(AppError::Database)?;
+            if let Some(existing) = existing {
+                return Err(AppError::Validation(format!("标签已存在: {}", existing)));
             }
 
-            if let Some(ref status) = updates.status {
-                tx.execute(
-                    "UPDATE todos SET status = ?, updated_at = ? WHERE id = ?",
-                    params![todo_status_to_db_string(status), Utc::now(), id],
-                )
-                .map_err(AppError::Database)?;
-            }
+            conn.execute(
+                "INSERT INTO tags (id, name, normalized_name, color, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, name, normalized, color, now],
+            )
+            .map_err(AppError::Database)?;
 
-            if let Some(ref github_issue_id) = updates.github_issue_id {
-                tx.execute(
-                    "UPDATE todos SET github_issue_id = ?, updated_at = ? WHERE id = ?",
-                    params![github_issue_id, Utc::now(), id],
-                )
-                .map_err(AppError::Database)?;
-            }
+            Ok(Tag {
+                id,
+                name,
+                color,
+                created_at: now,
+            })
+        })
+        .await
+    }
 
-            if let Some(ref github_project_id) = updates.github_project_id {
-                tx.execute(
-                    "UPDATE todos SET github_project_id = ?, updated_at = ? WHERE id = ?",
-                    params![github_project_id, Utc::now(), id],
-                )
-                .map_err(AppError::Database)?;
-            }
+    /// 按规范化名称查找标签（忽略首尾空白与大小写）
+    pub async fn get_tag_by_normalized_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<Tag>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let normalized = Tag::normalize_name(name);
 
-            if let Some(ref github_issue_number) = updates.github_issue_number {
-                tx.execute(
-                    "UPDATE todos SET github_issue_number = ?, updated_at = ? WHERE id = ?",
-                    params![github_issue_number, Utc::now(), id],
-                )
-                .map_err(AppError::Database)?;
-            }
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
+
+            conn.query_row(
+                "SELECT id, name, color, created_at FROM tags WHERE normalized_name = ?1",
+                params![normalized],
+                row_to_tag,
+            )
+            .optional()
+            .map_err(AppError::Database)
+        })
+        .await
+    }
+
+    /// 获取所有标签（按创建时间倒序）
+    pub async fn get_all_tags(&self) -> Result<Vec<Tag>> {
+        self.get_all_tags_sorted(TagSort::default()).await
+    }
 
-            tx.commit().map_err(AppError::Database)?;
+    /// 按指定方式获取所有标签
+    ///
+    /// 排序总是以 rowid 作为次级键，保证同一秒内创建的标签顺序稳定。
+    pub async fn get_all_tags_sorted(&self, sort: TagSort) -> Result<Vec<Tag>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let order_by = match sort {
+            TagSort::CreatedAt => "created_at DESC, rowid DESC",
+            TagSort::Name => "name COLLATE NOCASE ASC, rowid ASC",
+        };
+
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
 
-            // 在同一个闭包内获取更新后的任务
             let mut stmt = conn
-                .prepare("SELECT * FROM todos WHERE id = ?1")
+                .prepare(&format!(
+                    "SELECT id, name, color, created_at FROM tags ORDER BY {}",
+                    order_by
+                ))
                 .map_err(AppError::Database)?;
 
-            let mut rows = stmt.query(params![id]).map_err(AppError::Database)?;
+            let mut tags = Vec::new();
+            let rows = stmt
+                .query_map([], row_to_tag)
+                .map_err(AppError::Database)?;
 
-            if let Some(row) = rows.next().map_err(AppError::Database)? {
-                return Ok(Some(row_to_todo(row).map_err(AppError::Database)?));
+            for tag_result in rows {
+                tags.push(tag_result.map_err(AppError::Database)?);
             }
 
-            Ok(None)
+            Ok(tags)
         })
         .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    /// 删除任务
-    pub async fn delete_todo(&self, id: &str) -> Result<bool> {
+    /// 修改标签名称和/或颜色，只更新提供的字段，标签不存在时返回 None
+    ///
+    /// 新名称与其他标签规范化后重名时返回 `Validation` 错误。
+    pub async fn update_tag(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Option<Tag>> {
         let conn = Arc::clone(&self.conn);
         let id = id.to_string();
-        tokio::task::spawn_blocking(move || {
+        let name = name.map(|n| n.trim().to_string());
+        let color = color.map(str::to_string);
+
+        self.run_blocking(move || {
             let mut conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
             let tx = conn.transaction().map_err(AppError::Database)?;
 
-            let rows_affected = tx
-                .execute("DELETE FROM todos WHERE id = ?1", params![id])
-                .map_err(AppError::Database)?;
+            let Some(mut tag) = tx
+                .query_row(
+                    "SELECT id, name, color, created_at FROM tags WHERE id = ?1",
+                    params![id],
+                    row_to_tag,
+                )
+                .optional()
+                .map_err(AppError::Database)?
+            else {
+                return Ok(None);
+            };
 
-            tx.commit().map_err(AppError::Database)?;
+            if let Some(name) = name {
+                let normalized = Tag::normalize_name(&name);
+                let existing: Option<String> = tx
+                    .query_row(
+                        "SELECT name FROM tags WHERE normalized_name = ?1 AND id != ?2",
+                        params![normalized, id],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .map_err(AppError::Database)?;
+                if let Some(existing) = existing {
+                    return Err(AppError::Validation(format!("标签已存在: {}", existing)));
+                }
 
-            Ok(rows_affected > 0)
+                tx.execute(
+                    "UPDATE tags SET name = ?1, normalized_name = ?2 WHERE id = ?3",
+                    params![name, normalized, id],
+                )
+                .map_err(|e| match e {
+                    rusqlite::Error::SqliteFailure(err, _)
+                        if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+                    {
+                        AppError::Validation(format!("标签已存在: {}", name))
+                    }
+                    other => AppError::Database(other),
+                })?;
+                tag.name = name;
+            }
+
+            if let Some(color) = color {
+                tx.execute("UPDATE tags SET color = ?1 WHERE id = ?2", params![color, id])
+                    .map_err(AppError::Database)?;
+                tag.color = color;
+            }
+
+            tx.commit().map_err(AppError::Database)?;
+            Ok(Some(tag))
         })
         .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    /// 永久删除任务
-    pub async fn permanently_delete_todo(&self, id: &str) -> Result<bool> {
+    /// 删除标签
+    pub async fn delete_tag(&self, id: &str) -> Result<bool> {
         let conn = Arc::clone(&self.conn);
         let id = id.to_string();
-        tokio::task::spawn_blocking(move || {
+
+        self.run_blocking(move || {
             let conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
 
             let rows_affected = conn
-                .execute("DELETE FROM todos WHERE id = ?1", params![id])
+                .execute("DELETE FROM tags WHERE id = ?1", params![id])
                 .map_err(AppError::Database)?;
 
             Ok(rows_affected > 0)
         })
         .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    /// 获取需要同步的任务
-    pub async fn get_pending_sync_todos(&self) -> Result<Vec<Todo>> {
+    /// 为待办事项添加标签
+    pub async fn add_tag_to_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
         let conn = Arc::clone(&self.conn);
-        tokio::task::spawn_blocking(move || {
+        let todo_id = todo_id.to_string();
+        let tag_id = tag_id.to_string();
+
+        self.run_blocking(move || {
             let conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
 
-            let mut stmt = conn
-                .prepare("SELECT * FROM todos WHERE sync_pending = 1")
-                .map_err(AppError::Database)?;
-
-            let todo_iter = stmt
-                .query_map([], row_to_todo)
-                .map_err(AppError::Database)?;
-
-            let mut todos = Vec::new();
-            for todo_result in todo_iter {
-                todos.push(todo_result.map_err(AppError::Database)?);
-            }
+            // 使用 INSERT OR IGNORE 避免重复
+            conn.execute(
+                "INSERT OR IGNORE INTO todo_tags (todo_id, tag_id) VALUES (?1, ?2)",
+                params![todo_id, tag_id],
+            )
+            .map_err(AppError::Database)?;
 
-            Ok(todos)
+            Ok(())
         })
         .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    /// 标记任务为已同步
-    pub async fn mark_todo_synced(&self, id: &str) -> Result<()> {
+    /// 从待办事项移除标签
+    pub async fn remove_tag_from_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_string();
-        tokio::task::spawn_blocking(move || {
+        let todo_id = todo_id.to_string();
+        let tag_id = tag_id.to_string();
+
+        self.run_blocking(move || {
             let conn = conn
                 .lock()
                 .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
 
             conn.execute(
-                "UPDATE todos SET sync_pending = 0 WHERE id = ?1",
-                params![id],
+                "DELETE FROM todo_tags WHERE todo_id = ?1 AND tag_id = ?2",
+                params![todo_id, tag_id],
             )
             .map_err(AppError::Database)?;
 
             Ok(())
         })
         .await
-        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    // ========================================================================
-    // 同步队列操作
-    // ========================================================================
+    /// 获取待办事项的所有标签
+    pub async fn get_todo_tags(&self, todo_id: &str) -> Result<Vec<Tag>> {
+        let conn = Arc::clone(&self.conn);
+        let readers = Arc::clone(&self.readers);
+        let todo_id = todo_id.to_string();
 
-    /// 添加到同步队列
-    pub async fn add_to_sync_queue(
-        &self,
-        operation_type: &str,
-        record_id: &str,
-        payload: &serde_json::Value,
-    ) -> Result<i64> {
-        let conn = self.get_conn();
+        self.run_blocking(move || {
+            let conn = readers.lock(&conn)?;
 
-        conn.execute(
-            r#"
-            INSERT INTO sync_queue (operation_type, table_name, record_id, payload)
-            VALUES (?1, 'todos', ?2, ?3)
-            "#,
-            params![operation_type, record_id, payload.to_string()],
-        )
-        .map_err(AppError::Database)?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT t.id, t.name, t.color, t.created_at FROM tags t 
+                     INNER JOIN todo_tags tt ON t.id = tt.tag_id 
+                     WHERE tt.todo_id = ?1 
+                     ORDER BY t.created_at DESC, t.rowid DESC",
+                )
+                .map_err(AppError::Database)?;
 
-        Ok(conn.last_insert_rowid())
+            let mut tags = Vec::new();
+            let rows = stmt
+                .query_map(params![todo_id], row_to_tag)
+                .map_err(AppError::Database)?;
+
+            for tag_result in rows {
+                tags.push(tag_result.map_err(AppError::Database)?);
+            }
+
+            Ok(tags)
+        })
+        .await
     }
+}
 
-    /// 获取待同步队列
-    pub async fn get_pending_sync_queue(&self) -> Result<Vec<(i64, String, String, String)>> {
-        let conn = self.get_conn();
+#[cfg(test)]
+mod tests {
+    use super::{Database, MaintenanceSummary, READ_POOL_SIZE};
+    use crate::core::error::{AppError, ValidationKind};
+    use crate::core::pomodoro::{PauseCounts, PomodoroPhase};
+    use crate::core::state::{MinuteRounding, UserConfig};
+    use crate::core::todo::{
+        NewTodo, SortKey, Tag, TagSort, Todo, TodoFilter, TodoPriority, TodoStats, TodoStatus, TodoUpdate,
+        TodoWithProgress, DEFAULT_SEARCH_LIMIT,
+    };
+    use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+    use rusqlite::params;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    async fn create_test_database() -> (TempDir, Database) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::init(&temp_dir.path().join("test.db")).await.unwrap();
+        (temp_dir, db)
+    }
 
-        let mut stmt = conn.prepare(
-            "SELECT id, operation_type, record_id, payload FROM sync_queue WHERE status = 'pending' ORDER BY priority DESC, created_at ASC"
-        ).map_err(AppError::Database)?;
+    async fn create_todo_due(
+        db: &Database,
+        title: &str,
+        status: TodoStatus,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Todo {
+        db.create_todo(&NewTodo {
+            title: title.to_string(),
+            status,
+            due_at,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+    }
 
-        let mut items = Vec::new();
-        let rows = stmt
-            .query_map([], |row| {
-                Ok((
-                    row.get("id")?,
-                    row.get("operation_type")?,
-                    row.get("record_id")?,
-                    row.get("payload")?,
-                ))
+    /// 把任务的完成时间改写为 `completed_at`，用于构造历史完成记录
+    fn complete_todo_at(db: &Database, id: &str, completed_at: DateTime<Utc>) {
+        db.get_conn()
+            .execute(
+                "UPDATE todos SET completed_at = ?1 WHERE id = ?2",
+                params![completed_at, id],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn tags_sort_deterministically_within_same_timestamp() {
+        let (_dir, db) = create_test_database().await;
+        let created_at = Utc.with_ymd_and_hms(2024, 6, 10, 8, 0, 0).unwrap();
+        {
+            let conn = db.get_conn();
+            for (id, name) in [("id-1", "beta"), ("id-2", "Alpha"), ("id-3", "gamma")] {
+                conn.execute(
+                    "INSERT INTO tags (id, name, color, created_at) VALUES (?1, ?2, '#007AFF', ?3)",
+                    rusqlite::params![id, name, created_at],
+                )
+                .unwrap();
+            }
+        }
+
+        let names = |tags: Vec<Tag>| tags.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(db.get_all_tags().await.unwrap()),
+            vec!["gamma", "Alpha", "beta"]
+        );
+        assert_eq!(
+            names(db.get_all_tags_sorted(TagSort::Name).await.unwrap()),
+            vec!["Alpha", "beta", "gamma"]
+        );
+    }
+
+    #[tokio::test]
+    async fn todo_notes_are_kept_in_order_and_validated() {
+        let (_dir, db) = create_test_database().await;
+        let todo = create_todo_due(&db, "with notes", TodoStatus::Todo, None).await;
+
+        let first = db.add_todo_note(&todo.id, "  开始调研  ").await.unwrap();
+        assert_eq!(first.body, "开始调研");
+        db.add_todo_note(&todo.id, "找到了根因").await.unwrap();
+        db.add_todo_note(&todo.id, "已修复").await.unwrap();
+
+        let bodies: Vec<String> = db
+            .get_todo_notes(&todo.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|note| note.body)
+            .collect();
+        assert_eq!(bodies, vec!["开始调研", "找到了根因", "已修复"]);
+
+        assert!(db.delete_todo_note(&first.id).await.unwrap());
+        assert!(!db.delete_todo_note(&first.id).await.unwrap());
+        assert_eq!(db.get_todo_notes(&todo.id).await.unwrap().len(), 2);
+
+        let err = db.add_todo_note(&todo.id, "   ").await.unwrap_err();
+        assert_eq!(err.validation_kind(), Some(ValidationKind::NoteEmpty));
+        let err = db.add_todo_note(&todo.id, &"x".repeat(2001)).await.unwrap_err();
+        assert_eq!(err.validation_kind(), Some(ValidationKind::NoteTooLong));
+        assert!(matches!(
+            db.add_todo_note("missing", "note").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn deleting_todo_cascades_to_notes() {
+        let (_dir, db) = create_test_database().await;
+        let todo = create_todo_due(&db, "doomed", TodoStatus::Todo, None).await;
+        let other = create_todo_due(&db, "kept", TodoStatus::Todo, None).await;
+        db.add_todo_note(&todo.id, "一").await.unwrap();
+        db.add_todo_note(&todo.id, "二").await.unwrap();
+        db.add_todo_note(&other.id, "保留").await.unwrap();
+
+        // 移入回收站时保留备注，永久删除才级联
+        assert!(db.delete_todo(&todo.id).await.unwrap());
+        assert_eq!(db.get_todo_notes(&todo.id).await.unwrap().len(), 2);
+
+        assert!(db.permanently_delete_todo(&todo.id).await.unwrap());
+        let remaining: i64 = db
+            .get_conn()
+            .query_row("SELECT COUNT(*) FROM todo_notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+        assert_eq!(db.get_todo_notes(&other.id).await.unwrap()[0].body, "保留");
+    }
+
+    #[tokio::test]
+    async fn user_config_round_trips_github_and_notification_fields() {
+        let (_dir, db) = create_test_database().await;
+        let config = UserConfig {
+            github_token_encrypted: "encrypted-token".to_string(),
+            github_username: "octocat".to_string(),
+            last_sync_cursor: Some("cursor-1".to_string()),
+            selected_project_owner: Some("owner".to_string()),
+            selected_project_repo: Some("repo".to_string()),
+            selected_project_number: Some(7),
+            notifications_enabled: false,
+            system_notifications: false,
+            pomodoro_auto_start_next: true,
+            pomodoro_warn_before_seconds: Some(60),
+            pomodoro_phase_sequence: Some(vec![PomodoroPhase::Work, PomodoroPhase::DeepWork]),
+            pomodoro_require_phase_ack: true,
+            pomodoro_break_reminders: true,
+            pomodoro_reminder_grace_secs: 120,
+            ..UserConfig::default()
+        };
+        db.save_user_config(&config).await.unwrap();
+
+        let loaded = db.load_user_config().await.unwrap().unwrap();
+        assert_eq!(loaded.github_token_encrypted, "encrypted-token");
+        assert_eq!(loaded.github_username, "octocat");
+        assert_eq!(loaded.last_sync_cursor.as_deref(), Some("cursor-1"));
+        assert_eq!(loaded.selected_project_owner.as_deref(), Some("owner"));
+        assert_eq!(loaded.selected_project_repo.as_deref(), Some("repo"));
+        assert_eq!(loaded.selected_project_number, Some(7));
+        assert!(!loaded.notifications_enabled);
+        assert!(!loaded.system_notifications);
+        assert!(loaded.pomodoro_auto_start_next);
+        assert_eq!(loaded.pomodoro_warn_before_seconds, Some(60));
+        assert_eq!(
+            loaded.pomodoro_phase_sequence,
+            Some(vec![PomodoroPhase::Work, PomodoroPhase::DeepWork])
+        );
+        assert!(loaded.pomodoro_require_phase_ack && loaded.pomodoro_break_reminders);
+        assert_eq!(loaded.pomodoro_reminder_grace_secs, 120);
+
+        db.save_user_config(&UserConfig::default()).await.unwrap();
+        assert!(db.load_user_config().await.unwrap().unwrap().system_notifications);
+    }
+
+    #[tokio::test]
+    async fn github_token_is_encrypted_at_rest() {
+        let (_dir, db) = create_test_database().await;
+        db.set_encryption_key([3; 32]).unwrap();
+        db.save_user_config(&UserConfig {
+            github_token_encrypted: "ghp_secret".to_string(),
+            github_username: "octocat".to_string(),
+            ..UserConfig::default()
+        })
+        .await
+        .unwrap();
+
+        let stored: String = db
+            .get_conn()
+            .query_row("SELECT github_token_encrypted FROM user_config WHERE id = 1", [], |row| {
+                row.get(0)
             })
-            .map_err(AppError::Database)?;
+            .unwrap();
+        assert!(!stored.contains("ghp_secret"));
+        assert_eq!(
+            db.load_user_config().await.unwrap().unwrap().github_token_encrypted,
+            "ghp_secret"
+        );
 
-        for item in rows {
-            items.push(item.map_err(AppError::Database)?);
+        // 密钥更换后只丢失令牌，其余配置照常加载
+        db.set_encryption_key([4; 32]).unwrap();
+        let loaded = db.load_user_config().await.unwrap().unwrap();
+        assert_eq!(loaded.github_token_encrypted, "");
+        assert_eq!(loaded.github_username, "octocat");
+    }
+
+    #[tokio::test]
+    async fn plaintext_token_is_encrypted_on_open() {
+        let (dir, db) = create_test_database().await;
+        db.get_conn()
+            .execute("UPDATE user_config SET github_token_encrypted = 'legacy-token' WHERE id = 1", [])
+            .unwrap();
+        drop(db);
+
+        let db = Database::init(&dir.path().join("test.db")).await.unwrap();
+        let stored: String = db
+            .get_conn()
+            .query_row("SELECT github_token_encrypted FROM user_config WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_ne!(stored, "legacy-token");
+        assert_eq!(
+            db.load_user_config().await.unwrap().unwrap().github_token_encrypted,
+            "legacy-token"
+        );
+    }
+
+    #[tokio::test]
+    async fn ensure_config_row_inserts_defaults_into_empty_table() {
+        let (_dir, db) = create_test_database().await;
+        assert!(!db.ensure_config_row().await.unwrap());
+
+        db.get_conn().execute("DELETE FROM user_config", []).unwrap();
+        assert!(db.load_user_config().await.unwrap().is_none());
+
+        assert!(db.ensure_config_row().await.unwrap());
+        let config = db.load_user_config().await.unwrap().unwrap();
+        assert_eq!(config.pomodoro_work_duration, 1500);
+        assert_eq!(config.theme, "light");
+    }
+
+    #[tokio::test]
+    async fn ensure_config_row_removes_stray_rows() {
+        let (_dir, db) = create_test_database().await;
+        {
+            let conn = db.get_conn();
+            // 模拟旧版本或手工修改留下的多余行（绕过 id = 1 的检查约束）
+            conn.execute_batch(
+                "PRAGMA ignore_check_constraints = ON;
+                 INSERT INTO user_config (id, github_token_encrypted, github_username, theme)
+                 VALUES (2, '', 'bogus', 'dark');
+                 PRAGMA ignore_check_constraints = OFF;",
+            )
+            .unwrap();
         }
 
-        Ok(items)
+        assert!(db.ensure_config_row().await.unwrap());
+        let rows: i64 = db
+            .get_conn()
+            .query_row("SELECT COUNT(*) FROM user_config", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(db.load_user_config().await.unwrap().unwrap().github_username, "");
     }
 
-    /// 检查某条记录是否仍有待处理同步
-    pub async fn has_pending_sync_for_record(&self, record_id: &str) -> Result<bool> {
-        let conn = self.get_conn();
-        let mut stmt = conn
-            .prepare(
-                "SELECT COUNT(*) FROM sync_queue WHERE record_id = ?1 AND status = 'pending'",
+    #[tokio::test]
+    async fn untagged_todos_excludes_tagged_and_done() {
+        let (_dir, db) = create_test_database().await;
+        let tag_id = db.create_tag("work", "#007AFF").await.unwrap().id;
+        let tagged = create_todo_due(&db, "tagged", TodoStatus::Todo, None).await;
+        db.add_tag_to_todo(&tagged.id, &tag_id).await.unwrap();
+        let untagged = create_todo_due(&db, "untagged", TodoStatus::InProgress, None).await;
+        create_todo_due(&db, "untagged-done", TodoStatus::Done, None).await;
+
+        let todos = db.get_untagged_todos().await.unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].id, untagged.id);
+    }
+
+    #[tokio::test]
+    async fn search_is_capped_by_default_limit() {
+        let (_dir, db) = create_test_database().await;
+        for i in 0..DEFAULT_SEARCH_LIMIT + 5 {
+            create_todo_due(&db, &format!("Report {}", i), TodoStatus::Todo, None).await;
+        }
+        create_todo_due(&db, "50%_off", TodoStatus::Todo, None).await;
+
+        let capped = db
+            .search_todos_capped(&TodoFilter::all().with_search("report".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(capped.todos.len(), DEFAULT_SEARCH_LIMIT);
+        assert!(capped.truncated);
+
+        let few = db
+            .search_todos_capped(&TodoFilter::all().with_search("Report 10".to_string()))
+            .await
+            .unwrap();
+        // "Report 10" 以及 "Report 100" ~ "Report 109"
+        assert_eq!(few.todos.len(), 11);
+        assert!(!few.truncated);
+
+        let literal = db
+            .search_todos_capped(&TodoFilter::all().with_search("%_".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(literal.todos.len(), 1);
+        assert_eq!(literal.todos[0].title, "50%_off");
+        assert!(!literal.truncated);
+    }
+
+    #[tokio::test]
+    async fn status_distribution_follows_history_per_day() {
+        let (_dir, db) = create_test_database().await;
+        let today = Local::now().date_naive();
+        let local_at = |days: i64, hour: u32, minute: u32| {
+            (today + chrono::Duration::days(days))
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let set_status = |id: &str, status: &str, changed_at: DateTime<Utc>| {
+            db.get_conn()
+                .execute(
+                    "UPDATE todos SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                    rusqlite::params![status, changed_at, id],
+                )
+                .unwrap();
+        };
+
+        let a = create_todo_due(&db, "a", TodoStatus::Todo, None).await;
+        let b = create_todo_due(&db, "b", TodoStatus::Todo, None).await;
+        let removed = create_todo_due(&db, "removed", TodoStatus::Todo, None).await;
+        db.delete_todo(&removed.id).await.unwrap();
+        set_status(&a.id, "in_progress", local_at(1, 12, 0));
+        // 本地时间的当天凌晨与深夜都归入同一天
+        set_status(&a.id, "done", local_at(2, 0, 30));
+        set_status(&b.id, "in_progress", local_at(2, 23, 30));
+
+        let stats = |total, todo, in_progress, done| TodoStats {
+            total,
+            todo,
+            in_progress,
+            done,
+        };
+        let distribution = db
+            .get_status_distribution_by_day(
+                today - chrono::Duration::days(1),
+                today + chrono::Duration::days(3),
             )
-            .map_err(AppError::Database)?;
-        let count: i64 = stmt
-            .query_row(params![record_id], |row| row.get(0))
-            .map_err(AppError::Database)?;
-        Ok(count > 0)
+            .await
+            .unwrap();
+        let expected: Vec<(NaiveDate, TodoStats)> = [
+            stats(0, 0, 0, 0),
+            stats(2, 2, 0, 0),
+            stats(2, 1, 1, 0),
+            stats(2, 0, 1, 1),
+            stats(2, 0, 1, 1),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| (today + chrono::Duration::days(i as i64 - 1), s))
+        .collect();
+        assert_eq!(distribution, expected);
+
+        assert!(db
+            .get_status_distribution_by_day(today, today - chrono::Duration::days(1))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn completion_velocity_averages_over_window() {
+        let (_dir, db) = create_test_database().await;
+        assert_eq!(db.get_completion_velocity(7).await.unwrap(), 0.0);
+
+        let now = Utc::now();
+        for (title, days_ago) in [("a", 0), ("b", 1), ("c", 1), ("d", 3), ("e", 6), ("old", 10)] {
+            let todo = create_todo_due(&db, title, TodoStatus::Done, None).await;
+            complete_todo_at(&db, &todo.id, now - chrono::Duration::days(days_ago));
+        }
+        create_todo_due(&db, "open", TodoStatus::Todo, None).await;
+
+        assert!((db.get_completion_velocity(7).await.unwrap() - 5.0 / 7.0).abs() < 1e-6);
+        assert!((db.get_completion_velocity(2).await.unwrap() - 1.5).abs() < 1e-6);
+        assert_eq!(db.get_completion_velocity(0).await.unwrap(), 0.0);
+    }
+
+    #[tokio::test]
+    async fn todos_sorted_by_each_key_with_id_tiebreak() {
+        let (_dir, db) = create_test_database().await;
+        let base = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        let hours = |h: i64| base + chrono::Duration::hours(h);
+        // (标题, 创建, 更新, 截止, 优先级, 手动顺序)
+        let rows = [
+            ("banana", 1, 4, Some(30), "high", 2),
+            ("Apple", 2, 3, None, "medium", 1),
+            ("cherry", 3, 3, Some(10), "high", 1),
+            ("apple", 4, 1, Some(10), "low", 0),
+        ];
+        let mut ids = Vec::new();
+        for (title, created, updated, due, priority, sort_order) in rows {
+            let todo = create_todo_due(&db, title, TodoStatus::Todo, due.map(hours)).await;
+            db.get_conn()
+                .execute(
+                    "UPDATE todos SET created_at = ?1, updated_at = ?2, priority = ?3, sort_order = ?4
+                     WHERE id = ?5",
+                    rusqlite::params![hours(created), hours(updated), priority, sort_order, todo.id],
+                )
+                .unwrap();
+            ids.push(todo.id);
+        }
+        let by_id = |a: usize, b: usize| if ids[a] < ids[b] { [a, b] } else { [b, a] };
+
+        let order = |sort_key, ascending| {
+            let db = &db;
+            let ids = &ids;
+            async move {
+                db.query_todos_sorted(&TodoFilter::all(), sort_key, ascending)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| ids.iter().position(|id| *id == t.id).unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(order(SortKey::CreatedAt, true).await, vec![0, 1, 2, 3]);
+        assert_eq!(order(SortKey::CreatedAt, false).await, vec![3, 2, 1, 0]);
+
+        // 更新时间相同的两条按 ID 升序
+        let tied = by_id(1, 2);
+        assert_eq!(order(SortKey::UpdatedAt, false).await, vec![0, tied[0], tied[1], 3]);
+        assert_eq!(order(SortKey::UpdatedAt, true).await, vec![3, tied[0], tied[1], 0]);
+
+        // 没有截止时间的任务在两个方向上都排在最后
+        let tied = by_id(2, 3);
+        assert_eq!(order(SortKey::DueDate, true).await, vec![tied[0], tied[1], 0, 1]);
+        assert_eq!(order(SortKey::DueDate, false).await, vec![0, tied[0], tied[1], 1]);
+
+        // 标题忽略大小写，"Apple" 与 "apple" 按 ID 升序
+        let tied = by_id(1, 3);
+        assert_eq!(order(SortKey::Title, true).await, vec![tied[0], tied[1], 0, 2]);
+        assert_eq!(order(SortKey::Title, false).await, vec![2, 0, tied[0], tied[1]]);
+
+        // 两个高优先级任务按 ID 升序
+        let tied = by_id(0, 2);
+        assert_eq!(order(SortKey::Priority, false).await, vec![tied[0], tied[1], 1, 3]);
+        assert_eq!(order(SortKey::Priority, true).await, vec![3, 1, tied[0], tied[1]]);
+
+        // 手动顺序相同的两条按 ID 升序
+        let tied = by_id(1, 2);
+        assert_eq!(order(SortKey::Manual, true).await, vec![3, tied[0], tied[1], 0]);
+        assert_eq!(order(SortKey::Manual, false).await, vec![0, tied[0], tied[1], 3]);
+
+        // 内存排序与数据库在默认方向上的结果（含相同值的顺序）一致
+        let todos = db.get_all_todos().await.unwrap();
+        for (name, sort_key) in [
+            ("updated_at", SortKey::UpdatedAt),
+            ("created_at", SortKey::CreatedAt),
+            ("due_date", SortKey::DueDate),
+            ("title", SortKey::Title),
+            ("priority", SortKey::Priority),
+            ("manual", SortKey::Manual),
+        ] {
+            assert_eq!(SortKey::from_string(name).unwrap(), sort_key);
+            let in_memory: Vec<usize> = TodoFilter {
+                sort_by: Some(sort_key),
+                ..TodoFilter::all()
+            }
+            .apply(&todos)
+            .iter()
+            .map(|t| ids.iter().position(|id| *id == t.id).unwrap())
+            .collect();
+            assert_eq!(in_memory, order(sort_key, sort_key.default_ascending()).await, "{name}");
+        }
+        assert!(SortKey::from_string("random").is_err());
+    }
+
+    #[tokio::test]
+    async fn todo_priority_is_persisted_updated_and_sortable() {
+        let (_dir, db) = create_test_database().await;
+        let low = db
+            .create_todo(&NewTodo {
+                title: "low".to_string(),
+                priority: TodoPriority::Low,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let medium = create_todo_due(&db, "medium", TodoStatus::Todo, None).await;
+        let high = create_todo_due(&db, "high", TodoStatus::Todo, None).await;
+        assert_eq!(low.priority, TodoPriority::Low);
+        assert_eq!(medium.priority, TodoPriority::Medium);
+
+        let updated = db
+            .update_todo(&high.id, &TodoUpdate::new().with_priority(TodoPriority::High))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.priority, TodoPriority::High);
+        assert_eq!(
+            db.get_todo_by_id(&low.id).await.unwrap().unwrap().priority,
+            TodoPriority::Low
+        );
+
+        let titles = |todos: Vec<Todo>| todos.into_iter().map(|t| t.title).collect::<Vec<_>>();
+        let sorted = db
+            .query_todos_sorted(&TodoFilter::all(), SortKey::Priority, false)
+            .await
+            .unwrap();
+        assert_eq!(titles(sorted), vec!["high", "medium", "low"]);
+        let filter = TodoFilter {
+            sort_by: Some(SortKey::Priority),
+            ..TodoFilter::all()
+        };
+        assert_eq!(
+            titles(db.search_todos_capped(&filter).await.unwrap().todos),
+            vec!["high", "medium", "low"]
+        );
+        let all = db.get_all_todos().await.unwrap();
+        let applied: Vec<_> = filter.apply(&all).iter().map(|t| t.title.clone()).collect();
+        assert_eq!(applied, vec!["high", "medium", "low"]);
+    }
+
+    #[tokio::test]
+    async fn reorder_todos_rewrites_only_moved_rows() {
+        let (_dir, db) = create_test_database().await;
+        let mut ids = Vec::new();
+        for title in ["a", "b", "c", "d"] {
+            ids.push(create_todo_due(&db, title, TodoStatus::Todo, None).await.id);
+        }
+        // 新任务排在最前，当前手动顺序为 d c b a
+        let manual = TodoFilter {
+            sort_by: Some(SortKey::Manual),
+            ..TodoFilter::all()
+        };
+        let titles = |todos: Vec<Todo>| todos.into_iter().map(|t| t.title).collect::<Vec<_>>();
+        assert_eq!(
+            titles(db.search_todos_capped(&manual).await.unwrap().todos),
+            vec!["d", "c", "b", "a"]
+        );
+        let total_changes = || -> i64 {
+            db.get_conn()
+                .query_row("SELECT total_changes()", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        // 只交换 c 和 b：d、a 的位置不变，只写两行
+        let before = total_changes();
+        db.reorder_todos(&[ids[3].clone(), ids[1].clone(), ids[2].clone(), ids[0].clone()])
+            .await
+            .unwrap();
+        assert_eq!(total_changes() - before, 2);
+        let sorted = db
+            .query_todos_sorted(&TodoFilter::all(), SortKey::Manual, true)
+            .await
+            .unwrap();
+        assert_eq!(titles(sorted), vec!["d", "b", "c", "a"]);
+        let all = db.get_all_todos().await.unwrap();
+        let applied: Vec<_> = manual.apply(&all).iter().map(|t| t.title.clone()).collect();
+        assert_eq!(applied, vec!["d", "b", "c", "a"]);
+
+        // 子集重排只在子集原有位置间挪动
+        db.reorder_todos(&[ids[0].clone(), ids[3].clone()]).await.unwrap();
+        assert_eq!(
+            titles(db.search_todos_capped(&manual).await.unwrap().todos),
+            vec!["a", "b", "c", "d"]
+        );
+
+        let before = total_changes();
+        db.reorder_todos(&[ids[0].clone(), ids[1].clone()]).await.unwrap();
+        assert_eq!(total_changes(), before);
+
+        assert!(matches!(
+            db.reorder_todos(&[ids[0].clone(), "missing".to_string()]).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            db.reorder_todos(&[ids[0].clone(), ids[0].clone()]).await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn overdue_only_sql_filter_matches_in_memory_filter() {
+        let (_dir, db) = create_test_database().await;
+        let past = Utc::now() - chrono::Duration::hours(2);
+        create_todo_due(&db, "overdue", TodoStatus::Todo, Some(past)).await;
+        create_todo_due(&db, "done", TodoStatus::Done, Some(past)).await;
+        let later = Utc::now() + chrono::Duration::days(1);
+        create_todo_due(&db, "later", TodoStatus::Todo, Some(later)).await;
+        create_todo_due(&db, "undated", TodoStatus::Todo, None).await;
+
+        let filter = TodoFilter {
+            overdue_only: true,
+            ..TodoFilter::all()
+        };
+        let result = db.search_todos_capped(&filter).await.unwrap();
+        let titles: Vec<_> = result.todos.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["overdue"]);
+
+        let all = db.get_all_todos().await.unwrap();
+        assert_eq!(filter.apply(&all).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn completed_on_counts_by_completion_date_not_edits() {
+        let (_dir, db) = create_test_database().await;
+        let today = Local::now().date_naive();
+        let yesterday = today - chrono::Duration::days(1);
+        create_todo_due(&db, "today", TodoStatus::Done, None).await;
+        let earlier = create_todo_due(&db, "yesterday", TodoStatus::Done, None).await;
+        create_todo_due(&db, "open", TodoStatus::Todo, None).await;
+
+        let (yesterday_start, _) = crate::local_day_bounds(yesterday);
+        complete_todo_at(&db, &earlier.id, yesterday_start + chrono::Duration::hours(12));
+        // 今天编辑昨天完成的任务，不应算作今天完成
+        db.update_todo(&earlier.id, &TodoUpdate::new().with_title("edited".to_string()))
+            .await
+            .unwrap()
+            .unwrap();
+        db.update_todo(&earlier.id, &TodoUpdate::new().with_status(TodoStatus::Done))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(db.count_completed_on(today).await.unwrap(), 1);
+        assert_eq!(db.count_completed_on(yesterday).await.unwrap(), 1);
+        assert_eq!(db.count_completed_on(today - chrono::Duration::days(2)).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn current_streak_counts_local_days_with_work_sessions() {
+        let (_dir, db) = create_test_database().await;
+        assert_eq!(db.get_current_streak().await.unwrap(), 0);
+
+        let today = Local::now().date_naive();
+        let record_on = |phase, days_ago: i64| {
+            let db = &db;
+            async move {
+                db.record_pomodoro_session(phase, 1500, 1, None, None)
+                    .await
+                    .unwrap();
+                let (day_start, _) = crate::local_day_bounds(today - chrono::Duration::days(days_ago));
+                db.get_conn()
+                    .execute(
+                        "UPDATE pomodoro_sessions SET completed_at = ?1 WHERE id = last_insert_rowid()",
+                        rusqlite::params![day_start + chrono::Duration::hours(12)],
+                    )
+                    .unwrap();
+            }
+        };
+
+        record_on(PomodoroPhase::Work, 4).await;
+        record_on(PomodoroPhase::DeepWork, 3).await;
+        // 只有休息的日子打断连续天数
+        record_on(PomodoroPhase::ShortBreak, 2).await;
+        record_on(PomodoroPhase::Work, 1).await;
+        assert_eq!(db.get_current_streak().await.unwrap(), 1);
+
+        record_on(PomodoroPhase::Work, 0).await;
+        record_on(PomodoroPhase::Work, 0).await;
+        assert_eq!(db.get_current_streak().await.unwrap(), 2);
+
+        record_on(PomodoroPhase::Work, 2).await;
+        assert_eq!(db.get_current_streak().await.unwrap(), 5);
     }
 
-    /// 标记同步队列项为已同步
-    pub async fn mark_sync_queue_synced(&self, id: i64) -> Result<()> {
-        let conn = self.get_conn();
+    #[tokio::test]
+    async fn completed_between_uses_the_latest_completion() {
+        let (_dir, db) = create_test_database().await;
+        let todo = create_todo_due(&db, "recompleted", TodoStatus::Done, None).await;
+        let other = create_todo_due(&db, "other", TodoStatus::Done, None).await;
+        create_todo_due(&db, "open", TodoStatus::Todo, None).await;
+        let first_completion = todo.completed_at.unwrap();
+
+        let last_week = Utc::now() - chrono::Duration::days(7);
+        complete_todo_at(&db, &todo.id, last_week);
+        complete_todo_at(&db, &other.id, last_week);
+
+        // Done → Todo → Done：完成时间更新为最近一次完成
+        let reopened = db
+            .update_todo(&todo.id, &TodoUpdate::new().with_status(TodoStatus::Todo))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.completed_at, None);
+        let redone = db
+            .update_todo(&todo.id, &TodoUpdate::new().with_status(TodoStatus::Done))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(redone.completed_at.unwrap() >= first_completion);
+
+        let recent = db
+            .get_todos_completed_between(Utc::now() - chrono::Duration::days(1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(recent.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec![todo.id.as_str()]);
+
+        let older = db
+            .get_todos_completed_between(last_week, last_week)
+            .await
+            .unwrap();
+        assert_eq!(older.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec![other.id.as_str()]);
+    }
 
-        conn.execute(
-            "UPDATE sync_queue SET status = 'synced', synced_at = ?1 WHERE id = ?2",
-            params![Utc::now(), id],
-        )
-        .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn swap_todo_order_exchanges_positions() {
+        let (_dir, db) = create_test_database().await;
+        let a = create_todo_due(&db, "a", TodoStatus::Todo, None).await;
+        let b = create_todo_due(&db, "b", TodoStatus::Todo, None).await;
+        assert!(b.sort_order < a.sort_order);
+
+        assert!(db.swap_todo_order(&a.id, &b.id).await.unwrap());
+        let swapped_a = db.get_todo_by_id(&a.id).await.unwrap().unwrap();
+        let swapped_b = db.get_todo_by_id(&b.id).await.unwrap().unwrap();
+        assert_eq!(swapped_a.sort_order, b.sort_order);
+        assert_eq!(swapped_b.sort_order, a.sort_order);
+
+        assert!(!db.swap_todo_order(&a.id, "missing").await.unwrap());
+        assert_eq!(
+            db.get_todo_by_id(&a.id).await.unwrap().unwrap().sort_order,
+            b.sort_order
+        );
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn loaded_todos_carry_their_tags() {
+        let (_dir, db) = create_test_database().await;
+        let tagged = create_todo_due(&db, "tagged", TodoStatus::Todo, None).await;
+        let plain = create_todo_due(&db, "plain", TodoStatus::Todo, None).await;
+        let work = db.create_tag("work", "#007AFF").await.unwrap();
+        let home = db.create_tag("home", "#00FF00").await.unwrap();
+        db.add_tag_to_todo(&tagged.id, &work.id).await.unwrap();
+        db.add_tag_to_todo(&tagged.id, &home.id).await.unwrap();
+
+        let all = db.get_all_todos().await.unwrap();
+        let find = |id: &str| all.iter().find(|t| t.id == id).unwrap();
+        assert_eq!(find(&tagged.id).tags, db.get_todo_tags(&tagged.id).await.unwrap());
+        assert!(find(&plain.id).tags.is_empty());
+
+        let filter = TodoFilter::all().with_tags(vec![work.id.clone(), home.id.clone()]);
+        let result = db.search_todos_capped(&filter).await.unwrap();
+        assert_eq!(result.todos.len(), 1);
+        assert_eq!(result.todos[0].id, tagged.id);
+        assert_eq!(result.todos[0].tags.len(), 2);
+        assert_eq!(filter.apply(&all).len(), 1);
     }
 
-    /// 标记同步队列项为失败
-    pub async fn mark_sync_queue_failed(&self, id: i64, error_message: &str) -> Result<()> {
-        let conn = self.get_conn();
+    #[tokio::test]
+    async fn blank_search_is_ignored_by_sql_filter() {
+        let (_dir, db) = create_test_database().await;
+        create_todo_due(&db, "Write report", TodoStatus::Todo, None).await;
+        create_todo_due(&db, "Buy milk", TodoStatus::Todo, None).await;
 
-        conn.execute(
-            "UPDATE sync_queue SET status = 'failed', error_message = ?1 WHERE id = ?2",
-            params![error_message, id],
-        )
-        .map_err(AppError::Database)?;
+        let raw = TodoFilter {
+            search: Some("  ".to_string()),
+            ..TodoFilter::all()
+        };
+        let result = db.search_todos_capped(&raw).await.unwrap();
+        assert_eq!(result.todos.len(), 2);
+
+        let real = db
+            .search_todos_capped(&TodoFilter::all().with_search("milk".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(real.todos.len(), 1);
+        assert_eq!(real.todos[0].title, "Buy milk");
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn auto_archive_only_archives_old_done_todos() {
+        let (_dir, db) = create_test_database().await;
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for (title, days_ago) in [("recent", 10), ("old", 40)] {
+            let todo = create_todo_due(&db, title, TodoStatus::Done, None).await;
+            complete_todo_at(&db, &todo.id, now - chrono::Duration::days(days_ago));
+            ids.push(todo.id);
+        }
+        let open = create_todo_due(&db, "open", TodoStatus::Todo, None).await;
+
+        assert_eq!(db.auto_archive_done_todos(30).await.unwrap(), 1);
+        assert_eq!(db.auto_archive_done_todos(30).await.unwrap(), 0);
+
+        let archived = |id: &str| {
+            let db = &db;
+            let id = id.to_string();
+            async move { db.get_todo_by_id(&id).await.unwrap().unwrap().archived }
+        };
+        assert!(!archived(&ids[0]).await);
+        assert!(archived(&ids[1]).await);
+        assert!(!archived(&open.id).await);
     }
 
-    /// 清理已同步的队列项（保留最新的100条）
-    pub async fn cleanup_sync_queue(&self) -> Result<usize> {
-        let conn = self.get_conn();
+    #[tokio::test]
+    async fn lifetime_focus_counts_only_work_sessions() {
+        let (_dir, db) = create_test_database().await;
+        assert_eq!(db.get_lifetime_focus_seconds().await.unwrap(), 0);
+
+        for (phase, seconds) in [
+            (PomodoroPhase::Work, 1500),
+            (PomodoroPhase::ShortBreak, 300),
+            (PomodoroPhase::Work, 1200),
+            (PomodoroPhase::LongBreak, 900),
+        ] {
+            db.record_pomodoro_session(phase, seconds, 1, None, None)
+                .await
+                .unwrap();
+        }
 
-        // 获取需要清理的记录数
-        let mut stmt = conn
-            .prepare("SELECT COUNT(*) FROM sync_queue WHERE status = 'synced'")
-            .map_err(AppError::Database)?;
+        assert_eq!(db.get_lifetime_focus_seconds().await.unwrap(), 2700);
+    }
 
-        let count: i64 = stmt
-            .query_row([], |row| row.get(0))
-            .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn recording_same_session_uuid_twice_inserts_one_row() {
+        let (_dir, db) = create_test_database().await;
 
-        if count <= 100 {
-            return Ok(0);
+        for _ in 0..2 {
+            db.record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, Some("run-1"))
+                .await
+                .unwrap();
         }
+        assert!(!db
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, Some("run-1"))
+            .await
+            .unwrap());
+        assert!(db
+            .record_pomodoro_session(PomodoroPhase::Work, 1500, 2, None, Some("run-2"))
+            .await
+            .unwrap());
+
+        let rows: i64 = db
+            .get_conn()
+            .query_row("SELECT COUNT(*) FROM pomodoro_sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 2);
+        assert_eq!(db.get_lifetime_focus_seconds().await.unwrap(), 3000);
+    }
 
-        let to_delete = count - 100;
+    #[tokio::test]
+    async fn active_session_round_trips_and_clears_finished_sessions() {
+        let (_dir, db) = create_test_database().await;
+        assert!(db.load_active_session().await.unwrap().is_none());
+
+        let mut session = crate::core::pomodoro::PomodoroSession::new(Default::default());
+        session.switch_to_phase(PomodoroPhase::LongBreak).unwrap();
+        session.start().unwrap();
+        session.remaining = 321;
+        session.cycle_count = 4;
+        db.save_active_session(&session).await.unwrap();
+
+        let restored = db.load_active_session().await.unwrap().unwrap();
+        assert_eq!(restored.phase, PomodoroPhase::LongBreak);
+        assert_eq!(restored.duration, session.duration);
+        assert_eq!(restored.remaining, 321);
+        assert_eq!(restored.cycle_count, 4);
+        assert!(restored.is_running);
+        assert!(restored.started_at.is_none());
+
+        session.remaining = 0;
+        db.save_active_session(&session).await.unwrap();
+        assert!(db.load_active_session().await.unwrap().is_none());
+        let rows: i64 = db
+            .get_conn()
+            .query_row("SELECT COUNT(*) FROM active_pomodoro", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
 
-        conn.execute(
-            "DELETE FROM sync_queue WHERE status = 'synced' AND id IN (
-                SELECT id FROM sync_queue WHERE status = 'synced' ORDER BY synced_at ASC LIMIT ?
-            )",
-            params![to_delete],
-        )
-        .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn pomodoro_stats_aggregate_sessions_by_phase_and_day() {
+        let (_dir, db) = create_test_database().await;
+        let epoch = DateTime::<Utc>::UNIX_EPOCH;
+        assert_eq!(
+            db.get_pomodoro_stats(epoch, MinuteRounding::Round).await.unwrap(),
+            crate::core::pomodoro::PomodoroStats::empty()
+        );
 
-        Ok(to_delete as usize)
+        for (phase, seconds) in [
+            (PomodoroPhase::Work, 1500),
+            (PomodoroPhase::ShortBreak, 300),
+            (PomodoroPhase::DeepWork, 5430),
+            (PomodoroPhase::LongBreak, 900),
+            (PomodoroPhase::Work, 1500),
+        ] {
+            db.record_pomodoro_session(phase, seconds, 1, None, None)
+                .await
+                .unwrap();
+        }
+        // 按本地时间分布在两天：同一本地日期的凌晨和深夜算作一天，日均按两天计算
+        let local = |day: u32, hour: u32, minute: u32| {
+            chrono::Local
+                .with_ymd_and_hms(2026, 1, day, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        for (id, completed_at) in [
+            (1, local(5, 0, 30)),
+            (2, local(5, 23, 30)),
+            (3, local(6, 12, 0)),
+            (4, local(6, 12, 0)),
+            (5, local(6, 12, 0)),
+        ] {
+            db.get_conn()
+                .execute(
+                    "UPDATE pomodoro_sessions SET completed_at = ?1 WHERE id = ?2",
+                    rusqlite::params![completed_at, id],
+                )
+                .unwrap();
+        }
+
+        let stats = db.get_pomodoro_stats(epoch, MinuteRounding::Round).await.unwrap();
+        assert_eq!(stats.total_sessions, 5);
+        assert_eq!(stats.work_sessions, 3);
+        assert_eq!(stats.short_break_sessions, 1);
+        assert_eq!(stats.long_break_sessions, 1);
+        // 1500 + 5430 + 1500 = 8430 秒 = 140.5 分钟，按设置的方式取整
+        assert_eq!(stats.total_focus_time, 141);
+        assert_eq!(stats.average_daily_sessions, 2.5);
+        let floored = db.get_pomodoro_stats(epoch, MinuteRounding::Floor).await.unwrap();
+        assert_eq!(floored.total_focus_time, 140);
+
+        // 起点之后没有会话时同样返回空统计
+        assert_eq!(
+            db.get_pomodoro_stats(Utc::now() + chrono::Duration::hours(1), MinuteRounding::Round)
+                .await
+                .unwrap(),
+            crate::core::pomodoro::PomodoroStats::empty()
+        );
     }
 
-    // ========================================================================
-    // 番茄钟会话操作
-    // ========================================================================
+    #[tokio::test]
+    async fn best_weekday_picks_most_focused_local_weekday() {
+        let (_dir, db) = create_test_database().await;
+        let at = |day: u32| {
+            chrono::Local
+                .with_ymd_and_hms(2026, 1, day, 12, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let (from, to) = (at(1), at(31));
+        assert_eq!(db.get_best_weekday(from, to, MinuteRounding::Floor).await.unwrap(), None);
+
+        // 2026-01-05 是周一，01-06 和 01-13 是周二，01-08 是周四
+        for (phase, seconds, day) in [
+            (PomodoroPhase::Work, 1500, 5),
+            (PomodoroPhase::Work, 1500, 6),
+            (PomodoroPhase::DeepWork, 3000, 13),
+            (PomodoroPhase::Work, 30, 13),
+            (PomodoroPhase::LongBreak, 9000, 8),
+            (PomodoroPhase::Work, 1500, 8),
+        ] {
+            db.record_pomodoro_session(phase, seconds, 1, None, None)
+                .await
+                .unwrap();
+            db.get_conn()
+                .execute(
+                    "UPDATE pomodoro_sessions SET completed_at = ?1 WHERE id = last_insert_rowid()",
+                    rusqlite::params![at(day)],
+                )
+                .unwrap();
+        }
 
-    /// 记录番茄钟会话
-    pub async fn record_pomodoro_session(
-        &self,
-        phase: PomodoroPhase,
-        duration_seconds: u32,
-        cycle_count: u32,
-    ) -> Result<()> {
-        let conn = self.get_conn();
+        assert_eq!(
+            db.get_best_weekday(from, to, MinuteRounding::Floor).await.unwrap(),
+            Some((chrono::Weekday::Tue, 75))
+        );
+        // 周二共 4530 秒 = 75.5 分钟，汇总后再按设置取整
+        assert_eq!(
+            db.get_best_weekday(from, to, MinuteRounding::Round).await.unwrap(),
+            Some((chrono::Weekday::Tue, 76))
+        );
+        // 排除第二个周二后周一、周二、周四并列，取靠前的周一；休息不计入
+        assert_eq!(
+            db.get_best_weekday(from, at(12), MinuteRounding::Floor).await.unwrap(),
+            Some((chrono::Weekday::Mon, 25))
+        );
+        assert_eq!(db.get_best_weekday(at(20), to, MinuteRounding::Floor).await.unwrap(), None);
+    }
 
-        let phase_str = match phase {
-            PomodoroPhase::Work => "work",
-            PomodoroPhase::ShortBreak => "short_break",
-            PomodoroPhase::LongBreak => "long_break",
+    #[tokio::test]
+    async fn session_records_keep_pause_counts() {
+        let (_dir, db) = create_test_database().await;
+        let pauses = PauseCounts {
+            manual: 2,
+            idle_auto: 1,
+            break_enforced: 0,
+        };
+        db.record_pomodoro_session_with_pauses(PomodoroPhase::Work, 1500, 1, None, Some("run"), pauses, 3)
+            .await
+            .unwrap();
+        db.record_pomodoro_session(PomodoroPhase::ShortBreak, 300, 1, None, None)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let page = db
+            .get_sessions(now - chrono::Duration::hours(1), now, 0, 10)
+            .await
+            .unwrap();
+        let counts = |phase| {
+            page.sessions
+                .iter()
+                .find(|s| s.phase == phase)
+                .map(|s| s.pause_counts)
+                .unwrap()
         };
+        assert_eq!(counts(PomodoroPhase::Work), pauses);
+        assert_eq!(counts(PomodoroPhase::ShortBreak), PauseCounts::default());
+
+        let interruptions: Vec<_> = db
+            .get_today_pomodoro_sessions()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(phase, _, _, _, interruptions)| (phase, interruptions))
+            .collect();
+        assert!(interruptions.contains(&(PomodoroPhase::Work, 3)));
+        assert!(interruptions.contains(&(PomodoroPhase::ShortBreak, 0)));
+    }
 
-        conn.execute(
-            "INSERT INTO pomodoro_sessions (phase, duration_seconds, completed_at, cycle_count) VALUES (?1, ?2, ?3, ?4)",
-            params![phase_str, duration_seconds, Utc::now(), cycle_count]
-        ).map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn session_history_pages_by_offset_and_limit() {
+        let (_dir, db) = create_test_database().await;
+        let day = Utc.with_ymd_and_hms(2026, 2, 10, 0, 0, 0).unwrap();
+        let at = |minutes: i64| day + chrono::Duration::minutes(minutes);
+        // 当天 7 条会话，另有一条在前一天
+        for minutes in [60, 120, 180, 240, 300, 360, 420, -30] {
+            db.record_pomodoro_session(PomodoroPhase::Work, 1500, 1, None, None)
+                .await
+                .unwrap();
+            db.get_conn()
+                .execute(
+                    "UPDATE pomodoro_sessions SET completed_at = ?1, cycle_count = ?2 WHERE id = last_insert_rowid()",
+                    rusqlite::params![at(minutes), minutes / 60],
+                )
+                .unwrap();
+        }
+        let (from, to) = (day, at(24 * 60 - 1));
 
-        Ok(())
+        let first = db.get_sessions(from, to, 0, 3).await.unwrap();
+        assert_eq!(first.total, 7);
+        let cycles = |page: &crate::core::pomodoro::SessionPage| {
+            page.sessions.iter().map(|s| s.cycle_count).collect::<Vec<_>>()
+        };
+        assert_eq!(cycles(&first), vec![7, 6, 5]);
+        assert_eq!(first.sessions[0].completed_at, at(420));
+
+        let last = db.get_sessions(from, to, 6, 3).await.unwrap();
+        assert_eq!(last.total, 7);
+        assert_eq!(cycles(&last), vec![1]);
+
+        let beyond = db.get_sessions(from, to, 10, 3).await.unwrap();
+        assert!(beyond.sessions.is_empty());
+        assert_eq!(beyond.total, 7);
+
+        let all = db.get_sessions(from, to, 0, u32::MAX).await.unwrap();
+        assert_eq!(all.sessions.len(), 7);
     }
 
-    /// 获取今日番茄钟会话
-    pub async fn get_today_pomodoro_sessions(&self) -> Result<Vec<(PomodoroPhase, u32, u32)>> {
-        let conn = self.get_conn();
+    #[tokio::test]
+    async fn pomodoro_counts_are_fetched_for_all_requested_todos() {
+        let (_dir, db) = create_test_database().await;
+        let busy = create_todo_due(&db, "busy", TodoStatus::InProgress, None).await;
+        let light = create_todo_due(&db, "light", TodoStatus::Todo, None).await;
+        let idle = create_todo_due(&db, "idle", TodoStatus::Todo, None).await;
+        let other = create_todo_due(&db, "other", TodoStatus::Todo, None).await;
+
+        for (phase, todo_id) in [
+            (PomodoroPhase::Work, &busy.id),
+            (PomodoroPhase::DeepWork, &busy.id),
+            (PomodoroPhase::Work, &busy.id),
+            (PomodoroPhase::ShortBreak, &busy.id),
+            (PomodoroPhase::Work, &light.id),
+            (PomodoroPhase::Work, &other.id),
+        ] {
+            db.record_pomodoro_session(phase, 1500, 1, Some(todo_id), None)
+                .await
+                .unwrap();
+        }
 
-        let mut stmt = conn.prepare(
-            "SELECT phase, duration_seconds, cycle_count FROM pomodoro_sessions WHERE DATE(completed_at) = DATE('now') ORDER BY completed_at DESC"
-        ).map_err(AppError::Database)?;
+        let ids = vec![busy.id.clone(), light.id.clone(), idle.id.clone()];
+        let counts = db.get_pomodoro_counts_for_todos(&ids).await.unwrap();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&busy.id], 3);
+        assert_eq!(counts[&light.id], 1);
+        assert_eq!(counts[&idle.id], 0);
+        assert!(!counts.contains_key(&other.id));
 
-        let mut sessions = Vec::new();
-        let rows = stmt
-            .query_map([], |row| {
-                let phase_str: String = row.get("phase")?;
-                let phase = match phase_str.as_str() {
-                    "work" => PomodoroPhase::Work,
-                    "short_break" => PomodoroPhase::ShortBreak,
-                    "long_break" => PomodoroPhase::LongBreak,
-                    _ => PomodoroPhase::Work,
-                };
+        assert!(db.get_pomodoro_counts_for_todos(&[]).await.unwrap().is_empty());
+    }
 
-                Ok((phase, row.get("duration_seconds")?, row.get("cycle_count")?))
+    #[tokio::test]
+    async fn estimated_pomodoros_are_stored_and_compared_to_completed_work() {
+        let (_dir, db) = create_test_database().await;
+        let todo = db
+            .create_todo(&NewTodo {
+                title: "estimate".to_string(),
+                estimated_pomodoros: Some(2),
+                ..Default::default()
             })
-            .map_err(AppError::Database)?;
+            .await
+            .unwrap();
+        assert_eq!(todo.estimated_pomodoros, Some(2));
+        assert_eq!(db.get_todo_pomodoro_count(&todo.id).await.unwrap(), 0);
+
+        for phase in [
+            PomodoroPhase::Work,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::DeepWork,
+            PomodoroPhase::LongBreak,
+            PomodoroPhase::Work,
+        ] {
+            db.record_pomodoro_session(phase, 1500, 1, Some(&todo.id), None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(db.get_todo_pomodoro_count(&todo.id).await.unwrap(), 3);
+
+        let updated = db
+            .update_todo(&todo.id, &TodoUpdate::new().with_estimated_pomodoros(Some(4)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.estimated_pomodoros, Some(4));
+        let progress = TodoWithProgress::new(updated, 3);
+        assert_eq!(progress.estimated_pomodoros, Some(4));
+        assert_eq!(progress.variance(), Some(-1));
+
+        let cleared = db
+            .update_todo(&todo.id, &TodoUpdate::new().with_estimated_pomodoros(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(TodoWithProgress::new(cleared, 3).variance(), None);
+    }
 
-        for session in rows {
-            sessions.push(session.map_err(AppError::Database)?);
+    #[tokio::test]
+    async fn deep_work_sessions_are_stored_and_count_as_focus() {
+        let (_dir, db) = create_test_database().await;
+
+        db.record_pomodoro_session(PomodoroPhase::DeepWork, 5400, 1, None, None)
+            .await
+            .unwrap();
+        db.record_pomodoro_session(PomodoroPhase::Work, 1500, 2, None, None)
+            .await
+            .unwrap();
+        assert_eq!(db.get_lifetime_focus_seconds().await.unwrap(), 6900);
+
+        db.save_session_state(PomodoroPhase::DeepWork, 4000, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.load_session_state().await.unwrap(),
+            Some((PomodoroPhase::DeepWork, 4000, 2))
+        );
+    }
+
+    #[tokio::test]
+    async fn todos_created_between_includes_both_ends() {
+        let (_dir, db) = create_test_database().await;
+        let from = Utc.with_ymd_and_hms(2024, 6, 10, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 6, 16, 23, 59, 59).unwrap();
+        let cases = [
+            ("before", from - chrono::Duration::seconds(1)),
+            ("start", from),
+            ("middle", from + chrono::Duration::days(3)),
+            ("end", to),
+            ("after", to + chrono::Duration::seconds(1)),
+        ];
+        for (title, created_at) in cases {
+            let todo = create_todo_due(&db, title, TodoStatus::Todo, None).await;
+            db.get_conn()
+                .execute(
+                    "UPDATE todos SET created_at = ?1 WHERE id = ?2",
+                    rusqlite::params![created_at, todo.id],
+                )
+                .unwrap();
         }
 
-        Ok(sessions)
+        let titles: Vec<_> = db
+            .get_todos_created_between(from, to)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, vec!["start", "middle", "end"]);
     }
 
-    // ========================================================================
-    // 标签操作
-    // ========================================================================
+    #[tokio::test]
+    async fn tag_names_colliding_after_normalization_are_rejected() {
+        let (_dir, db) = create_test_database().await;
+        let created = db.create_tag("Work", "#007AFF").await.unwrap();
+        assert_eq!(created.name, "Work");
 
-    /// 创建标签
-    pub async fn create_tag(&self, name: &str, color: &str) -> Result<(String, String, String)> {
-        let conn = Arc::clone(&self.conn);
-        let name = name.to_string();
-        let color = color.to_string();
+        for duplicate in ["work ", "WORK", "  Work"] {
+            let err = db.create_tag(duplicate, "#FF0000").await.unwrap_err();
+            assert!(matches!(err, AppError::Validation(_)), "{duplicate:?}");
+        }
 
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+        let found = db.get_tag_by_normalized_name(" wOrK").await.unwrap().unwrap();
+        assert_eq!(found.id, created.id);
+        assert_eq!(found.name, "Work");
+        assert_eq!(found.color, "#007AFF");
+        assert_eq!(found.created_at, created.created_at);
+        assert_eq!(db.get_all_tags().await.unwrap().len(), 1);
+    }
 
-            let id = uuid::Uuid::new_v4().to_string();
-            let now = Utc::now();
+    #[tokio::test]
+    async fn update_tag_changes_only_given_fields() {
+        let (_dir, db) = create_test_database().await;
+        let work = db.create_tag("work", "#007AFF").await.unwrap();
+        db.create_tag("Home", "#00FF00").await.unwrap();
+
+        let renamed = db.update_tag(&work.id, Some(" Focus "), None).await.unwrap().unwrap();
+        assert_eq!(renamed.name, "Focus");
+        assert_eq!(renamed.color, "#007AFF");
+        assert_eq!(renamed.created_at, work.created_at);
+
+        let recolored = db.update_tag(&work.id, None, Some("#FF0000")).await.unwrap().unwrap();
+        assert_eq!(recolored.name, "Focus");
+        assert_eq!(recolored.color, "#FF0000");
+        assert_eq!(db.get_tag_by_normalized_name("focus").await.unwrap(), Some(recolored));
+
+        // 只改大小写仍视为同一标签
+        assert_eq!(
+            db.update_tag(&work.id, Some("FOCUS"), None).await.unwrap().unwrap().name,
+            "FOCUS"
+        );
+        let err = db.update_tag(&work.id, Some("home "), None).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        assert!(db.update_tag("missing", Some("x"), None).await.unwrap().is_none());
+    }
 
-            conn.execute(
-                "INSERT INTO tags (id, name, color, created_at) VALUES (?1, ?2, ?3, ?4)",
-                params![id, name, color, now],
-            )
-            .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn tags_created_in_quick_succession_list_newest_first() {
+        let (_dir, db) = create_test_database().await;
+        for name in ["first", "second", "third"] {
+            db.create_tag(name, "#007AFF").await.unwrap();
+        }
 
-            Ok((id, name, color))
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+        let tags = db.get_all_tags().await.unwrap();
+        let names: Vec<_> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["third", "second", "first"]);
     }
 
-    /// 获取所有标签
-    pub async fn get_all_tags(&self) -> Result<Vec<(String, String, String)>> {
-        let conn = Arc::clone(&self.conn);
+    #[tokio::test]
+    async fn synced_todo_can_be_requeued() {
+        let (_dir, db) = create_test_database().await;
+        let first = create_todo_due(&db, "first", TodoStatus::Todo, None).await;
+        let second = create_todo_due(&db, "second", TodoStatus::Todo, None).await;
+        db.mark_todo_synced(&first.id).await.unwrap();
+        db.mark_todo_synced(&second.id).await.unwrap();
+        assert!(db.get_pending_sync_todos().await.unwrap().is_empty());
+
+        assert!(db.mark_todo_pending_sync(&first.id).await.unwrap());
+        assert!(!db.mark_todo_pending_sync("missing").await.unwrap());
+        let pending = db.get_pending_sync_todos().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, first.id);
+
+        assert_eq!(db.mark_all_todos_pending_sync().await.unwrap(), 1);
+        assert_eq!(db.get_pending_sync_todos().await.unwrap().len(), 2);
+    }
 
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+    #[tokio::test]
+    async fn tag_focus_time_sums_work_sessions_of_tagged_todos() {
+        let (_dir, db) = create_test_database().await;
+        let first = create_todo_due(&db, "first", TodoStatus::Todo, None).await;
+        let second = create_todo_due(&db, "second", TodoStatus::Todo, None).await;
+        let untagged = create_todo_due(&db, "untagged", TodoStatus::Todo, None).await;
+        let tag_id = db.create_tag("work", "#007AFF").await.unwrap().id;
+        db.add_tag_to_todo(&first.id, &tag_id).await.unwrap();
+        db.add_tag_to_todo(&second.id, &tag_id).await.unwrap();
+
+        let work = PomodoroPhase::Work;
+        db.record_pomodoro_session(work, 1500, 1, Some(&first.id), None).await.unwrap();
+        db.record_pomodoro_session(work, 1500, 2, Some(&first.id), None).await.unwrap();
+        db.record_pomodoro_session(work, 600, 3, Some(&second.id), None).await.unwrap();
+        db.record_pomodoro_session(work, 1500, 4, Some(&untagged.id), None).await.unwrap();
+        db.record_pomodoro_session(work, 1500, 5, None, None).await.unwrap();
+        db.record_pomodoro_session(PomodoroPhase::ShortBreak, 300, 5, Some(&first.id), None)
+            .await
+            .unwrap();
+
+        assert_eq!(db.get_focus_time_for_tag(&tag_id, MinuteRounding::Round).await.unwrap(), 60);
+
+        db.remove_tag_from_todo(&second.id, &tag_id).await.unwrap();
+        assert_eq!(db.get_focus_time_for_tag(&tag_id, MinuteRounding::Round).await.unwrap(), 50);
+        assert_eq!(db.get_focus_time_for_tag("missing", MinuteRounding::Round).await.unwrap(), 0);
+    }
 
-            let mut stmt = conn
-                .prepare("SELECT id, name, color FROM tags ORDER BY created_at DESC")
-                .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn top_focused_todos_are_ranked_by_work_minutes() {
+        let (_dir, db) = create_test_database().await;
+        let light = create_todo_due(&db, "light", TodoStatus::Todo, None).await;
+        let heavy = create_todo_due(&db, "heavy", TodoStatus::Done, None).await;
+        let medium = create_todo_due(&db, "medium", TodoStatus::Todo, None).await;
+        let idle = create_todo_due(&db, "idle", TodoStatus::Todo, None).await;
+
+        let work = PomodoroPhase::Work;
+        db.record_pomodoro_session(work, 600, 1, Some(&light.id), None).await.unwrap();
+        db.record_pomodoro_session(work, 1500, 2, Some(&heavy.id), None).await.unwrap();
+        db.record_pomodoro_session(PomodoroPhase::DeepWork, 3000, 3, Some(&heavy.id), None)
+            .await
+            .unwrap();
+        db.record_pomodoro_session(work, 1500, 4, Some(&medium.id), None).await.unwrap();
+        // 休息阶段不计入专注
+        db.record_pomodoro_session(PomodoroPhase::LongBreak, 900, 4, Some(&light.id), None)
+            .await
+            .unwrap();
+        db.record_pomodoro_session(PomodoroPhase::ShortBreak, 300, 4, Some(&idle.id), None)
+            .await
+            .unwrap();
+
+        let ranked = db.get_top_focused_todos(10, MinuteRounding::Round).await.unwrap();
+        let summary: Vec<(&str, u64)> = ranked
+            .iter()
+            .map(|(todo, minutes)| (todo.title.as_str(), *minutes))
+            .collect();
+        assert_eq!(summary, vec![("heavy", 75), ("medium", 25), ("light", 10)]);
+
+        let top_two = db.get_top_focused_todos(2, MinuteRounding::Round).await.unwrap();
+        assert_eq!(top_two.len(), 2);
+        assert_eq!(top_two[1].0.id, medium.id);
+
+        db.delete_todo(&heavy.id).await.unwrap();
+        let ranked = db.get_top_focused_todos(10, MinuteRounding::Round).await.unwrap();
+        assert_eq!(ranked[0].0.id, medium.id);
+    }
 
-            let mut tags = Vec::new();
-            let rows = stmt
-                .query_map([], |row| {
-                    Ok((row.get("id")?, row.get("name")?, row.get("color")?))
-                })
-                .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn session_state_round_trips() {
+        let (_dir, db) = create_test_database().await;
+        assert_eq!(db.load_session_state().await.unwrap(), None);
 
-            for tag_result in rows {
-                tags.push(tag_result.map_err(AppError::Database)?);
-            }
+        db.save_session_state(PomodoroPhase::Work, 600, 2).await.unwrap();
+        db.save_session_state(PomodoroPhase::ShortBreak, 120, 3).await.unwrap();
 
-            Ok(tags)
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+        assert_eq!(
+            db.load_session_state().await.unwrap(),
+            Some((PomodoroPhase::ShortBreak, 120, 3))
+        );
     }
 
-    /// 删除标签
-    pub async fn delete_tag(&self, id: &str) -> Result<bool> {
-        let conn = Arc::clone(&self.conn);
-        let id = id.to_string();
-
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+    #[tokio::test]
+    async fn operations_fail_fast_when_connection_is_held() {
+        let (_dir, db) = create_test_database().await;
+        db.set_operation_timeout(Duration::from_millis(100));
+        let conn = Arc::clone(&db.conn);
+        let holder = std::thread::spawn(move || {
+            let _guard = conn.lock().unwrap();
+            std::thread::sleep(Duration::from_millis(500));
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let result = db.delete_todo("missing").await;
+        assert!(matches!(result, Err(AppError::Timeout)));
+        holder.join().unwrap();
+    }
 
-            let rows_affected = conn
-                .execute("DELETE FROM tags WHERE id = ?1", params![id])
-                .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn reads_do_not_wait_for_the_write_connection() {
+        let (_dir, db) = create_test_database().await;
+        let todo = create_todo_due(&db, "pooled", TodoStatus::Todo, None).await;
+        db.set_operation_timeout(Duration::from_millis(200));
+
+        let conn = Arc::clone(&db.conn);
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || {
+            let _guard = conn.lock().unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(500));
+        });
+        locked_rx.recv().unwrap();
+
+        let todos = db.get_all_todos().await.unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(
+            db.get_todo_by_id(&todo.id).await.unwrap().map(|found| found.title),
+            Some("pooled".to_string())
+        );
+        holder.join().unwrap();
+    }
 
-            Ok(rows_affected > 0)
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+    #[tokio::test]
+    async fn pooled_read_connections_enforce_foreign_keys_and_reject_writes() {
+        let (_dir, db) = create_test_database().await;
+        for _ in 0..READ_POOL_SIZE {
+            let conn = db.read_conn();
+            let foreign_keys: i64 = conn
+                .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(foreign_keys, 1);
+            let journal: String = conn
+                .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(journal, "wal");
+            assert!(conn.execute("DELETE FROM todos", []).is_err());
+        }
     }
 
-    /// 为待办事项添加标签
-    pub async fn add_tag_to_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
-        let conn = Arc::clone(&self.conn);
-        let todo_id = todo_id.to_string();
-        let tag_id = tag_id.to_string();
+    #[tokio::test]
+    async fn busy_database_surfaces_as_timeout() {
+        let (dir, db) = create_test_database().await;
+        db.set_busy_timeout(Duration::from_millis(50)).unwrap();
 
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+        let other = rusqlite::Connection::open(dir.path().join("test.db")).unwrap();
+        other.execute_batch("BEGIN EXCLUSIVE;").unwrap();
 
-            // 使用 INSERT OR IGNORE 避免重复
-            conn.execute(
-                "INSERT OR IGNORE INTO todo_tags (todo_id, tag_id) VALUES (?1, ?2)",
-                params![todo_id, tag_id],
-            )
-            .map_err(AppError::Database)?;
+        let result = db
+            .create_todo(&NewTodo {
+                title: "blocked".to_string(),
+                ..Default::default()
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::Timeout)));
 
-            Ok(())
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+        other.execute_batch("ROLLBACK;").unwrap();
     }
 
-    /// 从待办事项移除标签
-    pub async fn remove_tag_from_todo(&self, todo_id: &str, tag_id: &str) -> Result<()> {
-        let conn = Arc::clone(&self.conn);
-        let todo_id = todo_id.to_string();
-        let tag_id = tag_id.to_string();
+    #[tokio::test]
+    async fn paginated_todos_match_full_listing_and_skip_deleted() {
+        let (_dir, db) = create_test_database().await;
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let todo = db
+                .create_todo(&NewTodo {
+                    title: format!("todo {}", i),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            ids.push(todo.id);
+        }
+        assert!(db.delete_todo(&ids[2]).await.unwrap());
+
+        assert_eq!(db.count_todos().await.unwrap(), 4);
+        let all: Vec<String> = db
+            .get_all_todos()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+
+        let mut paged = Vec::new();
+        for offset in (0..6).step_by(3) {
+            let page = db.get_todos_paginated(offset, 3).await.unwrap();
+            assert!(page.len() <= 3);
+            paged.extend(page.into_iter().map(|t| t.id));
+        }
+        assert_eq!(paged, all);
+        assert!(!paged.contains(&ids[2]));
+        assert!(db.get_todos_paginated(10, 3).await.unwrap().is_empty());
+    }
 
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+    #[tokio::test]
+    async fn full_text_search_tracks_edits_and_escapes_queries() {
+        let (_dir, db) = create_test_database().await;
+        let cpp = db
+            .create_todo(&NewTodo {
+                title: "学习 C++ 模板".to_string(),
+                description: Some("读完 \"Effective\" 一书".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let report = db
+            .create_todo(&NewTodo {
+                title: "写周报".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let ids = |todos: Vec<Todo>| todos.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(db.search_todos("c++").await.unwrap()), vec![cpp.id.clone()]);
+        assert_eq!(ids(db.search_todos("\"effective\"").await.unwrap()), vec![cpp.id.clone()]);
+        assert_eq!(ids(db.search_todos("C++ 模板").await.unwrap()), vec![cpp.id.clone()]);
+        assert!(db.search_todos("AND OR NOT (").await.unwrap().is_empty());
+        // 少于三个字符时走内存筛选
+        assert_eq!(ids(db.search_todos("周报").await.unwrap()), vec![report.id.clone()]);
+        assert_eq!(db.search_todos("  ").await.unwrap().len(), 2);
+
+        db.update_todo(&report.id, &TodoUpdate::new().with_title("整理月度报告".to_string()))
+            .await
+            .unwrap();
+        assert!(db.search_todos("写周报").await.unwrap().is_empty());
+        assert_eq!(ids(db.search_todos("月度报告").await.unwrap()), vec![report.id.clone()]);
+
+        assert!(db.delete_todo(&cpp.id).await.unwrap());
+        assert!(db.search_todos("c++").await.unwrap().is_empty());
+    }
 
-            conn.execute(
-                "DELETE FROM todo_tags WHERE todo_id = ?1 AND tag_id = ?2",
-                params![todo_id, tag_id],
-            )
-            .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn maintenance_prunes_sync_queue_and_reclaims_space() {
+        let (_dir, db) = create_test_database().await;
+        {
+            let conn = db.get_conn();
+            let payload = "x".repeat(2000);
+            for i in 0..300 {
+                conn.execute(
+                    "INSERT INTO sync_queue (operation_type, table_name, record_id, payload, status, synced_at)
+                     VALUES ('update', 'todos', ?1, ?2, 'synced', ?3)",
+                    params![format!("todo-{}", i), payload, Utc::now()],
+                )
+                .unwrap();
+            }
+        }
 
-            Ok(())
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
-    }
+        let summary = db.run_maintenance().await.unwrap();
+        assert_eq!(summary.pruned_sync_rows, 200);
+        assert!(summary.bytes_reclaimed > 0);
 
-    /// 获取待办事项的所有标签
-    pub async fn get_todo_tags(&self, todo_id: &str) -> Result<Vec<(String, String, String)>> {
-        let conn = Arc::clone(&self.conn);
-        let todo_id = todo_id.to_string();
+        let remaining: i64 = db
+            .get_conn()
+            .query_row("SELECT COUNT(*) FROM sync_queue", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 100);
 
-        tokio::task::spawn_blocking(move || {
-            let conn = conn
-                .lock()
-                .map_err(|e| AppError::Other(format!("Failed to lock database: {}", e)))?;
+        let again = db.run_maintenance().await.unwrap();
+        assert_eq!(again, MaintenanceSummary::default());
+        db.vacuum().await.unwrap();
+    }
 
-            let mut stmt = conn
-                .prepare(
-                    "SELECT t.id, t.name, t.color FROM tags t 
-                     INNER JOIN todo_tags tt ON t.id = tt.tag_id 
-                     WHERE tt.todo_id = ?1 
-                     ORDER BY t.created_at DESC",
-                )
-                .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn subtasks_link_to_parents_and_are_orphaned_on_delete() {
+        let (_dir, db) = create_test_database().await;
+        let new_todo = |title: &str| NewTodo {
+            title: title.to_string(),
+            ..Default::default()
+        };
+        let parent = db.create_todo(&new_todo("parent")).await.unwrap();
+        let first = db
+            .create_todo(&new_todo("first").with_parent(parent.id.clone()))
+            .await
+            .unwrap();
+        let second = db
+            .create_todo(&new_todo("second").with_parent(parent.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(first.parent_id.as_deref(), Some(parent.id.as_str()));
+
+        let children: Vec<String> = db
+            .get_subtasks(&parent.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(children, vec![first.id.clone(), second.id.clone()]);
+
+        // 父任务必须存在，且不能形成环
+        assert!(matches!(
+            db.create_todo(&new_todo("orphan").with_parent("missing".to_string())).await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            db.update_todo(&parent.id, &TodoUpdate::new().with_parent(Some(first.id.clone()))).await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            db.update_todo(&parent.id, &TodoUpdate::new().with_parent(Some(parent.id.clone()))).await,
+            Err(AppError::Validation(_))
+        ));
+
+        let detached = db
+            .update_todo(&second.id, &TodoUpdate::new().with_parent(None))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(detached.parent_id, None);
+
+        // 移入回收站或永久删除父任务都只会把子任务改为顶层任务
+        assert!(db.delete_todo(&parent.id).await.unwrap());
+        let first_after = db.get_todo_by_id(&first.id).await.unwrap().unwrap();
+        assert_eq!(first_after.parent_id, None);
+        assert!(db.get_all_todos().await.unwrap().iter().any(|t| t.id == first.id));
+
+        let other_parent = db.create_todo(&new_todo("other")).await.unwrap();
+        db.update_todo(&first.id, &TodoUpdate::new().with_parent(Some(other_parent.id.clone())))
+            .await
+            .unwrap();
+        assert!(db.permanently_delete_todo(&other_parent.id).await.unwrap());
+        assert_eq!(db.get_todo_by_id(&first.id).await.unwrap().unwrap().parent_id, None);
+    }
 
-            let mut tags = Vec::new();
-            let rows = stmt
-                .query_map(params![todo_id], |row| {
-                    Ok((row.get("id")?, row.get("name")?, row.get("color")?))
-                })
-                .map_err(AppError::Database)?;
+    #[tokio::test]
+    async fn completed_at_follows_status_changes() {
+        let (_dir, db) = create_test_database().await;
+
+        let done = create_todo_due(&db, "done", TodoStatus::Done, None).await;
+        assert!(done.completed_at.is_some());
+        let stored = db.get_todo_by_id(&done.id).await.unwrap().unwrap();
+        assert_eq!(stored.completed_at, done.completed_at);
+
+        let open = create_todo_due(&db, "open", TodoStatus::Todo, None).await;
+        assert_eq!(open.completed_at, None);
+
+        let finished = db
+            .update_todo(&open.id, &TodoUpdate::new().with_status(TodoStatus::Done))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(finished.completed_at.is_some());
+
+        let reopened = db
+            .update_todo(&open.id, &TodoUpdate::new().with_status(TodoStatus::InProgress))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.completed_at, None);
+    }
 
-            for tag_result in rows {
-                tags.push(tag_result.map_err(AppError::Database)?);
-            }
+    #[tokio::test]
+    async fn carry_over_moves_only_overdue_unfinished_todos() {
+        let (_dir, db) = create_test_database().await;
+        let today_start = Utc.with_ymd_and_hms(2024, 6, 10, 0, 0, 0).unwrap();
+        let today_end = Utc.with_ymd_and_hms(2024, 6, 10, 23, 59, 59).unwrap();
+        let yesterday = Utc.with_ymd_and_hms(2024, 6, 9, 18, 0, 0).unwrap();
+        let tomorrow = Utc.with_ymd_and_hms(2024, 6, 11, 9, 0, 0).unwrap();
+
+        let overdue = create_todo_due(&db, "overdue", TodoStatus::Todo, Some(yesterday)).await;
+        let overdue_in_progress =
+            create_todo_due(&db, "in progress", TodoStatus::InProgress, Some(yesterday)).await;
+        let done = create_todo_due(&db, "done", TodoStatus::Done, Some(yesterday)).await;
+        let future = create_todo_due(&db, "future", TodoStatus::Todo, Some(tomorrow)).await;
+        let undated = create_todo_due(&db, "undated", TodoStatus::Todo, None).await;
+
+        let moved = db
+            .carry_over_due_dates(today_start, Some(today_end))
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+
+        let due_of = |todo: &Todo| {
+            let db = db.clone();
+            let id = todo.id.clone();
+            async move { db.get_todo_by_id(&id).await.unwrap().unwrap().due_at }
+        };
+        assert_eq!(due_of(&overdue).await, Some(today_end));
+        assert_eq!(due_of(&overdue_in_progress).await, Some(today_end));
+        assert_eq!(due_of(&done).await, Some(yesterday));
+        assert_eq!(due_of(&future).await, Some(tomorrow));
+        assert_eq!(due_of(&undated).await, None);
+    }
 
-            Ok(tags)
-        })
-        .await
-        .map_err(|e| AppError::Other(e.to_string()))?
+    #[tokio::test]
+    async fn carry_over_can_clear_due_dates() {
+        let (_dir, db) = create_test_database().await;
+        let today_start = Utc.with_ymd_and_hms(2024, 6, 10, 0, 0, 0).unwrap();
+        let yesterday = Utc.with_ymd_and_hms(2024, 6, 9, 18, 0, 0).unwrap();
+        let overdue = create_todo_due(&db, "overdue", TodoStatus::Todo, Some(yesterday)).await;
+
+        assert_eq!(db.carry_over_due_dates(today_start, None).await.unwrap(), 1);
+        let reloaded = db.get_todo_by_id(&overdue.id).await.unwrap().unwrap();
+        assert_eq!(reloaded.due_at, None);
     }
 }
@@ -1,3 +1,4 @@
 //! 数据存储层
 
+pub mod crypto;
 pub mod database;
@@ -16,18 +16,42 @@ export interface Todo {
   github_issue_id?: number | null;
   github_project_id?: number | null;
   github_issue_number?: number | null;
+  due_at?: string | null;
+  completed_at?: string | null;
+  estimate_minutes?: number | null;
+  estimated_pomodoros?: number | null;
+  archived?: boolean;
+  priority?: "low" | "medium" | "high";
+  sort_order?: number;
+  parent_id?: string | null;
   created_at: string;
   updated_at: string;
   tags?: Tag[];
+  pomodoro_count?: number;
 }
 
 export interface PomodoroSession {
-  phase: "work" | "short_break" | "long_break";
+  phase: "work" | "short_break" | "long_break" | "deep_work";
   duration: number;
   remaining: number;
   is_running: boolean;
   cycle_count: number;
   started_at?: string;
+  focus_todo_id?: string | null;
+  session_uuid?: string;
+  focus_block?: { completed: number; total: number } | null;
+  last_pause_reason?: PauseReason | null;
+  pause_counts?: PauseCounts;
+  interruptions?: number;
+  sequence_index?: number;
+}
+
+export type PauseReason = "manual" | "idle_auto" | "break_enforced";
+
+export interface PauseCounts {
+  manual: number;
+  idle_auto: number;
+  break_enforced: number;
 }
 
 export interface UserConfig {
@@ -41,9 +65,32 @@ export interface UserConfig {
   pomodoro_short_break_duration: number;
   pomodoro_long_break_duration: number;
   pomodoro_cycles_until_long_break: number;
+  pomodoro_deep_work_duration?: number;
+  pomodoro_auto_start_next?: boolean;
+  pomodoro_warn_before_seconds?: number | null;
+  pomodoro_phase_sequence?: PomodoroSession["phase"][] | null;
+  pomodoro_require_phase_ack?: boolean;
+  pomodoro_break_reminders?: boolean;
+  pomodoro_reminder_grace_secs?: number;
+  idle_auto_pause_seconds?: number | null;
+  focus_sound_enabled?: boolean;
+  focus_loop_sound?: string | null;
   notifications_enabled: boolean;
   sound_enabled: boolean;
+  system_notifications?: boolean;
   theme: string;
+  daily_carry_over?: boolean;
+  last_carry_over_date?: string | null;
+  daily_focus_goal_minutes?: number | null;
+  daily_pomodoro_goal?: number | null;
+  task_complete_sound?: string | null;
+  sync_focus_status?: boolean;
+  auto_archive_done_after_days?: number | null;
+  work_color?: string | null;
+  short_break_color?: string | null;
+  long_break_color?: string | null;
+  enforced_break_seconds?: number | null;
+  focus_minute_rounding?: "floor" | "round" | "ceil";
 }
 
 export interface GithubSyncTarget {
@@ -62,6 +109,28 @@ export interface GithubSyncReport {
   errors: string[];
 }
 
+export interface TodoNote {
+  id: string;
+  todo_id: string;
+  body: string;
+  created_at: string;
+}
+
+export interface StatusTransition {
+  from: Todo["status"];
+  to: Todo["status"];
+}
+
+export interface ToggleResult {
+  todo: Todo;
+  transition: StatusTransition;
+}
+
+export interface TodoSearchResult {
+  todos: Todo[];
+  truncated: boolean;
+}
+
 export interface Tag {
   id: string;
   name: string;
@@ -76,7 +145,6 @@ interface CommandResult<T> {
   error_code?: string | null;
 }
 
-type TagTuple = [string, string, string];
 export interface AppErrorPayload {
   message: string;
   code?: string | null;
@@ -342,20 +410,25 @@ export const useAppStore = create<AppState>((set, get) => ({
         return;
       }
       const todos = await invokeCommand<Todo[]>("get_todos");
+      const pomodoroCounts = await invokeCommand<Record<string, number>>(
+        "get_pomodoro_counts",
+        { ids: todos.map((todo) => todo.id) },
+      ).catch(() => ({}) as Record<string, number>);
       const todosWithTags = await Promise.all(
         todos.map(async (todo) => {
           const tagPayload = await invokeCommand<unknown>("get_todo_tags", {
             todo_id: todo.id,
           }).catch(() => []);
           const tags = Array.isArray(tagPayload)
-            ? (tagPayload.filter(
-                (item): item is TagTuple =>
-                  Array.isArray(item) && item.length === 3,
-              ) as TagTuple[])
+            ? tagPayload.filter(
+                (item): item is Tag =>
+                  typeof item === "object" && item !== null && "id" in item,
+              )
             : [];
           return {
             ...todo,
-            tags: tags.map(([id, name, color]) => ({ id, name, color })),
+            tags,
+            pomodoro_count: pomodoroCounts?.[todo.id] ?? 0,
           };
         }),
       );
@@ -501,7 +574,13 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   updateTodo: async (id: string, updates: Partial<Todo>) => {
     try {
-      await invokeCommand<Todo>("update_todo", { id, ...updates });
+      await invokeCommand<Todo>("update_todo", {
+        id,
+        ...updates,
+        // null 表示清除预估耗时或预估番茄数
+        clearEstimateMinutes: updates.estimate_minutes === null,
+        clearEstimatedPomodoros: updates.estimated_pomodoros === null,
+      });
       await get().loadTodos();
     } catch (error) {
       console.error("Failed to update todo:", error);
@@ -648,8 +727,7 @@ export const useAppStore = create<AppState>((set, get) => ({
         console.warn("Skipping loadTags - not in Tauri environment");
         return;
       }
-      const result = await invokeCommand<TagTuple[]>("get_tags");
-      const tags = result.map(([id, name, color]) => ({ id, name, color }));
+      const tags = await invokeCommand<Tag[]>("get_tags");
       set({ tags });
       console.log("Loaded tags:", tags.length);
     } catch (error) {
@@ -663,16 +741,10 @@ export const useAppStore = create<AppState>((set, get) => ({
         console.warn("Skipping createTag - not in Tauri environment");
         return;
       }
-      const result = await invokeCommand<TagTuple>("create_tag", {
+      const newTag = await invokeCommand<Tag>("create_tag", {
         name,
         color,
       });
-      const newTag: Tag = {
-        id: result[0],
-        name: result[1],
-        color: result[2],
-        created_at: new Date().toISOString(),
-      };
       set({ tags: [...get().tags, newTag] });
       get().setSuccess("标签创建成功");
     } catch (error) {
@@ -788,10 +860,9 @@ export const useAppStore = create<AppState>((set, get) => ({
         console.warn("Skipping getTodoTags - not in Tauri environment");
         return [];
       }
-      const tags = await invokeCommand<TagTuple[]>("get_todo_tags", {
+      return await invokeCommand<Tag[]>("get_todo_tags", {
         todo_id: todoId,
       });
-      return tags.map(([id, name, color]) => ({ id, name, color }));
     } catch (error) {
       console.error("Failed to get todo tags:", error);
       return [];
@@ -69,6 +69,13 @@ describe("AppStore", () => {
           error: null,
         } as any);
       }
+      if (command === "get_pomodoro_counts") {
+        return Promise.resolve({
+          success: true,
+          data: { "1": 2 },
+          error: null,
+        } as any);
+      }
       return Promise.resolve({ success: true, data: null, error: null } as any);
     });
 
@@ -80,8 +87,11 @@ describe("AppStore", () => {
     expect(vi.mocked(invoke)).toHaveBeenCalledWith("get_todo_tags", {
       todo_id: "1",
     });
+    expect(vi.mocked(invoke)).toHaveBeenCalledWith("get_pomodoro_counts", {
+      ids: ["1"],
+    });
     expect(useAppStore.getState().todos).toEqual(
-      mockTodos.map((todo) => ({ ...todo, tags: [] })),
+      mockTodos.map((todo) => ({ ...todo, tags: [], pomodoro_count: 2 })),
     );
   });
 
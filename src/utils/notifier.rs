@@ -1,6 +1,6 @@
 //! 系统通知工具
 
-use crate::core::error::{AppError, Result};
+use crate::core::error::Result;
 
 /// 通知管理器
 #[derive(Debug, Clone)]
@@ -30,7 +30,7 @@ impl Notifier {
                 .duration(Duration::Short);
 
             toast.show()
-                .map_err(|e| AppError::Other(format!("Windows 通知错误: {}", e)))?;
+                .map_err(|e| crate::core::error::AppError::Other(format!("Windows 通知错误: {}", e)))?;
         }
 
         #[cfg(target_os = "macos")]
@@ -1,17 +1,320 @@
 //! 音频播放工具
 
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
 use crate::core::error::Result;
+use crate::core::pomodoro::{PomodoroEvent, PomodoroSession};
+use crate::core::state::{AppEvent, UserConfig};
+use crate::core::todo::TodoStatus;
+
+/// 要播放的音效
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundEffect {
+    /// 默认成功音效
+    Success,
+    /// 番茄钟阶段完成音效
+    PomodoroComplete,
+    /// 自定义音效文件
+    File(PathBuf),
+}
+
+/// 专注背景音的默认音量
+pub const DEFAULT_FOCUS_VOLUME: f32 = 0.5;
+
+/// 正在循环播放的专注背景音
+///
+/// 音频输出流不能跨线程移动，由专用线程持有；停止时清空播放队列，并等该线程释放输出流。
+#[cfg(feature = "audio")]
+struct FocusLoop {
+    sink: Arc<rodio::Sink>,
+    release: std::sync::mpsc::Sender<()>,
+    output_thread: std::thread::JoinHandle<()>,
+}
+
+#[cfg(feature = "audio")]
+impl FocusLoop {
+    fn stop(self) {
+        self.sink.stop();
+        let _ = self.release.send(());
+        let _ = self.output_thread.join();
+    }
+}
+
+#[cfg(feature = "audio")]
+impl std::fmt::Debug for FocusLoop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FocusLoop")
+            .field("volume", &self.sink.volume())
+            .finish_non_exhaustive()
+    }
+}
+
+/// 未启用 `audio` 特性时不会创建背景音循环
+#[cfg(not(feature = "audio"))]
+#[derive(Debug)]
+struct FocusLoop;
+
+#[cfg(not(feature = "audio"))]
+impl FocusLoop {
+    fn stop(self) {}
+}
 
 /// 声音播放器
 #[derive(Debug, Clone)]
 pub struct SoundPlayer {
     enabled: bool,
+    task_complete_sound: Option<PathBuf>,
+    focus_sound_enabled: bool,
+    focus_loop_sound: Option<PathBuf>,
+    focus_volume: f32,
+    /// 克隆的播放器共享同一个背景音循环
+    focus_loop: Arc<Mutex<Option<FocusLoop>>>,
 }
 
 impl SoundPlayer {
     /// 创建新的声音播放器
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            task_complete_sound: None,
+            focus_sound_enabled: false,
+            focus_loop_sound: None,
+            focus_volume: DEFAULT_FOCUS_VOLUME,
+            focus_loop: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 按用户配置创建声音播放器
+    pub fn from_config(config: &UserConfig) -> Self {
+        let mut player = Self::new(config.sound_enabled);
+        player.update_from_config(config);
+        player
+    }
+
+    /// 应用用户配置中的声音设置
+    pub fn update_from_config(&mut self, config: &UserConfig) {
+        self.enabled = config.sound_enabled;
+        self.task_complete_sound = config
+            .task_complete_sound
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        self.focus_sound_enabled = config.focus_sound_enabled;
+        self.focus_loop_sound = config
+            .focus_loop_sound
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+    }
+
+    /// 设置任务完成音效文件
+    pub fn set_task_complete_sound(&mut self, path: Option<PathBuf>) {
+        self.task_complete_sound = path;
+    }
+
+    /// 任务完成时使用的音效，未配置文件时为默认成功音效
+    pub fn task_complete_effect(&self) -> SoundEffect {
+        match &self.task_complete_sound {
+            Some(path) => SoundEffect::File(path.clone()),
+            None => SoundEffect::Success,
+        }
+    }
+
+    /// 选择应用事件对应的音效：任务变为已完成、番茄钟阶段完成
+    pub fn effect_for_event(&self, event: &AppEvent) -> Option<SoundEffect> {
+        match event {
+            AppEvent::TodoStatusChanged(_, status) if status == TodoStatus::Done.as_str() => {
+                Some(self.task_complete_effect())
+            }
+            AppEvent::PomodoroEvent(PomodoroEvent::PhaseCompleted { .. }) => {
+                Some(SoundEffect::PomodoroComplete)
+            }
+            _ => None,
+        }
+    }
+
+    /// 播放指定音效，自定义文件播放失败时回退到默认成功音效
+    pub fn play(&self, effect: &SoundEffect) -> Result<()> {
+        match effect {
+            SoundEffect::Success => self.play_success(),
+            SoundEffect::PomodoroComplete => self.play_pomodoro_complete(),
+            SoundEffect::File(path) => {
+                if self.play_file(path).is_ok() {
+                    Ok(())
+                } else {
+                    self.play_success()
+                }
+            }
+        }
+    }
+
+    /// 播放任务完成音效
+    pub fn play_task_complete(&self) -> Result<()> {
+        self.play(&self.task_complete_effect())
+    }
+
+    /// 按会话状态同步专注背景音：仅在工作阶段运行中循环播放，暂停、重置、放弃或进入休息时停止
+    pub fn sync_focus_loop(&self, session: Option<&PomodoroSession>) -> Result<()> {
+        let sound = self
+            .focus_loop_sound
+            .as_deref()
+            .filter(|_| self.enabled && self.focus_sound_enabled)
+            .filter(|path| path.is_file());
+        let active = session.is_some_and(|s| s.is_running && s.phase.is_work());
+
+        match sound {
+            Some(path) if active => {
+                if !self.is_looping() {
+                    self.start_loop(path)?;
+                }
+                Ok(())
+            }
+            _ => self.stop_loop(),
+        }
+    }
+
+    /// 循环播放音效文件直到调用 [`SoundPlayer::stop_loop`]（需启用 `audio` 特性）
+    #[cfg(feature = "audio")]
+    pub fn start_loop(&self, path: &Path) -> Result<()> {
+        use crate::core::error::AppError;
+        use rodio::Source;
+
+        if !self.enabled {
+            return Ok(());
+        }
+        let source = Self::decode_file(path)?;
+        self.stop_loop()?;
+
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let volume = self.focus_volume;
+        let output_thread = std::thread::spawn(move || {
+            let (_output, mixer) = match Self::open_output() {
+                Ok(output) => output,
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return;
+                }
+            };
+            let sink = Arc::new(rodio::Sink::connect_new(&mixer));
+            sink.set_volume(volume);
+            sink.append(source.repeat_infinite());
+            if ready_tx.send(Ok(Arc::clone(&sink))).is_ok() {
+                // 停止（或循环被丢弃）之前一直持有输出流
+                let _ = released.recv();
+            }
+        });
+
+        let sink = ready_rx
+            .recv()
+            .map_err(|_| AppError::Other("音频线程意外退出".to_string()))??;
+        *self.lock_focus_loop()? = Some(FocusLoop {
+            sink,
+            release,
+            output_thread,
+        });
+        Ok(())
+    }
+
+    /// 未启用 `audio` 特性时不循环播放
+    #[cfg(not(feature = "audio"))]
+    pub fn start_loop(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    /// 设置专注背景音音量（0.0 到 1.0），正在播放的背景音立即生效
+    pub fn set_focus_volume(&mut self, volume: f32) {
+        self.focus_volume = volume.clamp(0.0, 1.0);
+        #[cfg(feature = "audio")]
+        if let Ok(focus_loop) = self.focus_loop.lock() {
+            if let Some(focus_loop) = focus_loop.as_ref() {
+                focus_loop.sink.set_volume(self.focus_volume);
+            }
+        }
+    }
+
+    /// 专注背景音音量
+    pub fn focus_volume(&self) -> f32 {
+        self.focus_volume
+    }
+
+    /// 停止专注背景音，未在播放时无操作
+    pub fn stop_loop(&self) -> Result<()> {
+        if let Some(focus_loop) = self.lock_focus_loop()?.take() {
+            focus_loop.stop();
+        }
+        Ok(())
+    }
+
+    /// 是否正在循环播放专注背景音
+    pub fn is_looping(&self) -> bool {
+        self.focus_loop
+            .lock()
+            .map(|focus_loop| focus_loop.is_some())
+            .unwrap_or(false)
+    }
+
+    fn lock_focus_loop(&self) -> Result<std::sync::MutexGuard<'_, Option<FocusLoop>>> {
+        self.focus_loop.lock().map_err(|e| {
+            crate::core::error::AppError::Other(format!("Failed to lock focus loop: {}", e))
+        })
+    }
+
+    /// 播放音效文件直到结束（需启用 `audio` 特性）
+    #[cfg(feature = "audio")]
+    fn play_file(&self, path: &Path) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let source = Self::decode_file(path)?;
+
+        let (_output, mixer) = Self::open_output()?;
+        let sink = rodio::Sink::connect_new(&mixer);
+        sink.append(source);
+        sink.sleep_until_end();
+        Ok(())
+    }
+
+    /// 打开并解码音效文件
+    #[cfg(feature = "audio")]
+    fn decode_file(path: &Path) -> Result<rodio::Decoder<std::io::BufReader<std::fs::File>>> {
+        use crate::core::error::AppError;
+
+        if !path.is_file() {
+            return Err(AppError::NotFound(format!("音效文件不存在: {}", path.display())));
+        }
+        let file = std::fs::File::open(path).map_err(AppError::Io)?;
+        rodio::Decoder::try_from(file)
+            .map_err(|e| AppError::Other(format!("无法解码音效文件 {}: {}", path.display(), e)))
+    }
+
+    /// 打开默认音频输出设备，返回需要保持存活的输出流及其混音器
+    #[cfg(all(feature = "audio", not(test)))]
+    fn open_output() -> Result<(rodio::OutputStream, rodio::mixer::Mixer)> {
+        let mut stream = rodio::OutputStreamBuilder::open_default_stream().map_err(|e| {
+            crate::core::error::AppError::Other(format!("无法打开音频输出设备: {}", e))
+        })?;
+        stream.log_on_drop(false);
+        let mixer = stream.mixer().clone();
+        Ok((stream, mixer))
+    }
+
+    /// 单元测试中使用不连接设备的混音器，不依赖声卡
+    #[cfg(all(feature = "audio", test))]
+    fn open_output() -> Result<(rodio::mixer::MixerSource, rodio::mixer::Mixer)> {
+        let (mixer, source) = rodio::mixer::mixer(2, 44_100);
+        Ok((source, mixer))
+    }
+
+    /// 未启用 `audio` 特性时不播放文件，由调用方回退到默认音效
+    #[cfg(not(feature = "audio"))]
+    fn play_file(&self, _path: &Path) -> Result<()> {
+        Err(crate::core::error::AppError::Other(
+            "audio feature is disabled".to_string(),
+        ))
     }
 
     /// 播放提示音
@@ -131,4 +434,144 @@ mod tests {
         assert!(!player.is_enabled());
         assert!(player.play_beep().is_ok());
     }
+
+    #[test]
+    fn test_task_completion_and_pomodoro_completion_use_distinct_sounds() {
+        let config = UserConfig {
+            task_complete_sound: Some("/sounds/ding.wav".to_string()),
+            ..UserConfig::default()
+        };
+        let player = SoundPlayer::from_config(&config);
+
+        let done = AppEvent::TodoStatusChanged("id".to_string(), "done".to_string());
+        let phase_completed = AppEvent::PomodoroEvent(PomodoroEvent::PhaseCompleted {
+            completed_phase: crate::core::pomodoro::PomodoroPhase::Work,
+            next_phase: crate::core::pomodoro::PomodoroPhase::ShortBreak,
+            cycle_count: 1,
+            session_uuid: "run".to_string(),
+            pause_counts: Default::default(),
+            interruptions: 0,
+        });
+        let started = AppEvent::TodoStatusChanged("id".to_string(), "in_progress".to_string());
+
+        assert_eq!(
+            player.effect_for_event(&done),
+            Some(SoundEffect::File(PathBuf::from("/sounds/ding.wav")))
+        );
+        assert_eq!(
+            player.effect_for_event(&phase_completed),
+            Some(SoundEffect::PomodoroComplete)
+        );
+        assert_eq!(player.effect_for_event(&started), None);
+    }
+
+    #[test]
+    fn test_task_completion_falls_back_to_success_sound() {
+        let player = SoundPlayer::from_config(&UserConfig::default());
+        let done = AppEvent::TodoStatusChanged("id".to_string(), "done".to_string());
+        assert_eq!(player.effect_for_event(&done), Some(SoundEffect::Success));
+    }
+
+    /// 写入 0.1 秒的静音 WAV（8 kHz、单声道、16 位）
+    #[cfg(feature = "audio")]
+    fn silent_wav() -> tempfile::NamedTempFile {
+        use std::io::Write;
+
+        let data_len: u32 = 800 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&8_000u32.to_le_bytes());
+        bytes.extend_from_slice(&16_000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(bytes.len() + data_len as usize, 0);
+
+        let mut file = tempfile::Builder::new().suffix(".wav").tempfile().unwrap();
+        file.write_all(&bytes).unwrap();
+        file
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_missing_sound_file_falls_back() {
+        let player = SoundPlayer::new(true);
+        let missing = PathBuf::from("/nonexistent/ding.wav");
+        assert!(player.play_file(&missing).is_err());
+        assert!(player.play(&SoundEffect::File(missing)).is_ok());
+    }
+
+    #[test]
+    fn test_focus_loop_not_started_when_disabled() {
+        let player = SoundPlayer::from_config(&UserConfig {
+            focus_loop_sound: Some("/sounds/rain.wav".to_string()),
+            ..UserConfig::default()
+        });
+        let mut session = PomodoroSession::new(crate::core::pomodoro::PomodoroConfig::default());
+        session.start().unwrap();
+
+        assert!(player.sync_focus_loop(Some(&session)).is_ok());
+        assert!(!player.is_looping());
+        assert!(player.stop_loop().is_ok());
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_focus_loop_start_and_stop() {
+        let file = silent_wav();
+        let mut player = SoundPlayer::new(true);
+
+        assert!(player.start_loop(file.path()).is_ok());
+        assert!(player.is_looping());
+        player.set_focus_volume(1.5);
+        assert_eq!(player.focus_volume(), 1.0);
+        let sink = Arc::downgrade(&player.focus_loop.lock().unwrap().as_ref().unwrap().sink);
+        assert_eq!(sink.upgrade().unwrap().volume(), 1.0);
+
+        // 停止后输出线程已经退出，播放队列随之释放
+        assert!(player.stop_loop().is_ok());
+        assert!(!player.is_looping());
+        assert!(sink.upgrade().is_none());
+        assert!(player.start_loop(Path::new("/nonexistent/rain.wav")).is_err());
+
+        // 无法解码的文件直接报错，不会反复重试
+        let garbage = tempfile::NamedTempFile::new().unwrap();
+        assert!(player.start_loop(garbage.path()).is_err());
+        assert!(!player.is_looping());
+    }
+
+    #[cfg(feature = "audio")]
+    #[test]
+    fn test_focus_loop_follows_work_phase_and_stops_on_pause() {
+        let file = silent_wav();
+        let player = SoundPlayer::from_config(&UserConfig {
+            focus_sound_enabled: true,
+            focus_loop_sound: Some(file.path().display().to_string()),
+            ..UserConfig::default()
+        });
+        let mut session = PomodoroSession::new(crate::core::pomodoro::PomodoroConfig::default());
+
+        player.sync_focus_loop(Some(&session)).unwrap();
+        assert!(!player.is_looping());
+
+        session.start().unwrap();
+        player.sync_focus_loop(Some(&session)).unwrap();
+        assert!(player.is_looping());
+
+        session.pause().unwrap();
+        player.sync_focus_loop(Some(&session)).unwrap();
+        assert!(!player.is_looping());
+
+        session.start().unwrap();
+        player.sync_focus_loop(Some(&session)).unwrap();
+        session.reset().unwrap();
+        player.sync_focus_loop(Some(&session)).unwrap();
+        assert!(!player.is_looping());
+    }
 }